@@ -7,6 +7,12 @@ use chrono::Utc;
 
 pub struct AmcacheAnalyzer;
 
+impl Default for AmcacheAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AmcacheAnalyzer {
     pub fn new() -> Self { Self {} }
 }
@@ -27,7 +33,7 @@ impl ArtifactAnalyzer for AmcacheAnalyzer {
             for rec in records {
                 events.push(ForensicEvent::Execution(ExecutionEvent {
                     timestamp: Utc::now(),
-                    process_name: rec.file_path.split('\\').last().unwrap_or("Unknown").to_string(),
+                    process_name: rec.file_path.split('\\').next_back().unwrap_or("Unknown").to_string(),
                     file_path: format!("{} [SHA1: {}]", rec.file_path, rec.sha1),
                     command_line: String::new(), // [추가]
                     parent_process_name: String::new(), // [추가]
@@ -1,4 +1,4 @@
-use models::event::ForensicEvent;
+use models::event::{FileSystemArtifact, ForensicEvent};
 use chrono::{DateTime, Utc, Duration};
 use std::collections::{HashMap, HashSet, VecDeque};
 use serde::{Serialize, Deserialize};
@@ -52,6 +52,12 @@ pub struct CorrelationEngine {
     pub entity_index: HashMap<String, Vec<String>>, 
 }
 
+impl Default for CorrelationEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CorrelationEngine {
     pub fn new() -> Self {
         Self { 
@@ -73,7 +79,7 @@ impl CorrelationEngine {
             let entry_id = format!("evt-{}", counter);
             
             for entity in &entities {
-                self.entity_index.entry(entity.clone()).or_insert_with(Vec::new).push(entry_id.clone());
+                self.entity_index.entry(entity.clone()).or_default().push(entry_id.clone());
             }
 
             self.events.push(TimelineEntry {
@@ -92,6 +98,9 @@ impl CorrelationEngine {
             ForensicEvent::Logon(l) => l.timestamp,
             ForensicEvent::SystemActivity(s) => s.timestamp,
             ForensicEvent::FileSystemActivity(f) => f.timestamp,
+            ForensicEvent::Download(d) => d.timestamp,
+            ForensicEvent::Deletion(d) => d.timestamp,
+            ForensicEvent::Usb(u) => u.timestamp,
         }
     }
 
@@ -101,8 +110,8 @@ impl CorrelationEngine {
 
         match event {
             ForensicEvent::Execution(e) => {
-                let filename = e.process_name.split('\\').last().unwrap_or(&e.process_name).to_lowercase();
-                let parent_name = e.parent_process_name.split('\\').last().unwrap_or(&e.parent_process_name).to_lowercase();
+                let filename = e.process_name.split('\\').next_back().unwrap_or(&e.process_name).to_lowercase();
+                let parent_name = e.parent_process_name.split('\\').next_back().unwrap_or(&e.parent_process_name).to_lowercase();
 
                 if !filename.is_empty() { entities.push(filename.clone()); }
 
@@ -125,7 +134,7 @@ impl CorrelationEngine {
                 for token in cmd_lower.split_whitespace() {
                     let clean_token = token.trim_matches(|c| c == '\'' || c == '"' || c == '\\' || c == ']' || c == '[');
                     if clean_token.ends_with(".exe") || clean_token.ends_with(".ps1") || clean_token.ends_with(".dll") {
-                        let extracted = clean_token.split('\\').last().unwrap_or(clean_token).to_string();
+                        let extracted = clean_token.split('\\').next_back().unwrap_or(clean_token).to_string();
                         if !extracted.is_empty() { entities.push(extracted); }
                     }
                 }
@@ -136,19 +145,20 @@ impl CorrelationEngine {
                 (score, "Execution".into(), format!("Run: {} (Parent: {})", filename, parent_name), entities)
             },
             ForensicEvent::FileSystemActivity(f) => {
-                let filename = f.file_name.split('\\').last().unwrap_or(&f.file_name).to_lowercase();
+                let filename = f.file_name.split('\\').next_back().unwrap_or(&f.file_name).to_lowercase();
                 if !filename.is_empty() { entities.push(filename.clone()); }
                 if f.is_timestomped { score += 80; }
                 if filename.ends_with(".ps1") || filename.ends_with(".vbs") || filename.ends_with(".bat") || filename.ends_with(".exe") || filename.ends_with(".dll") { score += 10; }
+                if f.artifact == FileSystemArtifact::AlternateDataStream && !f.stream_indicators.is_empty() { score += 60; }
                 (score, "FileSystem".into(), format!("File: {}", filename), entities)
             },
             ForensicEvent::Persistence(p) => {
                 let target_path_lower = p.target_path.to_lowercase();
                 if let Some(idx) = target_path_lower.find(".exe") {
-                    let ext = target_path_lower[..idx+4].split('\\').last().unwrap_or("").to_string();
+                    let ext = target_path_lower[..idx+4].split('\\').next_back().unwrap_or("").to_string();
                     if !ext.is_empty() { entities.push(ext); }
                 } else if let Some(idx) = target_path_lower.find(".ps1") {
-                    let ext = target_path_lower[..idx+4].split('\\').last().unwrap_or("").to_string();
+                    let ext = target_path_lower[..idx+4].split('\\').next_back().unwrap_or("").to_string();
                     if !ext.is_empty() { entities.push(ext); }
                 }
                 if p.persistence_type.contains("WMI Event") { score += 70; }
@@ -159,11 +169,36 @@ impl CorrelationEngine {
             },
             ForensicEvent::NetworkActivity(n) => {
                 entities.push(n.destination_ip.clone());
-                let proc_name = n.process_name.split('\\').last().unwrap_or(&n.process_name).to_lowercase();
+                let proc_name = n.process_name.split('\\').next_back().unwrap_or(&n.process_name).to_lowercase();
                 if !proc_name.is_empty() && proc_name != "unknown" { entities.push(proc_name); }
                 score += 20;
                 (score, "Network".into(), format!("Connect: {}:{}", n.destination_ip, n.destination_port), entities)
             },
+            ForensicEvent::Download(d) => {
+                let filename = d.file_path.split('\\').next_back().unwrap_or(&d.file_path).to_lowercase();
+                if !filename.is_empty() { entities.push(filename.clone()); }
+                // 인터넷(3)/제한(4) 영역에서 받은 실행 파일·스크립트가 초기 침투 페이로드의 전형이다.
+                if d.zone_id >= 3 { score += 20; }
+                let executable = [".exe", ".dll", ".scr", ".ps1", ".vbs", ".js", ".hta", ".bat", ".cmd", ".lnk", ".iso"];
+                if d.zone_id >= 3 && executable.iter().any(|ext| filename.ends_with(ext)) { score += 30; }
+                let origin = d.host_url.as_deref().or(d.referrer_url.as_deref()).unwrap_or("unknown origin");
+                (score, "Download".into(), format!("Download: {} (Zone {}) from {}", filename, d.zone_id, origin), entities)
+            },
+            ForensicEvent::Deletion(d) => {
+                let filename = d.original_path.split('\\').next_back().unwrap_or(&d.original_path).to_lowercase();
+                if !filename.is_empty() { entities.push(filename.clone()); }
+                // 실행 후 휴지통으로 지운 실행 파일·스크립트는 흔적 정리의 전형이다.
+                let executable = [".exe", ".dll", ".scr", ".ps1", ".vbs", ".js", ".hta", ".bat", ".cmd"];
+                score += if executable.iter().any(|ext| filename.ends_with(ext)) { 30 } else { 5 };
+                (score, "Deletion".into(), format!("Recycled: {} (User: {})", filename, d.user_sid), entities)
+            },
+            ForensicEvent::Usb(u) => {
+                // 이동식 저장 장치 연결은 그 자체로 반출 경로 후보이므로 항상 타임라인에 남긴다.
+                score += 20;
+                let user = u.user.as_deref().map(|user| format!(" by {}", user)).unwrap_or_default();
+                let letter = u.drive_letter.as_deref().map(|l| format!(" [{}]", l)).unwrap_or_default();
+                (score, "RemovableMedia".into(), format!("USB {}: {} ({}){}{}", u.action, u.device_name, u.serial_number, letter, user), entities)
+            },
             ForensicEvent::SystemActivity(s) => {
                 if s.activity_type.contains("[CRITICAL]") { score += 90; }
                 (score, "System".into(), s.activity_type.clone(), entities)
@@ -175,6 +210,8 @@ impl CorrelationEngine {
     pub fn analyze_multi_hop_causality(&mut self) {
         let mut rels = Vec::new();
         let default_window = Duration::minutes(30).num_seconds(); 
+        // 다운로드 후 실행은 사용자가 나중에 파일을 여는 경우가 많아 더 넓은 창을 쓴다.
+        let download_window = Duration::hours(24).num_seconds();
 
         let mut id_to_event = HashMap::new();
         for e in &self.events {
@@ -198,33 +235,37 @@ impl CorrelationEngine {
                         };
 
                         let delta = (tgt.timestamp - src.timestamp).num_seconds().abs();
-                        if delta > default_window { continue; }
+                        let is_download_execution = src.category == "Download" && tgt.category == "Execution" && src.timestamp <= tgt.timestamp;
+                        let window = if is_download_execution { download_window } else { default_window };
+                        if delta > window { continue; }
 
                         let mut rel_type = String::new();
                         let mut linked = false;
 
                         // 부모-자식 프로세스 엄격 매칭
-                        if src.category == "Execution" && tgt.category == "Execution" {
-                            if let (ForensicEvent::Execution(s_exec), ForensicEvent::Execution(t_exec)) = (&src.original_event, &tgt.original_event) {
-                                let s_proc = s_exec.process_name.split('\\').last().unwrap_or("").to_lowercase();
-                                let t_parent = t_exec.parent_process_name.split('\\').last().unwrap_or("").to_lowercase();
-                                let t_proc = t_exec.process_name.split('\\').last().unwrap_or("").to_lowercase();
-                                let s_parent = s_exec.parent_process_name.split('\\').last().unwrap_or("").to_lowercase();
+                        if src.category == "Execution" && tgt.category == "Execution"
+                            && let (ForensicEvent::Execution(s_exec), ForensicEvent::Execution(t_exec)) = (&src.original_event, &tgt.original_event) {
+                            let s_proc = s_exec.process_name.split('\\').next_back().unwrap_or("").to_lowercase();
+                            let t_parent = t_exec.parent_process_name.split('\\').next_back().unwrap_or("").to_lowercase();
+                            let t_proc = t_exec.process_name.split('\\').next_back().unwrap_or("").to_lowercase();
+                            let s_parent = s_exec.parent_process_name.split('\\').next_back().unwrap_or("").to_lowercase();
                                 
-                                if !s_proc.is_empty() && s_proc == t_parent && src.timestamp <= tgt.timestamp {
-                                    rel_type = "spawned_process".into(); linked = true;
-                                } else if !t_proc.is_empty() && t_proc == s_parent && tgt.timestamp <= src.timestamp {
-                                    rel_type = "spawned_process".into(); linked = true;
-                                }
+                            if (!s_proc.is_empty() && s_proc == t_parent && src.timestamp <= tgt.timestamp)
+                                || (!t_proc.is_empty() && t_proc == s_parent && tgt.timestamp <= src.timestamp) {
+                                rel_type = "spawned_process".into(); linked = true;
                             }
                         }
 
                         // 범용 fallback 없이 확정적인 시스템 킬체인 인과율만 선으로 긋는다.
                         if !linked {
-                            if src.category == "FileSystem" && tgt.category == "Execution" && src.timestamp <= tgt.timestamp {
+                            if is_download_execution {
+                                rel_type = "downloaded_and_executed".into(); linked = true;
+                            } else if src.category == "FileSystem" && tgt.category == "Execution" && src.timestamp <= tgt.timestamp {
                                 rel_type = "dropped_and_executed".into(); linked = true;
                             } else if src.category == "Execution" && tgt.category == "FileSystem" && src.timestamp <= tgt.timestamp {
                                 rel_type = "executed_and_dropped".into(); linked = true; // 새로 추가된 페이로드 드롭 인과율
+                            } else if src.category == "Execution" && tgt.category == "Deletion" && src.timestamp <= tgt.timestamp {
+                                rel_type = "executed_and_deleted".into(); linked = true;
                             } else if src.category == "Execution" && tgt.category == "Persistence" && src.timestamp <= tgt.timestamp {
                                 rel_type = "established_persistence".into(); linked = true;
                             } else if src.category == "Execution" && src.original_event.is_lnk_source() && src.timestamp <= tgt.timestamp {
@@ -254,8 +295,8 @@ impl CorrelationEngine {
     pub fn build_campaigns(&mut self) {
         let mut adj: HashMap<String, Vec<String>> = HashMap::new();
         for rel in &self.relationships {
-            adj.entry(rel.source_id.clone()).or_insert_with(Vec::new).push(rel.target_id.clone());
-            adj.entry(rel.target_id.clone()).or_insert_with(Vec::new).push(rel.source_id.clone());
+            adj.entry(rel.source_id.clone()).or_default().push(rel.target_id.clone());
+            adj.entry(rel.target_id.clone()).or_default().push(rel.source_id.clone());
         }
 
         let mut visited = HashSet::new();
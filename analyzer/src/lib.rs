@@ -2,9 +2,12 @@ pub mod prefetch;
 pub mod registry;
 pub mod evtx;
 pub mod usnjrnl;
+pub mod logfile;
 pub mod amcache;
 pub mod tasks;
 pub mod ntuser;
+pub mod mft;
+pub mod timestomp;
 pub mod preprocess; // [추가] 전처리기 모듈
 pub mod correlation;
 pub mod stix;
@@ -12,47 +15,86 @@ pub mod stix;
 use anyhow::Result;
 use models::event::ForensicEvent;
 use models::artifact::ArtifactTarget;
+use models::ReadSeek;
+use std::io::SeekFrom;
+use parser::logfile::VolumeGeometry;
 use prefetch::PrefetchAnalyzer;
 use registry::RegistryAnalyzer;
 use evtx::EvtxAnalyzer;
 use usnjrnl::UsnJrnlAnalyzer;
+use logfile::LogFileAnalyzer;
 use amcache::AmcacheAnalyzer;
 use tasks::TaskAnalyzer;
 use ntuser::NtUserAnalyzer;
+use mft::MftAnalyzer;
 
 pub use preprocess::Preprocessor; // [추가]
 pub use correlation::{CorrelationEngine, TimelineEntry};
 pub use stix::StixBuilder;
+pub use timestomp::TimestompDetector;
 
 pub trait ArtifactAnalyzer {
     fn analyze(&self, filename: &str, data: &[u8]) -> Result<Vec<ForensicEvent>>;
     fn can_handle(&self, target: &ArtifactTarget) -> bool;
+
+    /// 스트림을 직접 소비할 수 있는 분석기($MFT, $UsnJrnl, EVTX)는 재정의하여 아티팩트 전체를 메모리에 올리지 않는다.
+    fn analyze_stream(&self, filename: &str, reader: &mut dyn ReadSeek) -> Result<Vec<ForensicEvent>> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        self.analyze(filename, &data)
+    }
 }
 
 pub struct AnalysisEngine {
     analyzers: Vec<Box<dyn ArtifactAnalyzer>>,
 }
 
+impl Default for AnalysisEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AnalysisEngine {
     pub fn new() -> Self {
-        let mut analyzers: Vec<Box<dyn ArtifactAnalyzer>> = Vec::new();
-        analyzers.push(Box::new(PrefetchAnalyzer::new()));
-        analyzers.push(Box::new(RegistryAnalyzer::new()));
-        analyzers.push(Box::new(EvtxAnalyzer::new()));
-        analyzers.push(Box::new(UsnJrnlAnalyzer::new()));
-        analyzers.push(Box::new(AmcacheAnalyzer::new()));
-        analyzers.push(Box::new(TaskAnalyzer::new()));
-        analyzers.push(Box::new(NtUserAnalyzer::new()));
+        Self::with_volume_geometry(VolumeGeometry::default())
+    }
+
+    /// $LogFile처럼 볼륨의 클러스터/FILE 레코드 크기에 의존하는 분석기에 VBR 값을 전달한다.
+    pub fn with_volume_geometry(geometry: VolumeGeometry) -> Self {
+        let analyzers: Vec<Box<dyn ArtifactAnalyzer>> = vec![
+            Box::new(PrefetchAnalyzer::new()),
+            Box::new(RegistryAnalyzer::new()),
+            Box::new(EvtxAnalyzer::new()),
+            Box::new(UsnJrnlAnalyzer::new()),
+            Box::new(LogFileAnalyzer::with_geometry(geometry)),
+            Box::new(AmcacheAnalyzer::new()),
+            Box::new(TaskAnalyzer::new()),
+            Box::new(NtUserAnalyzer::new()),
+            Box::new(MftAnalyzer::new()),
+        ];
         Self { analyzers }
     }
 
     pub fn process_stream(&self, target: &ArtifactTarget, filename: &str, data: &[u8]) -> Vec<ForensicEvent> {
         let mut results = Vec::new();
         for analyzer in &self.analyzers {
-            if analyzer.can_handle(target) {
-                if let Ok(mut events) = analyzer.analyze(filename, data) {
-                    results.append(&mut events);
-                }
+            if analyzer.can_handle(target)
+                && let Ok(mut events) = analyzer.analyze(filename, data) {
+                results.append(&mut events);
+            }
+        }
+        results
+    }
+
+    /// process_stream의 스트림 버전. 분석기마다 스트림을 처음부터 다시 읽는다.
+    pub fn process_reader(&self, target: &ArtifactTarget, filename: &str, reader: &mut dyn ReadSeek) -> Vec<ForensicEvent> {
+        let mut results = Vec::new();
+        for analyzer in &self.analyzers {
+            if analyzer.can_handle(target)
+                && reader.seek(SeekFrom::Start(0)).is_ok()
+                && let Ok(mut events) = analyzer.analyze_stream(filename, reader) {
+                results.append(&mut events);
             }
         }
         results
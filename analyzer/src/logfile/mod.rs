@@ -0,0 +1,86 @@
+use crate::ArtifactAnalyzer;
+use anyhow::Result;
+use models::artifact::ArtifactTarget;
+use models::event::{ForensicEvent, FileSystemArtifact, FileSystemEvent};
+use parser::logfile::{parse_logfile_with_geometry, LogFileOperation, LogFileOperationKind, VolumeGeometry};
+
+/// $SI 시각 직접 변경 이벤트의 reason 접두어 (USN 저널의 BASIC_INFO_CHANGE와 동일한 표기)
+pub const BASIC_INFO_CHANGE_REASON: &str = "Basic Info Change";
+
+pub struct LogFileAnalyzer {
+    geometry: VolumeGeometry,
+}
+
+impl Default for LogFileAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogFileAnalyzer {
+    pub fn new() -> Self { Self { geometry: VolumeGeometry::default() } }
+
+    /// 4KB 클러스터/1KB FILE 레코드가 아닌 볼륨용
+    pub fn with_geometry(geometry: VolumeGeometry) -> Self { Self { geometry } }
+
+    fn to_event(op: &LogFileOperation, file_name: String, reason: String) -> ForensicEvent {
+        let si_mtime = match &op.kind {
+            LogFileOperationKind::TimestampChange { new_modification, .. } => *new_modification,
+            _ => None,
+        };
+        ForensicEvent::FileSystemActivity(FileSystemEvent {
+            timestamp: op.timestamp,
+            file_name,
+            reason,
+            is_dir: op.is_dir,
+            si_mtime,
+            fn_mtime: None,
+            is_timestomped: false,
+            source_artifact: "$LogFile".to_string(),
+            artifact: FileSystemArtifact::LogFile,
+            mft_reference: op.mft_reference,
+            parent_reference: op.parent_reference,
+            timestomp_rules: Vec::new(),
+            is_deleted: false,
+            stream_indicators: Vec::new(),
+        })
+    }
+
+    fn operation_events(op: &LogFileOperation) -> Vec<ForensicEvent> {
+        match &op.kind {
+            LogFileOperationKind::Create => vec![Self::to_event(op, op.file_name.clone(), "File Create".to_string())],
+            LogFileOperationKind::Delete => vec![Self::to_event(op, op.file_name.clone(), "File Delete".to_string())],
+            // USN 저널과 동일하게 이전 이름/새 이름을 별도 이벤트로 남긴다.
+            LogFileOperationKind::Rename { old_name } => vec![
+                Self::to_event(op, old_name.clone(), "Rename Old Name".to_string()),
+                Self::to_event(op, op.file_name.clone(), "Rename New Name".to_string()),
+            ],
+            LogFileOperationKind::TimestampChange { old_creation, new_creation, old_modification, new_modification } => {
+                let mut changes = Vec::new();
+                if let (Some(old), Some(new)) = (old_creation, new_creation) && old != new {
+                    changes.push(format!("$SI Creation {} -> {}", old.to_rfc3339(), new.to_rfc3339()));
+                }
+                if let (Some(old), Some(new)) = (old_modification, new_modification) && new < old {
+                    changes.push(format!("$SI Modified {} -> {}", old.to_rfc3339(), new.to_rfc3339()));
+                }
+                vec![Self::to_event(op, op.file_name.clone(), format!("{} [{}]", BASIC_INFO_CHANGE_REASON, changes.join(", ")))]
+            },
+        }
+    }
+}
+
+impl ArtifactAnalyzer for LogFileAnalyzer {
+    fn can_handle(&self, target: &ArtifactTarget) -> bool {
+        matches!(target, ArtifactTarget::LogFile)
+    }
+
+    fn analyze(&self, filename: &str, data: &[u8]) -> Result<Vec<ForensicEvent>> {
+        if !filename.eq_ignore_ascii_case("$LogFile") {
+            return Ok(Vec::new());
+        }
+
+        let operations = parse_logfile_with_geometry(data, self.geometry)?;
+        tracing::info!("  [*] $LogFile: reconstructed {} file operations", operations.len());
+        Ok(operations.iter().flat_map(Self::operation_events).collect())
+    }
+}
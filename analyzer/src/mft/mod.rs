@@ -0,0 +1,200 @@
+use crate::ArtifactAnalyzer;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use models::artifact::ArtifactTarget;
+use models::event::{DownloadEvent, ForensicEvent, FileSystemArtifact, FileSystemEvent};
+use models::mft::{AlternateDataStream, IndexSlackEntry, MftRecord, StandardInformation};
+use models::ReadSeek;
+use std::io::{Cursor, SeekFrom};
+use parser::ads::{classify_stream, parse_zone_identifier, zone_name, ZONE_IDENTIFIER_STREAM};
+use parser::mft::{MftRecordReader, MftPathResolver};
+use crate::timestomp::TimestompDetector;
+
+pub struct MftAnalyzer;
+
+impl Default for MftAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MftAnalyzer {
+    pub fn new() -> Self { Self {} }
+
+    /// M(수정), A(접근), C(MFT 변경), B(생성) 네 시각을 같은 값끼리 묶어 "M.CB" 형태의 플래그와 함께 반환한다.
+    fn group_macb(modified: u64, accessed: u64, changed: u64, born: u64) -> Vec<(u64, String)> {
+        let times = [modified, accessed, changed, born];
+        let mut groups: Vec<(u64, String)> = Vec::new();
+
+        for &time in &times {
+            if time == 0 || groups.iter().any(|(t, _)| *t == time) { continue; }
+            let flags = ['M', 'A', 'C', 'B'].iter().zip(times.iter())
+                .map(|(letter, t)| if *t == time { *letter } else { '.' })
+                .collect();
+            groups.push((time, flags));
+        }
+        groups
+    }
+
+    fn to_datetime(filetime: u64) -> DateTime<Utc> {
+        StandardInformation::to_datetime(filetime)
+    }
+
+    fn record_events(record: &MftRecord, resolver: &mut MftPathResolver) -> Vec<ForensicEvent> {
+        let mut events = Vec::new();
+        let preferred = record.preferred_file_name();
+        let fn_mtime = preferred.map(|f| Self::to_datetime(f.modification_time));
+        let si_mtime = record.standard_info.as_ref().map(|si| Self::to_datetime(si.modification_time));
+        let parent_reference = preferred.map(|f| f.parent_directory);
+        let timestomp_rules = match (&record.standard_info, preferred) {
+            (Some(si), Some(fn_attr)) => TimestompDetector::evaluate_record(si, fn_attr),
+            _ => Vec::new(),
+        };
+
+        let mut push = |timestamp: u64, file_name: String, reason: String, parent: Option<u64>| {
+            events.push(ForensicEvent::FileSystemActivity(FileSystemEvent {
+                timestamp: Self::to_datetime(timestamp),
+                file_name,
+                reason,
+                is_dir: record.is_directory,
+                si_mtime,
+                fn_mtime,
+                is_timestomped: !timestomp_rules.is_empty(),
+                source_artifact: "$MFT".to_string(),
+                artifact: FileSystemArtifact::Mft,
+                mft_reference: Some(record.reference()),
+                parent_reference: parent,
+                timestomp_rules: timestomp_rules.clone(),
+                is_deleted: !record.in_use,
+                stream_indicators: Vec::new(),
+            }));
+        };
+
+        if let Some(si) = &record.standard_info {
+            let path = resolver.resolve(record.entry_number);
+            for (time, flags) in Self::group_macb(si.modification_time, si.access_time, si.mft_modified_time, si.creation_time) {
+                push(time, path.clone(), format!("$SI [{}]", flags), parent_reference);
+            }
+        }
+
+        for fn_attr in &record.file_names {
+            // 같은 부모에 Win32 이름이 있으면 DOS(8.3) 단축 이름은 동일 시각의 중복이므로 제외한다.
+            if fn_attr.is_dos_only()
+                && record.file_names.iter().any(|o| !o.is_dos_only() && o.parent_directory == fn_attr.parent_directory) {
+                continue;
+            }
+            let path = resolver.resolve_file_name(fn_attr);
+            for (time, flags) in Self::group_macb(fn_attr.modification_time, fn_attr.access_time, fn_attr.mft_modified_time, fn_attr.creation_time) {
+                push(time, path.clone(), format!("$FN [{}]", flags), Some(fn_attr.parent_directory));
+            }
+        }
+        events
+    }
+
+    /// $I30 slack에서 카빙한 엔트리를 삭제 파일 증거로 변환한다. 파일 경로는 엔트리가 남아 있던 디렉터리 기준이다.
+    pub fn slack_events(entries: &[IndexSlackEntry]) -> Vec<ForensicEvent> {
+        let mut events = Vec::new();
+        for entry in entries {
+            let fn_attr = &entry.file_name;
+            let path = format!("{}\\{}", entry.directory_path, fn_attr.name);
+            let is_dir = fn_attr.flags & 0x1000_0000 != 0;
+            for (time, flags) in Self::group_macb(fn_attr.modification_time, fn_attr.access_time, fn_attr.mft_modified_time, fn_attr.creation_time) {
+                events.push(ForensicEvent::FileSystemActivity(FileSystemEvent {
+                    timestamp: Self::to_datetime(time),
+                    file_name: path.clone(),
+                    reason: format!("$FN [{}] (INDX VCN {} +{:#x})", flags, entry.index_vcn, entry.offset),
+                    is_dir,
+                    si_mtime: None,
+                    fn_mtime: Some(Self::to_datetime(fn_attr.modification_time)),
+                    is_timestomped: false,
+                    source_artifact: "$I30 Slack".to_string(),
+                    artifact: FileSystemArtifact::IndexSlack,
+                    mft_reference: entry.file_reference,
+                    parent_reference: Some(fn_attr.parent_directory),
+                    timestomp_rules: Vec::new(),
+                    is_deleted: true,
+                    stream_indicators: Vec::new(),
+                }));
+            }
+        }
+        events
+    }
+
+    /// 대체 데이터 스트림을 이벤트로 변환한다. Zone.Identifier는 파일 생성 시각의 다운로드 출처 이벤트가 되고,
+    /// 나머지 ADS는 실행 파일/스크립트 은닉 여부를 사유에 표시한 파일 시스템 이벤트가 된다.
+    pub fn stream_events(streams: &[AlternateDataStream]) -> Vec<ForensicEvent> {
+        let mut events = Vec::new();
+        for stream in streams {
+            if stream.stream_name.eq_ignore_ascii_case(ZONE_IDENTIFIER_STREAM)
+                && let Some(zone) = parse_zone_identifier(&stream.content) {
+                events.push(ForensicEvent::Download(DownloadEvent {
+                    timestamp: Self::to_datetime(stream.si_creation_time),
+                    file_path: stream.path.clone(),
+                    zone_id: zone.zone_id,
+                    referrer_url: zone.referrer_url,
+                    host_url: zone.host_url,
+                    mft_reference: Some(stream.file_reference),
+                    source_artifact: format!("{} ({})", ZONE_IDENTIFIER_STREAM, zone_name(zone.zone_id)),
+                }));
+                continue;
+            }
+
+            let mut reason = format!("Alternate Data Stream ({} bytes)", stream.size);
+            let suspicious = classify_stream(&stream.stream_name, &stream.content);
+            if !suspicious.is_empty() {
+                reason.push_str(&format!(" [Suspicious: {}]", suspicious.join(", ")));
+            }
+            events.push(ForensicEvent::FileSystemActivity(FileSystemEvent {
+                timestamp: Self::to_datetime(stream.si_mft_modified_time),
+                file_name: format!("{}:{}", stream.path, stream.stream_name),
+                reason,
+                is_dir: stream.is_directory,
+                si_mtime: None,
+                fn_mtime: None,
+                is_timestomped: false,
+                source_artifact: "$DATA ADS".to_string(),
+                artifact: FileSystemArtifact::AlternateDataStream,
+                mft_reference: Some(stream.file_reference),
+                parent_reference: None,
+                timestomp_rules: Vec::new(),
+                is_deleted: false,
+                stream_indicators: suspicious,
+            }));
+        }
+        events
+    }
+}
+
+impl ArtifactAnalyzer for MftAnalyzer {
+    fn can_handle(&self, target: &ArtifactTarget) -> bool {
+        matches!(target, ArtifactTarget::MFT)
+    }
+
+    fn analyze(&self, filename: &str, data: &[u8]) -> Result<Vec<ForensicEvent>> {
+        self.analyze_stream(filename, &mut Cursor::new(data))
+    }
+
+    /// $MFT를 두 번 순회한다. 첫 번째는 경로 테이블만, 두 번째는 레코드별 이벤트만 만들어 레코드 전체를 보관하지 않는다.
+    fn analyze_stream(&self, filename: &str, reader: &mut dyn ReadSeek) -> Result<Vec<ForensicEvent>> {
+        if !filename.eq_ignore_ascii_case("$MFT") {
+            return Ok(Vec::new());
+        }
+
+        let mut resolver = MftPathResolver::new();
+        let mut record_count = 0usize;
+        for record in MftRecordReader::new(&mut *reader) {
+            resolver.add_record(&record);
+            record_count += 1;
+        }
+        tracing::info!("  [*] $MFT: decoded {} FILE records", record_count);
+        reader.seek(SeekFrom::Start(0))?;
+
+        // 확장 레코드의 $FILE_NAME은 경로 테이블에 병합되었으므로, 타임라인은 베이스 레코드만 대상으로 한다.
+        // 삭제된 레코드도 마지막 시각 정보를 보존하므로 is_deleted 표시와 함께 포함한다.
+        let events = MftRecordReader::new(&mut *reader)
+            .filter(|r| r.base_reference == 0 && (r.in_use || !r.file_names.is_empty()))
+            .flat_map(|r| Self::record_events(&r, &mut resolver))
+            .collect();
+        Ok(events)
+    }
+}
@@ -2,10 +2,18 @@ use crate::ArtifactAnalyzer;
 use anyhow::Result;
 use models::artifact::ArtifactTarget;
 use models::event::ForensicEvent;
-use parser::ntuser::parse_ntuser_run_keys;
+use parser::ntuser::parse_ntuser_autostarts;
+use parser::registry::HiveParser;
+use crate::registry::RegistryAnalyzer;
 
 pub struct NtUserAnalyzer;
 
+impl Default for NtUserAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NtUserAnalyzer {
     pub fn new() -> Self { Self {} }
 }
@@ -23,10 +31,16 @@ impl ArtifactAnalyzer for NtUserAnalyzer {
             return Ok(events);
         }
 
-        if let Ok(mut parsed_events) = parse_ntuser_run_keys(data, filename) {
-            events.append(&mut parsed_events);
+        match parse_ntuser_autostarts(data, filename) {
+            Ok(mut parsed_events) => events.append(&mut parsed_events),
+            Err(e) => tracing::debug!("Skipping {} (Not a valid hive): {}", filename, e),
         }
-        
+
+        // [추가] 해제 셀에 남은 삭제 키/값 (공격 후 지운 Run 키 등)
+        if let Ok(parser) = HiveParser::new(data) {
+            events.extend(RegistryAnalyzer::deleted_key_events(&parser, filename));
+        }
+
         Ok(events)
     }
 }
\ No newline at end of file
@@ -6,6 +6,12 @@ use parser::prefetch::parse_prefetch_info;
 
 pub struct PrefetchAnalyzer;
 
+impl Default for PrefetchAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PrefetchAnalyzer {
     pub fn new() -> Self {
         Self {}
@@ -1,5 +1,7 @@
-use models::event::{ForensicEvent, ExecutionEvent};
+use crate::timestomp::TimestompDetector;
+use models::event::{ForensicEvent, ExecutionEvent, FileSystemArtifact};
 use base64::{Engine as _, engine::general_purpose::STANDARD};
+use std::collections::HashMap;
 
 pub struct Preprocessor;
 
@@ -13,6 +15,37 @@ impl Preprocessor {
         events
     }
 
+    /// 한 볼륨(또는 섀도 복사본)에서 나온 이벤트끼리만 교차해야 하는 전처리. MFT 파일 참조와 OS 설치일은 볼륨과
+    /// 스냅숏마다 다르므로 수집기가 볼륨 단위로 호출한다.
+    pub fn run_volume(events: &mut [ForensicEvent]) {
+        Self::resolve_journal_paths(events);
+        // 설치일/USN 저널 등 다른 아티팩트와 교차해야 하는 타임스톰핑 규칙은 볼륨의 이벤트가 모두 모인 뒤 평가한다.
+        TimestompDetector::apply_cross_artifact_rules(events);
+    }
+
+    /// 저널($UsnJrnl, $LogFile) 이벤트의 부모 참조를 같은 볼륨의 $MFT 디렉터리 경로로 치환한다. 시퀀스 번호까지 일치하는 디렉터리만 사용하므로,
+    /// 삭제·재사용된 디렉터리는 저널 이력으로 재구성한 경로를 그대로 유지한다.
+    fn resolve_journal_paths(events: &mut [ForensicEvent]) {
+        let mut directories: HashMap<u64, String> = HashMap::new();
+        for event in events.iter() {
+            if let ForensicEvent::FileSystemActivity(f) = event
+                && f.artifact == FileSystemArtifact::Mft && f.is_dir && !f.is_deleted
+                && let Some(reference) = f.mft_reference {
+                directories.entry(reference).or_insert_with(|| f.file_name.clone());
+            }
+        }
+        if directories.is_empty() { return; }
+
+        for event in events.iter_mut() {
+            if let ForensicEvent::FileSystemActivity(f) = event
+                && matches!(f.artifact, FileSystemArtifact::UsnJrnl | FileSystemArtifact::LogFile)
+                && let Some(directory) = f.parent_reference.and_then(|p| directories.get(&p)) {
+                let name = f.file_name.rsplit('\\').next().unwrap_or(&f.file_name);
+                f.file_name = format!("{}\\{}", directory, name);
+            }
+        }
+    }
+
     // PowerShell Base64(UTF-16LE) 인코딩 명령어 복호화 로직
     fn decode_powershell_enc(e: &mut ExecutionEvent) {
         let cmd = &e.command_line;
@@ -20,23 +53,97 @@ impl Preprocessor {
         
         if lower_cmd.contains("-enc") || lower_cmd.contains("-encodedcommand") {
             let parts: Vec<&str> = cmd.split_whitespace().collect();
-            if let Some(pos) = parts.iter().position(|&p| p.to_lowercase().starts_with("-enc")) {
-                if pos + 1 < parts.len() {
-                    let b64_str = parts[pos + 1];
-                    if let Ok(decoded_bytes) = STANDARD.decode(b64_str) {
-                        // PowerShell은 기본적으로 UTF-16LE 규격을 사용함
-                        let u16_data: Vec<u16> = decoded_bytes
-                            .chunks_exact(2)
-                            .map(|c| u16::from_le_bytes([c[0], c[1]]))
-                            .collect();
+            if let Some(pos) = parts.iter().position(|&p| p.to_lowercase().starts_with("-enc"))
+                && pos + 1 < parts.len() {
+                let b64_str = parts[pos + 1];
+                if let Ok(decoded_bytes) = STANDARD.decode(b64_str) {
+                    // PowerShell은 기본적으로 UTF-16LE 규격을 사용함
+                    let u16_data: Vec<u16> = decoded_bytes
+                        .chunks_exact(2)
+                        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                        .collect();
                         
-                        if let Ok(decoded_str) = String::from_utf16(&u16_data) {
-                            // 원본 로그 뒤에 복호화된 평문을 덧붙여 상관분석기로 넘김
-                            e.command_line = format!("{} [DECODED: {}]", cmd, decoded_str);
-                        }
+                    if let Ok(decoded_str) = String::from_utf16(&u16_data) {
+                        // 원본 로그 뒤에 복호화된 평문을 덧붙여 상관분석기로 넘김
+                        e.command_line = format!("{} [DECODED: {}]", cmd, decoded_str);
                     }
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use models::event::FileSystemEvent;
+    use models::vss::ShadowCopyInfo;
+
+    fn fs_event(artifact: FileSystemArtifact, path: &str, is_dir: bool, reference: Option<u64>, parent: Option<u64>) -> ForensicEvent {
+        ForensicEvent::FileSystemActivity(FileSystemEvent {
+            timestamp: Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap(),
+            file_name: path.to_string(),
+            reason: "FILE_CREATE".to_string(),
+            is_dir,
+            si_mtime: None,
+            fn_mtime: None,
+            is_timestomped: false,
+            source_artifact: "$Extend\\$UsnJrnl".to_string(),
+            artifact,
+            mft_reference: reference,
+            parent_reference: parent,
+            timestomp_rules: Vec::new(),
+            is_deleted: false,
+            stream_indicators: Vec::new(),
+        })
+    }
+
+    fn file_name(event: &ForensicEvent) -> &str {
+        match event {
+            ForensicEvent::FileSystemActivity(f) => &f.file_name,
+            _ => unreachable!(),
+        }
+    }
+
+    const TOOLS_REF: u64 = (3 << 48) | 0x40;
+
+    #[test]
+    fn journal_paths_use_the_same_volume_directory() {
+        // 두 볼륨이 같은 파일 참조를 가진 서로 다른 디렉터리를 가진다.
+        let mut volume_c = vec![
+            fs_event(FileSystemArtifact::Mft, "\\Tools", true, Some(TOOLS_REF), Some(5)),
+            fs_event(FileSystemArtifact::UsnJrnl, "\\$Unresolved\\Entry64-3\\a.exe", false, None, Some(TOOLS_REF)),
+        ];
+        let mut volume_d = vec![
+            fs_event(FileSystemArtifact::Mft, "\\Backup", true, Some(TOOLS_REF), Some(5)),
+            fs_event(FileSystemArtifact::LogFile, "b.dll", false, None, Some(TOOLS_REF)),
+        ];
+        Preprocessor::run_volume(&mut volume_c);
+        Preprocessor::run_volume(&mut volume_d);
+
+        assert_eq!(file_name(&volume_c[1]), "\\Tools\\a.exe");
+        assert_eq!(file_name(&volume_d[1]), "\\Backup\\b.dll");
+    }
+
+    #[test]
+    fn snapshot_journal_paths_are_resolved_after_tagging() {
+        let snapshot = ShadowCopyInfo {
+            index: 1, store_id: String::new(), shadow_copy_id: String::new(), shadow_copy_set_id: String::new(),
+            creation_time: Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap(), volume_size: 0,
+        };
+        let mut events = vec![
+            fs_event(FileSystemArtifact::Mft, "\\Tools", true, Some(TOOLS_REF), Some(5)),
+            fs_event(FileSystemArtifact::UsnJrnl, "a.exe", false, None, Some(TOOLS_REF)),
+            // 시퀀스가 다른(재사용된) 디렉터리 참조는 저널 경로를 유지한다.
+            fs_event(FileSystemArtifact::UsnJrnl, "\\$Unresolved\\Entry64-2\\old.exe", false, None, Some((2 << 48) | 0x40)),
+        ];
+        for event in events.iter_mut() {
+            event.tag_snapshot(&snapshot);
+        }
+        Preprocessor::run_volume(&mut events);
+
+        assert_eq!(file_name(&events[1]), "\\Tools\\a.exe");
+        assert_eq!(file_name(&events[2]), "\\$Unresolved\\Entry64-2\\old.exe");
+    }
+}
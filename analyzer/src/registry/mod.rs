@@ -3,12 +3,140 @@ use anyhow::Result;
 use models::artifact::ArtifactTarget;
 use models::event::{ForensicEvent, PersistenceEvent, SystemEvent};
 use parser::registry::HiveParser;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use crate::timestomp::OS_INSTALL_DATE_ACTIVITY;
 
 pub struct RegistryAnalyzer;
 
+impl Default for RegistryAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RegistryAnalyzer {
     pub fn new() -> Self { Self {} }
+
+    /// Windows NT\CurrentVersion의 InstallTime(FILETIME, Win10+) 또는 InstallDate(Unix 초)를 읽는다.
+    fn os_install_date(parser: &HiveParser) -> Option<ForensicEvent> {
+        const CURRENT_VERSION: &str = "Microsoft\\Windows NT\\CurrentVersion";
+        let key_off = parser.find_key(CURRENT_VERSION)?;
+        let values = parser.get_values(key_off);
+
+        let value = |name: &str| values.iter().find(|v| v.name.eq_ignore_ascii_case(name));
+        let install_time = value("InstallTime").and_then(|v| v.data.as_filetime());
+        let install_date = value("InstallDate")
+            .and_then(|v| v.data.as_u32())
+            .and_then(|secs| DateTime::from_timestamp(secs as i64, 0));
+        let timestamp = install_time.or(install_date).filter(|t| t.timestamp() > 0)?;
+
+        let product = value("ProductName").map(|v| v.data_string.clone()).unwrap_or_else(|| "Windows".to_string());
+        Some(ForensicEvent::SystemActivity(SystemEvent {
+            timestamp,
+            activity_type: OS_INSTALL_DATE_ACTIVITY.to_string(),
+            description: format!("{} installed", product),
+            source_artifact: format!("SOFTWARE\\{}", CURRENT_VERSION),
+        }))
+    }
+
+    /// [추가] 키 마지막 수정 시각. nk 셀에서 읽을 수 없으면 하이브 헤더의 마지막 기록 시각을 쓰고, 둘 다 없으면 None이다.
+    fn key_time(parser: &HiveParser, key_off: u32) -> Option<DateTime<Utc>> {
+        parser.get_key_last_write(key_off).or_else(|| parser.last_written())
+    }
+
+    /// [추가] 하이브 해제 셀에서 복원한 삭제 키/값을 이벤트로 변환한다. 삭제 키는 마지막 수정 시각(삭제 직전 상태)을 쓰고,
+    /// 삭제된 Run/RunOnce 키의 값과 ImagePath가 남은 삭제 서비스 키는 자동 실행 항목으로도 보고한다.
+    /// 원래 키를 알 수 없는 값은 시각이 없으므로 하이브 마지막 기록 시각으로 보고한다.
+    pub fn deleted_key_events(parser: &HiveParser, hive_name: &str) -> Vec<ForensicEvent> {
+        let deleted = parser.deleted_cells();
+        let mut events = Vec::new();
+
+        for deleted_key in &deleted.keys {
+            let Some(timestamp) = deleted_key.key.last_write else { continue };
+            let source_artifact = format!("{}\\{} (deleted key)", hive_name, deleted_key.path);
+            let name = &deleted_key.key.name;
+
+            if name.eq_ignore_ascii_case("Run") || name.eq_ignore_ascii_case("RunOnce") {
+                for val in &deleted_key.values {
+                    events.push(ForensicEvent::Persistence(PersistenceEvent {
+                        timestamp,
+                        persistence_type: format!("{} Key (deleted)", name),
+                        target_name: val.name.clone(),
+                        target_path: val.data_string.clone(),
+                        source_artifact: source_artifact.clone(),
+                        user: None,
+                    }));
+                }
+            }
+
+            let parent_is_services = deleted_key.path.rsplit('\\').nth(1).is_some_and(|p| p.eq_ignore_ascii_case("Services"));
+            if parent_is_services
+                && let Some(image_path) = deleted_key.values.iter().find(|v| v.name.eq_ignore_ascii_case("ImagePath")) {
+                events.push(ForensicEvent::Persistence(PersistenceEvent {
+                    timestamp,
+                    persistence_type: "System Service (deleted)".to_string(),
+                    target_name: name.clone(),
+                    target_path: image_path.data_string.clone(),
+                    source_artifact: source_artifact.clone(),
+                    user: None,
+                }));
+            }
+
+            let values: Vec<String> = deleted_key.values.iter().map(|v| format!("{}={}", v.name, v.data_string)).collect();
+            events.push(ForensicEvent::SystemActivity(SystemEvent {
+                timestamp,
+                activity_type: "Deleted Registry Key".to_string(),
+                description: if values.is_empty() { deleted_key.path.clone() } else { format!("{} [{}]", deleted_key.path, values.join(", ")) },
+                source_artifact,
+            }));
+        }
+
+        if let Some(timestamp) = parser.last_written() {
+            for val in &deleted.values {
+                events.push(ForensicEvent::SystemActivity(SystemEvent {
+                    timestamp,
+                    activity_type: "Deleted Registry Value".to_string(),
+                    description: format!("{}={}", val.name, val.data_string),
+                    source_artifact: format!("{} (deleted value, hive last written)", hive_name),
+                }));
+            }
+        }
+        events
+    }
+
+    /// [추가] 미할당 영역에서 카빙한 hbin의 키를 이벤트로 변환한다. 전체 경로는 알 수 없으므로 키 이름만 남기고,
+    /// Run/RunOnce 키의 값은 키 수정 시각의 자동 실행 항목으로 함께 보고한다.
+    pub fn hbin_events(data: &[u8]) -> Vec<ForensicEvent> {
+        let Ok(parser) = HiveParser::from_hbin(data) else { return Vec::new() };
+        let mut events = Vec::new();
+
+        for key in parser.carved_keys() {
+            let Some(timestamp) = key.last_write else { continue };
+            let state = if key.is_deleted { " (deleted cell)" } else { "" };
+
+            if key.name.eq_ignore_ascii_case("Run") || key.name.eq_ignore_ascii_case("RunOnce") {
+                for val in &key.values {
+                    events.push(ForensicEvent::Persistence(PersistenceEvent {
+                        timestamp,
+                        persistence_type: format!("{} Key", key.name),
+                        target_name: val.name.clone(),
+                        target_path: val.data_string.clone(),
+                        source_artifact: format!("Registry hbin{}", state),
+                        user: None,
+                    }));
+                }
+            }
+
+            let values: Vec<String> = key.values.iter().map(|v| format!("{}={}", v.name, v.data_string)).collect();
+            events.push(ForensicEvent::SystemActivity(SystemEvent {
+                timestamp,
+                activity_type: "Registry Key Last Write".to_string(),
+                description: if values.is_empty() { key.name.clone() } else { format!("{} [{}]", key.name, values.join(", ")) },
+                source_artifact: format!("Registry hbin{}", state),
+            }));
+        }
+        events
+    }
 }
 
 impl ArtifactAnalyzer for RegistryAnalyzer {
@@ -41,76 +169,84 @@ impl ArtifactAnalyzer for RegistryAnalyzer {
 
             for (path, desc) in targets {
                 if let Some(key_off) = parser.find_key(path) {
+                    // 값별 기록 시각은 없으므로 Run 키의 마지막 수정 시각(마지막 항목 추가/변경 시점)을 쓴다.
+                    let Some(timestamp) = Self::key_time(&parser, key_off) else { continue };
                     let values = parser.get_values(key_off);
                     for val in values {
                         events.push(ForensicEvent::Persistence(PersistenceEvent {
-                            timestamp: Utc::now(),
+                            timestamp,
                             persistence_type: desc.to_string(),
                             target_name: val.name,
                             target_path: val.data_string,
                             source_artifact: format!("SOFTWARE\\{}", path),
+                            user: None,
                         }));
                     }
                 } else {
                     tracing::debug!("    [-] Target path not found in Base Hive: {}", path);
                 }
             }
+
+            // OS 설치일: 타임스톰핑 교차 규칙(설치일 이전 $SI 생성 시각)의 기준점
+            if let Some(event) = Self::os_install_date(&parser) {
+                events.push(event);
+            }
         }
 
         // 2. SYSTEM 하이브 분석: 백그라운드 자동 실행 서비스 (Start=2)
-        if filename.eq_ignore_ascii_case("SYSTEM") {
-            if let Some(services_off) = parser.find_key("ControlSet001\\Services") {
-                let subkeys = parser.get_subkeys(services_off);
-                for sk in subkeys {
-                    let service_name = parser.get_key_name(sk);
-                    let values = parser.get_values(sk);
+        if filename.eq_ignore_ascii_case("SYSTEM")
+            && let Some(services_off) = parser.find_key("ControlSet001\\Services") {
+            let subkeys = parser.get_subkeys(services_off);
+            for sk in subkeys {
+                let service_name = parser.get_key_name(sk);
+                let values = parser.get_values(sk);
                     
-                    let mut is_auto_start = false;
-                    let mut image_path = String::new();
+                let mut is_auto_start = false;
+                let mut image_path = String::new();
 
-                    for val in values {
-                        if val.name.eq_ignore_ascii_case("Start") {
-                            if val.data_raw.len() >= 4 {
-                                let start_type = u32::from_le_bytes(val.data_raw[0..4].try_into().unwrap());
-                                if start_type == 2 { // Auto Start
-                                    is_auto_start = true;
-                                }
-                            }
-                        }
-                        if val.name.eq_ignore_ascii_case("ImagePath") {
-                            image_path = val.data_string.clone();
-                        }
+                for val in values {
+                    if val.name.eq_ignore_ascii_case("Start")
+                        && val.data.as_u32() == Some(2) { // Auto Start
+                        is_auto_start = true;
                     }
-
-                    if is_auto_start && !image_path.is_empty() {
-                        events.push(ForensicEvent::Persistence(PersistenceEvent {
-                            timestamp: Utc::now(),
-                            persistence_type: "System Service (Auto-Start)".to_string(),
-                            target_name: service_name,
-                            target_path: image_path,
-                            source_artifact: "SYSTEM\\ControlSet001\\Services".to_string(),
-                        }));
+                    if val.name.eq_ignore_ascii_case("ImagePath") {
+                        image_path = val.data_string.clone();
                     }
                 }
+
+                if is_auto_start && !image_path.is_empty()
+                    && let Some(timestamp) = Self::key_time(&parser, sk) {
+                    events.push(ForensicEvent::Persistence(PersistenceEvent {
+                        timestamp,
+                        persistence_type: "System Service (Auto-Start)".to_string(),
+                        target_name: service_name,
+                        target_path: image_path,
+                        source_artifact: "SYSTEM\\ControlSet001\\Services".to_string(),
+                        user: None,
+                    }));
+                }
             }
         }
 
         // 3. SAM 하이브 분석: 로컬 사용자 계정
-        if filename.eq_ignore_ascii_case("SAM") {
-            if let Some(names_off) = parser.find_key("SAM\\Domains\\Account\\Users\\Names") {
-                let subkeys = parser.get_subkeys(names_off);
-                for sk in subkeys {
-                    let user_name = parser.get_key_name(sk);
-                    events.push(ForensicEvent::SystemActivity(SystemEvent {
-                        timestamp: Utc::now(),
-                        activity_type: "Local User Account".to_string(),
-                        description: format!("Found user account: {}", user_name),
-                        source_artifact: "SAM\\...\\Users\\Names".to_string(),
-                    }));
-                }
+        if filename.eq_ignore_ascii_case("SAM")
+            && let Some(names_off) = parser.find_key("SAM\\Domains\\Account\\Users\\Names") {
+            let subkeys = parser.get_subkeys(names_off);
+            for sk in subkeys {
+                let Some(timestamp) = Self::key_time(&parser, sk) else { continue };
+                let user_name = parser.get_key_name(sk);
+                events.push(ForensicEvent::SystemActivity(SystemEvent {
+                    timestamp,
+                    activity_type: "Local User Account".to_string(),
+                    description: format!("Found user account: {}", user_name),
+                    source_artifact: "SAM\\...\\Users\\Names".to_string(),
+                }));
             }
         }
 
+        // 4. 모든 하이브: 해제 셀에 남은 삭제 키/값
+        events.extend(Self::deleted_key_events(&parser, filename));
+
         Ok(events)
     }
 }
\ No newline at end of file
@@ -6,6 +6,12 @@ use parser::tasks::parse_task_xml;
 
 pub struct TaskAnalyzer;
 
+impl Default for TaskAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TaskAnalyzer {
     pub fn new() -> Self { Self {} }
 }
@@ -0,0 +1,283 @@
+use chrono::{DateTime, Duration, Utc};
+use models::event::{ForensicEvent, FileSystemArtifact, FileSystemEvent};
+use models::mft::{FileNameAttribute, StandardInformation};
+use std::collections::{HashMap, HashSet};
+use crate::logfile::BASIC_INFO_CHANGE_REASON;
+
+/// $SI 생성 시각이 $FN 생성 시각보다 이르다 (SetFileTime 계열 API는 $FN을 갱신하지 못함)
+pub const RULE_SI_BEFORE_FN_CREATION: &str = "si_creation_before_fn_creation";
+/// $SI 생성/수정 시각의 100ns 하위 자릿수가 0이다 (초 단위 입력 도구의 흔적)
+pub const RULE_SI_ZERO_SUBSECOND: &str = "si_zero_subsecond";
+/// $SI 생성 시각이 OS 설치일보다 이르다
+pub const RULE_SI_BEFORE_INSTALL_DATE: &str = "si_creation_before_install_date";
+/// USN 저널(또는 $LogFile)의 File Create 기록이 $SI 생성 시각보다 늦다
+pub const RULE_USN_CREATE_AFTER_SI: &str = "usn_create_after_si_creation";
+/// $LogFile에 $SI 생성 시각 변경 또는 수정 시각 역행이 기록되어 있다
+pub const RULE_LOGFILE_SI_REWRITE: &str = "logfile_si_time_rewritten";
+
+/// SystemEvent로 전달되는 OS 설치일 활동 유형 (SOFTWARE 하이브 분석기가 생성)
+pub const OS_INSTALL_DATE_ACTIVITY: &str = "OS Install Date";
+
+const FILETIME_TICKS_PER_SECOND: u64 = 10_000_000;
+
+/// OS 설치 미디어에서 복사되어 설치일 이전 시각을 정상적으로 가지는 경로
+const INSTALL_MEDIA_PREFIXES: [&str; 4] = ["\\windows\\", "\\program files\\", "\\program files (x86)\\", "\\programdata\\"];
+
+pub struct TimestompDetector;
+
+impl TimestompDetector {
+    /// 단일 MFT 레코드의 $SI와 대표 $FN만으로 판단 가능한 규칙을 평가한다.
+    pub fn evaluate_record(si: &StandardInformation, fn_attr: &FileNameAttribute) -> Vec<String> {
+        let mut rules = Vec::new();
+
+        if si.creation_time != 0 && fn_attr.creation_time != 0
+            && si.creation_time + FILETIME_TICKS_PER_SECOND < fn_attr.creation_time {
+            rules.push(RULE_SI_BEFORE_FN_CREATION.to_string());
+        }
+
+        // 커널이 기록하는 $FN은 100ns 정밀도를 가지므로, $FN은 정밀하고 $SI만 초 단위인 경우에 한정한다.
+        let zero_subsecond = |t: u64| t != 0 && t.is_multiple_of(FILETIME_TICKS_PER_SECOND);
+        if (zero_subsecond(si.creation_time) || zero_subsecond(si.modification_time))
+            && !fn_attr.creation_time.is_multiple_of(FILETIME_TICKS_PER_SECOND) {
+            rules.push(RULE_SI_ZERO_SUBSECOND.to_string());
+        }
+        rules
+    }
+
+    /// 여러 아티팩트를 교차하는 규칙(설치일, USN 저널, $LogFile)을 평가하여 같은 파일의 모든 MFT 이벤트에 기록한다.
+    /// 파일 참조와 설치일은 볼륨마다 다르므로 한 볼륨(또는 섀도 복사본)에서 나온 이벤트만 넘겨야 한다.
+    pub fn apply_cross_artifact_rules(events: &mut [ForensicEvent]) {
+        let install_date = events.iter().find_map(|e| match e {
+            ForensicEvent::SystemActivity(s) if s.activity_type == OS_INSTALL_DATE_ACTIVITY => Some(s.timestamp),
+            _ => None,
+        });
+
+        // 파일 참조별 $SI 생성 시각("$SI [...B]" 이벤트의 시각)과 경로
+        let mut si_creation: HashMap<u64, (DateTime<Utc>, String)> = HashMap::new();
+        for e in events.iter() {
+            if let ForensicEvent::FileSystemActivity(f) = e
+                && let Some(reference) = f.mft_reference
+                && Self::is_si_birth_event(f) {
+                si_creation.insert(reference, (f.timestamp, f.file_name.to_lowercase()));
+            }
+        }
+        if si_creation.is_empty() { return; }
+
+        let mut hits: HashMap<u64, HashSet<&'static str>> = HashMap::new();
+
+        if let Some(install_date) = install_date {
+            for (reference, (created, path)) in &si_creation {
+                let from_install_media = INSTALL_MEDIA_PREFIXES.iter().any(|p| path.starts_with(p));
+                if *created < install_date && !from_install_media {
+                    hits.entry(*reference).or_default().insert(RULE_SI_BEFORE_INSTALL_DATE);
+                }
+            }
+        }
+
+        // $LogFile이 직접 기록한 $SI 덮어쓰기: 시각 값 자체와 무관하게 조작 행위의 증거다.
+        for e in events.iter() {
+            if let ForensicEvent::FileSystemActivity(f) = e
+                && f.artifact == FileSystemArtifact::LogFile
+                && f.reason.starts_with(BASIC_INFO_CHANGE_REASON)
+                && let Some(reference) = f.mft_reference
+                && si_creation.contains_key(&reference) {
+                hits.entry(reference).or_default().insert(RULE_LOGFILE_SI_REWRITE);
+            }
+        }
+
+        // USN/$LogFile 레코드에 파일 참조가 없으면 파일명으로 매칭하되, 이름이 유일한 MFT 레코드에만 적용한다.
+        let mut name_to_reference: HashMap<String, Option<u64>> = HashMap::new();
+        for (reference, (_, path)) in &si_creation {
+            let name = path.rsplit('\\').next().unwrap_or(path).to_string();
+            name_to_reference.entry(name)
+                .and_modify(|r| if *r != Some(*reference) { *r = None })
+                .or_insert(Some(*reference));
+        }
+
+        for e in events.iter() {
+            let ForensicEvent::FileSystemActivity(f) = e else { continue };
+            if f.artifact == FileSystemArtifact::Mft { continue; }
+            if !f.reason.contains("File Create") { continue; }
+
+            let reference = f.mft_reference.or_else(|| {
+                let name = f.file_name.rsplit('\\').next().unwrap_or(&f.file_name).to_lowercase();
+                name_to_reference.get(&name).copied().flatten()
+            });
+            if let Some(reference) = reference
+                && let Some((created, _)) = si_creation.get(&reference)
+                && f.timestamp > *created + Duration::seconds(2) {
+                hits.entry(reference).or_default().insert(RULE_USN_CREATE_AFTER_SI);
+            }
+        }
+
+        for e in events.iter_mut() {
+            if let ForensicEvent::FileSystemActivity(f) = e
+                && f.artifact == FileSystemArtifact::Mft
+                && let Some(rules) = f.mft_reference.and_then(|r| hits.get(&r)) {
+                for rule in rules {
+                    if !f.timestomp_rules.iter().any(|r| r == rule) { f.timestomp_rules.push(rule.to_string()); }
+                }
+                f.is_timestomped = true;
+            }
+        }
+    }
+
+    fn is_si_birth_event(f: &FileSystemEvent) -> bool {
+        f.artifact == FileSystemArtifact::Mft
+            && f.reason.strip_prefix("$SI [").is_some_and(|flags| flags.chars().nth(3) == Some('B'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use models::event::SystemEvent;
+    use models::vss::ShadowCopyInfo;
+
+    /// 2021-01-01T00:00:00Z 기준 FILETIME
+    const BASE_FILETIME: u64 = (1_609_459_200 + 11_644_473_600) * FILETIME_TICKS_PER_SECOND;
+
+    fn si(creation: u64, modification: u64) -> StandardInformation {
+        StandardInformation {
+            creation_time: creation, modification_time: modification, mft_modified_time: modification, access_time: modification,
+            file_flags: 0, max_versions: 0, version_number: 0, class_id: 0,
+        }
+    }
+
+    fn file_name(creation: u64) -> FileNameAttribute {
+        FileNameAttribute {
+            parent_directory: 5, creation_time: creation, modification_time: creation, mft_modified_time: creation, access_time: creation,
+            allocated_size: 0, real_size: 0, flags: 0, name_length: 8, namespace: 1, name: "evil.exe".to_string(),
+        }
+    }
+
+    fn at(year: i32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, 6, 1, 12, 0, 0).unwrap()
+    }
+
+    fn fs_event(artifact: FileSystemArtifact, source: &str, path: &str, reason: &str, timestamp: DateTime<Utc>, reference: Option<u64>) -> ForensicEvent {
+        ForensicEvent::FileSystemActivity(FileSystemEvent {
+            timestamp,
+            file_name: path.to_string(),
+            reason: reason.to_string(),
+            is_dir: false,
+            si_mtime: None,
+            fn_mtime: None,
+            is_timestomped: false,
+            source_artifact: source.to_string(),
+            artifact,
+            mft_reference: reference,
+            parent_reference: Some(5),
+            timestomp_rules: Vec::new(),
+            is_deleted: false,
+            stream_indicators: Vec::new(),
+        })
+    }
+
+    fn mft_birth(path: &str, created: DateTime<Utc>, reference: u64) -> ForensicEvent {
+        fs_event(FileSystemArtifact::Mft, "$MFT", path, "$SI [...B]", created, Some(reference))
+    }
+
+    fn install_date(timestamp: DateTime<Utc>) -> ForensicEvent {
+        ForensicEvent::SystemActivity(SystemEvent {
+            timestamp,
+            activity_type: OS_INSTALL_DATE_ACTIVITY.to_string(),
+            description: String::new(),
+            source_artifact: "SOFTWARE".to_string(),
+        })
+    }
+
+    fn rules_of(event: &ForensicEvent) -> &[String] {
+        match event {
+            ForensicEvent::FileSystemActivity(f) => &f.timestomp_rules,
+            _ => panic!("not a file system event"),
+        }
+    }
+
+    #[test]
+    fn si_creation_before_fn_creation_is_flagged() {
+        let precise = BASE_FILETIME + 1_234_567;
+        let rules = TimestompDetector::evaluate_record(&si(precise - 365 * 86_400 * FILETIME_TICKS_PER_SECOND, precise), &file_name(precise));
+        assert_eq!(rules, vec![RULE_SI_BEFORE_FN_CREATION]);
+
+        assert!(TimestompDetector::evaluate_record(&si(precise, precise), &file_name(precise)).is_empty());
+        // 1초 이내의 차이는 정상적인 기록 지연으로 본다.
+        assert!(TimestompDetector::evaluate_record(&si(precise - 5_000_000, precise), &file_name(precise)).is_empty());
+    }
+
+    #[test]
+    fn zeroed_subsecond_is_flagged_only_against_precise_fn() {
+        let precise = BASE_FILETIME + 1_234_567;
+        let rules = TimestompDetector::evaluate_record(&si(BASE_FILETIME + 10 * FILETIME_TICKS_PER_SECOND, precise), &file_name(precise));
+        assert_eq!(rules, vec![RULE_SI_ZERO_SUBSECOND]);
+
+        // $FN도 초 단위이면 파일 시스템/복사 도구 특성으로 보고 판정하지 않는다.
+        assert!(TimestompDetector::evaluate_record(&si(BASE_FILETIME, BASE_FILETIME), &file_name(BASE_FILETIME)).is_empty());
+    }
+
+    #[test]
+    fn creation_before_install_date_skips_install_media_paths() {
+        let mut events = vec![
+            install_date(at(2022)),
+            mft_birth("\\Users\\bob\\evil.exe", at(2021), 100),
+            mft_birth("\\Windows\\System32\\kernel32.dll", at(2019), 200),
+            mft_birth("\\Users\\bob\\report.docx", at(2023), 300),
+        ];
+        TimestompDetector::apply_cross_artifact_rules(&mut events);
+
+        assert_eq!(rules_of(&events[1]), [RULE_SI_BEFORE_INSTALL_DATE]);
+        assert!(rules_of(&events[2]).is_empty());
+        assert!(rules_of(&events[3]).is_empty());
+    }
+
+    #[test]
+    fn usn_create_after_si_creation_matches_by_reference_or_unique_name() {
+        let mut events = vec![
+            mft_birth("\\Users\\bob\\evil.exe", at(2021), 100),
+            mft_birth("\\Users\\bob\\tool.dll", at(2021), 200),
+            mft_birth("\\Users\\bob\\fine.txt", at(2021), 300),
+            fs_event(FileSystemArtifact::UsnJrnl, "$Extend\\$UsnJrnl", "\\Users\\bob\\evil.exe", "File Create | Close", at(2023), Some(100)),
+            fs_event(FileSystemArtifact::LogFile, "$LogFile", "tool.dll", "File Create", at(2023), None),
+            fs_event(FileSystemArtifact::UsnJrnl, "$Extend\\$UsnJrnl", "\\Users\\bob\\fine.txt", "File Create", at(2021), Some(300)),
+        ];
+        TimestompDetector::apply_cross_artifact_rules(&mut events);
+
+        assert_eq!(rules_of(&events[0]), [RULE_USN_CREATE_AFTER_SI]);
+        assert_eq!(rules_of(&events[1]), [RULE_USN_CREATE_AFTER_SI]);
+        assert!(rules_of(&events[2]).is_empty());
+        // 저널 이벤트 자체에는 규칙을 기록하지 않는다.
+        assert!(rules_of(&events[3]).is_empty());
+    }
+
+    #[test]
+    fn logfile_si_rewrite_is_flagged() {
+        let mut events = vec![
+            mft_birth("\\Users\\bob\\evil.exe", at(2021), 100),
+            fs_event(FileSystemArtifact::LogFile, "$LogFile", "evil.exe",
+                &format!("{} ($SI Creation 2023-01-01 -> 2021-06-01)", BASIC_INFO_CHANGE_REASON), at(2023), Some(100)),
+        ];
+        TimestompDetector::apply_cross_artifact_rules(&mut events);
+
+        assert_eq!(rules_of(&events[0]), [RULE_LOGFILE_SI_REWRITE]);
+        assert!(matches!(&events[0], ForensicEvent::FileSystemActivity(f) if f.is_timestomped));
+    }
+
+    #[test]
+    fn snapshot_tagged_events_are_still_matched() {
+        let snapshot = ShadowCopyInfo {
+            index: 1, store_id: String::new(), shadow_copy_id: String::new(), shadow_copy_set_id: String::new(),
+            creation_time: at(2024), volume_size: 0,
+        };
+        let mut events = vec![
+            mft_birth("\\Users\\bob\\evil.exe", at(2021), 100),
+            fs_event(FileSystemArtifact::UsnJrnl, "$Extend\\$UsnJrnl", "evil.exe", "File Create", at(2023), Some(100)),
+        ];
+        for event in &mut events {
+            event.tag_snapshot(&snapshot);
+        }
+        TimestompDetector::apply_cross_artifact_rules(&mut events);
+
+        assert_eq!(rules_of(&events[0]), [RULE_USN_CREATE_AFTER_SI]);
+    }
+}
@@ -1,11 +1,19 @@
 use crate::ArtifactAnalyzer;
 use anyhow::Result;
 use models::artifact::ArtifactTarget;
-use models::event::{ForensicEvent, FileSystemEvent};
-use parser::usnjrnl::parse_usnjrnl_stream;
+use models::event::{ForensicEvent, FileSystemArtifact, FileSystemEvent};
+use models::ReadSeek;
+use parser::usnjrnl::{UsnPathResolver, UsnRecordReader};
+use std::io::{Cursor, SeekFrom};
 
 pub struct UsnJrnlAnalyzer;
 
+impl Default for UsnJrnlAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UsnJrnlAnalyzer {
     pub fn new() -> Self { Self {} }
     
@@ -31,6 +39,11 @@ impl ArtifactAnalyzer for UsnJrnlAnalyzer {
     }
 
     fn analyze(&self, filename: &str, data: &[u8]) -> Result<Vec<ForensicEvent>> {
+        self.analyze_stream(filename, &mut Cursor::new(data))
+    }
+
+    /// 저널을 두 번 순회한다. 첫 번째는 경로 재구성용 이름 이력만, 두 번째는 이벤트만 만들어 레코드 전체를 보관하지 않는다.
+    fn analyze_stream(&self, filename: &str, reader: &mut dyn ReadSeek) -> Result<Vec<ForensicEvent>> {
         let mut events = Vec::new();
         
         // $UsnJrnl 필터 (파일 이름이 매칭되거나 $J 스트림일 경우)
@@ -38,27 +51,38 @@ impl ArtifactAnalyzer for UsnJrnlAnalyzer {
             return Ok(events);
         }
 
-        if let Ok(records) = parse_usnjrnl_stream(data) {
-            for rec in records {
-                let reasons = Self::translate_reason(rec.reason_flags).join(" | ");
-                let is_dir = (rec.file_attributes & 0x00000010) != 0;
+        // 저널 이력 기반 경로 (MFT 경로가 있으면 전처리기에서 교체된다)
+        let mut resolver = UsnPathResolver::new();
+        for rec in UsnRecordReader::new(&mut *reader) {
+            resolver.add(&rec);
+        }
+        reader.seek(SeekFrom::Start(0))?;
+
+        for rec in UsnRecordReader::new(&mut *reader) {
+            let reasons = Self::translate_reason(rec.reason_flags).join(" | ");
+            let is_dir = (rec.file_attributes & 0x00000010) != 0;
 
-                // 의미 있는 조작(생성, 삭제, 이름변경)만 필터링하여 노이즈 감소
-                if rec.reason_flags & 0x00003300 != 0 { 
-                    events.push(ForensicEvent::FileSystemActivity(FileSystemEvent {
-                        timestamp: rec.timestamp,
-                        file_name: rec.file_name,
-                        reason: reasons,
-                        is_dir,
-                        si_mtime: None,        // [추가] USN 저널은 SI/FN 상세 시간이 없으므로 None 처리
-                        fn_mtime: None,        // [추가]
-                        is_timestomped: false, // [추가]
-                        source_artifact: "$Extend\\$UsnJrnl".to_string(),
-                    }));
-                }
+            // 의미 있는 조작(생성, 삭제, 이름변경)만 필터링하여 노이즈 감소
+            if rec.reason_flags & 0x00003300 != 0 { 
+                events.push(ForensicEvent::FileSystemActivity(FileSystemEvent {
+                    timestamp: rec.timestamp,
+                    file_name: resolver.resolve(&rec),
+                    reason: reasons,
+                    is_dir,
+                    si_mtime: None,        // [추가] USN 저널은 SI/FN 상세 시간이 없으므로 None 처리
+                    fn_mtime: None,        // [추가]
+                    is_timestomped: false, // [추가]
+                    source_artifact: "$Extend\\$UsnJrnl".to_string(),
+                    artifact: FileSystemArtifact::UsnJrnl,
+                    mft_reference: Some(rec.file_reference),
+                    parent_reference: Some(rec.parent_reference),
+                    timestomp_rules: Vec::new(),
+                    is_deleted: false,
+                    stream_indicators: Vec::new(),
+                }));
             }
         }
 
         Ok(events)
     }
-}
\ No newline at end of file
+}
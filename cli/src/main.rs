@@ -1,106 +1,627 @@
 use anyhow::{Context, Result};
-use windows::core::w;
-use collector::privilege::enable_privilege;
-use collector::reader::open_locked_file;
-use collector::mft::MftReader;
+use clap::Parser;
+use collector::image::{open_image, ReadSeek, SharedImage};
+use collector::image::partition::discover_ntfs_volumes;
+use collector::image::ewf::{is_ewf_signature, EwfImage};
+use collector::image::vss::ShadowVolume;
+use collector::mft::{MftReader, RecoveryStatus};
 use collector::filesystem::NtfsFileSystem;
-use collector::artifacts::ForensicCollector;
+use collector::artifacts::{CollectedFile, ForensicCollector};
+use collector::source::ArtifactSource;
+use collector::triage::TriagePackage;
+use collector::triage_folder::TriageFolderCollector;
 use models::artifact::ArtifactTarget;
+use models::profile::CollectionProfile;
+use parser::carve::CarvedKind;
+use parser::recycle_bin::{RecycleBin, MAX_INDEX_FILE_SIZE};
+use parser::usb::{UsbHistory, PARTITION_DIAGNOSTIC_LOG};
+use parser::registry_log::{self, log_hive_path};
+use models::usb::UsbDevice;
 use models::event::{ForensicEvent, ExecutionEvent};
 use analyzer::AnalysisEngine;
+use analyzer::mft::MftAnalyzer;
+use analyzer::registry::RegistryAnalyzer;
 use analyzer::preprocess::Preprocessor;
 use chrono::Utc;
 use tracing_subscriber::EnvFilter;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::fs::File;
-use std::io::Write;
-use std::path::Path;
+use std::io::{BufWriter, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Parser, Debug)]
+#[command(name = "fact", about = "Forensic Artifact Correlation & Timeline engine")]
+struct Args {
+    /// 오프라인 분석할 디스크/볼륨 이미지 경로 (dd/raw, E01/Ex01, VHD/VHDX, VMDK). 생략 시 라이브 C: 볼륨을 수집
+    #[arg(long, value_name = "PATH")]
+    image: Option<PathBuf>,
+
+    /// 이미 수집된 트리아지 폴더 또는 ZIP(KAPE/Velociraptor/--export)을 NTFS 접근 없이 분석
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["image", "index_slack", "recover_deleted", "ads", "carve", "vss"])]
+    triage: Option<PathBuf>,
+
+    /// 분석 전에 EWF 이미지에 저장된 MD5/SHA1 해시를 재계산하여 무결성을 검증
+    #[arg(long, requires = "image")]
+    verify: bool,
+
+    /// 미사용 MFT 레코드를 스캔하여 삭제 파일의 상주 데이터 또는 미할당 클러스터 데이터를 Results/Recovered에 복구
+    #[arg(long)]
+    recover_deleted: bool,
+
+    /// 디렉터리 $I30 인덱스 레코드의 slack을 카빙하여 삭제된 파일명 흔적을 타임라인에 추가
+    #[arg(long)]
+    index_slack: bool,
+
+    /// 이름 있는 $DATA 스트림(ADS)을 나열하고 Zone.Identifier에서 다운로드 출처를 추출
+    #[arg(long)]
+    ads: bool,
+
+    /// $Bitmap의 미할당 클러스터에서 EVTX 청크, 레지스트리 hbin, LNK, 프리페치를 카빙하여 타임라인에 추가
+    #[arg(long)]
+    carve: bool,
+
+    /// 수집 대상을 정의한 TOML 프로파일 (경로 패턴, 확장자, ADS, 크기 제한, 파서). 생략 시 내장 기본 프로파일
+    #[arg(long, value_name = "PATH")]
+    profile: Option<PathBuf>,
+
+    /// 수집한 원본 아티팩트를 원래 경로 구조로 ZIP(.zip) 또는 디렉터리에 보존하고 해시/MFT 메타데이터 매니페스트를 기록
+    #[arg(long, value_name = "PATH")]
+    export: Option<PathBuf>,
+
+    /// --export ZIP 파일이 이미 있으면 덮어쓴다. (생략 시 기존 패키지를 보존하고 중단)
+    #[arg(long, requires = "export")]
+    overwrite: bool,
+
+    /// 볼륨 섀도 복사본(VSS)마다 동일한 아티팩트를 추가 수집 (삭제·정리된 로그/하이브/$MFT의 이전 버전)
+    #[arg(long)]
+    vss: bool,
+}
+
+/// 라이브 C: 볼륨을 OS 잠금을 우회하여 연다. (SeBackupPrivilege 필요)
+#[cfg(windows)]
+fn open_live_volume() -> Result<Box<dyn ReadSeek>> {
+    use collector::privilege::enable_privilege;
+    use collector::reader::open_locked_file;
+    use windows::core::w;
+
+    enable_privilege(w!("SeBackupPrivilege")).context("Failed to enable SeBackupPrivilege")?;
+    let file = open_locked_file(w!("\\\\.\\C:")).context("Failed to open C: volume")?;
+    Ok(Box::new(file))
+}
+
+#[cfg(not(windows))]
+fn open_live_volume() -> Result<Box<dyn ReadSeek>> {
+    anyhow::bail!("Live volume collection is only supported on Windows; use --image <PATH>")
+}
+
+/// EWF 이미지의 저장 해시와 재구성한 미디어 해시를 비교한다. 불일치 시 분석을 중단한다.
+fn verify_image(path: &Path) -> Result<()> {
+    let mut signature = [0u8; 8];
+    let n = std::io::Read::read(&mut File::open(path)?, &mut signature)?;
+    if !is_ewf_signature(&signature[..n]) {
+        tracing::warn!("--verify ignored: {} is not an EWF image", path.display());
+        return Ok(());
+    }
+
+    tracing::info!("Verifying EWF image integrity...");
+    let verification = EwfImage::open(path)?.verify_hashes()?;
+    if let Some(md5) = verification.md5 { tracing::info!("  [+] MD5 verified: {}", md5); }
+    if let Some(sha1) = verification.sha1 { tracing::info!("  [+] SHA1 verified: {}", sha1); }
+    Ok(())
+}
+
+/// --profile로 지정한 수집 프로파일을 읽는다. 생략 시 내장 기본 프로파일을 쓴다.
+fn load_profile(path: Option<&Path>) -> Result<CollectionProfile> {
+    let profile = match path {
+        Some(path) => CollectionProfile::load(path).with_context(|| format!("Failed to load collection profile {}", path.display()))?,
+        None => CollectionProfile::default(),
+    };
+    tracing::info!("Collection profile: {} ({} targets)", profile.name, profile.targets.len());
+    Ok(profile)
+}
+
+/// 수집된 아티팩트 스트림 하나를 타겟별 파서/분석기로 해석하여 이벤트 목록에 누적한다.
+/// 대용량이 될 수 있는 EVTX/$UsnJrnl/$MFT는 스트림을 그대로 넘기고, 나머지는 메모리로 읽어 파싱한다.
+fn process_artifact(target: &ArtifactTarget, filename: &str, reader: &mut dyn ReadSeek, analyzer: &AnalysisEngine, all_raw_events: &mut Vec<ForensicEvent>) {
+    match target {
+        ArtifactTarget::EventLogs => {
+            if let Ok(mut events) = parser::evtx::parse_security_evtx_stream(reader, filename) {
+                all_raw_events.append(&mut events);
+            }
+        },
+        ArtifactTarget::UsnJrnl | ArtifactTarget::MFT => {
+            let mut events = analyzer.process_reader(target, filename, reader);
+            all_raw_events.append(&mut events);
+        },
+        _ => {
+            let mut data = Vec::new();
+            match reader.read_to_end(&mut data) {
+                Ok(_) => process_artifact_data(target, filename, &data, analyzer, all_raw_events),
+                Err(e) => tracing::debug!("    [-] Failed to read {}: {}", filename, e),
+            }
+        },
+    }
+}
+
+fn process_artifact_data(target: &ArtifactTarget, filename: &str, data: &[u8], analyzer: &AnalysisEngine, all_raw_events: &mut Vec<ForensicEvent>) {
+    match target {
+        ArtifactTarget::Prefetch => {
+            if let Ok(info) = parser::prefetch::parse_prefetch_info(data) {
+                all_raw_events.push(ForensicEvent::Execution(ExecutionEvent {
+                    timestamp: info.last_run_times.first().copied().unwrap_or_else(Utc::now),
+                    process_name: info.executable_name, file_path: filename.to_string(),
+                    command_line: String::new(), parent_process_name: String::new(),
+                    run_count: info.run_count, referenced_files: info.referenced_files,
+                    source_artifact: format!("Prefetch ({})", filename),
+                }));
+            }
+        },
+        ArtifactTarget::ScheduledTasks => {
+            if let Ok(mut events) = parser::tasks::parse_task_xml(data, filename) {
+                all_raw_events.append(&mut events);
+            }
+        },
+        ArtifactTarget::Amcache => {
+            if let Ok(records) = parser::amcache::parse_amcache_carve(data) {
+                for rec in records {
+                    all_raw_events.push(ForensicEvent::Execution(ExecutionEvent {
+                        timestamp: Utc::now(),
+                        process_name: rec.file_path.split('\\').next_back().unwrap_or("Unknown").to_string(),
+                        file_path: format!("{} [SHA1: {}]", rec.file_path, rec.sha1),
+                        command_line: String::new(), parent_process_name: String::new(),
+                        run_count: 1, referenced_files: vec![],
+                        source_artifact: "Amcache.hve".to_string(),
+                    }));
+                }
+            }
+        },
+        ArtifactTarget::LNK => {
+            if let Ok(mut events) = parser::lnk::parse_lnk_carve(data, filename) {
+                all_raw_events.append(&mut events);
+            }
+        },
+        ArtifactTarget::WMI => {
+            if let Ok(mut events) = parser::wmi::parse_wmi_carve(data, filename) {
+                all_raw_events.append(&mut events);
+            }
+        },
+        ArtifactTarget::RegistrySYSTEM => {
+            if let Ok(mut events) = parser::system_hive::parse_system_services(data, filename) {
+                all_raw_events.append(&mut events);
+            }
+            let mut events = analyzer.process_stream(target, filename, data);
+            all_raw_events.append(&mut events);
+        },
+        _ => {
+            let mut events = analyzer.process_stream(target, filename, data);
+            all_raw_events.append(&mut events);
+        }
+    }
+}
+
+/// 수집한 스트림을 트리아지 패키지에 기록한 뒤, 분석기가 처음부터 읽을 수 있도록 되감는다.
+fn export_artifact(package: &mut TriagePackage, volume_label: &str, target_name: &str, file: &CollectedFile, reader: &mut dyn ReadSeek) {
+    if let Err(e) = package.add_file(volume_label, target_name, file, reader) {
+        tracing::warn!("    [!] Failed to export {}: {:#}", file.path, e);
+    }
+    if let Err(e) = reader.seek(SeekFrom::Start(0)) {
+        tracing::warn!("    [!] Failed to rewind {} after export: {}", file.path, e);
+    }
+}
+
+/// 여러 파일을 모아야 해석할 수 있는 볼륨(또는 섀도 복사본) 단위 아티팩트: 휴지통 $I/$R 짝짓기, USB 장치 이력,
+/// 하이브 트랜잭션 로그 재생
+#[derive(Default)]
+struct VolumeArtifacts {
+    recycle_bin: RecycleBin,
+    usb: UsbHistory,
+    /// 트랜잭션 로그를 재생해야 하는 하이브 (타겟 수집이 끝난 뒤 처리)
+    dirty_hives: Vec<(CollectedFile, Vec<u8>)>,
+    /// 하이브 경로(대문자) -> 같은 디렉터리의 .LOG1/.LOG2 내용
+    hive_logs: HashMap<String, Vec<Vec<u8>>>,
+}
+
+impl VolumeArtifacts {
+    /// 볼륨 단위 분석에 쓰는 파일을 기록한다. true를 반환하면 이 파일은 타겟 파서로 넘기지 않는다.
+    /// 하이브/이벤트 로그처럼 타겟 파서도 읽어야 하는 스트림은 읽은 뒤 처음으로 되감는다.
+    fn observe(&mut self, target: &ArtifactTarget, file: &CollectedFile, reader: &mut dyn ReadSeek) -> bool {
+        if target.is_registry_hive() {
+            return self.observe_hive(target, file, reader);
+        }
+        let file_name = file.path.rsplit('\\').next().unwrap_or(&file.path);
+        let result = match target {
+            ArtifactTarget::RecycleBin => {
+                self.add_recycle_bin_file(file, reader);
+                return true;
+            },
+            ArtifactTarget::USBLog => {
+                if let Some(data) = read_artifact(file, reader) { self.usb.add_setupapi_log(&data); }
+                return true;
+            },
+            ArtifactTarget::EventLogs if file_name.eq_ignore_ascii_case(PARTITION_DIAGNOSTIC_LOG) => {
+                self.usb.add_partition_diagnostic(&mut *reader)
+            },
+            _ => return false,
+        };
+        if let Err(e) = result {
+            tracing::debug!("    [-] USB history: failed to parse {}: {}", file.path, e);
+        }
+        rewind_artifact(file, reader);
+        false
+    }
+
+    /// 트랜잭션 로그는 모아 두고, 더티 하이브는 로그를 모두 수집할 때까지 처리를 미룬다.
+    /// 정상 하이브는 바로 USB 이력에 반영하고 타겟 파서로 넘긴다.
+    fn observe_hive(&mut self, target: &ArtifactTarget, file: &CollectedFile, reader: &mut dyn ReadSeek) -> bool {
+        if let Some(hive_path) = log_hive_path(&file.path) {
+            if let Some(data) = read_artifact(file, reader) {
+                self.hive_logs.entry(hive_path.to_ascii_uppercase()).or_default().push(data);
+            }
+            return true;
+        }
+
+        let mut base_block = [0u8; 512];
+        let is_dirty = reader.read_exact(&mut base_block).is_ok() && registry_log::is_dirty(&base_block);
+        rewind_artifact(file, reader);
+        if is_dirty {
+            if let Some(data) = read_artifact(file, reader) {
+                self.dirty_hives.push((file.clone(), data));
+            }
+            return true;
+        }
+        if Self::feeds_usb_history(target, file)
+            && let Some(data) = read_artifact(file, reader) {
+            self.add_hive(target, file, &data);
+            rewind_artifact(file, reader);
+        }
+        false
+    }
+
+    /// USB 장치 이력에 쓰는 하이브: SYSTEM과 사용자 NTUSER.DAT
+    fn feeds_usb_history(target: &ArtifactTarget, file: &CollectedFile) -> bool {
+        let file_name = file.path.rsplit('\\').next().unwrap_or(&file.path);
+        *target == ArtifactTarget::RegistrySYSTEM || (*target == ArtifactTarget::RegistryNTUSER && file_name.eq_ignore_ascii_case("NTUSER.DAT"))
+    }
+
+    fn add_hive(&mut self, target: &ArtifactTarget, file: &CollectedFile, data: &[u8]) {
+        if !Self::feeds_usb_history(target, file) { return; }
+        let result = match target {
+            ArtifactTarget::RegistrySYSTEM => self.usb.add_system_hive(data),
+            _ => self.usb.add_ntuser_hive(&file.path, data),
+        };
+        if let Err(e) = result {
+            tracing::debug!("    [-] USB history: failed to parse {}: {}", file.path, e);
+        }
+    }
+
+    /// 타겟 수집이 끝난 뒤 더티 하이브에 같은 경로의 .LOG1/.LOG2를 재생하여 USB 이력에 반영하고 타겟 파서로 넘긴다.
+    fn replay_dirty_hives(&mut self, target: &ArtifactTarget, analyzer: &AnalysisEngine, all_raw_events: &mut Vec<ForensicEvent>) {
+        for (file, mut data) in std::mem::take(&mut self.dirty_hives) {
+            let logs: Vec<&[u8]> = self.hive_logs.get(&file.path.to_ascii_uppercase())
+                .map(|logs| logs.iter().map(Vec::as_slice).collect())
+                .unwrap_or_default();
+            match registry_log::replay_transaction_logs(&mut data, &logs) {
+                Ok(summary) => tracing::info!("    [+] Replayed {} transaction log entries ({} pages) into dirty hive {}",
+                    summary.applied_entries, summary.dirty_pages, file.path),
+                Err(e) => tracing::warn!("    [!] Dirty hive {} analyzed without log replay: {}", file.path, e),
+            }
+            self.add_hive(target, &file, &data);
+            process_artifact_data(target, &file.name, &data, analyzer, all_raw_events);
+        }
+        self.hive_logs.clear();
+    }
+
+    /// $I는 내용을 읽어 해석하고 $R은 크기만 기록한다. 삭제 이벤트는 볼륨 수집이 끝난 뒤 $I/$R을 짝지어 만든다.
+    fn add_recycle_bin_file(&mut self, file: &CollectedFile, reader: &mut dyn ReadSeek) {
+        if !RecycleBin::is_index_path(&file.path) {
+            self.recycle_bin.add_content(&file.path, file.size);
+            return;
+        }
+        if file.size > MAX_INDEX_FILE_SIZE {
+            tracing::debug!("    [-] Skipping oversized $I file {} ({} bytes)", file.path, file.size);
+            return;
+        }
+        if let Some(data) = read_artifact(file, reader)
+            && let Err(e) = self.recycle_bin.add_index(&file.path, &data) {
+            tracing::debug!("    [-] Invalid $I file {}: {}", file.path, e);
+        }
+    }
+
+    fn events(&self) -> Vec<ForensicEvent> {
+        let mut events = self.recycle_bin.events();
+        events.extend(self.usb.events());
+        events
+    }
+}
+
+fn rewind_artifact(file: &CollectedFile, reader: &mut dyn ReadSeek) {
+    if let Err(e) = reader.seek(SeekFrom::Start(0)) {
+        tracing::warn!("    [!] Failed to rewind {}: {}", file.path, e);
+    }
+}
+
+fn read_artifact(file: &CollectedFile, reader: &mut dyn ReadSeek) -> Option<Vec<u8>> {
+    let mut data = Vec::new();
+    match reader.read_to_end(&mut data) {
+        Ok(_) => Some(data),
+        Err(e) => {
+            tracing::debug!("    [-] Failed to read {}: {}", file.path, e);
+            None
+        },
+    }
+}
+
+/// 수집 백엔드(NTFS 볼륨 또는 트리아지 폴더) 하나에서 모든 타겟 아티팩트를 수집하여 원시 이벤트 목록에 누적하고,
+/// 볼륨에서 확인한 USB 장치 이력을 반환한다. 볼륨 단위 전처리는 호출자가 볼륨의 다른 분석까지 마친 뒤 실행한다.
+fn collect_volume(source: &mut dyn ArtifactSource, volume_label: &str, profile: &CollectionProfile, mut package: Option<&mut TriagePackage>, analyzer: &AnalysisEngine, all_raw_events: &mut Vec<ForensicEvent>) -> Vec<UsbDevice> {
+    if let Some(package) = package.as_deref_mut() {
+        package.add_volume(volume_label, source.volume_serial_number(), source.computer_name(), source.snapshot());
+    }
+
+    let mut volume_artifacts = VolumeArtifacts::default();
+    for target in &profile.targets {
+        tracing::info!("Processing: {}", target.name);
+        let _ = source.collect_files(target, &mut |file, reader| {
+            if let Some(package) = package.as_deref_mut() {
+                export_artifact(package, volume_label, &target.name, file, reader);
+            }
+            if !volume_artifacts.observe(&target.parser, file, reader) {
+                process_artifact(&target.parser, &file.name, reader, analyzer, all_raw_events);
+            }
+        });
+        volume_artifacts.replay_dirty_hives(&target.parser, analyzer, all_raw_events);
+    }
+    all_raw_events.append(&mut volume_artifacts.events());
+    volume_artifacts.usb.devices()
+}
+
+/// NTFS 볼륨 하나에서 타겟 아티팩트를 수집하고 선택한 심층 분석(인덱스 slack, ADS, 삭제 파일 복구, 카빙)을 수행한다.
+/// 볼륨의 이벤트가 모두 모인 뒤 볼륨 단위 전처리를 실행하고, 볼륨에서 확인한 USB 장치 이력을 반환한다.
+#[allow(clippy::too_many_arguments)]
+fn analyze_ntfs_volume(mft_reader: &mut MftReader, volume_label: &str, volume_offset: u64, args: &Args, profile: &CollectionProfile, package: Option<&mut TriagePackage>, analyzer: &AnalysisEngine, all_raw_events: &mut Vec<ForensicEvent>) -> Vec<UsbDevice> {
+    let first_event = all_raw_events.len();
+    let usb_devices = collect_volume(&mut ForensicCollector::new(NtfsFileSystem::new(mft_reader)), volume_label, profile, package, analyzer, all_raw_events);
+    if args.index_slack && let Err(e) = carve_index_slack(mft_reader, all_raw_events) {
+        tracing::warn!("  [!] $I30 slack carving failed on {}: {}", volume_label, e);
+    }
+    if args.ads && let Err(e) = scan_alternate_streams(mft_reader, all_raw_events) {
+        tracing::warn!("  [!] ADS enumeration failed on {}: {}", volume_label, e);
+    }
+    if args.recover_deleted && let Err(e) = recover_deleted_files(mft_reader, volume_label) {
+        tracing::warn!("  [!] Deleted file recovery failed on {}: {}", volume_label, e);
+    }
+    if args.carve && let Err(e) = carve_unallocated(mft_reader, volume_offset, analyzer, all_raw_events) {
+        tracing::warn!("  [!] Unallocated carving failed on {}: {}", volume_label, e);
+    }
+    Preprocessor::run_volume(&mut all_raw_events[first_event..]);
+    usb_devices
+}
+
+/// 볼륨의 섀도 복사본(VSS)마다 동일한 타겟을 수집하고, 이벤트 출처에 스냅숏 번호와 생성 시각을 표시한다.
+fn collect_shadow_copies(volume: SharedImage, volume_label: &str, profile: &CollectionProfile, mut package: Option<&mut TriagePackage>, analyzer: &AnalysisEngine, all_raw_events: &mut Vec<ForensicEvent>) -> Result<()> {
+    let Some(shadow_volume) = ShadowVolume::open(volume).context("Failed to read VSS catalog")? else {
+        tracing::info!("  [*] No volume shadow copies found");
+        return Ok(());
+    };
+
+    let mut snapshots = Vec::new();
+    for info in shadow_volume.snapshots() {
+        tracing::info!("  [*] VSS #{} {} created {}", info.index, info.shadow_copy_id, info.creation_time.to_rfc3339());
+        let stream = shadow_volume.open_snapshot(info.index)?;
+        match MftReader::bootstrap(Box::new(stream)) {
+            Ok(mut mft_reader) => {
+                if let Some(package) = package.as_deref_mut() {
+                    let serial_number = mft_reader.volume_serial_number();
+                    let host_name = ForensicCollector::new(NtfsFileSystem::new(&mut mft_reader)).computer_name();
+                    package.add_volume(&snapshot_label(volume_label, info.index), serial_number, host_name, Some(info));
+                }
+                snapshots.push((info.clone(), mft_reader));
+            },
+            Err(e) => tracing::warn!("  [!] Skipping VSS #{}: {}", info.index, e),
+        }
+    }
+
+    let mut volume_artifacts: BTreeMap<usize, VolumeArtifacts> = BTreeMap::new();
+    // 스냅숏마다 파일 참조가 겹치므로 이벤트를 스냅숏별로 모아 전처리한 뒤 출처를 표시한다.
+    let mut snapshot_events: BTreeMap<usize, Vec<ForensicEvent>> = BTreeMap::new();
+    for target in &profile.targets {
+        tracing::info!("Processing (VSS): {}", target.name);
+        let _ = ForensicCollector::collect_from_snapshots(&mut snapshots, target, |snapshot, file, reader| {
+            if let Some(package) = package.as_deref_mut() {
+                export_artifact(package, &snapshot_label(volume_label, snapshot.index), &target.name, file, reader);
+            }
+            if volume_artifacts.entry(snapshot.index).or_default().observe(&target.parser, file, reader) {
+                return;
+            }
+            process_artifact(&target.parser, &file.name, reader, analyzer, snapshot_events.entry(snapshot.index).or_default());
+        });
+        for (info, _) in &snapshots {
+            let Some(artifacts) = volume_artifacts.get_mut(&info.index) else { continue };
+            artifacts.replay_dirty_hives(&target.parser, analyzer, snapshot_events.entry(info.index).or_default());
+        }
+    }
+    for (info, _) in &snapshots {
+        let mut events = snapshot_events.remove(&info.index).unwrap_or_default();
+        if let Some(artifacts) = volume_artifacts.get(&info.index) {
+            events.extend(artifacts.events());
+        }
+        Preprocessor::run_volume(&mut events);
+        for event in &mut events {
+            event.tag_snapshot(info);
+        }
+        all_raw_events.append(&mut events);
+    }
+    Ok(())
+}
+
+/// 섀도 복사본의 패키지 내 디렉터리 이름 (예: vol0/vss2)
+fn snapshot_label(volume_label: &str, snapshot_index: usize) -> String {
+    format!("{}/vss{}", volume_label, snapshot_index)
+}
+
+/// 삭제된 MFT 레코드를 찾아 복구 가능한 데이터를 Results/Recovered/<볼륨>/<entry>_<name>으로 추출한다.
+fn recover_deleted_files(mft_reader: &mut MftReader, volume_label: &str) -> Result<()> {
+    let deleted = mft_reader.scan_deleted_records().context("Failed to scan MFT for deleted records")?;
+    tracing::info!("  [*] Found {} deleted MFT records", deleted.len());
+    if deleted.is_empty() { return Ok(()); }
+
+    let bitmap = mft_reader.load_cluster_bitmap().context("Failed to load $Bitmap")?;
+    let out_dir = Path::new("Results").join("Recovered").join(volume_label);
+    fs::create_dir_all(&out_dir).context("Failed to create recovery directory")?;
+
+    let mut recovered = 0usize;
+    for entry in deleted.iter().filter(|d| !d.record.is_directory) {
+        let name = entry.record.preferred_file_name().map(|f| f.name.as_str()).unwrap_or("unnamed");
+        let safe_name: String = name.chars().map(|c| if "\\/:*?\"<>|".contains(c) { '_' } else { c }).collect();
+        let out_path = out_dir.join(format!("{}_{}", entry.record.entry_number, safe_name));
+
+        // 복구 데이터는 메모리에 모으지 않고 바로 파일로 기록하며, 복구하지 못한 경우 만든 파일을 지운다.
+        let file = File::create(&out_path).with_context(|| format!("Failed to create {}", out_path.display()))?;
+        let mut writer = BufWriter::new(file);
+        let result = mft_reader.recover_deleted_data(&entry.record, &bitmap, &mut writer);
+        let result = result.and_then(|status| { writer.flush()?; Ok(status) });
+        drop(writer);
+        match result {
+            Ok(status @ (RecoveryStatus::Resident | RecoveryStatus::Unallocated)) => {
+                recovered += 1;
+                let size = fs::metadata(&out_path).map(|m| m.len()).unwrap_or(0);
+                tracing::info!("    [+] {:?} {} ({} bytes){}", status, entry.path, size,
+                    if entry.parent_path_valid { "" } else { " [parent reused]" });
+                continue;
+            },
+            Ok(RecoveryStatus::Overwritten { reallocated_clusters }) => {
+                tracing::debug!("    [-] {} overwritten ({} clusters reallocated)", entry.path, reallocated_clusters);
+            },
+            Ok(RecoveryStatus::NoData) => {},
+            Err(e) => tracing::debug!("    [!] Recovery failed for {}: {}", entry.path, e),
+        }
+        let _ = fs::remove_file(&out_path);
+    }
+    tracing::info!("  [+] Recovered {} deleted files to {}", recovered, out_dir.display());
+    Ok(())
+}
+
+/// 모든 디렉터리의 $I30 slack에서 삭제 엔트리를 카빙하여 이벤트로 추가한다.
+fn carve_index_slack(mft_reader: &mut MftReader, all_raw_events: &mut Vec<ForensicEvent>) -> Result<()> {
+    let entries = mft_reader.scan_index_slack().context("Failed to carve $I30 slack")?;
+    tracing::info!("  [*] $I30 slack: carved {} deleted index entries", entries.len());
+    all_raw_events.extend(MftAnalyzer::slack_events(&entries));
+    Ok(())
+}
+
+/// 모든 대체 데이터 스트림을 나열하여 다운로드 출처(MOTW)와 ADS 이벤트로 추가한다.
+fn scan_alternate_streams(mft_reader: &mut MftReader, all_raw_events: &mut Vec<ForensicEvent>) -> Result<()> {
+    let streams = mft_reader.scan_alternate_data_streams().context("Failed to enumerate alternate data streams")?;
+    tracing::info!("  [*] ADS: found {} named $DATA streams", streams.len());
+    all_raw_events.extend(MftAnalyzer::stream_events(&streams));
+    Ok(())
+}
+
+/// 미할당 클러스터에서 카빙한 아티팩트를 기존 파서로 해석하고, 출처에 물리 오프셋(이미지 기준)을 표시한다.
+fn carve_unallocated(mft_reader: &mut MftReader, volume_offset: u64, analyzer: &AnalysisEngine, all_raw_events: &mut Vec<ForensicEvent>) -> Result<()> {
+    let bitmap = mft_reader.load_cluster_bitmap().context("Failed to load $Bitmap")?;
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+
+    let carved = mft_reader.carve_unallocated(&bitmap, |artifact| {
+        let physical_offset = volume_offset + artifact.volume_offset;
+        let name = artifact.kind.label();
+        let mut events = Vec::new();
+        match artifact.kind {
+            CarvedKind::EvtxChunk => match parser::evtx::parse_evtx_chunk(&artifact.data, name) {
+                Ok(mut chunk_events) => events.append(&mut chunk_events),
+                Err(e) => tracing::debug!("    [-] Carved EVTX chunk @ {:#X} rejected: {}", physical_offset, e),
+            },
+            CarvedKind::RegistryHbin => events.extend(RegistryAnalyzer::hbin_events(&artifact.data)),
+            CarvedKind::Lnk => process_artifact_data(&ArtifactTarget::LNK, name, &artifact.data, analyzer, &mut events),
+            CarvedKind::Prefetch => process_artifact_data(&ArtifactTarget::Prefetch, name, &artifact.data, analyzer, &mut events),
+        }
+        for event in &mut events {
+            event.tag_carved(physical_offset);
+        }
+        *counts.entry(artifact.kind.label()).or_default() += 1;
+        all_raw_events.append(&mut events);
+    }).context("Failed to carve unallocated clusters")?;
+
+    tracing::info!("  [*] Unallocated carving: {} artifacts {:?}", carved, counts);
+    Ok(())
+}
 
 fn main() -> Result<()> {
+    let args = Args::parse();
     tracing_subscriber::fmt().with_env_filter(EnvFilter::new("info,evtx=warn")).init();
     tracing::info!("FACT Engine v5 - Final Correlation & STIX Generation");
 
-    enable_privilege(w!("SeBackupPrivilege")).context("Failed to enable SeBackupPrivilege")?;
-    
-    let volume_path = w!("\\\\.\\C:");
-    let file = open_locked_file(volume_path).context("Failed to open C: volume")?;
-    
-    let mut mft_reader = MftReader::bootstrap(file).context("Failed to bootstrap MFT Engine")?;
-    let fs = NtfsFileSystem::new(&mut mft_reader);
-    let mut collector = ForensicCollector::new(fs);
-    let analyzer = AnalysisEngine::new();
-
-    let targets = vec![
-        ArtifactTarget::Prefetch, ArtifactTarget::EventLogs, ArtifactTarget::ScheduledTasks,
-        ArtifactTarget::Amcache, ArtifactTarget::RegistrySOFTWARE, ArtifactTarget::RegistryNTUSER,
-        ArtifactTarget::RegistrySYSTEM, ArtifactTarget::LNK, ArtifactTarget::WMI,
-        ArtifactTarget::UsnJrnl, ArtifactTarget::MFT,
-    ];
-
+    let profile = load_profile(args.profile.as_deref())?;
+    let mut package = match &args.export {
+        Some(path) => Some(TriagePackage::create(path, args.overwrite).with_context(|| format!("Failed to create triage package {}", path.display()))?),
+        None => None,
+    };
     let mut all_raw_events = Vec::new();
+    let mut usb_devices = Vec::new();
+
+    match (&args.image, &args.triage) {
+        (_, Some(path)) => {
+            tracing::info!("Triage mode: analysing collected artifacts in {}", path.display());
+            let mut source = TriageFolderCollector::open(path)?;
+            let analyzer = AnalysisEngine::new();
+            for volume in source.volumes().to_vec() {
+                tracing::info!("[Volume {}] serial {:016X}", volume.label, volume.serial_number);
+                source.select_volume(&volume.label)?;
+                // 볼륨(스냅숏)마다 파일 참조가 겹치므로 볼륨별로 전처리한 뒤 합친다.
+                let mut events = Vec::new();
+                let devices = collect_volume(&mut source, &volume.label, &profile, package.as_mut(), &analyzer, &mut events);
+                Preprocessor::run_volume(&mut events);
+                match &volume.snapshot {
+                    Some(info) => events.iter_mut().for_each(|event| event.tag_snapshot(info)),
+                    // 섀도 복사본의 USB 이력은 현재 볼륨과 중복되므로 --vss 수집과 같이 현재 볼륨 것만 쓴다.
+                    None => usb_devices.extend(devices),
+                }
+                all_raw_events.append(&mut events);
+            }
+        },
+        (Some(path), None) => {
+            tracing::info!("Offline mode: analysing image {}", path.display());
+            if args.verify { verify_image(path)?; }
+            let image = SharedImage::new(open_image(path)?);
+            let volumes = discover_ntfs_volumes(&image).context("Failed to discover NTFS volumes")?;
+            if volumes.is_empty() { anyhow::bail!("No NTFS volume found in {}", path.display()); }
 
-    for target in targets {
-        tracing::info!("Processing: {:?}", target);
-        let _ = collector.collect_to_memory_stream(&target, |filename, data| {
-            match target {
-                ArtifactTarget::Prefetch => {
-                    if let Ok(info) = parser::prefetch::parse_prefetch_info(data) {
-                        all_raw_events.push(ForensicEvent::Execution(ExecutionEvent {
-                            timestamp: info.last_run_times.first().copied().unwrap_or_else(Utc::now),
-                            process_name: info.executable_name, file_path: filename.to_string(),
-                            command_line: String::new(), parent_process_name: String::new(),
-                            run_count: info.run_count, referenced_files: info.referenced_files,
-                            source_artifact: format!("Prefetch ({})", filename),
-                        }));
-                    }
-                },
-                ArtifactTarget::EventLogs => {
-                    if let Ok(mut events) = parser::evtx::parse_security_evtx_buffer(data, &filename) {
-                        all_raw_events.append(&mut events);
-                    }
-                },
-                ArtifactTarget::ScheduledTasks => {
-                    if let Ok(mut events) = parser::tasks::parse_task_xml(data, &filename) {
-                        all_raw_events.append(&mut events);
-                    }
-                },
-                ArtifactTarget::Amcache => {
-                    if let Ok(records) = parser::amcache::parse_amcache_carve(data) {
-                        for rec in records {
-                            all_raw_events.push(ForensicEvent::Execution(ExecutionEvent {
-                                timestamp: Utc::now(),
-                                process_name: rec.file_path.split('\\').last().unwrap_or("Unknown").to_string(),
-                                file_path: format!("{} [SHA1: {}]", rec.file_path, rec.sha1),
-                                command_line: String::new(), parent_process_name: String::new(),
-                                run_count: 1, referenced_files: vec![],
-                                source_artifact: "Amcache.hve".to_string(),
-                            }));
+            for volume in volumes {
+                tracing::info!("[Volume #{}] {:?} {} @ offset {:#X} ({} bytes)",
+                    volume.index, volume.scheme, volume.name, volume.start_offset, volume.length);
+                let slice = image.slice(volume.start_offset, volume.length);
+                match MftReader::bootstrap(Box::new(slice)) {
+                    Ok(mut mft_reader) => {
+                        let analyzer = AnalysisEngine::with_volume_geometry(mft_reader.volume_geometry());
+                        let volume_label = format!("vol{}", volume.index);
+                        usb_devices.extend(analyze_ntfs_volume(&mut mft_reader, &volume_label, volume.start_offset, &args, &profile, package.as_mut(), &analyzer, &mut all_raw_events));
+                        if args.vss {
+                            let volume_image = SharedImage::new(Box::new(image.slice(volume.start_offset, volume.length)));
+                            if let Err(e) = collect_shadow_copies(volume_image, &volume_label, &profile, package.as_mut(), &analyzer, &mut all_raw_events) {
+                                tracing::warn!("  [!] Shadow copy collection failed on volume #{}: {}", volume.index, e);
+                            }
                         }
-                    }
-                },
-                ArtifactTarget::LNK => {
-                    if let Ok(mut events) = parser::lnk::parse_lnk_carve(data, &filename) {
-                        all_raw_events.append(&mut events);
-                    }
-                },
-                ArtifactTarget::WMI => {
-                    if let Ok(mut events) = parser::wmi::parse_wmi_carve(data, &filename) {
-                        all_raw_events.append(&mut events);
-                    }
-                },
-                ArtifactTarget::RegistrySYSTEM => {
-                    if let Ok(mut events) = parser::system_hive::parse_system_services(data, &filename) {
-                        all_raw_events.append(&mut events);
-                    }
-                    let mut events = analyzer.process_stream(&target, filename, data);
-                    all_raw_events.append(&mut events);
-                },
-                _ => {
-                    let mut events = analyzer.process_stream(&target, filename, data);
-                    all_raw_events.append(&mut events);
+                    },
+                    Err(e) => tracing::warn!("  [!] Skipping volume #{}: {}", volume.index, e),
                 }
             }
-        });
+        },
+        (None, None) => {
+            let mut mft_reader = MftReader::bootstrap(open_live_volume()?).context("Failed to bootstrap MFT Engine")?;
+            let analyzer = AnalysisEngine::with_volume_geometry(mft_reader.volume_geometry());
+            usb_devices.extend(analyze_ntfs_volume(&mut mft_reader, "vol0", 0, &args, &profile, package.as_mut(), &analyzer, &mut all_raw_events));
+            if args.vss && let Err(e) = open_live_volume().and_then(|volume| collect_shadow_copies(SharedImage::new(volume), "vol0", &profile, package.as_mut(), &analyzer, &mut all_raw_events)) {
+                tracing::warn!("  [!] Shadow copy collection failed: {}", e);
+            }
+        },
+    }
+
+    if let Some(package) = package {
+        let file_count = package.file_count();
+        let manifest_sha256 = package.finish().context("Failed to finalize triage package")?;
+        tracing::info!("Triage package: {} files exported to {} (manifest SHA-256 {})",
+            file_count, args.export.as_ref().map(|p| p.display().to_string()).unwrap_or_default(), manifest_sha256);
     }
 
     tracing::info!("Running Preprocessor...");
@@ -127,9 +648,15 @@ fn main() -> Result<()> {
         &campaigns
     );
 
-    let mut stix_file = File::create("Results\\final_threat_report.json").context("Failed to create JSON file")?;
+    let mut stix_file = File::create(results_dir.join("final_threat_report.json")).context("Failed to create JSON file")?;
     stix_file.write_all(serde_json::to_string_pretty(&stix_bundle)?.as_bytes()).context("Failed to write JSON")?;
 
     tracing::info!("STIX 2.1 Threat Report saved to Results/final_threat_report.json");
+
+    if !usb_devices.is_empty() {
+        let mut usb_file = File::create(results_dir.join("usb_devices.json")).context("Failed to create USB device report")?;
+        usb_file.write_all(serde_json::to_string_pretty(&usb_devices)?.as_bytes()).context("Failed to write USB device report")?;
+        tracing::info!("USB device history ({} devices) saved to Results/usb_devices.json", usb_devices.len());
+    }
     Ok(())
 }
\ No newline at end of file
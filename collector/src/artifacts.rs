@@ -1,9 +1,34 @@
 use crate::filesystem::NtfsFileSystem;
+use crate::image::ReadSeek;
+use crate::mft::{join_extents, MftReader};
 use anyhow::{Result, bail};
-use parser::mft::{parse_file_record_header, parse_attributes, parse_non_resident_header, parse_runlist};
+use parser::mft::{attribute_name, parse_file_record_header, parse_mft_record, parse_attributes, parse_non_resident_header, parse_runlist, resident_content};
+use parser::compression::{decompress_wof, WofAlgorithm};
+use models::mft::{AttributeHeader, MftRecord, mft_entry_number};
+use models::vss::ShadowCopyInfo;
 use std::collections::HashSet;
-use std::io::{Write, Cursor};
-use models::artifact::{ArtifactTarget, TargetType};
+use std::io::{Cursor, Write};
+use models::profile::{wildcard_match, CollectionTarget, PathPattern};
+
+/// 비상주 속성 헤더 플래그: LZNT1 압축
+const ATTRIBUTE_FLAG_COMPRESSED: u16 = 0x0001;
+/// Windows Overlay Filter(시스템 압축) 리파스 태그
+const IO_REPARSE_TAG_WOF: u32 = 0x8000_0017;
+
+/// 수집한 스트림의 출처 정보
+#[derive(Debug, Clone)]
+pub struct CollectedFile {
+    /// 분석기에 넘기는 수집 이름
+    pub name: String,
+    /// 볼륨 루트 기준 원본 경로
+    pub path: String,
+    /// 수집한 ADS 이름 (기본 스트림이면 None)
+    pub stream: Option<String>,
+    pub inode: u64,
+    pub size: u64,
+    /// 베이스 FILE 레코드 (시퀀스 번호, SI/FN 타임스탬프)
+    pub record: Option<MftRecord>,
+}
 
 pub struct ForensicCollector<'a> { 
     fs: NtfsFileSystem<'a> 
@@ -12,138 +37,172 @@ pub struct ForensicCollector<'a> {
 impl<'a> ForensicCollector<'a> {
     pub fn new(fs: NtfsFileSystem<'a>) -> Self { Self { fs } }
 
-    pub fn collect_to_memory_stream<F>(&mut self, target: &ArtifactTarget, mut callback: F) -> Result<(usize, u64)> 
+    /// 타겟의 파일을 하나씩 Read + Seek 스트림으로 콜백에 넘긴다. 일반 비상주 스트림은 런리스트를 직접 읽으므로
+    /// 수 GB의 $UsnJrnl:$J나 $MFT도 메모리에 올리지 않는다.
+    pub fn collect_streams<F>(&mut self, target: &CollectionTarget, mut callback: F) -> Result<(usize, u64)>
     where
-        F: FnMut(&str, &[u8]),
+        F: FnMut(&str, &mut dyn ReadSeek),
+    {
+        self.collect_files(target, |file, reader| callback(&file.name, reader))
+    }
+
+    /// collect_streams와 같지만 콜백에 원본 경로와 MFT 레코드 등 출처 정보를 함께 넘긴다. (트리아지 패키지용)
+    pub fn collect_files<F>(&mut self, target: &CollectionTarget, mut callback: F) -> Result<(usize, u64)>
+    where
+        F: FnMut(&CollectedFile, &mut dyn ReadSeek),
     {
         let mut processed_count = 0;
         let mut total_bytes_streamed = 0;
-        
-        for detail in target.get_details() {
-            match detail {
-                TargetType::SingleFile { path } => {
-                    // [Fix] ADS(대체 데이터 스트림) 식별자 분리 로직 추가
-                    let parts: Vec<&str> = path.split(':').collect();
-                    let file_path = parts[0]; // 순수 경로 (예: $Extend\$UsnJrnl)
-                    let requested_ads = if parts.len() > 1 { parts[1] } else { "" }; // ADS 이름 (예: $J)
-                    let file_name = file_path.split('\\').last().unwrap();
-                    
-                    if let Ok(inode) = self.fs.get_inode_by_path(file_path) {
-                        let mut buffer = Vec::new();
-                        let mut virtual_sink = Cursor::new(&mut buffer);
-                        
-                        // [Fix] requested_ads를 주입하여 해당 데이터 스트림을 낚아챔
-                        match self.extract_comprehensive_data(inode, requested_ads, &mut virtual_sink) {
-                            Ok(written) => {
-                                if written > 0 {
-                                    callback(file_name, &buffer);
-                                    processed_count += 1;
-                                    total_bytes_streamed += written;
-                                }
-                            },
-                            Err(e) => tracing::debug!("    [-] Failed to stream {}: {}", file_name, e),
+        let mut processed_inodes = HashSet::new();
+        // ADS 지정이 없으면 기본 스트림("")을 타격
+        let requested_ads = target.ads.as_deref().unwrap_or("");
+
+        for pattern in target.path_patterns() {
+            for (inode, name, path) in self.resolve_pattern(&pattern, target) {
+                if !processed_inodes.insert(inode) { continue; }
+                let record = self.fs.mft.read_record(inode).ok().and_then(|rec| parse_mft_record(&rec, inode).ok());
+                match self.open_data_stream(inode, requested_ads) {
+                    Ok((mut reader, size)) => {
+                        if size == 0 { continue; }
+                        if let Some(max_size) = target.max_size
+                            && size > max_size {
+                            tracing::info!("    [-] Skipping {} ({} bytes exceeds max_size {})", name, size, max_size);
+                            continue;
                         }
+                        let file = CollectedFile { name, path, stream: target.ads.clone(), inode, size, record };
+                        callback(&file, &mut *reader);
+                        processed_count += 1;
+                        total_bytes_streamed += size;
+                    },
+                    Err(e) => tracing::debug!("    [-] Failed to stream {}: {}", name, e),
+                }
+            }
+        }
+        Ok((processed_count, total_bytes_streamed))
+    }
+
+    /// VBR에 기록된 볼륨 일련번호
+    pub fn volume_serial_number(&self) -> u64 {
+        self.fs.mft.volume_serial_number()
+    }
+
+    /// [추가] SYSTEM 하이브에서 수집 대상 시스템의 컴퓨터 이름을 읽는다.
+    pub fn computer_name(&mut self) -> Option<String> {
+        let inode = self.fs.get_inode_by_path("Windows\\System32\\config\\SYSTEM").ok()?;
+        let (mut reader, _) = self.open_data_stream(inode, "").ok()?;
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).ok()?;
+        parser::system_hive::computer_name(&data)
+    }
+
+    /// 경로 패턴과 일치하는 파일의 (inode, 수집 이름, 원본 경로) 목록. 고정 경로는 파일명을, 와일드카드 아래에서 찾은 파일은
+    /// 고정 접두 디렉터리 기준 상대 경로를 '_'로 이은 이름을 쓴다.
+    fn resolve_pattern(&mut self, pattern: &PathPattern, target: &CollectionTarget) -> Vec<(u64, String, String)> {
+        let literal_len = pattern.literal_len();
+        if pattern.is_literal() {
+            let file_name = pattern.segments[literal_len - 1].clone();
+            let path = pattern.segments.join("\\");
+            return match self.fs.get_inode_by_path(&path) {
+                Ok(inode) if target.accepts_extension(&file_name) => vec![(inode, file_name, path)],
+                _ => Vec::new(),
+            };
+        }
+
+        let base = pattern.segments[..literal_len].join("\\");
+        let Ok(root_inode) = self.fs.get_inode_by_path(&base) else { return Vec::new() };
+        tracing::info!("  [*] Directory located: {} (Inode: {})", base, root_inode);
+
+        let mut matches = Vec::new();
+        let mut stack = vec![(literal_len, root_inode, String::new())];
+        let mut seen_dirs = HashSet::new();
+
+        while let Some((index, dir_inode, rel_path)) = stack.pop() {
+            if !seen_dirs.insert((index, dir_inode)) { continue; }
+            let Ok(entries) = self.fs.list_directory(dir_inode) else { continue };
+            let segment = pattern.segments[index].as_str();
+            let is_last = index + 1 == pattern.segments.len();
+            // '**'는 현재 디렉터리에서 다음 구성요소를 매칭하는 경우(0단계)도 포함한다.
+            if segment == "**" {
+                stack.push((index + 1, dir_inode, rel_path.clone()));
+            }
+
+            for entry in entries {
+                let name = entry.filename.trim_matches(char::from(0)).trim();
+                if name.is_empty() || name == "." || name == ".." { continue; }
+                if name.contains('~') && name.len() <= 12 { continue; }
+
+                let mut is_real_directory = entry.is_directory;
+                if let Ok(rec) = self.fs.mft.read_record(entry.file_reference)
+                    && let Ok(hdr) = parse_file_record_header(&rec) {
+                    is_real_directory = (hdr.flags & 0x02) != 0;
+                }
+                let child_path = format!("{}\\{}", rel_path, name);
+
+                if segment == "**" {
+                    if is_real_directory {
+                        stack.push((index, entry.file_reference, child_path));
                     }
-                },
-                TargetType::Directory { path, extension, recursive } => {
-                    if let Ok(root_inode) = self.fs.get_inode_by_path(path) {
-                        tracing::info!("  [*] Directory located: {} (Inode: {})", path, root_inode);
-                        let mut stack = vec![(root_inode, String::new())];
-                        let mut seen_dirs = HashSet::new();
-                        let mut processed_inodes = HashSet::new();
-                        
-                        while let Some((dir_inode, rel_path)) = stack.pop() {
-                            if !seen_dirs.insert(dir_inode) { continue; }
-                            
-                            if let Ok(entries) = self.fs.list_directory(dir_inode) {
-                                for entry in entries {
-                                    let name = entry.filename.trim_matches(char::from(0)).trim();
-                                    if name.is_empty() || name == "." || name == ".." { continue; }
-                                    if name.contains('~') && name.len() <= 12 { continue; }
-                                    if !processed_inodes.insert(entry.file_reference) { continue; }
-
-                                    let mut is_real_directory = entry.is_directory;
-                                    if let Ok(rec) = self.fs.mft.read_record(entry.file_reference) {
-                                        if let Ok(hdr) = parse_file_record_header(&rec) {
-                                            is_real_directory = (hdr.flags & 0x02) != 0;
-                                        }
-                                    }
-
-                                    if is_real_directory {
-                                        if recursive {
-                                            stack.push((entry.file_reference, format!("{}\\{}", rel_path, name)));
-                                        }
-                                    } else {
-                                        if let Some(ext) = extension { 
-                                            let target_ext = ext.to_lowercase();
-                                            let file_ext = name.split('.').last().unwrap_or("").to_lowercase();
-                                            if file_ext != target_ext { continue; }
-                                        }
-                                        
-                                        let s_name = format!("{}_{}", rel_path.replace("\\", "_"), name).trim_start_matches('_').to_string();
-                                        
-                                        let mut buffer = Vec::new();
-                                        let mut virtual_sink = Cursor::new(&mut buffer);
-                                        
-                                        // 디렉터리 스캔 시에는 기본 스트림("")을 타격
-                                        if let Ok(written) = self.extract_comprehensive_data(entry.file_reference, "", &mut virtual_sink) {
-                                            if written > 0 { 
-                                                callback(&s_name, &buffer);
-                                                processed_count += 1;
-                                                total_bytes_streamed += written;
-                                            }
-                                        }
-                                    }
-                                }
-                            }
+                } else if wildcard_match(segment, name) {
+                    if !is_last {
+                        if is_real_directory {
+                            stack.push((index + 1, entry.file_reference, child_path));
                         }
+                    } else if !is_real_directory && target.accepts_extension(name) {
+                        let s_name = child_path.replace("\\", "_").trim_start_matches('_').to_string();
+                        let path = format!("{}{}", base, child_path).trim_start_matches('\\').to_string();
+                        matches.push((entry.file_reference, s_name, path));
                     }
                 }
             }
         }
+        matches
+    }
+
+    /// 작은 아티팩트용: 각 스트림을 메모리 버퍼로 읽어 콜백에 넘긴다.
+    pub fn collect_to_memory_stream<F>(&mut self, target: &CollectionTarget, mut callback: F) -> Result<(usize, u64)>
+    where
+        F: FnMut(&str, &[u8]),
+    {
+        self.collect_streams(target, |name, reader| {
+            let mut buffer = Vec::new();
+            match reader.read_to_end(&mut buffer) {
+                Ok(_) => callback(name, &buffer),
+                Err(e) => tracing::debug!("    [-] Failed to read {}: {}", name, e),
+            }
+        })
+    }
+
+    /// [추가] 볼륨의 섀도 복사본마다 동일한 타겟을 수집한다. 콜백에는 스트림이 나온 스냅숏 정보가 함께 전달된다.
+    pub fn collect_from_snapshots<F>(snapshots: &mut [(ShadowCopyInfo, MftReader)], target: &CollectionTarget, mut callback: F) -> Result<(usize, u64)>
+    where
+        F: FnMut(&ShadowCopyInfo, &CollectedFile, &mut dyn ReadSeek),
+    {
+        let (mut processed_count, mut total_bytes_streamed) = (0, 0);
+        for (info, mft_reader) in snapshots.iter_mut() {
+            let mut collector = ForensicCollector::new(NtfsFileSystem::new(mft_reader));
+            match collector.collect_files(target, |file, reader| callback(info, file, reader)) {
+                Ok((count, bytes)) => {
+                    processed_count += count;
+                    total_bytes_streamed += bytes;
+                },
+                Err(e) => tracing::debug!("    [-] VSS #{}: failed to collect {}: {}", info.index, target.name, e),
+            }
+        }
         Ok((processed_count, total_bytes_streamed))
     }
 
-    // [Fix] 파라미터에 requested_ads 추가
-    fn extract_comprehensive_data(&mut self, base_index: u64, requested_ads: &str, writer: &mut dyn Write) -> Result<u64> {
+    /// 요청한 $DATA 스트림을 (Read + Seek, 크기)로 연다. 일반/희소 비상주 스트림은 런리스트를 그대로 스트리밍하고,
+    /// 상주 스트림과 압축(LZNT1/WOF) 스트림은 해제한 내용을 메모리에서 제공한다.
+    fn open_data_stream(&mut self, base_index: u64, requested_ads: &str) -> Result<(Box<dyn ReadSeek + '_>, u64)> {
         let mut inodes = vec![base_index];
-        let mut total_written: u64 = 0;
         
         let record = match self.fs.mft.read_record(base_index) { Ok(rec) => rec, Err(e) => bail!("MFT Read Error: {}", e) };
         let header = match parse_file_record_header(&record) { Ok(h) => h, Err(e) => bail!("MFT Header Error: {}", e) };
-        let attrs = match parse_attributes(&record, &header) { Ok(a) => a, Err(_) => vec![] };
-
-        for attr in &attrs {
-            if attr.type_code == 0x20 { 
-                let v_off = u16::from_le_bytes([record[attr.offset+20], record[attr.offset+21]]) as usize;
-                let list = if attr.non_resident_flag == 0 { 
-                    let end = std::cmp::min(attr.offset + attr.length as usize, record.len());
-                    if attr.offset + v_off <= end {
-                        record[attr.offset+v_off .. end].to_vec() 
-                    } else { vec![] }
-                } else { 
-                    if let Ok(nr) = parse_non_resident_header(&record[attr.offset..]) {
-                        let start = attr.offset + nr.run_array_offset as usize;
-                        let end = std::cmp::min(attr.offset + attr.length as usize, record.len());
-                        if start <= end {
-                            if let Ok(runs) = parse_runlist(&record[start..end]) {
-                                self.fs.mft.read_data_from_runlist(&runs, nr.real_size).unwrap_or_default()
-                            } else { vec![] }
-                        } else { vec![] }
-                    } else { vec![] }
-                };
-                
-                let mut cur = 0;
-                while cur + 26 <= list.len() {
-                    let t = u32::from_le_bytes(list[cur..cur+4].try_into().unwrap());
-                    if t == 0x80 {
-                        let r = u64::from_le_bytes(list[cur+16..cur+24].try_into().unwrap()) & 0x0000FFFFFFFFFFFF;
-                        if !inodes.contains(&r) { inodes.push(r); }
-                    }
-                    let l = u16::from_le_bytes(list[cur+4..cur+6].try_into().unwrap()) as usize;
-                    if l == 0 { break; } cur += l;
-                }
-            }
+        let attrs = parse_attributes(&record, &header).unwrap_or_default();
+
+        for (type_code, _, reference) in self.fs.mft.attribute_list_entries(&record, &attrs) {
+            let inode = mft_entry_number(reference);
+            if type_code == 0x80 && !inodes.contains(&inode) { inodes.push(inode); }
         }
 
         let mut target_ads = requested_ads.to_string();
@@ -151,21 +210,19 @@ impl<'a> ForensicCollector<'a> {
         // 특정 ADS 요청이 없으면 기존처럼 WofCompressedData 우선 탐색
         if target_ads.is_empty() {
             for &inode in &inodes {
-                if let Ok(r) = self.fs.mft.read_record(inode) {
-                    if let Ok(h) = parse_file_record_header(&r) {
-                        let inode_attrs = parse_attributes(&r, &h).unwrap_or_default();
-                        for attr in inode_attrs {
-                            if attr.type_code == 0x80 && attr.name_length > 0 {
-                                let ns = attr.offset + attr.name_offset as usize;
-                                let end = std::cmp::min(ns + (attr.name_length as usize * 2), r.len());
-                                if ns <= end {
-                                    if let Some(nb) = r.get(ns..end) {
-                                        let u16v: Vec<u16> = nb.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
-                                        let name = String::from_utf16_lossy(&u16v);
-                                        if name.eq_ignore_ascii_case("WofCompressedData") {
-                                            target_ads = "WofCompressedData".to_string();
-                                        }
-                                    }
+                if let Ok(r) = self.fs.mft.read_record(inode)
+                    && let Ok(h) = parse_file_record_header(&r) {
+                    let inode_attrs = parse_attributes(&r, &h).unwrap_or_default();
+                    for attr in inode_attrs {
+                        if attr.type_code == 0x80 && attr.name_length > 0 {
+                            let ns = attr.offset + attr.name_offset as usize;
+                            let end = std::cmp::min(ns + (attr.name_length as usize * 2), r.len());
+                            if ns <= end
+                                && let Some(nb) = r.get(ns..end) {
+                                let u16v: Vec<u16> = nb.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+                                let name = String::from_utf16_lossy(&u16v);
+                                if name.eq_ignore_ascii_case("WofCompressedData") {
+                                    target_ads = "WofCompressedData".to_string();
                                 }
                             }
                         }
@@ -174,68 +231,215 @@ impl<'a> ForensicCollector<'a> {
             }
         }
 
-        let mut data_attr_found = false;
+        let wof_info = if target_ads.eq_ignore_ascii_case("WofCompressedData") { self.wof_info(&inodes) } else { None };
+        let wof_info = wof_info.filter(|_| requested_ads.is_empty());
+        let mut matching = Vec::new();
 
         for &inode in &inodes {
             let r = match self.fs.mft.read_record(inode) { Ok(rec) => rec, Err(_) => continue };
             let h = match parse_file_record_header(&r) { Ok(hdr) => hdr, Err(_) => continue };
-            
-            let inode_attrs = parse_attributes(&r, &h).unwrap_or_default();
-            for attr in inode_attrs {
-                if attr.type_code == 0x80 {
-                    let mut name = String::new();
-                    if attr.name_length > 0 {
-                        let ns = attr.offset + attr.name_offset as usize;
-                        let end = std::cmp::min(ns + (attr.name_length as usize * 2), r.len());
-                        if ns <= end {
-                            if let Some(nb) = r.get(ns..end) {
-                                let u16v: Vec<u16> = nb.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
-                                name = String::from_utf16_lossy(&u16v);
-                            }
-                        }
-                    }
-                    
-                    // [Fix] 요청한 스트림 이름(예: $J)과 정확히 매칭될 때만 데이터를 추출
-                    if name.eq_ignore_ascii_case(&target_ads) {
-                        data_attr_found = true;
-                        
-                        if attr.non_resident_flag == 0 {
-                            let data_size = u32::from_le_bytes([
-                                r[attr.offset + 16], r[attr.offset + 17], 
-                                r[attr.offset + 18], r[attr.offset + 19]
-                            ]) as usize;
-                            
-                            let data_offset = u16::from_le_bytes([
-                                r[attr.offset + 20], r[attr.offset + 21]
-                            ]) as usize;
-
-                            let start = attr.offset + data_offset;
-                            let end = start + data_size;
-
-                            if start < r.len() && end <= r.len() {
-                                writer.write_all(&r[start..end])?;
-                                total_written += data_size as u64;
-                            }
-                        } else {
-                            if let Ok(nr) = parse_non_resident_header(&r[attr.offset..]) {
-                                let start = attr.offset + nr.run_array_offset as usize;
-                                let end = std::cmp::min(attr.offset + attr.length as usize, r.len());
-                                if start <= end {
-                                    if let Ok(runs) = parse_runlist(&r[start..end]) {
-                                        total_written += self.fs.mft.extract_runlist_to_writer(&runs, nr.real_size, writer).unwrap_or(0);
-                                    }
-                                }
-                            }
-                        }
-                    }
+
+            for attr in parse_attributes(&r, &h).unwrap_or_default() {
+                // [Fix] 요청한 스트림 이름(예: $J)과 정확히 매칭될 때만 데이터를 추출
+                if attr.type_code == 0x80 && attribute_name(&r, &attr).eq_ignore_ascii_case(&target_ads) {
+                    matching.push((r.clone(), attr));
                 }
             }
         }
 
-        if !data_attr_found {
+        if matching.is_empty() {
             bail!("Missing $DATA attribute for requested ADS: {}", target_ads);
         }
-        
-        Ok(total_written)
+
+        let streamable = wof_info.is_none()
+            && matching.iter().all(|(_, attr)| attr.non_resident_flag != 0 && attr.flags & ATTRIBUTE_FLAG_COMPRESSED == 0);
+        if streamable {
+            let (runlist, real_size) = join_extents(&matching)?;
+            let reader = self.fs.mft.open_runlist(&runlist, real_size);
+            let size = reader.len();
+            return Ok((Box::new(reader), size));
+        }
+
+        let mut buffer = Vec::new();
+        for (r, attr) in &matching {
+            // [추가] WOF 시스템 압축 파일은 청크 테이블을 해제하여 원본 내용을 기록한다.
+            if let Some((algorithm, original_size)) = wof_info {
+                let mut compressed = Vec::new();
+                self.write_data_attribute(r, attr, &mut compressed)?;
+                buffer.extend(decompress_wof(&compressed, algorithm, original_size)?);
+            } else {
+                self.write_data_attribute(r, attr, &mut buffer)?;
+            }
+        }
+        let size = buffer.len() as u64;
+        Ok((Box::new(Cursor::new(buffer)), size))
     }
-}
\ No newline at end of file
+
+    /// $DATA 속성 하나를 기록한다. [추가] LZNT1 압축 속성(플래그 0x0001 + 압축 단위)은 압축 단위별로 해제한다.
+    fn write_data_attribute(&mut self, r: &[u8], attr: &AttributeHeader, writer: &mut dyn Write) -> Result<u64> {
+        if attr.non_resident_flag == 0 {
+            return match resident_content(r, attr) {
+                Some(content) => {
+                    writer.write_all(content)?;
+                    Ok(content.len() as u64)
+                },
+                None => Ok(0),
+            };
+        }
+
+        let Ok(nr) = parse_non_resident_header(&r[attr.offset..]) else { return Ok(0) };
+        let start = attr.offset + nr.run_array_offset as usize;
+        let end = std::cmp::min(attr.offset + attr.length as usize, r.len());
+        if start > end { return Ok(0); }
+        let Ok(runs) = parse_runlist(&r[start..end]) else { return Ok(0) };
+
+        if attr.flags & ATTRIBUTE_FLAG_COMPRESSED != 0 && nr.compression_unit != 0 {
+            return self.fs.mft.extract_compressed_runlist_to_writer(&runs, nr.compression_unit, nr.real_size, writer);
+        }
+        Ok(self.fs.mft.extract_runlist_to_writer(&runs, nr.real_size, writer).unwrap_or(0))
+    }
+
+    /// WOF 리파스 포인트(IO_REPARSE_TAG_WOF)의 압축 알고리즘과 기본 $DATA의 원본 크기
+    fn wof_info(&mut self, inodes: &[u64]) -> Option<(WofAlgorithm, u64)> {
+        let (mut algorithm, mut original_size) = (None, None);
+        for &inode in inodes {
+            let Ok(r) = self.fs.mft.read_record(inode) else { continue };
+            let Ok(h) = parse_file_record_header(&r) else { continue };
+            for attr in parse_attributes(&r, &h).unwrap_or_default() {
+                if attr.type_code == 0xC0 && attr.non_resident_flag == 0
+                    && let Some(content) = resident_content(&r, &attr)
+                    && content.len() >= 24
+                    && u32::from_le_bytes(content[0..4].try_into().unwrap()) == IO_REPARSE_TAG_WOF {
+                    algorithm = WofAlgorithm::from_u32(u32::from_le_bytes(content[20..24].try_into().unwrap()));
+                }
+                if attr.type_code == 0x80 && attr.name_length == 0 {
+                    original_size = if attr.non_resident_flag == 0 {
+                        resident_content(&r, &attr).map(|c| c.len() as u64)
+                    } else {
+                        parse_non_resident_header(&r[attr.offset..]).ok().map(|nr| nr.real_size)
+                    };
+                }
+            }
+        }
+        algorithm.zip(original_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_volume::*;
+    use models::artifact::ArtifactTarget;
+    use std::io::SeekFrom;
+
+    const ROOT_REFERENCE: u64 = (5 << 48) | 5;
+    const LOGS_REFERENCE: u64 = (1 << 48) | 11;
+
+    fn target(paths: &[&str], ads: Option<&str>, max_size: Option<u64>) -> CollectionTarget {
+        CollectionTarget {
+            name: "Test".into(),
+            parser: ArtifactTarget::EventLogs,
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+            extensions: Vec::new(),
+            ads: ads.map(str::to_string),
+            max_size,
+        }
+    }
+
+    fn entry(entry: u64, parent: u64, name: &str, flags: u32) -> Vec<u8> {
+        index_entry((1 << 48) | entry, Some(file_name(parent, name, flags)))
+    }
+
+    /// \Logs 아래 비상주(희소 런 포함) a.evtx, 상주 b.evtx, 빈 empty.evtx와 Zone.Identifier ADS가 있는 notes.txt
+    fn logs_volume() -> (MftReader, Vec<u8>) {
+        let content: Vec<u8> = (0..4 * CLUSTER_SIZE - 10).map(|i| (i % 241) as u8 + 1).collect();
+        let mut volume = Volume::new();
+        volume.record(5, file_record(5, 0x03, &[
+            resident(0x30, "", &file_name(ROOT_REFERENCE, ".", 0x1000_0000)),
+            resident(0x90, "$I30", &index_root(&[entry(11, ROOT_REFERENCE, "Logs", 0x1000_0000)])),
+        ]));
+        volume.record(11, file_record(1, 0x03, &[
+            resident(0x30, "", &file_name(ROOT_REFERENCE, "Logs", 0x1000_0000)),
+            resident(0x90, "$I30", &index_root(&[
+                entry(12, LOGS_REFERENCE, "a.evtx", 0x20),
+                entry(13, LOGS_REFERENCE, "b.evtx", 0x20),
+                entry(14, LOGS_REFERENCE, "empty.evtx", 0x20),
+                entry(15, LOGS_REFERENCE, "notes.txt", 0x20),
+            ])),
+        ]));
+        volume.record(12, file_record(1, 0x01, &[
+            resident(0x30, "", &file_name(LOGS_REFERENCE, "a.evtx", 0x20)),
+            non_resident(0x80, "", &[(Some(100), 2), (None, 1), (Some(120), 1)], content.len() as u64),
+        ]));
+        volume.record(13, file_record(1, 0x01, &[
+            resident(0x30, "", &file_name(LOGS_REFERENCE, "b.evtx", 0x20)),
+            resident(0x80, "", b"resident log"),
+        ]));
+        volume.record(14, file_record(1, 0x01, &[
+            resident(0x30, "", &file_name(LOGS_REFERENCE, "empty.evtx", 0x20)),
+            resident(0x80, "", b""),
+        ]));
+        volume.record(15, file_record(1, 0x01, &[
+            resident(0x30, "", &file_name(LOGS_REFERENCE, "notes.txt", 0x20)),
+            resident(0x80, "", b"notes"),
+            resident(0x80, "Zone.Identifier", b"[ZoneTransfer]\r\nZoneId=3\r\n"),
+        ]));
+
+        let mut expected = content.clone();
+        expected[2 * CLUSTER_SIZE..3 * CLUSTER_SIZE].fill(0);
+        volume.write(100, 0, &content[..2 * CLUSTER_SIZE]);
+        volume.write(120, 0, &content[3 * CLUSTER_SIZE..]);
+        (volume.reader(), expected)
+    }
+
+    fn collect(mft: &mut MftReader, target: &CollectionTarget) -> Vec<(CollectedFile, Vec<u8>)> {
+        let mut collected = Vec::new();
+        let mut collector = ForensicCollector::new(NtfsFileSystem::new(mft));
+        collector.collect_files(target, |file, reader| {
+            let mut data = Vec::new();
+            reader.read_to_end(&mut data).unwrap();
+            collected.push((file.clone(), data));
+        }).unwrap();
+        collected
+    }
+
+    #[test]
+    fn wildcard_target_streams_matching_files() {
+        let (mut mft, expected) = logs_volume();
+        let mut collected = collect(&mut mft, &target(&["Logs\\*.evtx"], None, None));
+        collected.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+
+        let names: Vec<(&str, &str)> = collected.iter().map(|(f, _)| (f.name.as_str(), f.path.as_str())).collect();
+        assert_eq!(names, [("a.evtx", "Logs\\a.evtx"), ("b.evtx", "Logs\\b.evtx")]);
+        assert_eq!(collected[0].0.size, expected.len() as u64);
+        assert_eq!(collected[0].1, expected);
+        assert_eq!(collected[0].0.record.as_ref().map(|r| r.entry_number), Some(12));
+        assert_eq!(collected[1].1, b"resident log");
+    }
+
+    #[test]
+    fn non_resident_stream_is_seekable_without_buffering() {
+        let (mut mft, expected) = logs_volume();
+        let mut collector = ForensicCollector::new(NtfsFileSystem::new(&mut mft));
+        let mut tail = Vec::new();
+        collector.collect_files(&target(&["Logs\\a.evtx"], None, None), |_, reader| {
+            reader.seek(SeekFrom::End(-20)).unwrap();
+            reader.read_to_end(&mut tail).unwrap();
+        }).unwrap();
+        assert_eq!(tail, expected[expected.len() - 20..]);
+    }
+
+    #[test]
+    fn max_size_and_named_streams_are_honoured() {
+        let (mut mft, _) = logs_volume();
+        let collected = collect(&mut mft, &target(&["Logs\\*.evtx"], None, Some(100)));
+        let names: Vec<&str> = collected.iter().map(|(f, _)| f.name.as_str()).collect();
+        assert_eq!(names, ["b.evtx"]);
+
+        let collected = collect(&mut mft, &target(&["Logs\\notes.txt"], Some("Zone.Identifier"), None));
+        assert_eq!(collected.len(), 1);
+        assert_eq!(collected[0].0.stream.as_deref(), Some("Zone.Identifier"));
+        assert_eq!(collected[0].1, b"[ZoneTransfer]\r\nZoneId=3\r\n");
+        assert!(collect(&mut mft, &target(&["Logs\\b.evtx"], Some("Zone.Identifier"), None)).is_empty());
+    }
+}
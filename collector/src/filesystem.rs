@@ -1,9 +1,9 @@
-use crate::mft::{MftReader, apply_fixup};
+use crate::mft::MftReader;
 use anyhow::{Result, bail};
 use models::mft::IndexEntry;
 use parser::mft::{
     parse_file_record_header, parse_attributes, parse_non_resident_header, 
-    parse_runlist, parse_index_entries, parse_index_record
+    parse_runlist, parse_index_entries, parse_index_record, apply_fixup
 };
 
 pub struct NtfsFileSystem<'a> {
@@ -19,6 +19,8 @@ impl<'a> NtfsFileSystem<'a> {
         let mut entries = Vec::new();
         let mut queue = vec![dir_index];
         let mut seen = std::collections::HashSet::new();
+        // [Fix] INDX 블록 크기를 4096으로 가정하지 않는다. VBR 값을 기본으로, $INDEX_ROOT에 기록된 값이 있으면 그 값을 쓴다.
+        let mut index_block_size = self.mft.index_record_size() as usize;
         
         while let Some(idx) = queue.pop() {
             if !seen.insert(idx) { continue; }
@@ -51,20 +53,18 @@ impl<'a> NtfsFileSystem<'a> {
                             if let Ok(nr) = parse_non_resident_header(&data[attr.offset..]) {
                                 let start = attr.offset + nr.run_array_offset as usize;
                                 let end = std::cmp::min(attr.offset + attr.length as usize, data.len());
-                                if start <= end {
-                                    if let Ok(runs) = parse_runlist(&data[start..end]) {
-                                        if let Ok(list) = self.mft.read_data_from_runlist(&runs, nr.real_size) {
-                                            let mut cur = 0;
-                                            while cur + 26 <= list.len() {
-                                                let t = u32::from_le_bytes(list[cur..cur+4].try_into().unwrap());
-                                                if t == 0x90 || t == 0xA0 { 
-                                                    let r = u64::from_le_bytes(list[cur+16..cur+24].try_into().unwrap()) & 0x0000FFFFFFFFFFFF;
-                                                    if r != idx && r != 0 { queue.push(r); }
-                                                }
-                                                let l = u16::from_le_bytes(list[cur+4..cur+6].try_into().unwrap()) as usize;
-                                                if l == 0 { break; } cur += l;
-                                            }
+                                if start <= end
+                                    && let Ok(runs) = parse_runlist(&data[start..end])
+                                    && let Ok(list) = self.mft.read_data_from_runlist(&runs, nr.real_size) {
+                                    let mut cur = 0;
+                                    while cur + 26 <= list.len() {
+                                        let t = u32::from_le_bytes(list[cur..cur+4].try_into().unwrap());
+                                        if t == 0x90 || t == 0xA0 { 
+                                            let r = u64::from_le_bytes(list[cur+16..cur+24].try_into().unwrap()) & 0x0000FFFFFFFFFFFF;
+                                            if r != idx && r != 0 { queue.push(r); }
                                         }
+                                        let l = u16::from_le_bytes(list[cur+4..cur+6].try_into().unwrap()) as usize;
+                                        if l == 0 { break; } cur += l;
                                     }
                                 }
                             }
@@ -76,6 +76,8 @@ impl<'a> NtfsFileSystem<'a> {
                         if attr.offset + v_off <= end {
                             let rd = &data[attr.offset+v_off .. end];
                             if rd.len() >= 32 {
+                                let size = u32::from_le_bytes([rd[8], rd[9], rd[10], rd[11]]) as usize;
+                                if size.is_power_of_two() && size >= 512 { index_block_size = size; }
                                 let first_entry = u32::from_le_bytes([rd[16], rd[17], rd[18], rd[19]]) as usize;
                                 if 16 + first_entry < rd.len() {
                                     let entries_data = &rd[16 + first_entry..];
@@ -88,17 +90,14 @@ impl<'a> NtfsFileSystem<'a> {
                         if let Ok(nr) = parse_non_resident_header(&data[attr.offset..]) {
                             let start = attr.offset + nr.run_array_offset as usize;
                             let end = std::cmp::min(attr.offset + attr.length as usize, data.len());
-                            if start <= end {
-                                if let Ok(runs) = parse_runlist(&data[start..end]) {
-                                    if let Ok(id) = self.mft.read_data_from_runlist(&runs, u64::MAX) {
-                                        for chunk in id.chunks(4096) { 
-                                            if chunk.len() < 4096 { continue; }
-                                            let mut fixed_chunk = chunk.to_vec();
-                                            let _ = apply_fixup(&mut fixed_chunk);
-                                            if let Ok(p) = parse_index_record(&fixed_chunk) {
-                                                entries.extend(p);
-                                            }
-                                        }
+                            if start <= end
+                                && let Ok(runs) = parse_runlist(&data[start..end])
+                                && let Ok(id) = self.mft.read_data_from_runlist(&runs, u64::MAX) {
+                                for chunk in id.chunks_exact(index_block_size) { 
+                                    let mut fixed_chunk = chunk.to_vec();
+                                    let _ = apply_fixup(&mut fixed_chunk);
+                                    if let Ok(p) = parse_index_record(&fixed_chunk) {
+                                        entries.extend(p);
                                     }
                                 }
                             }
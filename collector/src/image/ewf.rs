@@ -0,0 +1,769 @@
+use anyhow::{Context, Result, bail};
+use flate2::read::ZlibDecoder;
+use md5::{Digest, Md5};
+use models::FactError;
+use sha1::Sha1;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const EWF1_SIGNATURE: &[u8; 8] = b"EVF\x09\x0D\x0A\xFF\x00";
+const EWF2_SIGNATURE: &[u8; 8] = b"EVF2\x0D\x0A\x81\x00";
+
+const EWF1_SECTION_DESCRIPTOR_SIZE: u64 = 76;
+const EWF2_SECTION_DESCRIPTOR_SIZE: u64 = 64;
+const EWF2_FILE_HEADER_SIZE: u64 = 32;
+/// 청크 하나의 최대 크기. 실제 이미지는 32KB 전후이며, 손상된 볼륨 섹션으로 거대한 버퍼를 할당하지 않도록 제한한다.
+const MAX_CHUNK_SIZE: u64 = 64 * 1024 * 1024;
+
+// EWF2 섹션 타입 코드
+const EWF2_DEVICE_INFORMATION: u32 = 0x01;
+const EWF2_CASE_DATA: u32 = 0x02;
+const EWF2_SECTOR_TABLE: u32 = 0x04;
+const EWF2_MD5_HASH: u32 = 0x08;
+const EWF2_SHA1_HASH: u32 = 0x09;
+
+/// 파일 첫 8바이트로 E01(EWF1) 또는 Ex01(EWF2) 세그먼트 여부를 판별한다.
+pub fn is_ewf_signature(header: &[u8]) -> bool {
+    header.len() >= 8 && (&header[0..8] == EWF1_SIGNATURE || &header[0..8] == EWF2_SIGNATURE)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ChunkEncoding {
+    Stored,
+    Zlib,
+    Pattern(u64),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ChunkLocation {
+    segment: u16,
+    encoding: ChunkEncoding,
+    offset: u64,
+    size: u32,
+}
+
+/// 저장된 해시와 재구성한 미디어의 해시가 일치함을 확인한 결과
+#[derive(Debug, Clone)]
+pub struct EwfVerification {
+    pub md5: Option<String>,
+    pub sha1: Option<String>,
+}
+
+/// 세그먼트 파일(.E01/.E02... 또는 .Ex01/.Ex02...)로 분할·압축된 EWF 이미지를 연속된 미디어 스트림으로 노출한다.
+pub struct EwfImage {
+    segments: Vec<File>,
+    chunks: Vec<Option<ChunkLocation>>,
+    chunk_size: u64,
+    media_size: u64,
+    stored_md5: Option<[u8; 16]>,
+    stored_sha1: Option<[u8; 20]>,
+    pos: u64,
+    cached_chunk: Option<(usize, Vec<u8>)>,
+}
+
+/// 세그먼트 파싱 중 누적되는 미디어 기하 정보
+#[derive(Default)]
+struct MediaGeometry {
+    sectors_per_chunk: u64,
+    bytes_per_sector: u64,
+    sector_count: u64,
+}
+
+impl EwfImage {
+    /// 첫 세그먼트 경로를 받아 같은 세트의 모든 세그먼트를 찾아 청크 테이블을 구성한다.
+    pub fn open(first_segment: &Path) -> Result<Self> {
+        let segment_paths = find_segment_files(first_segment)?;
+        let mut image = Self {
+            segments: Vec::new(), chunks: Vec::new(), chunk_size: 0, media_size: 0,
+            stored_md5: None, stored_sha1: None, pos: 0, cached_chunk: None,
+        };
+        let mut geometry = MediaGeometry::default();
+
+        for (segment_idx, path) in segment_paths.iter().enumerate() {
+            let mut file = File::open(path).with_context(|| format!("Failed to open EWF segment {}", path.display()))?;
+            let mut signature = [0u8; 8];
+            file.read_exact(&mut signature)?;
+
+            if &signature == EWF1_SIGNATURE {
+                image.load_ewf1_segment(segment_idx as u16, &mut file, &mut geometry)
+                    .with_context(|| format!("Corrupted EWF segment {}", path.display()))?;
+            } else if &signature == EWF2_SIGNATURE {
+                image.load_ewf2_segment(segment_idx as u16, &mut file, &mut geometry)
+                    .with_context(|| format!("Corrupted Ex01 segment {}", path.display()))?;
+            } else {
+                bail!("{} is not an EWF segment", path.display());
+            }
+            image.segments.push(file);
+        }
+
+        if geometry.sectors_per_chunk == 0 { geometry.sectors_per_chunk = 64; }
+        if geometry.bytes_per_sector == 0 { geometry.bytes_per_sector = 512; }
+        image.chunk_size = geometry.sectors_per_chunk.checked_mul(geometry.bytes_per_sector)
+            .filter(|size| *size <= MAX_CHUNK_SIZE)
+            .with_context(|| format!("Invalid EWF chunk geometry ({} sectors x {} bytes)", geometry.sectors_per_chunk, geometry.bytes_per_sector))?;
+        image.media_size = if geometry.sector_count > 0 {
+            geometry.sector_count.checked_mul(geometry.bytes_per_sector)
+        } else {
+            (image.chunks.len() as u64).checked_mul(image.chunk_size)
+        }.with_context(|| format!("EWF media size overflows ({} sectors x {} bytes)", geometry.sector_count, geometry.bytes_per_sector))?;
+
+        if image.chunks.is_empty() { bail!("EWF image contains no chunk table"); }
+        tracing::info!("  [*] EWF image: {} segment(s), {} chunks x {} bytes, media size {} bytes",
+            image.segments.len(), image.chunks.len(), image.chunk_size, image.media_size);
+        Ok(image)
+    }
+
+    pub fn media_size(&self) -> u64 {
+        self.media_size
+    }
+
+    /// 전체 미디어를 재구성하여 세그먼트에 저장된 MD5/SHA1과 비교한다. 불일치는 FactError::HashMismatch로 보고한다.
+    pub fn verify_hashes(&mut self) -> Result<EwfVerification, FactError> {
+        if self.stored_md5.is_none() && self.stored_sha1.is_none() {
+            return Err(FactError::UnsupportedFormat("EWF image carries no stored MD5/SHA1 hash".into()));
+        }
+
+        let saved_pos = self.pos;
+        self.pos = 0;
+        let mut md5 = Md5::new();
+        let mut sha1 = Sha1::new();
+        let mut buffer = vec![0u8; 4 * 1024 * 1024];
+
+        loop {
+            let n = self.read(&mut buffer)?;
+            if n == 0 { break; }
+            md5.update(&buffer[..n]);
+            sha1.update(&buffer[..n]);
+        }
+        self.pos = saved_pos;
+
+        let mut result = EwfVerification { md5: None, sha1: None };
+        let computed_md5 = to_hex(&md5.finalize());
+        let computed_sha1 = to_hex(&sha1.finalize());
+
+        if let Some(stored) = self.stored_md5 {
+            let expected = to_hex(&stored);
+            if expected != computed_md5 {
+                return Err(FactError::HashMismatch {
+                    artifact_name: "EWF media".into(), algorithm: "MD5".into(), expected, actual: computed_md5,
+                });
+            }
+            result.md5 = Some(computed_md5);
+        }
+        if let Some(stored) = self.stored_sha1 {
+            let expected = to_hex(&stored);
+            if expected != computed_sha1 {
+                return Err(FactError::HashMismatch {
+                    artifact_name: "EWF media".into(), algorithm: "SHA1".into(), expected, actual: computed_sha1,
+                });
+            }
+            result.sha1 = Some(computed_sha1);
+        }
+        Ok(result)
+    }
+
+    fn load_ewf1_segment(&mut self, segment: u16, file: &mut File, geometry: &mut MediaGeometry) -> Result<()> {
+        let file_len = file.seek(SeekFrom::End(0))?;
+        let mut offset = 13u64; // 파일 헤더(시그니처 8 + fields start 1 + 세그먼트 번호 2 + fields end 2)
+        let mut sectors_range: Option<(u64, u64)> = None;
+
+        while offset + EWF1_SECTION_DESCRIPTOR_SIZE <= file_len {
+            let mut desc = [0u8; 76];
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_exact(&mut desc)?;
+
+            let section_type = String::from_utf8_lossy(&desc[0..16]).trim_end_matches('\0').to_string();
+            let next_offset = u64::from_le_bytes(desc[16..24].try_into().unwrap());
+            let section_size = u64::from_le_bytes(desc[24..32].try_into().unwrap());
+            let stored_checksum = u32::from_le_bytes(desc[72..76].try_into().unwrap());
+            if adler32(&desc[0..72]) != stored_checksum {
+                tracing::warn!("  [!] EWF section '{}' @ {:#X} has a bad descriptor checksum", section_type, offset);
+            }
+
+            let data_offset = offset + EWF1_SECTION_DESCRIPTOR_SIZE;
+            let data_size = section_size.saturating_sub(EWF1_SECTION_DESCRIPTOR_SIZE);
+
+            match section_type.as_str() {
+                "volume" | "disk" => {
+                    let data = read_section(file, data_offset, std::cmp::min(data_size, 1052))?;
+                    if data.len() >= 24 {
+                        geometry.sectors_per_chunk = u32::from_le_bytes(data[8..12].try_into().unwrap()) as u64;
+                        geometry.bytes_per_sector = u32::from_le_bytes(data[12..16].try_into().unwrap()) as u64;
+                        // SMART(94바이트) 볼륨 섹션은 섹터 수가 32비트이다.
+                        geometry.sector_count = if data_size >= 1052 {
+                            u64::from_le_bytes(data[16..24].try_into().unwrap())
+                        } else {
+                            u32::from_le_bytes(data[16..20].try_into().unwrap()) as u64
+                        };
+                    }
+                },
+                "sectors" => sectors_range = Some((data_offset, offset + section_size)),
+                "table" => {
+                    let data = read_section(file, data_offset, data_size)?;
+                    // 테이블의 마지막 청크는 sectors 섹션의 끝(또는 구형 포맷에서는 테이블 섹션 자체)에서 끝난다.
+                    let chunk_data_end = |chunk_offset: u64| match sectors_range {
+                        Some((start, end)) if chunk_offset >= start && chunk_offset < end => end,
+                        _ if chunk_offset > offset => offset + section_size,
+                        _ => offset,
+                    };
+                    self.load_ewf1_table(segment, &data, chunk_data_end)?;
+                },
+                "hash" => {
+                    let data = read_section(file, data_offset, std::cmp::min(data_size, 16))?;
+                    if data.len() == 16 { self.stored_md5 = Some(data[0..16].try_into().unwrap()); }
+                },
+                "digest" => {
+                    let data = read_section(file, data_offset, std::cmp::min(data_size, 36))?;
+                    if data.len() == 36 {
+                        self.stored_md5 = Some(data[0..16].try_into().unwrap());
+                        self.stored_sha1 = Some(data[16..36].try_into().unwrap());
+                    }
+                },
+                "next" | "done" => break,
+                _ => {}, // header, header2, table2(백업), error2, session 등은 미디어 재구성에 불필요
+            }
+
+            if next_offset <= offset { break; }
+            offset = next_offset;
+        }
+        Ok(())
+    }
+
+    fn load_ewf1_table(&mut self, segment: u16, data: &[u8], chunk_data_end: impl Fn(u64) -> u64) -> Result<()> {
+        if data.len() < 24 { bail!("Truncated table section"); }
+        let entry_count = u32::from_le_bytes(data[0..4].try_into().unwrap()) as usize;
+        let base_offset = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        if 24 + entry_count * 4 > data.len() { bail!("Table entry count {} exceeds section size", entry_count); }
+
+        let entries: Vec<(u64, bool)> = data[24..24 + entry_count * 4].chunks_exact(4)
+            .map(|c| {
+                let raw = u32::from_le_bytes([c[0], c[1], c[2], c[3]]);
+                (base_offset + (raw & 0x7FFF_FFFF) as u64, (raw & 0x8000_0000) != 0)
+            })
+            .collect();
+
+        for (i, &(chunk_offset, compressed)) in entries.iter().enumerate() {
+            let end = match entries.get(i + 1) {
+                Some(&(next, _)) => next,
+                None => chunk_data_end(chunk_offset),
+            };
+            let size = end.saturating_sub(chunk_offset);
+            self.chunks.push(Some(ChunkLocation {
+                segment,
+                encoding: if compressed { ChunkEncoding::Zlib } else { ChunkEncoding::Stored },
+                offset: chunk_offset,
+                size: size.min(u32::MAX as u64) as u32,
+            }));
+        }
+        Ok(())
+    }
+
+    /// EWF2는 섹션 디스크립터가 섹션 데이터 뒤에 위치하므로, 파일 끝에서부터 previous offset을 따라 역순으로 순회한다.
+    fn load_ewf2_segment(&mut self, segment: u16, file: &mut File, geometry: &mut MediaGeometry) -> Result<()> {
+        let mut header = [0u8; 32];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut header)?;
+        let compression_method = u16::from_le_bytes([header[10], header[11]]);
+
+        let file_len = file.seek(SeekFrom::End(0))?;
+        let mut descriptors = Vec::new();
+        let mut desc_offset = file_len.saturating_sub(EWF2_SECTION_DESCRIPTOR_SIZE);
+
+        while desc_offset >= EWF2_FILE_HEADER_SIZE && descriptors.len() < 1_000_000 {
+            let mut desc = [0u8; 64];
+            file.seek(SeekFrom::Start(desc_offset))?;
+            file.read_exact(&mut desc)?;
+            if adler32(&desc[0..60]) != u32::from_le_bytes(desc[60..64].try_into().unwrap()) {
+                bail!("Bad Ex01 section descriptor checksum @ {:#X}", desc_offset);
+            }
+
+            let section_type = u32::from_le_bytes(desc[0..4].try_into().unwrap());
+            let previous_offset = u64::from_le_bytes(desc[8..16].try_into().unwrap());
+            let data_size = u64::from_le_bytes(desc[16..24].try_into().unwrap());
+            descriptors.push((section_type, desc_offset, previous_offset, data_size));
+
+            if previous_offset == 0 || previous_offset >= desc_offset { break; }
+            desc_offset = previous_offset;
+        }
+        descriptors.reverse();
+
+        for (section_type, desc_offset, previous_offset, data_size) in descriptors {
+            let data_offset = if previous_offset == 0 { EWF2_FILE_HEADER_SIZE } else { previous_offset + EWF2_SECTION_DESCRIPTOR_SIZE };
+            let data_size = std::cmp::min(data_size, desc_offset.saturating_sub(data_offset));
+
+            match section_type {
+                EWF2_DEVICE_INFORMATION | EWF2_CASE_DATA => {
+                    let data = read_section(file, data_offset, data_size)?;
+                    let values = parse_ewf2_metadata(&data);
+                    let number = |key: &str| values.iter().find(|(k, _)| k == key).and_then(|(_, v)| v.trim().parse::<u64>().ok());
+                    if let Some(v) = number("ts") { geometry.sector_count = v; }
+                    if let Some(v) = number("bp") { geometry.bytes_per_sector = v; }
+                    if let Some(v) = number("sb") { geometry.sectors_per_chunk = v; }
+                },
+                EWF2_SECTOR_TABLE => {
+                    let data = read_section(file, data_offset, data_size)?;
+                    self.load_ewf2_table(segment, &data, compression_method)?;
+                },
+                EWF2_MD5_HASH => {
+                    let data = read_section(file, data_offset, std::cmp::min(data_size, 16))?;
+                    if data.len() == 16 { self.stored_md5 = Some(data[0..16].try_into().unwrap()); }
+                },
+                EWF2_SHA1_HASH => {
+                    let data = read_section(file, data_offset, std::cmp::min(data_size, 20))?;
+                    if data.len() == 20 { self.stored_sha1 = Some(data[0..20].try_into().unwrap()); }
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+
+    fn load_ewf2_table(&mut self, segment: u16, data: &[u8], compression_method: u16) -> Result<()> {
+        if data.len() < 32 { bail!("Truncated Ex01 sector table"); }
+        let first_chunk = u64::from_le_bytes(data[0..8].try_into().unwrap()) as usize;
+        let entry_count = u32::from_le_bytes(data[8..12].try_into().unwrap()) as usize;
+        if 32 + entry_count * 16 > data.len() { bail!("Ex01 sector table entry count {} exceeds section size", entry_count); }
+
+        if self.chunks.len() < first_chunk + entry_count {
+            self.chunks.resize(first_chunk + entry_count, None);
+        }
+
+        for (i, entry) in data[32..32 + entry_count * 16].chunks_exact(16).enumerate() {
+            let chunk_offset = u64::from_le_bytes(entry[0..8].try_into().unwrap());
+            let size = u32::from_le_bytes(entry[8..12].try_into().unwrap());
+            let flags = u32::from_le_bytes(entry[12..16].try_into().unwrap());
+
+            let encoding = if (flags & 0x04) != 0 {
+                ChunkEncoding::Pattern(chunk_offset)
+            } else if (flags & 0x01) != 0 {
+                if compression_method == 2 { bail!("bzip2-compressed Ex01 images are not supported"); }
+                ChunkEncoding::Zlib
+            } else {
+                ChunkEncoding::Stored
+            };
+            self.chunks[first_chunk + i] = Some(ChunkLocation { segment, encoding, offset: chunk_offset, size });
+        }
+        Ok(())
+    }
+
+    fn load_chunk(&mut self, index: usize) -> io::Result<()> {
+        if matches!(&self.cached_chunk, Some((cached, _)) if *cached == index) { return Ok(()); }
+
+        let location = self.chunks.get(index).copied().flatten()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("EWF chunk {} is missing from the chunk table", index)))?;
+        let chunk_size = self.chunk_size as usize;
+
+        let chunk = match location.encoding {
+            ChunkEncoding::Pattern(pattern) => pattern.to_le_bytes().iter().copied().cycle().take(chunk_size).collect(),
+            ChunkEncoding::Stored | ChunkEncoding::Zlib => {
+                let file = &mut self.segments[location.segment as usize];
+                file.seek(SeekFrom::Start(location.offset))?;
+                let mut raw = vec![0u8; location.size as usize];
+                file.read_exact(&mut raw)?;
+
+                if let ChunkEncoding::Zlib = location.encoding {
+                    // 손상된 청크가 무한정 풀리지 않도록 청크 크기보다 1바이트만 더 읽어 초과 여부를 판단한다.
+                    let mut decoded = Vec::with_capacity(chunk_size);
+                    ZlibDecoder::new(&raw[..]).take(self.chunk_size + 1).read_to_end(&mut decoded)?;
+                    if decoded.len() > chunk_size {
+                        return Err(io::Error::new(io::ErrorKind::InvalidData,
+                            format!("EWF chunk {} inflates beyond the {} byte chunk size", index, chunk_size)));
+                    }
+                    decoded
+                } else {
+                    // 비압축 청크 뒤에는 4바이트 Adler-32 체크섬이 붙어 있다.
+                    raw.truncate(chunk_size);
+                    raw
+                }
+            },
+        };
+        self.cached_chunk = Some((index, chunk));
+        Ok(())
+    }
+}
+
+impl Read for EwfImage {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.media_size || buf.is_empty() { return Ok(0); }
+
+        let index = (self.pos / self.chunk_size) as usize;
+        let offset_in_chunk = (self.pos % self.chunk_size) as usize;
+        self.load_chunk(index)?;
+
+        let chunk = &self.cached_chunk.as_ref().unwrap().1;
+        if offset_in_chunk >= chunk.len() { return Ok(0); }
+        let available = std::cmp::min(chunk.len() - offset_in_chunk, (self.media_size - self.pos) as usize);
+        let n = std::cmp::min(buf.len(), available);
+        buf[..n].copy_from_slice(&chunk[offset_in_chunk..offset_in_chunk + n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for EwfImage {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(p) => p as i128,
+            SeekFrom::End(off) => self.media_size as i128 + off as i128,
+            SeekFrom::Current(off) => self.pos as i128 + off as i128,
+        };
+        if new_pos < 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Seek before start of EWF media"));
+        }
+        self.pos = new_pos as u64;
+        Ok(self.pos)
+    }
+}
+
+fn read_section(file: &mut File, offset: u64, size: u64) -> Result<Vec<u8>> {
+    if size > 256 * 1024 * 1024 { bail!("Unreasonable EWF section size {}", size); }
+    let mut data = vec![0u8; size as usize];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// 같은 디렉터리에서 동일한 파일명(stem)과 같은 형태의 확장자를 가진 세그먼트를 모아 세그먼트 번호 순으로 정렬한다.
+fn find_segment_files(first_segment: &Path) -> Result<Vec<PathBuf>> {
+    let stem = first_segment.file_stem().and_then(|s| s.to_str()).context("Invalid EWF segment name")?;
+    let ext_len = first_segment.extension().and_then(|e| e.to_str()).map(|e| e.len()).unwrap_or(0);
+    let dir = match first_segment.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let mut segments = Vec::new();
+    for entry in std::fs::read_dir(&dir)?.flatten() {
+        let path = entry.path();
+        let same_stem = path.file_stem().and_then(|s| s.to_str()) == Some(stem);
+        let same_ext_len = path.extension().and_then(|e| e.to_str()).map(|e| e.len()) == Some(ext_len);
+        if !same_stem || !same_ext_len { continue; }
+
+        let mut header = [0u8; 16];
+        let Ok(mut file) = File::open(&path) else { continue };
+        if file.read_exact(&mut header).is_err() { continue; }
+
+        let number = if &header[0..8] == EWF1_SIGNATURE {
+            u16::from_le_bytes([header[9], header[10]]) as u32
+        } else if &header[0..8] == EWF2_SIGNATURE {
+            u32::from_le_bytes(header[12..16].try_into().unwrap())
+        } else {
+            continue;
+        };
+        segments.push((number, path));
+    }
+
+    segments.sort_by_key(|(n, _)| *n);
+    for (expected, (number, path)) in (1u32..).zip(&segments) {
+        if *number != expected { bail!("EWF segment {} missing (found {} at {})", expected, number, path.display()); }
+    }
+    if segments.is_empty() { bail!("No EWF segments found for {}", first_segment.display()); }
+    Ok(segments.into_iter().map(|(_, p)| p).collect())
+}
+
+/// Ex01 device information / case data 섹션(zlib 압축 UTF-16LE, 탭 구분 키/값 행)을 (키, 값) 목록으로 변환한다.
+fn parse_ewf2_metadata(data: &[u8]) -> Vec<(String, String)> {
+    let mut decoded = Vec::new();
+    if ZlibDecoder::new(data).read_to_end(&mut decoded).is_err() { return Vec::new(); }
+
+    let u16_data: Vec<u16> = decoded.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    let text = String::from_utf16_lossy(&u16_data).trim_start_matches('\u{FEFF}').replace('\r', "");
+    let lines: Vec<&str> = text.split('\n').collect();
+
+    // "1\nmain\n<keys>\n<values>\n" 형식: 키 행 바로 다음 행이 값 행이다.
+    let mut pairs = Vec::new();
+    for window in lines.windows(2) {
+        if !window[0].contains('\t') { continue; }
+        let keys: Vec<&str> = window[0].split('\t').collect();
+        let values: Vec<&str> = window[1].split('\t').collect();
+        if keys.len() != values.len() { continue; }
+        pairs.extend(keys.iter().zip(values.iter()).map(|(k, v)| (k.to_string(), v.to_string())));
+        break;
+    }
+    pairs
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use std::io::Write;
+
+    const CHUNK_SIZE: usize = 512;
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn media() -> Vec<Vec<u8>> {
+        vec![
+            (0..CHUNK_SIZE).map(|i| (i % 251) as u8).collect(),
+            b"ZLIB".iter().copied().cycle().take(CHUNK_SIZE).collect(),
+            vec![0xC3; CHUNK_SIZE],
+        ]
+    }
+
+    /// EWF1 세그먼트 작성기. 섹션 디스크립터 뒤에 데이터가 오고 next offset으로 다음 섹션을 가리킨다.
+    struct Ewf1Segment(Vec<u8>);
+
+    impl Ewf1Segment {
+        fn new(number: u16) -> Self {
+            let mut data = EWF1_SIGNATURE.to_vec();
+            data.push(1);
+            data.extend(number.to_le_bytes());
+            data.extend([0, 0]);
+            Self(data)
+        }
+
+        fn section(&mut self, kind: &str, data: &[u8]) {
+            let offset = self.0.len() as u64;
+            let size = EWF1_SECTION_DESCRIPTOR_SIZE + data.len() as u64;
+            let next = if kind == "next" || kind == "done" { offset } else { offset + size };
+            let mut desc = vec![0u8; 76];
+            desc[..kind.len()].copy_from_slice(kind.as_bytes());
+            desc[16..24].copy_from_slice(&next.to_le_bytes());
+            desc[24..32].copy_from_slice(&size.to_le_bytes());
+            let checksum = adler32(&desc[0..72]);
+            desc[72..76].copy_from_slice(&checksum.to_le_bytes());
+            self.0.extend(desc);
+            self.0.extend(data);
+        }
+
+        /// sectors 섹션에 (압축 여부, 청크 데이터)를 기록하고 이어서 table 섹션을 만든다.
+        fn chunks(&mut self, chunks: &[(bool, Vec<u8>)]) {
+            let mut sectors = Vec::new();
+            let mut entries = Vec::new();
+            let sectors_start = self.0.len() as u64 + EWF1_SECTION_DESCRIPTOR_SIZE;
+            for (compressed, chunk) in chunks {
+                let offset = (sectors_start + sectors.len() as u64) as u32;
+                entries.push(if *compressed { offset | 0x8000_0000 } else { offset });
+                if *compressed {
+                    sectors.extend(zlib(chunk));
+                } else {
+                    sectors.extend(chunk);
+                    sectors.extend(adler32(chunk).to_le_bytes());
+                }
+            }
+            self.section("sectors", &sectors);
+
+            let mut table = vec![0u8; 24];
+            table[0..4].copy_from_slice(&(entries.len() as u32).to_le_bytes());
+            for entry in entries {
+                table.extend(entry.to_le_bytes());
+            }
+            self.section("table", &table);
+        }
+    }
+
+    fn ewf1_volume(sector_count: u32) -> Vec<u8> {
+        let mut volume = vec![0u8; 94];
+        volume[8..12].copy_from_slice(&1u32.to_le_bytes());
+        volume[12..16].copy_from_slice(&(CHUNK_SIZE as u32).to_le_bytes());
+        volume[16..20].copy_from_slice(&sector_count.to_le_bytes());
+        volume
+    }
+
+    /// 청크 0(비압축)과 1(zlib)은 E01에, 청크 2는 E02에 둔 두 세그먼트 세트
+    fn write_ewf1_set(dir: &Path, md5: &[u8; 16]) -> PathBuf {
+        let media = media();
+        let mut first = Ewf1Segment::new(1);
+        first.section("volume", &ewf1_volume(3));
+        first.chunks(&[(false, media[0].clone()), (true, media[1].clone())]);
+        first.section("next", &[]);
+
+        let mut second = Ewf1Segment::new(2);
+        second.chunks(&[(false, media[2].clone())]);
+        second.section("hash", md5);
+        second.section("done", &[]);
+
+        let path = dir.join("case.E01");
+        std::fs::write(&path, first.0).unwrap();
+        std::fs::write(dir.join("case.E02"), second.0).unwrap();
+        path
+    }
+
+    /// EWF2 섹션: 데이터 뒤에 디스크립터가 오고, 디스크립터는 이전 디스크립터 위치를 가리킨다.
+    fn ewf2_section(segment: &mut Vec<u8>, previous: &mut u64, section_type: u32, data: &[u8]) {
+        segment.extend(data);
+        let mut desc = vec![0u8; 64];
+        desc[0..4].copy_from_slice(&section_type.to_le_bytes());
+        desc[8..16].copy_from_slice(&previous.to_le_bytes());
+        desc[16..24].copy_from_slice(&(data.len() as u64).to_le_bytes());
+        desc[24..28].copy_from_slice(&64u32.to_le_bytes());
+        let checksum = adler32(&desc[0..60]);
+        desc[60..64].copy_from_slice(&checksum.to_le_bytes());
+        *previous = segment.len() as u64;
+        segment.extend(desc);
+    }
+
+    fn write_ex01(dir: &Path) -> PathBuf {
+        write_ex01_with_geometry(dir, &format!("1\t{}\t3", CHUNK_SIZE))
+    }
+
+    /// geometry는 device information의 "sb\tbp\tts" 값 행이다.
+    fn write_ex01_with_geometry(dir: &Path, geometry: &str) -> PathBuf {
+        let media = media();
+        let mut segment = EWF2_SIGNATURE.to_vec();
+        segment.extend([2, 1]);
+        segment.extend(1u16.to_le_bytes()); // zlib
+        segment.extend(1u32.to_le_bytes());
+        segment.resize(EWF2_FILE_HEADER_SIZE as usize, 0);
+        let mut previous = 0u64;
+
+        let info: Vec<u8> = format!("\u{FEFF}1\nmain\nsb\tbp\tts\n{}\n\n", geometry)
+            .encode_utf16().flat_map(u16::to_le_bytes).collect();
+        ewf2_section(&mut segment, &mut previous, EWF2_DEVICE_INFORMATION, &zlib(&info));
+
+        // 청크 0은 비압축, 청크 1은 zlib, 청크 2는 8바이트 패턴으로 채운 청크
+        let sectors_start = segment.len() as u64;
+        let compressed = zlib(&media[1]);
+        let mut sectors = media[0].clone();
+        sectors.extend(&compressed);
+        ewf2_section(&mut segment, &mut previous, 0x03, &sectors);
+
+        let mut table = vec![0u8; 32];
+        table[8..12].copy_from_slice(&3u32.to_le_bytes());
+        for (offset, size, flags) in [
+            (sectors_start, CHUNK_SIZE as u32, 0u32),
+            (sectors_start + CHUNK_SIZE as u64, compressed.len() as u32, 0x01),
+            (u64::from_le_bytes([0xC3; 8]), 0, 0x05),
+        ] {
+            table.extend(offset.to_le_bytes());
+            table.extend(size.to_le_bytes());
+            table.extend(flags.to_le_bytes());
+        }
+        ewf2_section(&mut segment, &mut previous, EWF2_SECTOR_TABLE, &table);
+
+        let flat = media.concat();
+        ewf2_section(&mut segment, &mut previous, EWF2_MD5_HASH, &Md5::digest(&flat));
+        ewf2_section(&mut segment, &mut previous, EWF2_SHA1_HASH, &Sha1::digest(&flat));
+
+        let path = dir.join("case.Ex01");
+        std::fs::write(&path, segment).unwrap();
+        path
+    }
+
+    fn read_all(image: &mut EwfImage) -> Vec<u8> {
+        let mut data = Vec::new();
+        image.read_to_end(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn ewf1_multi_segment_set_reassembles_media() {
+        let dir = tempfile::tempdir().unwrap();
+        let md5: [u8; 16] = Md5::digest(media().concat()).into();
+        let path = write_ewf1_set(dir.path(), &md5);
+
+        let mut image = EwfImage::open(&path).unwrap();
+        assert_eq!(image.media_size(), 3 * CHUNK_SIZE as u64);
+        assert_eq!(read_all(&mut image), media().concat());
+
+        // 세그먼트 경계를 넘는 임의 위치 읽기
+        let mut buf = [0u8; 8];
+        image.seek(SeekFrom::Start(2 * CHUNK_SIZE as u64 - 4)).unwrap();
+        image.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ZLIB\xC3\xC3\xC3\xC3");
+
+        let verification = image.verify_hashes().unwrap();
+        assert_eq!(verification.md5, Some(to_hex(&md5)));
+        assert_eq!(verification.sha1, None);
+    }
+
+    #[test]
+    fn ewf1_hash_mismatch_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_ewf1_set(dir.path(), &[0u8; 16]);
+
+        let mut image = EwfImage::open(&path).unwrap();
+        match image.verify_hashes() {
+            Err(FactError::HashMismatch { algorithm, expected, actual, .. }) => {
+                assert_eq!(algorithm, "MD5");
+                assert_eq!(expected, "0".repeat(32));
+                assert_eq!(actual, to_hex(&Md5::digest(media().concat())));
+            },
+            other => panic!("expected HashMismatch, got {:?}", other.map(|v| v.md5)),
+        }
+    }
+
+    #[test]
+    fn missing_segment_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_ewf1_set(dir.path(), &[0u8; 16]);
+        // 두 번째 세그먼트의 헤더 번호를 3으로 바꾸면 2번 세그먼트가 빠진 세트가 된다.
+        let second = dir.path().join("case.E02");
+        let mut data = std::fs::read(&second).unwrap();
+        data[9..11].copy_from_slice(&3u16.to_le_bytes());
+        std::fs::write(&second, data).unwrap();
+        assert!(EwfImage::open(&path).is_err());
+    }
+
+    #[test]
+    fn ex01_stored_zlib_and_pattern_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_ex01(dir.path());
+
+        let mut image = EwfImage::open(&path).unwrap();
+        assert_eq!(image.media_size(), 3 * CHUNK_SIZE as u64);
+        assert_eq!(read_all(&mut image), media().concat());
+
+        let verification = image.verify_hashes().unwrap();
+        assert!(verification.md5.is_some());
+        assert_eq!(verification.sha1, Some(to_hex(&Sha1::digest(media().concat()))));
+    }
+
+    #[test]
+    fn ex01_bad_descriptor_checksum_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_ex01(dir.path());
+        let mut data = std::fs::read(&path).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0xFF;
+        std::fs::write(&path, data).unwrap();
+        assert!(EwfImage::open(&path).is_err());
+    }
+
+    #[test]
+    fn overflowing_geometry_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        for geometry in [format!("1\t{}\t{}", CHUNK_SIZE, u64::MAX / 2), format!("{}\t{}\t3", u64::MAX / 2, CHUNK_SIZE)] {
+            let path = write_ex01_with_geometry(dir.path(), &geometry);
+            assert!(EwfImage::open(&path).is_err(), "{}", geometry);
+        }
+    }
+
+    #[test]
+    fn over_long_zlib_chunk_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut segment = Ewf1Segment::new(1);
+        segment.section("volume", &ewf1_volume(2));
+        segment.chunks(&[(false, media()[0].clone()), (true, vec![0x41; CHUNK_SIZE * 4])]);
+        segment.section("done", &[]);
+        let path = dir.path().join("bomb.E01");
+        std::fs::write(&path, segment.0).unwrap();
+
+        let mut image = EwfImage::open(&path).unwrap();
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        image.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, media()[0]);
+        let err = image.read_exact(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,219 @@
+pub mod ewf;
+pub mod partition;
+pub mod vhd;
+pub mod vhdx;
+pub mod vmdk;
+pub mod vss;
+
+use anyhow::{Context, Result};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// MFT 엔진이 부트스트랩할 수 있는 모든 바이트 소스 (라이브 볼륨 핸들, dd/raw 이미지 등)
+pub use models::io::ReadSeek;
+
+/// 차분(differencing) 디스크의 부모 체인 최대 깊이 (순환 참조 방지)
+const MAX_PARENT_DEPTH: usize = 16;
+
+/// 획득한 디스크 이미지 파일을 열어 MftReader가 소비할 수 있는 소스로 반환한다.
+/// 시그니처로 컨테이너 포맷(E01/Ex01, VHD/VHDX, VMDK)을 판별하고, 그 외에는 raw(dd) 이미지로 취급한다.
+pub fn open_image(path: &Path) -> Result<Box<dyn ReadSeek>> {
+    open_image_chain(path, 0)
+}
+
+/// 차분 디스크가 부모 이미지를 열 때 사용한다. depth는 현재 자식의 체인 깊이이다.
+pub(crate) fn open_parent_image(path: &Path, depth: usize) -> Result<Box<dyn ReadSeek>> {
+    if depth + 1 > MAX_PARENT_DEPTH { anyhow::bail!("Parent disk chain exceeds {} levels at {}", MAX_PARENT_DEPTH, path.display()); }
+    tracing::info!("  [*] Opening parent disk {}", path.display());
+    open_image_chain(path, depth + 1)
+}
+
+fn open_image_chain(path: &Path, depth: usize) -> Result<Box<dyn ReadSeek>> {
+    let mut file = File::open(path).with_context(|| format!("Failed to open image {}", path.display()))?;
+
+    let mut signature = [0u8; 32];
+    let n = file.read(&mut signature)?;
+    let signature = &signature[..n];
+
+    if ewf::is_ewf_signature(signature) {
+        return Ok(Box::new(ewf::EwfImage::open(path)?));
+    }
+    if vhdx::is_vhdx_signature(signature) {
+        return Ok(Box::new(VirtualDiskStream::new(vhdx::VhdxImage::open(path, depth)?)));
+    }
+    if vmdk::is_vmdk_signature(signature) {
+        return Ok(Box::new(VirtualDiskStream::new(vmdk::VmdkImage::open(path, depth)?)));
+    }
+    // VHD는 고정 디스크의 경우 파일 끝 512바이트에만 푸터가 존재한다.
+    if vhd::has_vhd_footer(&mut file)? {
+        return Ok(Box::new(VirtualDiskStream::new(vhd::VhdImage::open(path, depth)?)));
+    }
+
+    file.seek(SeekFrom::Start(0))?;
+    Ok(Box::new(file))
+}
+
+/// 부모 로케이터에 기록된 경로 후보들을 자식 이미지 위치 기준으로 해석한다.
+/// 획득 장비의 절대 경로는 분석 환경에 없으므로, 최종적으로 자식과 같은 디렉터리의 동일 파일명을 시도한다.
+pub(crate) fn resolve_parent_path(child: &Path, candidates: &[String]) -> Option<PathBuf> {
+    let child_dir = child.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+
+    for candidate in candidates {
+        let normalized = candidate.trim().trim_start_matches("file://").replace('\\', "/");
+        if normalized.is_empty() { continue; }
+
+        let as_given = PathBuf::from(&normalized);
+        let relative = child_dir.join(normalized.trim_start_matches("./"));
+        let sibling = normalized.rsplit('/').next().map(|name| child_dir.join(name));
+
+        for path in [Some(relative), Some(as_given), sibling].into_iter().flatten() {
+            if path.is_file() { return Some(path); }
+        }
+    }
+    None
+}
+
+/// 헤더에 기록된 위치/길이로 파일 구간을 읽는다. 구간이 파일 밖을 가리키면 버퍼를 할당하기 전에 거부한다.
+pub(crate) fn read_file_region(file: &mut File, offset: u64, length: u64, what: &str) -> Result<Vec<u8>> {
+    let file_len = file.seek(SeekFrom::End(0))?;
+    if offset.checked_add(length).is_none_or(|end| end > file_len) {
+        anyhow::bail!("{} ({} bytes @ {:#X}) exceeds file size {}", what, length, offset, file_len);
+    }
+    let mut data = vec![0u8; length as usize];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// 블록 단위로 매핑되는 가상 디스크 포맷(VHD/VHDX/VMDK)의 공통 인터페이스
+pub trait VirtualDisk {
+    /// 게스트에게 보이는 가상 디스크 크기(바이트)
+    fn disk_size(&self) -> u64;
+
+    /// offset에서 최대 buf.len() 바이트를 읽는다. 블록 경계에서 요청보다 짧게 반환할 수 있다.
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize>;
+}
+
+/// VirtualDisk를 연속된 Read + Seek 스트림으로 노출하는 어댑터
+pub struct VirtualDiskStream<D> {
+    disk: D,
+    pos: u64,
+}
+
+impl<D: VirtualDisk> VirtualDiskStream<D> {
+    pub(crate) fn new(disk: D) -> Self {
+        Self { disk, pos: 0 }
+    }
+}
+
+impl<D: VirtualDisk> Read for VirtualDiskStream<D> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = self.disk.disk_size();
+        if self.pos >= size || buf.is_empty() { return Ok(0); }
+        let to_read = std::cmp::min(buf.len() as u64, size - self.pos) as usize;
+        let n = self.disk.read_at(self.pos, &mut buf[..to_read])?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<D: VirtualDisk> Seek for VirtualDiskStream<D> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(p) => p as i128,
+            SeekFrom::End(off) => self.disk.disk_size() as i128 + off as i128,
+            SeekFrom::Current(off) => self.pos as i128 + off as i128,
+        };
+        if new_pos < 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Seek before start of virtual disk"));
+        }
+        self.pos = new_pos as u64;
+        Ok(self.pos)
+    }
+}
+
+/// 부모 디스크(또는 자식이 소유하지 않은 영역)에서 지정 위치를 읽는다. 부모가 없으면 0으로 채운다.
+pub(crate) fn read_parent_or_zero(parent: &mut Option<Box<dyn ReadSeek>>, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+    match parent {
+        Some(parent) => {
+            parent.seek(SeekFrom::Start(offset))?;
+            parent.read_exact(buf)
+        },
+        None => {
+            buf.fill(0);
+            Ok(())
+        },
+    }
+}
+
+/// 하나의 물리 디스크 이미지를 여러 볼륨 뷰(VolumeSlice)가 공유하기 위한 핸들
+#[derive(Clone)]
+pub struct SharedImage {
+    inner: Rc<RefCell<Box<dyn ReadSeek>>>,
+}
+
+impl SharedImage {
+    pub fn new(source: Box<dyn ReadSeek>) -> Self {
+        Self { inner: Rc::new(RefCell::new(source)) }
+    }
+
+    /// 이미지 전체 크기(바이트)
+    pub fn len(&self) -> io::Result<u64> {
+        self.inner.borrow_mut().seek(SeekFrom::End(0))
+    }
+
+    pub fn is_empty(&self) -> io::Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// 절대 오프셋에서 버퍼를 가득 채워 읽는다.
+    pub fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        let mut source = self.inner.borrow_mut();
+        source.seek(SeekFrom::Start(offset))?;
+        source.read_exact(buf)
+    }
+
+    /// [start, start + length) 구간만 노출하는 볼륨 뷰를 생성한다.
+    pub fn slice(&self, start: u64, length: u64) -> VolumeSlice {
+        VolumeSlice { image: self.clone(), start, length, pos: 0 }
+    }
+}
+
+/// 디스크 이미지 내 특정 파티션을 오프셋 0부터 시작하는 독립 볼륨처럼 보이게 하는 Read + Seek 뷰
+pub struct VolumeSlice {
+    image: SharedImage,
+    start: u64,
+    length: u64,
+    pos: u64,
+}
+
+impl Read for VolumeSlice {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.length { return Ok(0); }
+        let to_read = std::cmp::min(buf.len() as u64, self.length - self.pos) as usize;
+
+        let mut source = self.image.inner.borrow_mut();
+        source.seek(SeekFrom::Start(self.start + self.pos))?;
+        let n = source.read(&mut buf[..to_read])?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for VolumeSlice {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(p) => p as i128,
+            SeekFrom::End(off) => self.length as i128 + off as i128,
+            SeekFrom::Current(off) => self.pos as i128 + off as i128,
+        };
+        if new_pos < 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Seek before start of volume"));
+        }
+        self.pos = new_pos as u64;
+        Ok(self.pos)
+    }
+}
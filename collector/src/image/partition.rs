@@ -0,0 +1,288 @@
+use crate::image::SharedImage;
+use anyhow::{Context, Result, bail};
+use models::partition::{PartitionEntry, PartitionScheme};
+use parser::partition::{is_ntfs_vbr, parse_gpt_entries, parse_gpt_header, parse_mbr, MbrEntry};
+use std::collections::HashSet;
+
+const MAX_EBR_CHAIN: usize = 128;
+const MAX_GPT_ENTRIES: u32 = 1024;
+/// UEFI 사양상 파티션 엔트리 크기는 128 * 2^n 이다. 손상된 헤더로 거대한 테이블을 할당하지 않도록 상한을 둔다.
+const MIN_GPT_ENTRY_SIZE: usize = 128;
+const MAX_GPT_ENTRY_SIZE: usize = 4096;
+
+/// 디스크 이미지의 파티션 테이블(MBR/EBR/GPT)을 해석하여 모든 파티션을 나열한다.
+/// 오프셋 0에 NTFS VBR이 있으면 파티션 테이블이 없는 볼륨 이미지로 간주한다.
+pub fn discover_partitions(image: &SharedImage) -> Result<Vec<PartitionEntry>> {
+    let image_len = image.len()?;
+    let mut sector0 = [0u8; 512];
+    image.read_at(0, &mut sector0)?;
+
+    if is_ntfs_vbr(&sector0) {
+        return Ok(vec![PartitionEntry {
+            index: 0, scheme: PartitionScheme::Volume, type_id: "NTFS".into(),
+            name: "Volume Image".into(), start_offset: 0, length: image_len, is_ntfs: true,
+        }]);
+    }
+
+    let mbr = match parse_mbr(&sector0) {
+        Ok(entries) => entries,
+        Err(e) => bail!("Neither an NTFS volume nor a partitioned disk image: {}", e),
+    };
+
+    if mbr.iter().any(|e| e.is_protective()) {
+        for sector_size in [512u64, 4096] {
+            if let Ok(parts) = read_gpt(image, sector_size, image_len) {
+                return Ok(parts);
+            }
+        }
+        tracing::warn!("  [!] Protective MBR found but GPT header is unreadable, falling back to MBR slots");
+    }
+
+    read_mbr(image, &mbr, image_len)
+}
+
+/// NTFS VBR 시그니처가 확인된 파티션만 반환한다.
+pub fn discover_ntfs_volumes(image: &SharedImage) -> Result<Vec<PartitionEntry>> {
+    Ok(discover_partitions(image)?.into_iter().filter(|p| p.is_ntfs).collect())
+}
+
+fn probe_ntfs(image: &SharedImage, offset: u64) -> bool {
+    let mut vbr = [0u8; 512];
+    image.read_at(offset, &mut vbr).is_ok() && is_ntfs_vbr(&vbr)
+}
+
+fn read_gpt(image: &SharedImage, sector_size: u64, image_len: u64) -> Result<Vec<PartitionEntry>> {
+    let mut header_buf = vec![0u8; 512];
+    image.read_at(sector_size, &mut header_buf)?;
+    let header = parse_gpt_header(&header_buf)?;
+
+    let count = std::cmp::min(header.num_partition_entries, MAX_GPT_ENTRIES) as usize;
+    let entry_size = header.partition_entry_size as usize;
+    if !(MIN_GPT_ENTRY_SIZE..=MAX_GPT_ENTRY_SIZE).contains(&entry_size) || !entry_size.is_multiple_of(8) {
+        bail!("Invalid GPT partition entry size {}", entry_size);
+    }
+    let table_len = count.checked_mul(entry_size).context("GPT partition table size overflows")?;
+    let table_offset = header.partition_entry_lba.checked_mul(sector_size)
+        .with_context(|| format!("GPT partition entry LBA {} is out of range", header.partition_entry_lba))?;
+    let mut table = vec![0u8; table_len];
+    image.read_at(table_offset, &mut table)?;
+
+    let mut partitions = Vec::new();
+    for entry in parse_gpt_entries(&table, entry_size)? {
+        if entry.last_lba < entry.first_lba { continue; }
+        let Some(start_offset) = entry.first_lba.checked_mul(sector_size) else { continue };
+        let length = (entry.last_lba - entry.first_lba).saturating_add(1).saturating_mul(sector_size);
+        if start_offset >= image_len { continue; }
+
+        let name = if entry.name.is_empty() { entry.type_description().to_string() } else { entry.name.clone() };
+        partitions.push(PartitionEntry {
+            index: partitions.len(), scheme: PartitionScheme::Gpt, type_id: entry.type_guid.clone(),
+            name, start_offset, length, is_ntfs: probe_ntfs(image, start_offset),
+        });
+    }
+    Ok(partitions)
+}
+
+/// 4Kn 디스크의 MBR은 LBA가 4096바이트 단위이므로, 첫 번째 일반 파티션의 VBR 위치로 섹터 크기를 추정한다.
+fn detect_mbr_sector_size(image: &SharedImage, mbr: &[MbrEntry]) -> u64 {
+    if let Some(primary) = mbr.iter().find(|e| !e.is_extended()) {
+        let lba = primary.start_lba as u64;
+        if !probe_ntfs(image, lba * 512) && probe_ntfs(image, lba * 4096) {
+            return 4096;
+        }
+    }
+    512
+}
+
+fn read_mbr(image: &SharedImage, mbr: &[MbrEntry], image_len: u64) -> Result<Vec<PartitionEntry>> {
+    let sector_size = detect_mbr_sector_size(image, mbr);
+    let mut partitions = Vec::new();
+
+    let push = |partitions: &mut Vec<PartitionEntry>, entry: &MbrEntry, base_lba: u64| {
+        let start_offset = (base_lba + entry.start_lba as u64) * sector_size;
+        if start_offset >= image_len { return; }
+        partitions.push(PartitionEntry {
+            index: partitions.len(), scheme: PartitionScheme::Mbr,
+            type_id: format!("0x{:02X}", entry.partition_type), name: entry.type_description().to_string(),
+            start_offset, length: entry.sector_count as u64 * sector_size,
+            is_ntfs: probe_ntfs(image, start_offset),
+        });
+    };
+
+    for entry in mbr {
+        if !entry.is_extended() {
+            push(&mut partitions, entry, 0);
+            continue;
+        }
+
+        // 확장 파티션: EBR 체인을 따라가며 논리 드라이브를 수집한다.
+        // 논리 파티션의 시작 LBA는 현재 EBR 기준, 다음 EBR의 LBA는 확장 파티션 시작 기준이다.
+        let extended_base = entry.start_lba as u64;
+        let mut ebr_lba = extended_base;
+        let mut visited = HashSet::new();
+
+        while visited.len() < MAX_EBR_CHAIN && visited.insert(ebr_lba) {
+            let mut ebr = [0u8; 512];
+            if image.read_at(ebr_lba * sector_size, &mut ebr).is_err() { break; }
+            let Ok(slots) = parse_mbr(&ebr) else { break };
+
+            if let Some(logical) = slots.iter().find(|e| !e.is_extended()) {
+                push(&mut partitions, logical, ebr_lba);
+            }
+            match slots.iter().find(|e| e.is_extended()) {
+                Some(next) => ebr_lba = extended_base + next.start_lba as u64,
+                None => break,
+            }
+        }
+    }
+    Ok(partitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const SECTOR: usize = 512;
+    const BASIC_DATA: &str = "EBD0A0A2-B9E5-4433-87C0-68B6B72699C7";
+    const EFI_SYSTEM: &str = "C12A7328-F81F-11D2-BA4B-00A0C93EC93B";
+
+    fn image(data: Vec<u8>) -> SharedImage {
+        SharedImage::new(Box::new(Cursor::new(data)))
+    }
+
+    fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u64(data: &mut [u8], offset: usize, value: u64) {
+        data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn guid_bytes(guid: &str) -> [u8; 16] {
+        let hex: String = guid.chars().filter(|c| *c != '-').collect();
+        let raw: Vec<u8> = (0..16).map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap()).collect();
+        let mut bytes = [0u8; 16];
+        bytes[0..4].copy_from_slice(&[raw[3], raw[2], raw[1], raw[0]]);
+        bytes[4..8].copy_from_slice(&[raw[5], raw[4], raw[7], raw[6]]);
+        bytes[8..16].copy_from_slice(&raw[8..16]);
+        bytes
+    }
+
+    /// (타입, 시작 LBA, 섹터 수) 슬롯으로 MBR/EBR 섹터를 채운다.
+    fn partition_table(data: &mut [u8], lba: usize, slots: &[(u8, u32, u32)]) {
+        let sector = &mut data[lba * SECTOR..(lba + 1) * SECTOR];
+        for (slot, &(partition_type, start_lba, sector_count)) in slots.iter().enumerate() {
+            let base = 446 + slot * 16;
+            sector[base + 4] = partition_type;
+            put_u32(sector, base + 8, start_lba);
+            put_u32(sector, base + 12, sector_count);
+        }
+        sector[510..512].copy_from_slice(&[0x55, 0xAA]);
+    }
+
+    fn ntfs_vbr(data: &mut [u8], lba: usize) {
+        let sector = &mut data[lba * SECTOR..(lba + 1) * SECTOR];
+        sector[3..11].copy_from_slice(b"NTFS    ");
+        sector[510..512].copy_from_slice(&[0x55, 0xAA]);
+    }
+
+    /// 보호 MBR + GPT 헤더(LBA 1) + 엔트리 배열(LBA 2). 엔트리는 (타입 GUID, 첫 LBA, 마지막 LBA, 이름)이다.
+    fn gpt_disk(entry_size: u32, entry_lba: u64, entries: &[(&str, u64, u64, &str)]) -> Vec<u8> {
+        let mut data = vec![0u8; 8192 * SECTOR];
+        partition_table(&mut data, 0, &[(0xEE, 1, u32::MAX)]);
+        let header = &mut data[SECTOR..2 * SECTOR];
+        header[0..8].copy_from_slice(b"EFI PART");
+        put_u64(header, 24, 1);
+        put_u64(header, 72, entry_lba);
+        put_u32(header, 80, 128);
+        put_u32(header, 84, entry_size);
+
+        for (i, &(type_guid, first_lba, last_lba, name)) in entries.iter().enumerate() {
+            let raw = &mut data[2 * SECTOR + i * 128..2 * SECTOR + (i + 1) * 128];
+            raw[0..16].copy_from_slice(&guid_bytes(type_guid));
+            raw[16..32].fill(0x11);
+            put_u64(raw, 32, first_lba);
+            put_u64(raw, 40, last_lba);
+            for (j, unit) in name.encode_utf16().enumerate() {
+                raw[56 + j * 2..58 + j * 2].copy_from_slice(&unit.to_le_bytes());
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn bare_volume_image_is_a_single_partition() {
+        let mut data = vec![0u8; 16 * SECTOR];
+        ntfs_vbr(&mut data, 0);
+        let partitions = discover_partitions(&image(data)).unwrap();
+        assert_eq!(partitions.len(), 1);
+        assert_eq!((partitions[0].scheme, partitions[0].start_offset, partitions[0].length), (PartitionScheme::Volume, 0, 16 * SECTOR as u64));
+    }
+
+    #[test]
+    fn mbr_primary_partitions_are_probed_for_ntfs() {
+        let mut data = vec![0u8; 8192 * SECTOR];
+        partition_table(&mut data, 0, &[(0x07, 2048, 2048), (0x0B, 4096, 2048), (0x07, 100_000, 2048)]);
+        ntfs_vbr(&mut data, 2048);
+
+        let partitions = discover_partitions(&image(data)).unwrap();
+        // 이미지 밖을 가리키는 세 번째 슬롯은 버린다.
+        assert_eq!(partitions.len(), 2);
+        assert_eq!(partitions[0].scheme, PartitionScheme::Mbr);
+        assert_eq!((partitions[0].type_id.as_str(), partitions[0].start_offset, partitions[0].length), ("0x07", 1024 * 1024, 1024 * 1024));
+        assert!(partitions[0].is_ntfs);
+        assert_eq!((partitions[1].name.as_str(), partitions[1].is_ntfs), ("FAT32", false));
+    }
+
+    #[test]
+    fn ebr_chain_yields_logical_drives_and_stops_on_a_loop() {
+        let mut data = vec![0u8; 8192 * SECTOR];
+        partition_table(&mut data, 0, &[(0x07, 2048, 1024), (0x0F, 4096, 4096)]);
+        ntfs_vbr(&mut data, 2048);
+        // 논리 드라이브 시작은 현재 EBR 기준, 다음 EBR은 확장 파티션 시작 기준이다.
+        partition_table(&mut data, 4096, &[(0x07, 63, 1000), (0x05, 2048, 2048)]);
+        ntfs_vbr(&mut data, 4096 + 63);
+        // 마지막 EBR이 첫 EBR을 다시 가리키는 손상된 체인
+        partition_table(&mut data, 6144, &[(0x0B, 63, 1000), (0x05, 0, 2048)]);
+
+        let partitions = discover_ntfs_volumes(&image(data.clone())).unwrap();
+        let offsets: Vec<u64> = partitions.iter().map(|p| p.start_offset).collect();
+        assert_eq!(offsets, [2048 * SECTOR as u64, (4096 + 63) * SECTOR as u64]);
+
+        let all = discover_partitions(&image(data)).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!((all[2].start_offset, all[2].name.as_str()), ((6144 + 63) * SECTOR as u64, "FAT32"));
+    }
+
+    #[test]
+    fn gpt_entries_are_listed_with_names() {
+        let mut data = gpt_disk(128, 2, &[(EFI_SYSTEM, 34, 2047, ""), (BASIC_DATA, 2048, 4095, "Basic data partition"), (BASIC_DATA, 1 << 40, 1 << 41, "")]);
+        ntfs_vbr(&mut data, 2048);
+
+        let partitions = discover_partitions(&image(data)).unwrap();
+        assert_eq!(partitions.len(), 2);
+        assert_eq!((partitions[0].name.as_str(), partitions[0].is_ntfs), ("EFI System", false));
+        assert_eq!(partitions[1].scheme, PartitionScheme::Gpt);
+        assert_eq!(partitions[1].type_id, BASIC_DATA);
+        assert_eq!(partitions[1].name, "Basic data partition");
+        assert_eq!((partitions[1].start_offset, partitions[1].length), (2048 * SECTOR as u64, 2048 * SECTOR as u64));
+        assert!(partitions[1].is_ntfs);
+    }
+
+    #[test]
+    fn corrupt_gpt_geometry_is_rejected() {
+        let entries = [(BASIC_DATA, 2048, 4095, "")];
+        for entry_size in [0, 64, 132, 8192] {
+            let disk = image(gpt_disk(entry_size, 2, &entries));
+            assert!(read_gpt(&disk, 512, 8192 * SECTOR as u64).is_err(), "entry size {}", entry_size);
+        }
+        let disk = image(gpt_disk(128, u64::MAX / 256, &entries));
+        assert!(read_gpt(&disk, 512, 8192 * SECTOR as u64).is_err());
+
+        // GPT를 읽을 수 없으면 보호 MBR 슬롯으로 대체한다.
+        let partitions = discover_partitions(&image(gpt_disk(8192, 2, &entries))).unwrap();
+        assert_eq!(partitions.len(), 1);
+        assert_eq!(partitions[0].name, "GPT Protective");
+    }
+}
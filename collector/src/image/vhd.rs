@@ -0,0 +1,333 @@
+use super::{read_file_region, read_parent_or_zero, resolve_parent_path, open_parent_image, ReadSeek, VirtualDisk};
+use anyhow::{Context, Result, bail};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const VHD_FOOTER_COOKIE: &[u8; 8] = b"conectix";
+const VHD_DYNAMIC_COOKIE: &[u8; 8] = b"cxsparse";
+const VHD_SECTOR_SIZE: u64 = 512;
+const BAT_UNUSED: u32 = 0xFFFF_FFFF;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VhdDiskType {
+    Fixed,
+    Dynamic,
+    Differencing,
+}
+
+/// 파일 끝 512바이트가 VHD 푸터("conectix")인지 확인한다. 읽기 위치는 호출자가 복원해야 한다.
+pub fn has_vhd_footer(file: &mut File) -> io::Result<bool> {
+    let len = file.seek(SeekFrom::End(0))?;
+    if len < VHD_SECTOR_SIZE { return Ok(false); }
+    // 구형 Virtual PC는 511바이트 푸터를 쓰므로 두 위치 모두 확인한다.
+    for footer_size in [512u64, 511] {
+        let mut cookie = [0u8; 8];
+        file.seek(SeekFrom::Start(len - footer_size))?;
+        file.read_exact(&mut cookie)?;
+        if &cookie == VHD_FOOTER_COOKIE { return Ok(true); }
+    }
+    Ok(false)
+}
+
+/// Microsoft Virtual Hard Disk (VHD) 이미지. 고정/동적/차분 디스크를 지원한다.
+pub struct VhdImage {
+    file: File,
+    disk_type: VhdDiskType,
+    disk_size: u64,
+    block_size: u64,
+    bitmap_size: u64,
+    bat: Vec<u32>,
+    parent: Option<Box<dyn ReadSeek>>,
+    /// 마지막으로 읽은 블록의 섹터 비트맵 캐시 (차분 디스크 전용)
+    cached_bitmap: Option<(usize, Vec<u8>)>,
+}
+
+impl VhdImage {
+    pub(crate) fn open(path: &Path, depth: usize) -> Result<Self> {
+        let mut file = File::open(path).with_context(|| format!("Failed to open VHD {}", path.display()))?;
+        let len = file.seek(SeekFrom::End(0))?;
+
+        let mut footer = [0u8; 512];
+        file.seek(SeekFrom::Start(len - 512))?;
+        file.read_exact(&mut footer)?;
+        if &footer[0..8] != VHD_FOOTER_COOKIE {
+            // 511바이트 푸터 변형
+            file.seek(SeekFrom::Start(len - 511))?;
+            file.read_exact(&mut footer[..511])?;
+            if &footer[0..8] != VHD_FOOTER_COOKIE { bail!("VHD footer cookie not found"); }
+        }
+
+        let data_offset = u64::from_be_bytes(footer[16..24].try_into().unwrap());
+        let disk_size = u64::from_be_bytes(footer[48..56].try_into().unwrap());
+        let disk_type = match u32::from_be_bytes(footer[60..64].try_into().unwrap()) {
+            2 => VhdDiskType::Fixed,
+            3 => VhdDiskType::Dynamic,
+            4 => VhdDiskType::Differencing,
+            other => bail!("Unsupported VHD disk type {}", other),
+        };
+
+        let mut image = Self {
+            file, disk_type, disk_size, block_size: 0, bitmap_size: 0,
+            bat: Vec::new(), parent: None, cached_bitmap: None,
+        };
+
+        if disk_type != VhdDiskType::Fixed {
+            image.load_dynamic_header(path, data_offset, depth)?;
+        }
+
+        tracing::info!("  [*] VHD image: {:?} disk, {} bytes{}", image.disk_type, image.disk_size,
+            if image.parent.is_some() { " (with parent chain)" } else { "" });
+        Ok(image)
+    }
+
+    fn load_dynamic_header(&mut self, path: &Path, header_offset: u64, depth: usize) -> Result<()> {
+        let mut header = [0u8; 1024];
+        self.file.seek(SeekFrom::Start(header_offset))?;
+        self.file.read_exact(&mut header)?;
+        if &header[0..8] != VHD_DYNAMIC_COOKIE { bail!("VHD dynamic header cookie not found @ {:#X}", header_offset); }
+
+        let table_offset = u64::from_be_bytes(header[16..24].try_into().unwrap());
+        let max_entries = u32::from_be_bytes(header[28..32].try_into().unwrap()) as u64;
+        self.block_size = u32::from_be_bytes(header[32..36].try_into().unwrap()) as u64;
+        if self.block_size == 0 || !self.block_size.is_multiple_of(VHD_SECTOR_SIZE) { bail!("Invalid VHD block size {}", self.block_size); }
+
+        // 섹터 비트맵: 블록 내 섹터당 1비트, 512바이트 단위로 올림
+        let sectors_per_block = self.block_size / VHD_SECTOR_SIZE;
+        self.bitmap_size = sectors_per_block.div_ceil(8).div_ceil(VHD_SECTOR_SIZE) * VHD_SECTOR_SIZE;
+
+        let raw_bat = read_file_region(&mut self.file, table_offset, max_entries * 4, "VHD block allocation table")?;
+        self.bat = raw_bat.chunks_exact(4).map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]])).collect();
+
+        if self.disk_type == VhdDiskType::Differencing {
+            let candidates = self.parent_locators(&header)?;
+            let parent_path = resolve_parent_path(path, &candidates)
+                .with_context(|| format!("Parent of differencing VHD not found (candidates: {:?})", candidates))?;
+            self.parent = Some(open_parent_image(&parent_path, depth)?);
+        }
+        Ok(())
+    }
+
+    /// 부모 로케이터 엔트리(W2ru/W2ku/MacX)와 부모 유니코드 이름에서 경로 후보를 추출한다. 상대 경로가 우선이다.
+    fn parent_locators(&mut self, header: &[u8]) -> Result<Vec<String>> {
+        let mut relative = Vec::new();
+        let mut absolute = Vec::new();
+
+        for entry in header[576..768].chunks_exact(24) {
+            let platform_code = &entry[0..4];
+            let data_length = u32::from_be_bytes(entry[8..12].try_into().unwrap()) as usize;
+            let data_offset = u64::from_be_bytes(entry[16..24].try_into().unwrap());
+            if data_length == 0 || data_length > 64 * 1024 { continue; }
+
+            let mut data = vec![0u8; data_length];
+            self.file.seek(SeekFrom::Start(data_offset))?;
+            self.file.read_exact(&mut data)?;
+
+            match platform_code {
+                b"W2ru" => relative.push(decode_utf16le(&data)),
+                b"W2ku" => absolute.push(decode_utf16le(&data)),
+                b"MacX" | b"Mac " => absolute.push(String::from_utf8_lossy(&data).trim_end_matches('\0').to_string()),
+                _ => {},
+            }
+        }
+
+        let be_units: Vec<u16> = header[64..576].chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+        let parent_name = String::from_utf16_lossy(&be_units).trim_end_matches('\0').to_string();
+
+        relative.extend(absolute);
+        if !parent_name.is_empty() { relative.push(parent_name); }
+        Ok(relative)
+    }
+
+    /// 블록 내 섹터 비트맵에서 해당 섹터가 이 디스크에 기록되어 있는지 확인한다. (MSB 우선 비트 순서)
+    fn sector_present(&mut self, block_index: usize, block_sector_offset: u64, sector_in_block: u64) -> io::Result<bool> {
+        if !matches!(&self.cached_bitmap, Some((cached, _)) if *cached == block_index) {
+            let mut bitmap = vec![0u8; self.bitmap_size as usize];
+            self.file.seek(SeekFrom::Start(block_sector_offset * VHD_SECTOR_SIZE))?;
+            self.file.read_exact(&mut bitmap)?;
+            self.cached_bitmap = Some((block_index, bitmap));
+        }
+        let bitmap = &self.cached_bitmap.as_ref().unwrap().1;
+        let byte = bitmap[(sector_in_block / 8) as usize];
+        Ok((byte & (0x80 >> (sector_in_block % 8))) != 0)
+    }
+}
+
+impl VirtualDisk for VhdImage {
+    fn disk_size(&self) -> u64 {
+        self.disk_size
+    }
+
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        if self.disk_type == VhdDiskType::Fixed {
+            self.file.seek(SeekFrom::Start(offset))?;
+            self.file.read_exact(buf)?;
+            return Ok(buf.len());
+        }
+
+        let block_index = (offset / self.block_size) as usize;
+        let offset_in_block = offset % self.block_size;
+        let block_entry = self.bat.get(block_index).copied().unwrap_or(BAT_UNUSED);
+
+        if block_entry == BAT_UNUSED {
+            // 할당되지 않은 블록: 동적 디스크는 0, 차분 디스크는 부모에서 읽는다.
+            let n = std::cmp::min(buf.len() as u64, self.block_size - offset_in_block) as usize;
+            read_parent_or_zero(&mut self.parent, offset, &mut buf[..n])?;
+            return Ok(n);
+        }
+
+        let block_data_offset = block_entry as u64 * VHD_SECTOR_SIZE + self.bitmap_size;
+
+        if self.disk_type == VhdDiskType::Dynamic {
+            let n = std::cmp::min(buf.len() as u64, self.block_size - offset_in_block) as usize;
+            self.file.seek(SeekFrom::Start(block_data_offset + offset_in_block))?;
+            self.file.read_exact(&mut buf[..n])?;
+            return Ok(n);
+        }
+
+        // 차분 디스크: 섹터 단위로 비트맵을 확인하여 자신 또는 부모에서 읽는다.
+        let sector_in_block = offset_in_block / VHD_SECTOR_SIZE;
+        let offset_in_sector = offset_in_block % VHD_SECTOR_SIZE;
+        let n = std::cmp::min(buf.len() as u64, VHD_SECTOR_SIZE - offset_in_sector) as usize;
+
+        if self.sector_present(block_index, block_entry as u64, sector_in_block)? {
+            self.file.seek(SeekFrom::Start(block_data_offset + offset_in_block))?;
+            self.file.read_exact(&mut buf[..n])?;
+        } else {
+            read_parent_or_zero(&mut self.parent, offset, &mut buf[..n])?;
+        }
+        Ok(n)
+    }
+}
+
+fn decode_utf16le(data: &[u8]) -> String {
+    let units: Vec<u16> = data.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    String::from_utf16_lossy(&units).trim_end_matches('\0').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::{open_image, VirtualDiskStream};
+
+    const BLOCK_SIZE: usize = 4096;
+
+    fn footer(disk_type: u32, disk_size: u64, data_offset: u64) -> Vec<u8> {
+        let mut footer = vec![0u8; 512];
+        footer[0..8].copy_from_slice(VHD_FOOTER_COOKIE);
+        footer[16..24].copy_from_slice(&data_offset.to_be_bytes());
+        footer[48..56].copy_from_slice(&disk_size.to_be_bytes());
+        footer[60..64].copy_from_slice(&disk_type.to_be_bytes());
+        footer
+    }
+
+    /// 동적/차분 VHD: 푸터 사본, 동적 헤더(0x200), BAT(0x600), 블록(0x800~), 푸터.
+    /// blocks[i]가 Some((비트맵 첫 바이트, 데이터))이면 i번째 블록을 할당한다.
+    fn sparse_vhd(disk_type: u32, max_entries: u32, blocks: &[Option<(u8, Vec<u8>)>], parent_name: Option<&str>) -> Vec<u8> {
+        let disk_size = (blocks.len() * BLOCK_SIZE) as u64;
+        let mut image = footer(disk_type, disk_size, 512);
+
+        let mut header = vec![0u8; 1024];
+        header[0..8].copy_from_slice(VHD_DYNAMIC_COOKIE);
+        header[16..24].copy_from_slice(&0x600u64.to_be_bytes());
+        header[28..32].copy_from_slice(&max_entries.to_be_bytes());
+        header[32..36].copy_from_slice(&(BLOCK_SIZE as u32).to_be_bytes());
+        image.extend(header);
+
+        let mut bat = vec![0xFFu8; 512];
+        let mut data = Vec::new();
+        let mut locator = Vec::new();
+        for (i, block) in blocks.iter().enumerate() {
+            let Some((bitmap_byte, content)) = block else { continue };
+            let sector = (0x800 + data.len()) / 512;
+            bat[i * 4..i * 4 + 4].copy_from_slice(&(sector as u32).to_be_bytes());
+            let mut bitmap = vec![0u8; 512];
+            bitmap[0] = *bitmap_byte;
+            data.extend(bitmap);
+            data.extend(content);
+        }
+        image.extend(bat);
+        image.extend(data);
+
+        if let Some(name) = parent_name {
+            let encoded: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+            let entry = 512 + 576;
+            image[entry..entry + 4].copy_from_slice(b"W2ru");
+            image[entry + 8..entry + 12].copy_from_slice(&(encoded.len() as u32).to_be_bytes());
+            let locator_offset = image.len() as u64;
+            image[entry + 16..entry + 24].copy_from_slice(&locator_offset.to_be_bytes());
+            locator.extend(encoded);
+            locator.resize(512, 0);
+        }
+        image.extend(locator);
+        image.extend(footer(disk_type, disk_size, 512));
+        image
+    }
+
+    fn read_all(disk: VhdImage) -> Vec<u8> {
+        let mut data = Vec::new();
+        VirtualDiskStream::new(disk).read_to_end(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn fixed_disk_reads_data_before_footer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixed.vhd");
+        let content: Vec<u8> = (0..BLOCK_SIZE).map(|i| (i % 251) as u8).collect();
+        let mut image = content.clone();
+        image.extend(footer(2, BLOCK_SIZE as u64, u64::MAX));
+        std::fs::write(&path, image).unwrap();
+
+        let disk = VhdImage::open(&path, 0).unwrap();
+        assert_eq!(disk.disk_type, VhdDiskType::Fixed);
+        assert_eq!(read_all(disk), content);
+    }
+
+    #[test]
+    fn dynamic_disk_maps_blocks_and_zero_fills_unallocated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dynamic.vhd");
+        std::fs::write(&path, sparse_vhd(3, 2, &[Some((0xFF, vec![0xAB; BLOCK_SIZE])), None], None)).unwrap();
+
+        let data = read_all(VhdImage::open(&path, 0).unwrap());
+        assert_eq!(data.len(), 2 * BLOCK_SIZE);
+        assert!(data[..BLOCK_SIZE].iter().all(|&b| b == 0xAB));
+        assert!(data[BLOCK_SIZE..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn differencing_disk_reads_unwritten_sectors_from_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut parent = vec![0x11u8; 2 * BLOCK_SIZE];
+        parent.extend(footer(2, 2 * BLOCK_SIZE as u64, u64::MAX));
+        std::fs::write(dir.path().join("base.vhd"), parent).unwrap();
+        // 블록 0은 첫 섹터만 자식에 기록(MSB 우선 비트맵), 블록 1은 미할당
+        let child = sparse_vhd(4, 2, &[Some((0x80, vec![0x22; BLOCK_SIZE])), None], Some(".\\base.vhd"));
+        let path = dir.path().join("child.vhd");
+        std::fs::write(&path, child).unwrap();
+
+        let mut stream = open_image(&path).unwrap();
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).unwrap();
+        assert_eq!(data.len(), 2 * BLOCK_SIZE);
+        assert!(data[..512].iter().all(|&b| b == 0x22));
+        assert!(data[512..].iter().all(|&b| b == 0x11));
+    }
+
+    #[test]
+    fn oversized_bat_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("corrupt.vhd");
+        std::fs::write(&path, sparse_vhd(3, 0x4000_0000, &[None], None)).unwrap();
+        assert!(VhdImage::open(&path, 0).is_err());
+    }
+
+    #[test]
+    fn short_file_has_no_footer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("short.vhd");
+        std::fs::write(&path, &footer(2, 0, 0)[..100]).unwrap();
+        assert!(!has_vhd_footer(&mut File::open(&path).unwrap()).unwrap());
+    }
+}
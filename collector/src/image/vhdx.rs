@@ -0,0 +1,470 @@
+use super::{read_file_region, read_parent_or_zero, resolve_parent_path, open_parent_image, ReadSeek, VirtualDisk};
+use anyhow::{Context, Result, bail};
+use parser::partition::format_guid;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const VHDX_FILE_IDENTIFIER: &[u8; 8] = b"vhdxfile";
+const VHDX_HEADER_OFFSETS: [u64; 2] = [64 * 1024, 128 * 1024];
+const VHDX_REGION_TABLE_OFFSETS: [u64; 2] = [192 * 1024, 256 * 1024];
+
+const REGION_BAT: &str = "2DC27766-F623-4200-9D64-115E9BFD4A08";
+const REGION_METADATA: &str = "8B7CA206-4790-4B9A-B8FE-575F050F886E";
+
+const META_FILE_PARAMETERS: &str = "CAA16737-FA36-4D43-B3B6-33F0AA44E76B";
+const META_VIRTUAL_DISK_SIZE: &str = "2FA54224-CD1B-4876-B211-5DBED83BF4B8";
+const META_LOGICAL_SECTOR_SIZE: &str = "8141BF1D-A96F-4709-BA47-F233A8FAAB5F";
+const META_PARENT_LOCATOR: &str = "A8D35F2D-B30B-454D-ABF7-D3D84834AB0C";
+
+// BAT 페이로드 블록 상태
+const PAYLOAD_BLOCK_NOT_PRESENT: u64 = 0;
+const PAYLOAD_BLOCK_FULLY_PRESENT: u64 = 6;
+const PAYLOAD_BLOCK_PARTIALLY_PRESENT: u64 = 7;
+const SB_BLOCK_PRESENT: u64 = 6;
+
+const MIN_BLOCK_SIZE: u64 = 1024 * 1024;
+const MAX_BLOCK_SIZE: u64 = 256 * 1024 * 1024;
+
+/// 파일 첫 8바이트가 VHDX 파일 식별자("vhdxfile")인지 확인한다.
+pub fn is_vhdx_signature(header: &[u8]) -> bool {
+    header.len() >= 8 && &header[0..8] == VHDX_FILE_IDENTIFIER
+}
+
+/// Hyper-V VHDX 이미지. BAT, 섹터 비트맵, 부모 체인(차분 디스크)을 지원한다.
+pub struct VhdxImage {
+    file: File,
+    disk_size: u64,
+    block_size: u64,
+    logical_sector_size: u64,
+    chunk_ratio: u64,
+    bat: Vec<u64>,
+    parent: Option<Box<dyn ReadSeek>>,
+    /// 마지막으로 읽은 섹터 비트맵 블록 캐시 (청크 번호, 1MB 비트맵)
+    cached_bitmap: Option<(u64, Vec<u8>)>,
+}
+
+impl VhdxImage {
+    pub(crate) fn open(path: &Path, depth: usize) -> Result<Self> {
+        let mut file = File::open(path).with_context(|| format!("Failed to open VHDX {}", path.display()))?;
+
+        let mut identifier = [0u8; 8];
+        file.read_exact(&mut identifier)?;
+        if !is_vhdx_signature(&identifier) { bail!("VHDX file identifier not found"); }
+
+        // 두 헤더 중 체크섬이 유효하고 시퀀스 번호가 큰 쪽이 현재 헤더이다.
+        let mut current_header: Option<(u64, [u8; 4096])> = None;
+        for offset in VHDX_HEADER_OFFSETS {
+            let mut header = [0u8; 4096];
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_exact(&mut header)?;
+            if &header[0..4] != b"head" || !checksum_valid(&header, 4) { continue; }
+
+            let sequence = u64::from_le_bytes(header[8..16].try_into().unwrap());
+            if current_header.as_ref().is_none_or(|(seq, _)| sequence > *seq) {
+                current_header = Some((sequence, header));
+            }
+        }
+        let (_, header) = current_header.context("No valid VHDX header found")?;
+        if header[48..64].iter().any(|&b| b != 0) {
+            tracing::warn!("  [!] VHDX log is not empty (unclean shutdown); reading without log replay");
+        }
+
+        let regions = read_region_table(&mut file)?;
+        let (bat_offset, bat_length) = *regions.iter().find(|(guid, _)| guid == REGION_BAT).map(|(_, r)| r).context("VHDX BAT region missing")?;
+        let (meta_offset, meta_length) = *regions.iter().find(|(guid, _)| guid == REGION_METADATA).map(|(_, r)| r).context("VHDX metadata region missing")?;
+
+        let metadata = read_metadata(&mut file, meta_offset, meta_length)?;
+        let item = |id: &str| metadata.iter().find(|(guid, _)| guid == id).map(|(_, data)| data.as_slice());
+
+        let file_parameters = item(META_FILE_PARAMETERS).and_then(|data| data.get(..8)).context("VHDX file parameters missing or truncated")?;
+        let block_size = u32::from_le_bytes(file_parameters[0..4].try_into().unwrap()) as u64;
+        let has_parent = (u32::from_le_bytes(file_parameters[4..8].try_into().unwrap()) & 0x2) != 0;
+        let disk_size = item(META_VIRTUAL_DISK_SIZE).and_then(|data| data.get(..8)).context("VHDX virtual disk size missing or truncated")?;
+        let disk_size = u64::from_le_bytes(disk_size.try_into().unwrap());
+        let logical_sector_size = item(META_LOGICAL_SECTOR_SIZE).and_then(|data| data.get(..4)).context("VHDX logical sector size missing or truncated")?;
+        let logical_sector_size = u32::from_le_bytes(logical_sector_size.try_into().unwrap()) as u64;
+
+        // 사양상 블록 크기는 1MB~256MB의 2의 거듭제곱, 논리 섹터는 512 또는 4096바이트이다. (chunk_ratio가 0이 되지 않도록 보장)
+        if !block_size.is_power_of_two() || !(MIN_BLOCK_SIZE..=MAX_BLOCK_SIZE).contains(&block_size) || !matches!(logical_sector_size, 512 | 4096) {
+            bail!("Invalid VHDX geometry (block {}, sector {})", block_size, logical_sector_size);
+        }
+        // 하나의 섹터 비트맵 블록(1MB = 2^23 비트)이 커버하는 페이로드 블록 수
+        let chunk_ratio = ((1u64 << 23) * logical_sector_size) / block_size;
+
+        let raw_bat = read_file_region(&mut file, bat_offset, bat_length, "VHDX BAT region")?;
+        let bat = raw_bat.chunks_exact(8).map(|c| u64::from_le_bytes(c.try_into().unwrap())).collect();
+
+        let parent = if has_parent {
+            let locator = item(META_PARENT_LOCATOR).context("Differencing VHDX has no parent locator")?;
+            let entries = parse_parent_locator(locator);
+            let candidates: Vec<String> = ["relative_path", "absolute_win32_path", "volume_path"].iter()
+                .filter_map(|key| entries.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone()))
+                .collect();
+            let parent_path = resolve_parent_path(path, &candidates)
+                .with_context(|| format!("Parent of differencing VHDX not found (candidates: {:?})", candidates))?;
+            Some(open_parent_image(&parent_path, depth)?)
+        } else {
+            None
+        };
+
+        tracing::info!("  [*] VHDX image: {} bytes, block size {}, sector size {}{}", disk_size, block_size, logical_sector_size,
+            if parent.is_some() { " (with parent chain)" } else { "" });
+
+        Ok(Self { file, disk_size, block_size, logical_sector_size, chunk_ratio, bat, parent, cached_bitmap: None })
+    }
+
+    /// 섹터 비트맵에서 섹터가 이 디스크에 기록되어 있는지 확인한다. (LSB 우선 비트 순서)
+    fn sector_present(&mut self, chunk: u64, sector_in_chunk: u64) -> io::Result<bool> {
+        if !matches!(&self.cached_bitmap, Some((cached, _)) if *cached == chunk) {
+            let entry_index = (chunk * (self.chunk_ratio + 1) + self.chunk_ratio) as usize;
+            let entry = self.bat.get(entry_index).copied().unwrap_or(0);
+            let bitmap = if (entry & 0x7) == SB_BLOCK_PRESENT {
+                let mut bitmap = vec![0u8; 1024 * 1024];
+                self.file.seek(SeekFrom::Start(entry & !0xFFFFF))?;
+                self.file.read_exact(&mut bitmap)?;
+                bitmap
+            } else {
+                Vec::new()
+            };
+            self.cached_bitmap = Some((chunk, bitmap));
+        }
+        let bitmap = &self.cached_bitmap.as_ref().unwrap().1;
+        Ok(bitmap.get((sector_in_chunk / 8) as usize).is_some_and(|byte| (byte >> (sector_in_chunk % 8)) & 1 != 0))
+    }
+}
+
+impl VirtualDisk for VhdxImage {
+    fn disk_size(&self) -> u64 {
+        self.disk_size
+    }
+
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let block = offset / self.block_size;
+        let offset_in_block = offset % self.block_size;
+        // 페이로드 블록 사이에 chunk_ratio개마다 섹터 비트맵 엔트리가 끼어 있다.
+        let entry = self.bat.get((block + block / self.chunk_ratio) as usize).copied().unwrap_or(PAYLOAD_BLOCK_NOT_PRESENT);
+        let block_file_offset = entry & !0xFFFFF;
+
+        match entry & 0x7 {
+            PAYLOAD_BLOCK_FULLY_PRESENT => {
+                let n = std::cmp::min(buf.len() as u64, self.block_size - offset_in_block) as usize;
+                self.file.seek(SeekFrom::Start(block_file_offset + offset_in_block))?;
+                self.file.read_exact(&mut buf[..n])?;
+                Ok(n)
+            },
+            PAYLOAD_BLOCK_PARTIALLY_PRESENT => {
+                let offset_in_sector = offset_in_block % self.logical_sector_size;
+                let n = std::cmp::min(buf.len() as u64, self.logical_sector_size - offset_in_sector) as usize;
+                let sectors_per_chunk = self.chunk_ratio * self.block_size / self.logical_sector_size;
+                let sector = offset / self.logical_sector_size;
+
+                if self.sector_present(block / self.chunk_ratio, sector % sectors_per_chunk)? {
+                    self.file.seek(SeekFrom::Start(block_file_offset + offset_in_block))?;
+                    self.file.read_exact(&mut buf[..n])?;
+                } else {
+                    read_parent_or_zero(&mut self.parent, offset, &mut buf[..n])?;
+                }
+                Ok(n)
+            },
+            PAYLOAD_BLOCK_NOT_PRESENT => {
+                let n = std::cmp::min(buf.len() as u64, self.block_size - offset_in_block) as usize;
+                read_parent_or_zero(&mut self.parent, offset, &mut buf[..n])?;
+                Ok(n)
+            },
+            _ => {
+                // UNDEFINED / ZERO / UNMAPPED: 게스트에게는 0으로 보인다.
+                let n = std::cmp::min(buf.len() as u64, self.block_size - offset_in_block) as usize;
+                buf[..n].fill(0);
+                Ok(n)
+            },
+        }
+    }
+}
+
+/// 리전 테이블("regi")에서 (GUID, (파일 오프셋, 길이)) 목록을 읽는다.
+fn read_region_table(file: &mut File) -> Result<Vec<(String, (u64, u64))>> {
+    for offset in VHDX_REGION_TABLE_OFFSETS {
+        let mut table = vec![0u8; 64 * 1024];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut table)?;
+        if &table[0..4] != b"regi" || !checksum_valid(&table, 4) { continue; }
+
+        let entry_count = std::cmp::min(u32::from_le_bytes(table[8..12].try_into().unwrap()) as usize, 2047);
+        return Ok(table[16..16 + entry_count * 32].chunks_exact(32)
+            .map(|e| (format_guid(&e[0..16]), (
+                u64::from_le_bytes(e[16..24].try_into().unwrap()),
+                u32::from_le_bytes(e[24..28].try_into().unwrap()) as u64,
+            )))
+            .collect());
+    }
+    bail!("No valid VHDX region table found")
+}
+
+/// 메타데이터 리전의 테이블을 읽어 (아이템 GUID, 데이터) 목록으로 반환한다.
+fn read_metadata(file: &mut File, offset: u64, length: u64) -> Result<Vec<(String, Vec<u8>)>> {
+    if length < 32 { bail!("VHDX metadata region too small ({} bytes)", length); }
+    let region = read_file_region(file, offset, length, "VHDX metadata region")?;
+    if &region[0..8] != b"metadata" { bail!("VHDX metadata table signature not found"); }
+
+    let entry_count = u16::from_le_bytes([region[10], region[11]]) as usize;
+    let mut items = Vec::new();
+    for entry in region[32..].chunks_exact(32).take(entry_count) {
+        let item_offset = u32::from_le_bytes(entry[16..20].try_into().unwrap()) as usize;
+        let item_length = u32::from_le_bytes(entry[20..24].try_into().unwrap()) as usize;
+        if item_offset + item_length > region.len() { bail!("VHDX metadata item exceeds region"); }
+        items.push((format_guid(&entry[0..16]), region[item_offset..item_offset + item_length].to_vec()));
+    }
+    Ok(items)
+}
+
+/// 부모 로케이터 아이템의 키/값(UTF-16LE) 쌍을 추출한다.
+fn parse_parent_locator(data: &[u8]) -> Vec<(String, String)> {
+    if data.len() < 20 { return Vec::new(); }
+    let count = u16::from_le_bytes([data[18], data[19]]) as usize;
+
+    let utf16 = |start: usize, len: usize| -> Option<String> {
+        let bytes = data.get(start..start + len)?;
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+        Some(String::from_utf16_lossy(&units))
+    };
+
+    data[20..].chunks_exact(12).take(count)
+        .filter_map(|e| {
+            let key_offset = u32::from_le_bytes(e[0..4].try_into().unwrap()) as usize;
+            let value_offset = u32::from_le_bytes(e[4..8].try_into().unwrap()) as usize;
+            let key_length = u16::from_le_bytes([e[8], e[9]]) as usize;
+            let value_length = u16::from_le_bytes([e[10], e[11]]) as usize;
+            Some((utf16(key_offset, key_length)?, utf16(value_offset, value_length)?))
+        })
+        .collect()
+}
+
+/// 체크섬 필드를 0으로 간주하고 CRC-32C를 계산하여 저장 값과 비교한다.
+fn checksum_valid(structure: &[u8], checksum_offset: usize) -> bool {
+    let stored = u32::from_le_bytes(structure[checksum_offset..checksum_offset + 4].try_into().unwrap());
+    let mut copy = structure.to_vec();
+    copy[checksum_offset..checksum_offset + 4].fill(0);
+    crc32c(&copy) == stored
+}
+
+fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0x82F6_3B78 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::{open_image, VirtualDiskStream};
+
+    const MB: usize = 1024 * 1024;
+    const BAT_OFFSET: usize = 384 * 1024;
+    const METADATA_OFFSET: usize = 512 * 1024;
+    const BLOCK_SIZE: usize = MB;
+    const SECTOR_SIZE: usize = 512;
+    /// 블록 1MB, 섹터 512바이트일 때 섹터 비트맵 엔트리 간격
+    const CHUNK_RATIO: usize = (1 << 23) * SECTOR_SIZE / BLOCK_SIZE;
+
+    fn guid_bytes(guid: &str) -> [u8; 16] {
+        let hex: String = guid.chars().filter(|c| *c != '-').collect();
+        let raw: Vec<u8> = (0..16).map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap()).collect();
+        let mut bytes = [0u8; 16];
+        bytes[0..4].copy_from_slice(&[raw[3], raw[2], raw[1], raw[0]]);
+        bytes[4..8].copy_from_slice(&[raw[5], raw[4], raw[7], raw[6]]);
+        bytes[8..16].copy_from_slice(&raw[8..16]);
+        bytes
+    }
+
+    fn seal(structure: &mut [u8]) {
+        structure[4..8].fill(0);
+        let checksum = crc32c(structure);
+        structure[4..8].copy_from_slice(&checksum.to_le_bytes());
+    }
+
+    struct Layout {
+        disk_size: u64,
+        has_parent: bool,
+        parent_path: Option<&'static str>,
+        /// BAT 엔트리 (인덱스, 상태, 파일 오프셋)
+        bat: Vec<(usize, u64, usize)>,
+        /// 파일에 기록할 (오프셋, 데이터)
+        payload: Vec<(usize, Vec<u8>)>,
+        metadata_length: u32,
+        file_parameters_length: u32,
+        block_size: u32,
+        logical_sector_size: u32,
+    }
+
+    impl Default for Layout {
+        fn default() -> Self {
+            Self {
+                disk_size: 2 * MB as u64, has_parent: false, parent_path: None, bat: Vec::new(), payload: Vec::new(),
+                metadata_length: 64 * 1024, file_parameters_length: 8,
+                block_size: BLOCK_SIZE as u32, logical_sector_size: SECTOR_SIZE as u32,
+            }
+        }
+    }
+
+    fn build(layout: &Layout) -> Vec<u8> {
+        let end = layout.payload.iter().map(|(offset, data)| offset + data.len()).max().unwrap_or(MB).max(MB);
+        let mut image = vec![0u8; end];
+        image[0..8].copy_from_slice(VHDX_FILE_IDENTIFIER);
+
+        let header = &mut image[64 * 1024..68 * 1024];
+        header[0..4].copy_from_slice(b"head");
+        header[8..16].copy_from_slice(&1u64.to_le_bytes());
+        seal(header);
+
+        let regions = &mut image[192 * 1024..256 * 1024];
+        regions[0..4].copy_from_slice(b"regi");
+        regions[8..12].copy_from_slice(&2u32.to_le_bytes());
+        for (i, (guid, offset, length)) in [(REGION_BAT, BAT_OFFSET, 64 * 1024u32), (REGION_METADATA, METADATA_OFFSET, layout.metadata_length)].into_iter().enumerate() {
+            let entry = &mut regions[16 + i * 32..48 + i * 32];
+            entry[0..16].copy_from_slice(&guid_bytes(guid));
+            entry[16..24].copy_from_slice(&(offset as u64).to_le_bytes());
+            entry[24..28].copy_from_slice(&length.to_le_bytes());
+        }
+        seal(regions);
+
+        let mut items: Vec<(&str, Vec<u8>)> = Vec::new();
+        let mut file_parameters = layout.block_size.to_le_bytes().to_vec();
+        file_parameters.extend((if layout.has_parent { 2u32 } else { 0 }).to_le_bytes());
+        file_parameters.truncate(layout.file_parameters_length as usize);
+        items.push((META_FILE_PARAMETERS, file_parameters));
+        items.push((META_VIRTUAL_DISK_SIZE, layout.disk_size.to_le_bytes().to_vec()));
+        items.push((META_LOGICAL_SECTOR_SIZE, layout.logical_sector_size.to_le_bytes().to_vec()));
+        if let Some(parent) = layout.parent_path {
+            let utf16 = |s: &str| -> Vec<u8> { s.encode_utf16().flat_map(u16::to_le_bytes).collect() };
+            let (key, value) = (utf16("relative_path"), utf16(parent));
+            let mut locator = vec![0u8; 32];
+            locator[18..20].copy_from_slice(&1u16.to_le_bytes());
+            locator[20..24].copy_from_slice(&32u32.to_le_bytes());
+            locator[24..28].copy_from_slice(&(32 + key.len() as u32).to_le_bytes());
+            locator[28..30].copy_from_slice(&(key.len() as u16).to_le_bytes());
+            locator[30..32].copy_from_slice(&(value.len() as u16).to_le_bytes());
+            locator.extend(key);
+            locator.extend(value);
+            items.push((META_PARENT_LOCATOR, locator));
+        }
+
+        let metadata = &mut image[METADATA_OFFSET..METADATA_OFFSET + 64 * 1024];
+        metadata[0..8].copy_from_slice(b"metadata");
+        metadata[10..12].copy_from_slice(&(items.len() as u16).to_le_bytes());
+        let mut data_offset = 0x1000usize;
+        for (i, (guid, data)) in items.iter().enumerate() {
+            let entry = &mut metadata[32 + i * 32..64 + i * 32];
+            entry[0..16].copy_from_slice(&guid_bytes(guid));
+            entry[16..20].copy_from_slice(&(data_offset as u32).to_le_bytes());
+            entry[20..24].copy_from_slice(&(data.len() as u32).to_le_bytes());
+            metadata[data_offset..data_offset + data.len()].copy_from_slice(data);
+            data_offset += data.len().next_multiple_of(8);
+        }
+
+        for &(index, state, offset) in &layout.bat {
+            let entry = offset as u64 | state;
+            image[BAT_OFFSET + index * 8..BAT_OFFSET + index * 8 + 8].copy_from_slice(&entry.to_le_bytes());
+        }
+        for (offset, data) in &layout.payload {
+            image[*offset..offset + data.len()].copy_from_slice(data);
+        }
+        image
+    }
+
+    #[test]
+    fn dynamic_disk_maps_present_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dynamic.vhdx");
+        let layout = Layout {
+            bat: vec![(0, PAYLOAD_BLOCK_FULLY_PRESENT, MB), (1, PAYLOAD_BLOCK_NOT_PRESENT, 0)],
+            payload: vec![(MB, vec![0x5A; BLOCK_SIZE])],
+            ..Layout::default()
+        };
+        std::fs::write(&path, build(&layout)).unwrap();
+
+        let mut data = Vec::new();
+        VirtualDiskStream::new(VhdxImage::open(&path, 0).unwrap()).read_to_end(&mut data).unwrap();
+        assert_eq!(data.len(), 2 * MB);
+        assert!(data[..MB].iter().all(|&b| b == 0x5A));
+        assert!(data[MB..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn differencing_disk_uses_sector_bitmap_and_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("base.img"), vec![0x11u8; 2 * MB]).unwrap();
+
+        // 블록 0은 부분 기록: 섹터 비트맵(LSB 우선)에서 섹터 1만 자식에 존재
+        let mut bitmap = vec![0u8; MB];
+        bitmap[0] = 0b0000_0010;
+        let layout = Layout {
+            has_parent: true,
+            parent_path: Some(".\\base.img"),
+            bat: vec![
+                (0, PAYLOAD_BLOCK_PARTIALLY_PRESENT, MB),
+                (1, PAYLOAD_BLOCK_NOT_PRESENT, 0),
+                (CHUNK_RATIO, SB_BLOCK_PRESENT, 2 * MB),
+            ],
+            payload: vec![(MB, vec![0x22; BLOCK_SIZE]), (2 * MB, bitmap)],
+            ..Layout::default()
+        };
+        let path = dir.path().join("child.vhdx");
+        std::fs::write(&path, build(&layout)).unwrap();
+
+        let mut stream = open_image(&path).unwrap();
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).unwrap();
+        assert_eq!(data.len(), 2 * MB);
+        assert!(data[..512].iter().all(|&b| b == 0x11));
+        assert!(data[512..1024].iter().all(|&b| b == 0x22));
+        assert!(data[1024..].iter().all(|&b| b == 0x11));
+    }
+
+    #[test]
+    fn short_metadata_region_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("short-metadata.vhdx");
+        std::fs::write(&path, build(&Layout { metadata_length: 16, ..Layout::default() })).unwrap();
+        let err = VhdxImage::open(&path, 0).err().unwrap();
+        assert!(err.to_string().contains("too small"));
+    }
+
+    #[test]
+    fn truncated_file_parameters_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("truncated.vhdx");
+        std::fs::write(&path, build(&Layout { file_parameters_length: 4, ..Layout::default() })).unwrap();
+        let err = VhdxImage::open(&path, 0).err().unwrap();
+        assert!(err.to_string().contains("file parameters"));
+    }
+
+    #[test]
+    fn corrupt_header_checksum_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("corrupt.vhdx");
+        let mut image = build(&Layout::default());
+        image[64 * 1024 + 100] ^= 0xFF;
+        std::fs::write(&path, image).unwrap();
+        assert!(VhdxImage::open(&path, 0).is_err());
+    }
+
+    #[test]
+    fn invalid_block_and_sector_sizes_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("geometry.vhdx");
+        for (block_size, logical_sector_size) in [(0, 512), (3 * MB as u32, 512), (MB as u32 / 2, 512), (512 * MB as u32, 512), (MB as u32, 0), (MB as u32, 1024)] {
+            std::fs::write(&path, build(&Layout { block_size, logical_sector_size, ..Layout::default() })).unwrap();
+            let err = VhdxImage::open(&path, 0).err().unwrap();
+            assert!(err.to_string().contains("Invalid VHDX geometry"), "block {} sector {}", block_size, logical_sector_size);
+        }
+
+        std::fs::write(&path, build(&Layout { block_size: 256 * MB as u32, logical_sector_size: 4096, ..Layout::default() })).unwrap();
+        assert!(VhdxImage::open(&path, 0).is_ok());
+    }
+}
@@ -0,0 +1,513 @@
+use super::{read_file_region, read_parent_or_zero, resolve_parent_path, open_parent_image, ReadSeek, VirtualDisk};
+use anyhow::{Context, Result, bail};
+use flate2::read::ZlibDecoder;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const VMDK_SPARSE_MAGIC: &[u8; 4] = b"KDMV";
+const VMDK_COWD_MAGIC: &[u8; 4] = b"COWD";
+const VMDK_DESCRIPTOR_MARKER: &[u8] = b"# Disk DescriptorFile";
+const SECTOR_SIZE: u64 = 512;
+const GD_AT_END: u64 = 0xFFFF_FFFF_FFFF_FFFF;
+/// 헤더 값으로 버퍼를 할당하기 전 상한: 그레인 최대 1MB, 그레인 테이블 최대 64K 엔트리
+const MAX_GRAIN_SECTORS: u64 = 2048;
+const MAX_GTE_PER_GT: u64 = 65536;
+
+// 스파스 헤더 플래그
+const FLAG_ZEROED_GRAIN_GTE: u32 = 1 << 2;
+const FLAG_COMPRESSED_GRAINS: u32 = 1 << 16;
+
+/// 파일 시작이 스파스 익스텐트("KDMV") 또는 텍스트 디스크립터인지 확인한다.
+pub fn is_vmdk_signature(header: &[u8]) -> bool {
+    header.starts_with(VMDK_SPARSE_MAGIC) || header.starts_with(VMDK_COWD_MAGIC) || header.starts_with(VMDK_DESCRIPTOR_MARKER)
+}
+
+/// 호스티드 스파스 익스텐트(monolithicSparse, twoGbMaxExtentSparse, streamOptimized)의 그레인 매핑
+struct SparseExtent {
+    file: File,
+    grain_size: u64,
+    gte_per_gt: u64,
+    grain_directory: Vec<u32>,
+    zeroed_grain_gte: bool,
+    compressed: bool,
+    /// 마지막으로 읽은 그레인 테이블 캐시 (디렉터리 인덱스, 엔트리)
+    cached_table: Option<(usize, Vec<u32>)>,
+    /// 압축 그레인은 그레인 단위로 해제해야 하므로 마지막 그레인을 캐시한다.
+    cached_grain: Option<(u64, Vec<u8>)>,
+}
+
+enum ExtentKind {
+    Flat { file: File, start_sector: u64 },
+    Sparse(Box<SparseExtent>),
+    Zero,
+}
+
+struct Extent {
+    start: u64,
+    length: u64,
+    kind: ExtentKind,
+}
+
+/// 읽기 요청 한 건에 대해 스파스 익스텐트가 돌려주는 결과
+enum GrainRead {
+    Data(usize),
+    Unallocated(usize),
+}
+
+/// VMware VMDK 이미지. 디스크립터에 나열된 FLAT/SPARSE/ZERO 익스텐트를 하나의 가상 디스크로 연결한다.
+pub struct VmdkImage {
+    extents: Vec<Extent>,
+    disk_size: u64,
+    parent: Option<Box<dyn ReadSeek>>,
+}
+
+impl VmdkImage {
+    pub(crate) fn open(path: &Path, depth: usize) -> Result<Self> {
+        let mut file = File::open(path).with_context(|| format!("Failed to open VMDK {}", path.display()))?;
+        let mut magic = [0u8; 4];
+        file.read_exact(&mut magic)?;
+
+        let descriptor = if &magic == VMDK_SPARSE_MAGIC {
+            // monolithicSparse/streamOptimized: 디스크립터가 스파스 파일 안에 내장되어 있다.
+            let header = read_sparse_header(&mut file)?;
+            let (offset, size) = (header.descriptor_offset, header.descriptor_size);
+            if offset == 0 || size == 0 {
+                // 디스크립터가 없는 단일 익스텐트
+                let extent = open_sparse_extent(path)?;
+                let length = header.capacity.checked_mul(SECTOR_SIZE).context("VMDK capacity overflows")?;
+                tracing::info!("  [*] VMDK image: single sparse extent, {} bytes", length);
+                return Ok(Self { extents: vec![Extent { start: 0, length, kind: ExtentKind::Sparse(Box::new(extent)) }], disk_size: length, parent: None });
+            }
+            let raw = read_file_region(&mut file, offset.saturating_mul(SECTOR_SIZE), size.saturating_mul(SECTOR_SIZE), "VMDK embedded descriptor")?;
+            String::from_utf8_lossy(&raw).trim_end_matches('\0').to_string()
+        } else if &magic == VMDK_COWD_MAGIC {
+            bail!("ESX VMFS sparse (COWD) extents are not supported");
+        } else {
+            let mut text = String::new();
+            file.seek(SeekFrom::Start(0))?;
+            file.take(1024 * 1024).read_to_string(&mut text).context("VMDK descriptor is not valid text")?;
+            text
+        };
+
+        Self::from_descriptor(path, &descriptor, depth)
+    }
+
+    fn from_descriptor(path: &Path, descriptor: &str, depth: usize) -> Result<Self> {
+        let base_dir = path.parent().filter(|p| !p.as_os_str().is_empty()).map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."));
+        let mut extents = Vec::new();
+        let mut disk_size = 0u64;
+        let mut parent_hint = None;
+        let mut parent_cid = None;
+
+        for line in descriptor.lines().map(str::trim) {
+            if line.starts_with('#') || line.is_empty() { continue; }
+
+            if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim().trim_matches('"').to_string();
+                match key.trim() {
+                    "parentFileNameHint" => parent_hint = Some(value),
+                    "parentCID" => parent_cid = Some(value),
+                    _ => {},
+                }
+                continue;
+            }
+
+            // 익스텐트 행: <access> <sectors> <type> ["filename" [offset]]
+            let fields = split_descriptor_fields(line);
+            if fields.len() < 3 || !matches!(fields[0].as_str(), "RW" | "RDONLY" | "NOACCESS") { continue; }
+            let sectors: u64 = fields[1].parse().with_context(|| format!("Invalid VMDK extent line: {}", line))?;
+            let length = sectors.checked_mul(SECTOR_SIZE).with_context(|| format!("VMDK extent size overflows: {}", line))?;
+
+            let kind = match fields[2].as_str() {
+                "FLAT" | "VMFS" => {
+                    let extent_path = base_dir.join(fields.get(3).context("FLAT extent without file name")?);
+                    let file = File::open(&extent_path).with_context(|| format!("Failed to open VMDK extent {}", extent_path.display()))?;
+                    let start_sector: u64 = fields.get(4).and_then(|s| s.parse().ok()).unwrap_or(0);
+                    if start_sector.checked_mul(SECTOR_SIZE).and_then(|start| start.checked_add(length)).is_none() {
+                        bail!("VMDK extent offset overflows: {}", line);
+                    }
+                    ExtentKind::Flat { file, start_sector }
+                },
+                "SPARSE" => {
+                    let extent_path = base_dir.join(fields.get(3).context("SPARSE extent without file name")?);
+                    ExtentKind::Sparse(Box::new(open_sparse_extent(&extent_path)?))
+                },
+                "ZERO" => ExtentKind::Zero,
+                other => bail!("Unsupported VMDK extent type {}", other),
+            };
+            extents.push(Extent { start: disk_size, length, kind });
+            disk_size = disk_size.checked_add(length).context("VMDK disk size overflows")?;
+        }
+
+        if extents.is_empty() { bail!("VMDK descriptor lists no extents"); }
+
+        // parentCID=ffffffff 는 부모가 없음을 뜻한다.
+        let parent = match (parent_hint, parent_cid.as_deref()) {
+            (Some(hint), cid) if cid != Some("ffffffff") => {
+                let parent_path = resolve_parent_path(path, std::slice::from_ref(&hint))
+                    .with_context(|| format!("Parent of VMDK delta disk not found ({})", hint))?;
+                Some(open_parent_image(&parent_path, depth)?)
+            },
+            _ => None,
+        };
+
+        tracing::info!("  [*] VMDK image: {} extent(s), {} bytes{}", extents.len(), disk_size,
+            if parent.is_some() { " (with parent chain)" } else { "" });
+        Ok(Self { extents, disk_size, parent })
+    }
+}
+
+impl VirtualDisk for VmdkImage {
+    fn disk_size(&self) -> u64 {
+        self.disk_size
+    }
+
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(extent) = self.extents.iter_mut().find(|e| offset >= e.start && offset < e.start + e.length) else {
+            return Ok(0);
+        };
+        let offset_in_extent = offset - extent.start;
+        let max = std::cmp::min(buf.len() as u64, extent.length - offset_in_extent) as usize;
+
+        match &mut extent.kind {
+            ExtentKind::Flat { file, start_sector } => {
+                file.seek(SeekFrom::Start(*start_sector * SECTOR_SIZE + offset_in_extent))?;
+                file.read_exact(&mut buf[..max])?;
+                Ok(max)
+            },
+            ExtentKind::Zero => {
+                buf[..max].fill(0);
+                Ok(max)
+            },
+            ExtentKind::Sparse(sparse) => match sparse.read_grain(offset_in_extent, &mut buf[..max])? {
+                GrainRead::Data(n) => Ok(n),
+                GrainRead::Unallocated(n) => {
+                    // 할당되지 않은 그레인: 델타 디스크면 부모, 아니면 0
+                    read_parent_or_zero(&mut self.parent, offset, &mut buf[..n])?;
+                    Ok(n)
+                },
+            },
+        }
+    }
+}
+
+impl SparseExtent {
+    fn read_grain(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<GrainRead> {
+        let grain_bytes = self.grain_size * SECTOR_SIZE;
+        let grain = offset / grain_bytes;
+        let offset_in_grain = offset % grain_bytes;
+        let n = std::cmp::min(buf.len() as u64, grain_bytes - offset_in_grain) as usize;
+
+        let gd_index = (grain / self.gte_per_gt) as usize;
+        let gt_index = (grain % self.gte_per_gt) as usize;
+        let table_sector = self.grain_directory.get(gd_index).copied().unwrap_or(0);
+        if table_sector == 0 {
+            return Ok(GrainRead::Unallocated(n));
+        }
+
+        if !matches!(&self.cached_table, Some((cached, _)) if *cached == gd_index) {
+            let mut raw = vec![0u8; (self.gte_per_gt * 4) as usize];
+            self.file.seek(SeekFrom::Start(table_sector as u64 * SECTOR_SIZE))?;
+            self.file.read_exact(&mut raw)?;
+            let table = raw.chunks_exact(4).map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]])).collect();
+            self.cached_table = Some((gd_index, table));
+        }
+
+        let grain_sector = self.cached_table.as_ref().unwrap().1[gt_index];
+        match grain_sector {
+            0 => return Ok(GrainRead::Unallocated(n)),
+            1 if self.zeroed_grain_gte => {
+                buf[..n].fill(0);
+                return Ok(GrainRead::Data(n));
+            },
+            _ => {},
+        }
+
+        if self.compressed {
+            if !matches!(&self.cached_grain, Some((cached, _)) if *cached == grain) {
+                // 압축 그레인 마커: LBA(u64) + 압축 크기(u32) + zlib 데이터
+                let mut marker = [0u8; 12];
+                self.file.seek(SeekFrom::Start(grain_sector as u64 * SECTOR_SIZE))?;
+                self.file.read_exact(&mut marker)?;
+                let compressed_size = u32::from_le_bytes(marker[8..12].try_into().unwrap()) as usize;
+                if compressed_size as u64 > grain_bytes * 2 {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, format!("VMDK compressed grain size {} exceeds grain", compressed_size)));
+                }
+                let mut compressed = vec![0u8; compressed_size];
+                self.file.read_exact(&mut compressed)?;
+
+                let mut data = Vec::with_capacity(grain_bytes as usize);
+                ZlibDecoder::new(&compressed[..]).read_to_end(&mut data)?;
+                data.resize(grain_bytes as usize, 0);
+                self.cached_grain = Some((grain, data));
+            }
+            let data = &self.cached_grain.as_ref().unwrap().1;
+            buf[..n].copy_from_slice(&data[offset_in_grain as usize..offset_in_grain as usize + n]);
+        } else {
+            self.file.seek(SeekFrom::Start(grain_sector as u64 * SECTOR_SIZE + offset_in_grain))?;
+            self.file.read_exact(&mut buf[..n])?;
+        }
+        Ok(GrainRead::Data(n))
+    }
+}
+
+struct SparseHeader {
+    flags: u32,
+    capacity: u64,
+    grain_size: u64,
+    descriptor_offset: u64,
+    descriptor_size: u64,
+    gte_per_gt: u64,
+    gd_offset: u64,
+}
+
+fn parse_sparse_header(h: &[u8]) -> Result<SparseHeader> {
+    if &h[0..4] != VMDK_SPARSE_MAGIC { bail!("VMDK sparse magic not found"); }
+    Ok(SparseHeader {
+        flags: u32::from_le_bytes(h[8..12].try_into().unwrap()),
+        capacity: u64::from_le_bytes(h[12..20].try_into().unwrap()),
+        grain_size: u64::from_le_bytes(h[20..28].try_into().unwrap()),
+        descriptor_offset: u64::from_le_bytes(h[28..36].try_into().unwrap()),
+        descriptor_size: u64::from_le_bytes(h[36..44].try_into().unwrap()),
+        gte_per_gt: u32::from_le_bytes(h[44..48].try_into().unwrap()) as u64,
+        gd_offset: u64::from_le_bytes(h[56..64].try_into().unwrap()),
+    })
+}
+
+/// 스파스 헤더를 읽는다. streamOptimized 이미지는 GD 위치가 파일 끝의 푸터 헤더에 기록된다.
+fn read_sparse_header(file: &mut File) -> Result<SparseHeader> {
+    let mut raw = [0u8; 512];
+    file.seek(SeekFrom::Start(0))?;
+    file.read_exact(&mut raw)?;
+    let header = parse_sparse_header(&raw)?;
+
+    if header.gd_offset == GD_AT_END {
+        let len = file.seek(SeekFrom::End(0))?;
+        file.seek(SeekFrom::Start(len.saturating_sub(1024)))?;
+        file.read_exact(&mut raw)?;
+        return parse_sparse_header(&raw).context("streamOptimized VMDK footer not found");
+    }
+    Ok(header)
+}
+
+fn open_sparse_extent(path: &Path) -> Result<SparseExtent> {
+    let mut file = File::open(path).with_context(|| format!("Failed to open VMDK extent {}", path.display()))?;
+    let header = read_sparse_header(&mut file)?;
+    if header.grain_size == 0 || header.grain_size > MAX_GRAIN_SECTORS || header.gte_per_gt == 0 || header.gte_per_gt > MAX_GTE_PER_GT {
+        bail!("Invalid VMDK sparse geometry in {} (grain {} sectors, {} GTEs per GT)", path.display(), header.grain_size, header.gte_per_gt);
+    }
+
+    let gd_entries = header.capacity.div_ceil(header.grain_size * header.gte_per_gt);
+    let raw = read_file_region(&mut file, header.gd_offset.saturating_mul(SECTOR_SIZE), gd_entries.saturating_mul(4), "VMDK grain directory")?;
+
+    Ok(SparseExtent {
+        file,
+        grain_size: header.grain_size,
+        gte_per_gt: header.gte_per_gt,
+        grain_directory: raw.chunks_exact(4).map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]])).collect(),
+        zeroed_grain_gte: (header.flags & FLAG_ZEROED_GRAIN_GTE) != 0,
+        compressed: (header.flags & FLAG_COMPRESSED_GRAINS) != 0,
+        cached_table: None,
+        cached_grain: None,
+    })
+}
+
+/// 공백으로 구분하되 따옴표로 감싼 파일명은 하나의 필드로 취급한다.
+fn split_descriptor_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in line.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() { fields.push(std::mem::take(&mut current)); }
+            },
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() { fields.push(current); }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::{open_image, VirtualDiskStream};
+    use flate2::{write::ZlibEncoder, Compression};
+    use std::io::Write;
+
+    const GRAIN_SECTORS: u64 = 8;
+    const GRAIN_BYTES: usize = (GRAIN_SECTORS * SECTOR_SIZE) as usize;
+    const GTE_PER_GT: u32 = 512;
+
+    fn sparse_header(flags: u32, capacity: u64, grain_size: u64, descriptor: (u64, u64), gd_offset: u64) -> Vec<u8> {
+        let mut header = vec![0u8; 512];
+        header[0..4].copy_from_slice(VMDK_SPARSE_MAGIC);
+        header[4..8].copy_from_slice(&1u32.to_le_bytes());
+        header[8..12].copy_from_slice(&flags.to_le_bytes());
+        header[12..20].copy_from_slice(&capacity.to_le_bytes());
+        header[20..28].copy_from_slice(&grain_size.to_le_bytes());
+        header[28..36].copy_from_slice(&descriptor.0.to_le_bytes());
+        header[36..44].copy_from_slice(&descriptor.1.to_le_bytes());
+        header[44..48].copy_from_slice(&GTE_PER_GT.to_le_bytes());
+        header[56..64].copy_from_slice(&gd_offset.to_le_bytes());
+        header
+    }
+
+    /// 2그레인 스파스 익스텐트: 헤더(0), GD(1), GT(2~5), 그레인 0(8~15). grain_zero가 None이면 두 그레인 모두 미할당.
+    fn sparse_extent(grain_zero: Option<u8>) -> Vec<u8> {
+        let mut image = sparse_header(0, 2 * GRAIN_SECTORS, GRAIN_SECTORS, (0, 0), 1);
+        let mut gd = vec![0u8; 512];
+        gd[0..4].copy_from_slice(&2u32.to_le_bytes());
+        image.extend(gd);
+        let mut gt = vec![0u8; GTE_PER_GT as usize * 4];
+        if grain_zero.is_some() { gt[0..4].copy_from_slice(&8u32.to_le_bytes()); }
+        image.extend(gt);
+        image.resize(8 * 512, 0);
+        if let Some(fill) = grain_zero { image.extend(vec![fill; GRAIN_BYTES]); }
+        image
+    }
+
+    fn read_all(path: &Path) -> Vec<u8> {
+        let mut data = Vec::new();
+        VirtualDiskStream::new(VmdkImage::open(path, 0).unwrap()).read_to_end(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn flat_and_zero_extents_are_concatenated() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut flat = vec![0u8; 512];
+        flat.extend(vec![0x44u8; 4096]);
+        std::fs::write(dir.path().join("disk-flat.vmdk"), flat).unwrap();
+        let descriptor = "# Disk DescriptorFile\nversion=1\nCID=fffffffe\nparentCID=ffffffff\ncreateType=\"monolithicFlat\"\n\n\
+                          RW 8 FLAT \"disk-flat.vmdk\" 1\nRW 8 ZERO\n";
+        let path = dir.path().join("disk.vmdk");
+        std::fs::write(&path, descriptor).unwrap();
+
+        let data = read_all(&path);
+        assert_eq!(data.len(), 8192);
+        assert!(data[..4096].iter().all(|&b| b == 0x44));
+        assert!(data[4096..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn monolithic_sparse_maps_grains() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sparse.vmdk");
+        std::fs::write(&path, sparse_extent(Some(0x66))).unwrap();
+
+        let data = read_all(&path);
+        assert_eq!(data.len(), 2 * GRAIN_BYTES);
+        assert!(data[..GRAIN_BYTES].iter().all(|&b| b == 0x66));
+        assert!(data[GRAIN_BYTES..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn delta_disk_reads_unallocated_grains_from_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("base.img"), vec![0x11u8; 2 * GRAIN_BYTES]).unwrap();
+        std::fs::write(dir.path().join("delta-s001.vmdk"), sparse_extent(Some(0x33))).unwrap();
+        let descriptor = "# Disk DescriptorFile\nversion=1\nCID=12345678\nparentCID=9abcdef0\ncreateType=\"twoGbMaxExtentSparse\"\n\
+                          parentFileNameHint=\"C:\\VMs\\base.img\"\n\nRW 16 SPARSE \"delta-s001.vmdk\"\n";
+        let path = dir.path().join("delta.vmdk");
+        std::fs::write(&path, descriptor).unwrap();
+
+        let mut stream = open_image(&path).unwrap();
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).unwrap();
+        assert_eq!(data.len(), 2 * GRAIN_BYTES);
+        assert!(data[..GRAIN_BYTES].iter().all(|&b| b == 0x33));
+        assert!(data[GRAIN_BYTES..].iter().all(|&b| b == 0x11));
+    }
+
+    /// streamOptimized: 헤더(GD는 파일 끝), 압축 그레인(1), GT(4~7), GD(8), 푸터 헤더(9), EOS 마커(10)
+    fn stream_optimized(compressed_size_override: Option<u32>) -> Vec<u8> {
+        let flags = FLAG_COMPRESSED_GRAINS | (1 << 17);
+        let mut image = sparse_header(flags, 2 * GRAIN_SECTORS, GRAIN_SECTORS, (0, 0), GD_AT_END);
+
+        let grain: Vec<u8> = (0..GRAIN_BYTES).map(|i| (i % 7) as u8).collect();
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&grain).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut marker = 0u64.to_le_bytes().to_vec();
+        marker.extend(compressed_size_override.unwrap_or(compressed.len() as u32).to_le_bytes());
+        marker.extend(compressed);
+        assert!(marker.len() <= 3 * 512);
+        image.extend(marker);
+        image.resize(4 * 512, 0);
+
+        let mut gt = vec![0u8; GTE_PER_GT as usize * 4];
+        gt[0..4].copy_from_slice(&1u32.to_le_bytes());
+        image.extend(gt);
+        let mut gd = vec![0u8; 512];
+        gd[0..4].copy_from_slice(&4u32.to_le_bytes());
+        image.extend(gd);
+        image.extend(sparse_header(flags, 2 * GRAIN_SECTORS, GRAIN_SECTORS, (0, 0), 8));
+        image.extend(vec![0u8; 512]);
+        image
+    }
+
+    #[test]
+    fn stream_optimized_inflates_compressed_grains() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stream.vmdk");
+        std::fs::write(&path, stream_optimized(None)).unwrap();
+
+        let data = read_all(&path);
+        assert_eq!(data.len(), 2 * GRAIN_BYTES);
+        assert!(data[..GRAIN_BYTES].iter().enumerate().all(|(i, &b)| b == (i % 7) as u8));
+        assert!(data[GRAIN_BYTES..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn oversized_compressed_grain_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad-grain.vmdk");
+        std::fs::write(&path, stream_optimized(Some(0x7FFF_FFFF))).unwrap();
+
+        let mut disk = VmdkImage::open(&path, 0).unwrap();
+        let mut buf = [0u8; 512];
+        assert_eq!(disk.read_at(0, &mut buf).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn corrupt_sparse_headers_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let huge_grain = dir.path().join("huge-grain.vmdk");
+        let mut image = sparse_extent(None);
+        image[20..28].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        std::fs::write(&huge_grain, image).unwrap();
+        assert!(VmdkImage::open(&huge_grain, 0).is_err());
+
+        let bad_descriptor = dir.path().join("bad-descriptor.vmdk");
+        let mut image = sparse_extent(None);
+        image[..512].copy_from_slice(&sparse_header(0, 16, GRAIN_SECTORS, (1, 1 << 40), 1));
+        std::fs::write(&bad_descriptor, image).unwrap();
+        let err = VmdkImage::open(&bad_descriptor, 0).err().unwrap();
+        assert!(err.to_string().contains("exceeds file size"));
+
+        let truncated = dir.path().join("truncated.vmdk");
+        std::fs::write(&truncated, &sparse_extent(None)[..100]).unwrap();
+        assert!(VmdkImage::open(&truncated, 0).is_err());
+    }
+
+    #[test]
+    fn overflowing_extent_sizes_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("disk-flat.vmdk"), vec![0u8; 4096]).unwrap();
+        let path = dir.path().join("disk.vmdk");
+        for extents in [
+            format!("RW {} FLAT \"disk-flat.vmdk\" 0\n", u64::MAX / 2),
+            format!("RW 8 FLAT \"disk-flat.vmdk\" {}\n", u64::MAX / 256),
+            format!("RW {} ZERO\nRW {} ZERO\n", u64::MAX / 768, u64::MAX / 768),
+        ] {
+            std::fs::write(&path, format!("# Disk DescriptorFile\nversion=1\nparentCID=ffffffff\n\n{}", extents)).unwrap();
+            let err = VmdkImage::open(&path, 0).err().unwrap();
+            assert!(err.to_string().contains("overflows"), "{}", extents);
+        }
+    }
+}
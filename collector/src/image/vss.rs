@@ -0,0 +1,436 @@
+use super::SharedImage;
+use anyhow::{Context, Result};
+use models::vss::ShadowCopyInfo;
+use parser::vss::{
+    parse_block_list, parse_catalog_block, parse_store_header, parse_volume_header, BlockDescriptor, CatalogEntry,
+    BLOCK_FLAG_FORWARDER, BLOCK_FLAG_NOT_USED, BLOCK_FLAG_OVERLAY, VSS_BLOCK_SIZE, VSS_VOLUME_HEADER_OFFSET,
+};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::rc::Rc;
+
+/// 카탈로그/블록 목록 체인의 최대 길이 (순환 참조 방지)
+const MAX_BLOCK_CHAIN: usize = 65536;
+/// 포워더가 다른 포워더를 가리킬 수 있는 최대 깊이
+const MAX_FORWARD_DEPTH: usize = 64;
+const SECTOR_SIZE: usize = 512;
+
+/// 스냅숏 하나의 저장소: 스냅숏 이후 덮어쓰인 원본 블록의 이전 내용 위치
+#[derive(Default)]
+struct ShadowStore {
+    blocks: HashMap<u64, BlockDescriptor>,
+    overlays: HashMap<u64, Vec<BlockDescriptor>>,
+}
+
+/// 볼륨의 VSS 카탈로그. 스냅숏은 생성 시각 오름차순이며, 스냅숏 i의 블록은 저장소 i, i+1, ... 순으로 찾고
+/// 어느 저장소에도 없으면(스냅숏 이후 변경되지 않은 블록) 현재 볼륨에서 읽는다.
+pub struct ShadowVolume {
+    volume: SharedImage,
+    snapshots: Vec<ShadowCopyInfo>,
+    stores: Rc<Vec<ShadowStore>>,
+}
+
+impl ShadowVolume {
+    /// NTFS 볼륨(오프셋 0 = VBR)에서 VSS 카탈로그를 읽는다. VSS 헤더나 스냅숏이 없으면 None.
+    pub fn open(volume: SharedImage) -> Result<Option<Self>> {
+        let mut header_buf = [0u8; 512];
+        volume.read_at(VSS_VOLUME_HEADER_OFFSET, &mut header_buf)?;
+        let Ok(header) = parse_volume_header(&header_buf) else { return Ok(None) };
+        if header.catalog_offset == 0 { return Ok(None); }
+
+        let mut snapshot_entries = HashMap::new();
+        let mut store_entries = Vec::new();
+        for block in read_block_chain(&volume, header.catalog_offset)? {
+            let (next, entries) = match parse_catalog_block(&block) {
+                Ok((block_header, entries)) => (block_header.next_offset, entries),
+                Err(e) => {
+                    tracing::warn!("  [!] VSS catalog block unreadable: {}", e);
+                    break;
+                },
+            };
+            for entry in entries {
+                match entry {
+                    CatalogEntry::Snapshot { store_id, volume_size, creation_time } => {
+                        snapshot_entries.insert(store_id, (volume_size, creation_time));
+                    },
+                    CatalogEntry::Store { store_id, block_list_offset, store_header_offset } => {
+                        store_entries.push((store_id, block_list_offset, store_header_offset));
+                    },
+                }
+            }
+            if next == 0 { break; }
+        }
+
+        let mut paired = Vec::new();
+        for (store_id, block_list_offset, store_header_offset) in store_entries {
+            let Some(&(volume_size, creation_time)) = snapshot_entries.get(&store_id) else { continue };
+            let store = match load_store(&volume, block_list_offset) {
+                Ok(store) => store,
+                Err(e) => {
+                    tracing::warn!("  [!] VSS store {} unreadable: {}", store_id, e);
+                    continue;
+                },
+            };
+
+            let mut block = vec![0u8; VSS_BLOCK_SIZE as usize];
+            let information = volume.read_at(store_header_offset, &mut block).ok()
+                .and_then(|_| parse_store_header(&block).ok());
+            let (shadow_copy_id, shadow_copy_set_id) = information
+                .map(|i| (i.shadow_copy_id, i.shadow_copy_set_id))
+                .unwrap_or_default();
+
+            paired.push((ShadowCopyInfo { index: 0, store_id, shadow_copy_id, shadow_copy_set_id, creation_time, volume_size }, store));
+        }
+        if paired.is_empty() { return Ok(None); }
+
+        paired.sort_by_key(|(info, _)| info.creation_time);
+        let (mut snapshots, stores): (Vec<_>, Vec<_>) = paired.into_iter().unzip();
+        for (index, info) in snapshots.iter_mut().enumerate() {
+            info.index = index;
+        }
+        Ok(Some(Self { volume, snapshots, stores: Rc::new(stores) }))
+    }
+
+    pub fn snapshots(&self) -> &[ShadowCopyInfo] {
+        &self.snapshots
+    }
+
+    /// 스냅숏 시점의 볼륨을 오프셋 0부터 읽을 수 있는 Read + Seek 스트림으로 연다.
+    pub fn open_snapshot(&self, index: usize) -> Result<ShadowCopyStream> {
+        let info = self.snapshots.get(index).with_context(|| format!("No shadow copy #{}", index))?;
+        Ok(ShadowCopyStream {
+            volume: self.volume.clone(),
+            stores: Rc::clone(&self.stores),
+            first_store: index,
+            size: info.volume_size,
+            pos: 0,
+            cached: None,
+        })
+    }
+}
+
+/// next_offset으로 이어진 16KB 블록 목록을 읽는다.
+fn read_block_chain(volume: &SharedImage, start: u64) -> Result<Vec<Vec<u8>>> {
+    let mut blocks = Vec::new();
+    let mut visited = HashSet::new();
+    let mut offset = start;
+
+    while offset != 0 && visited.len() < MAX_BLOCK_CHAIN && visited.insert(offset) {
+        let mut block = vec![0u8; VSS_BLOCK_SIZE as usize];
+        volume.read_at(offset, &mut block).with_context(|| format!("VSS block read failed at {:#X}", offset))?;
+        offset = u64::from_le_bytes(block[40..48].try_into().unwrap());
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
+
+fn load_store(volume: &SharedImage, block_list_offset: u64) -> Result<ShadowStore> {
+    let mut store = ShadowStore::default();
+    for block in read_block_chain(volume, block_list_offset)? {
+        let (_, descriptors) = parse_block_list(&block)?;
+        for descriptor in descriptors {
+            if descriptor.flags & BLOCK_FLAG_NOT_USED != 0 { continue; }
+            if descriptor.flags & BLOCK_FLAG_OVERLAY != 0 {
+                store.overlays.entry(descriptor.original_offset).or_default().push(descriptor);
+            } else {
+                // 같은 원본 블록이 여러 번 기록되면 처음 기록(가장 이른 시점의 내용)이 스냅숏 시점 데이터다.
+                store.blocks.entry(descriptor.original_offset).or_insert(descriptor);
+            }
+        }
+    }
+    Ok(store)
+}
+
+/// 스냅숏 시점 볼륨의 Read + Seek 뷰
+pub struct ShadowCopyStream {
+    volume: SharedImage,
+    stores: Rc<Vec<ShadowStore>>,
+    first_store: usize,
+    size: u64,
+    pos: u64,
+    /// 마지막으로 재구성한 16KB 블록 (MFT 레코드 단위의 작은 읽기가 연속되므로)
+    cached: Option<(u64, Vec<u8>)>,
+}
+
+impl ShadowCopyStream {
+    /// 저장소 first부터 시작하여 원본 블록 offset의 스냅숏 시점 내용을 재구성한다.
+    fn resolve_block(&self, first: usize, offset: u64, depth: usize) -> io::Result<Vec<u8>> {
+        if depth > MAX_FORWARD_DEPTH {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "VSS forwarder chain too deep"));
+        }
+        let mut overlays = Vec::new();
+        let mut block = None;
+
+        for (index, store) in self.stores.iter().enumerate().skip(first) {
+            if let Some(list) = store.overlays.get(&offset) {
+                overlays.extend(list.iter());
+            }
+            if let Some(descriptor) = store.blocks.get(&offset) {
+                block = Some(if descriptor.flags & BLOCK_FLAG_FORWARDER != 0 {
+                    self.resolve_block(index + 1, descriptor.relative_offset, depth + 1)?
+                } else {
+                    self.read_volume(descriptor.store_offset)?
+                });
+                break;
+            }
+        }
+        let mut block = match block {
+            Some(block) => block,
+            None => self.read_volume(offset)?,
+        };
+
+        // 오버레이는 비트맵에 표시된 512바이트 섹터만 덮어쓴다. 더 오래된 저장소의 오버레이가 우선한다.
+        for overlay in overlays.iter().rev() {
+            let data = self.read_volume(overlay.store_offset)?;
+            for sector in (0..32).filter(|bit| overlay.allocation_bitmap & (1 << bit) != 0) {
+                let range = sector * SECTOR_SIZE..(sector + 1) * SECTOR_SIZE;
+                block[range.clone()].copy_from_slice(&data[range]);
+            }
+        }
+        Ok(block)
+    }
+
+    /// 현재 볼륨에서 16KB를 읽는다. 볼륨이 스냅숏 이후 축소되어 범위를 벗어나면 0으로 채운다.
+    fn read_volume(&self, offset: u64) -> io::Result<Vec<u8>> {
+        let mut block = vec![0u8; VSS_BLOCK_SIZE as usize];
+        match self.volume.read_at(offset, &mut block) {
+            Ok(()) => Ok(block),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(vec![0u8; VSS_BLOCK_SIZE as usize]),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Read for ShadowCopyStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.size || buf.is_empty() { return Ok(0); }
+        let block_offset = self.pos - self.pos % VSS_BLOCK_SIZE;
+
+        if self.cached.as_ref().is_none_or(|(offset, _)| *offset != block_offset) {
+            let block = self.resolve_block(self.first_store, block_offset, 0)?;
+            self.cached = Some((block_offset, block));
+        }
+        let (_, block) = self.cached.as_ref().unwrap();
+
+        let within = (self.pos - block_offset) as usize;
+        let available = std::cmp::min(VSS_BLOCK_SIZE - within as u64, self.size - self.pos) as usize;
+        let n = std::cmp::min(buf.len(), available);
+        buf[..n].copy_from_slice(&block[within..within + n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for ShadowCopyStream {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(p) => p as i128,
+            SeekFrom::End(off) => self.size as i128 + off as i128,
+            SeekFrom::Current(off) => self.pos as i128 + off as i128,
+        };
+        if new_pos < 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Seek before start of shadow copy"));
+        }
+        self.pos = new_pos as u64;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const VSS_IDENTIFIER: [u8; 16] = [0x6B, 0x87, 0x08, 0x38, 0x76, 0xC1, 0x48, 0x4E, 0xB7, 0xAE, 0x04, 0x04, 0x6E, 0x6C, 0xC7, 0x52];
+    const BLOCK: usize = VSS_BLOCK_SIZE as usize;
+    const VOLUME_BLOCKS: usize = 32;
+    /// 2024-03-01T09:00:00Z 기준 FILETIME
+    const FILETIME: u64 = (1_709_283_600 + 11_644_473_600) * 10_000_000;
+    const HOUR: u64 = 3600 * 10_000_000;
+
+    fn at(block: usize) -> u64 {
+        (block * BLOCK) as u64
+    }
+
+    fn put_u64(data: &mut [u8], offset: usize, value: u64) {
+        data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// 16KB 블록 단위 볼륨. 데이터 블록 n은 현재 내용으로 0xC0 + n을 채운다.
+    struct Volume(Vec<u8>);
+
+    impl Volume {
+        fn new() -> Self {
+            let mut data = vec![0u8; VOLUME_BLOCKS * BLOCK];
+            for n in 8..16 {
+                data[n * BLOCK..(n + 1) * BLOCK].fill(0xC0 + n as u8);
+            }
+            Self(data)
+        }
+
+        fn block(&mut self, n: usize) -> &mut [u8] {
+            &mut self.0[n * BLOCK..(n + 1) * BLOCK]
+        }
+
+        fn record(&mut self, n: usize, record_type: u32, next: u64) -> &mut [u8] {
+            let block = self.block(n);
+            block[0..16].copy_from_slice(&VSS_IDENTIFIER);
+            block[16..20].copy_from_slice(&1u32.to_le_bytes());
+            block[20..24].copy_from_slice(&record_type.to_le_bytes());
+            put_u64(block, 32, at(n));
+            put_u64(block, 40, next);
+            block
+        }
+
+        fn volume_header(&mut self, catalog_offset: u64) {
+            let header = &mut self.0[VSS_VOLUME_HEADER_OFFSET as usize..VSS_VOLUME_HEADER_OFFSET as usize + 512];
+            header[0..16].copy_from_slice(&VSS_IDENTIFIER);
+            header[16..20].copy_from_slice(&1u32.to_le_bytes());
+            header[20..24].copy_from_slice(&1u32.to_le_bytes());
+            put_u64(header, 48, catalog_offset);
+        }
+
+        /// (원본 블록, 상대 블록, 저장소 블록, 플래그, 비트맵) 목록으로 블록 목록 블록을 만든다.
+        fn block_list(&mut self, n: usize, descriptors: &[(usize, usize, usize, u32, u32)]) {
+            let block = self.record(n, 3, 0);
+            for (i, &(original, relative, store, flags, bitmap)) in descriptors.iter().enumerate() {
+                let d = 128 + i * 32;
+                put_u64(block, d, at(original));
+                put_u64(block, d + 8, at(relative));
+                put_u64(block, d + 16, at(store));
+                block[d + 24..d + 28].copy_from_slice(&flags.to_le_bytes());
+                block[d + 28..d + 32].copy_from_slice(&bitmap.to_le_bytes());
+            }
+        }
+
+        fn shared(self) -> SharedImage {
+            SharedImage::new(Box::new(Cursor::new(self.0)))
+        }
+    }
+
+    /// 스냅숏 0(저장소 A, 블록 목록 2, 헤더 3)과 스냅숏 1(저장소 B, 블록 목록 4, 헤더 5). 카탈로그(블록 1)에는 B가 먼저 기록된다.
+    ///   블록 8: 스냅숏 1 이후 변경 (B -> 20)
+    ///   블록 9: 스냅숏 0과 1 사이에 변경 (A -> 21)
+    ///   블록 10: A의 포워더 -> 블록 11, 블록 11은 B -> 22
+    ///   블록 12: B의 오버레이, 섹터 0과 2만 23번 블록 내용
+    ///   블록 13: 변경 없음 (A의 미사용 디스크립터는 무시)
+    fn shadowed_volume() -> Volume {
+        let mut volume = Volume::new();
+        volume.volume_header(at(1));
+        for (n, fill) in [(20, 0xB8), (21, 0xA9), (22, 0xBB), (23, 0xD0), (24, 0xEE)] {
+            volume.block(n).fill(fill);
+        }
+
+        let catalog = volume.record(1, 2, 0);
+        for (i, (guid, created, block_list, store_header)) in [(0xBBu8, FILETIME + HOUR, 4, 5), (0xAA, FILETIME, 2, 3)].into_iter().enumerate() {
+            let snapshot = 128 + i * 256;
+            put_u64(catalog, snapshot, 2);
+            put_u64(catalog, snapshot + 8, at(VOLUME_BLOCKS));
+            catalog[snapshot + 16..snapshot + 32].fill(guid);
+            put_u64(catalog, snapshot + 48, created);
+            let store = snapshot + 128;
+            put_u64(catalog, store, 3);
+            put_u64(catalog, store + 8, at(block_list));
+            catalog[store + 16..store + 32].fill(guid);
+            put_u64(catalog, store + 32, at(store_header));
+        }
+
+        volume.block_list(2, &[
+            (9, 0, 21, 0, 0),
+            (10, 11, 0, BLOCK_FLAG_FORWARDER, 0),
+            (13, 0, 24, BLOCK_FLAG_NOT_USED, 0),
+        ]);
+        volume.block_list(4, &[
+            (8, 0, 20, 0, 0),
+            (11, 0, 22, 0, 0),
+            (12, 0, 23, BLOCK_FLAG_OVERLAY, 0b101),
+        ]);
+        for (n, id) in [(3, 0x0A), (5, 0x0B)] {
+            let header = volume.record(n, 4, 0);
+            header[128 + 16..128 + 32].fill(id);
+        }
+        volume
+    }
+
+    fn read_block(stream: &mut ShadowCopyStream, n: usize) -> Vec<u8> {
+        let mut block = vec![0u8; BLOCK];
+        stream.seek(SeekFrom::Start(at(n))).unwrap();
+        stream.read_exact(&mut block).unwrap();
+        block
+    }
+
+    fn filled(byte: u8) -> Vec<u8> {
+        vec![byte; BLOCK]
+    }
+
+    #[test]
+    fn catalog_orders_snapshots_by_creation_time() {
+        let shadow = ShadowVolume::open(shadowed_volume().shared()).unwrap().unwrap();
+        let snapshots = shadow.snapshots();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].index, 0);
+        assert_eq!(snapshots[0].store_id, "AAAAAAAA-AAAA-AAAA-AAAA-AAAAAAAAAAAA");
+        assert_eq!(snapshots[0].shadow_copy_id, "0A0A0A0A-0A0A-0A0A-0A0A-0A0A0A0A0A0A");
+        assert_eq!(snapshots[1].store_id, "BBBBBBBB-BBBB-BBBB-BBBB-BBBBBBBBBBBB");
+        assert!(snapshots[0].creation_time < snapshots[1].creation_time);
+        assert_eq!(snapshots[1].volume_size, at(VOLUME_BLOCKS));
+    }
+
+    #[test]
+    fn newest_snapshot_reads_its_store_then_the_live_volume() {
+        let shadow = ShadowVolume::open(shadowed_volume().shared()).unwrap().unwrap();
+        let mut stream = shadow.open_snapshot(1).unwrap();
+
+        assert_eq!(read_block(&mut stream, 8), filled(0xB8));
+        assert_eq!(read_block(&mut stream, 9), filled(0xC9));
+        assert_eq!(read_block(&mut stream, 13), filled(0xCD));
+
+        let overlay = read_block(&mut stream, 12);
+        for (sector, data) in overlay.chunks(SECTOR_SIZE).enumerate() {
+            let expected = if sector == 0 || sector == 2 { 0xD0 } else { 0xCC };
+            assert!(data.iter().all(|&b| b == expected), "sector {}", sector);
+        }
+    }
+
+    #[test]
+    fn older_snapshot_follows_later_stores_and_forwarders() {
+        let shadow = ShadowVolume::open(shadowed_volume().shared()).unwrap().unwrap();
+        let mut stream = shadow.open_snapshot(0).unwrap();
+
+        // 저장소 A에 없는 블록은 더 새로운 저장소 B에서 찾는다.
+        assert_eq!(read_block(&mut stream, 8), filled(0xB8));
+        assert_eq!(read_block(&mut stream, 9), filled(0xA9));
+        // 포워더: A의 블록 10 -> 블록 11을 저장소 B부터 다시 찾는다.
+        assert_eq!(read_block(&mut stream, 10), filled(0xBB));
+        assert_eq!(read_block(&mut stream, 11), filled(0xBB));
+        assert_eq!(read_block(&mut stream, 13), filled(0xCD));
+        assert_eq!(read_block(&mut stream, 12)[..SECTOR_SIZE], filled(0xD0)[..SECTOR_SIZE]);
+
+        // 블록 경계를 넘는 읽기와 볼륨 끝
+        let mut buf = [0u8; 4];
+        stream.seek(SeekFrom::Start(at(10) - 2)).unwrap();
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [0xA9, 0xA9, 0xBB, 0xBB]);
+        stream.seek(SeekFrom::End(0)).unwrap();
+        assert_eq!(stream.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn forwarder_in_newest_store_reads_the_live_volume() {
+        let mut volume = shadowed_volume();
+        // B의 블록 11이 블록 14로 포워딩되면, 더 새로운 저장소가 없으므로 현재 볼륨의 블록 14를 읽는다.
+        volume.block_list(4, &[(11, 14, 0, BLOCK_FLAG_FORWARDER, 0)]);
+        let shadow = ShadowVolume::open(volume.shared()).unwrap().unwrap();
+
+        assert_eq!(read_block(&mut shadow.open_snapshot(1).unwrap(), 11), filled(0xCE));
+        assert_eq!(read_block(&mut shadow.open_snapshot(0).unwrap(), 10), filled(0xCE));
+    }
+
+    #[test]
+    fn volume_without_catalog_has_no_snapshots() {
+        assert!(ShadowVolume::open(Volume::new().shared()).unwrap().is_none());
+        let mut volume = Volume::new();
+        volume.volume_header(0);
+        assert!(ShadowVolume::open(volume.shared()).unwrap().is_none());
+    }
+}
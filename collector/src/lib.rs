@@ -1,7 +1,16 @@
+#[cfg(windows)]
 pub mod privilege;
+#[cfg(windows)]
 pub mod reader;
+pub mod image; // 라이브 볼륨과 오프라인 이미지를 동일하게 다루는 소스 추상화 계층
 pub mod mft;
+pub mod stream; // 런리스트 기반 스트리밍 읽기
 pub mod filesystem; // [New] 파일 시스템 논리 제어 계층
 pub mod artifacts;
+pub mod triage; // 원본 아티팩트 + 해시 매니페스트 패키징
+pub mod source; // 수집 백엔드 공통 인터페이스
+pub mod triage_folder; // 이미 수집된 트리아지 폴더/ZIP 백엔드
+#[cfg(test)]
+mod test_volume;
 
 pub use models::FactError;
\ No newline at end of file
@@ -1,53 +1,121 @@
-use std::fs::File;
+use crate::image::ReadSeek;
+use crate::stream::RunlistReader;
 use std::io::{Read, Seek, SeekFrom, Write};
 use anyhow::{Result, Context, bail};
-use models::mft::DataRun;
+use models::mft::{AlternateDataStream, AttributeHeader, DataRun, MftRecord, DeletedFileRecord, IndexSlackEntry, mft_entry_number};
+use parser::carve::{find_artifacts, CarvedKind, MAX_CARVE_SIZE};
+use parser::logfile::VolumeGeometry;
 use parser::mft::{
     parse_file_record_header, parse_attributes, parse_non_resident_header, 
-    parse_runlist, parse_boot_sector_manual
+    parse_runlist, parse_boot_sector_manual, apply_fixup, parse_mft_record,
+    resident_content, MftPathResolver, MftRecordReader, ORPHAN_PATH_PREFIX,
+    attribute_name, parse_index_entries, parse_index_record, carve_index_slack
 };
+use std::collections::HashSet;
 
-pub(crate) fn apply_fixup(data: &mut [u8]) -> Result<()> {
-    if data.len() < 512 { return Ok(()); }
-    let signature = &data[0..4];
-    if signature != b"FILE" && signature != b"INDX" { return Ok(()); }
-    let usa_offset = u16::from_le_bytes([data[4], data[5]]) as usize;
-    let usa_count = u16::from_le_bytes([data[6], data[7]]) as usize;
-    if usa_offset == 0 || usa_count <= 1 || usa_offset + (usa_count * 2) > data.len() { return Ok(()); }
-    let update_seq_num = [data[usa_offset], data[usa_offset+1]];
-    let sector_count = usa_count - 1;
-    let sector_size = 512;
-    for i in 0..sector_count {
-        let sector_end = (i + 1) * sector_size - 2;
-        let fixup_idx = usa_offset + 2 + (i * 2);
-        if sector_end + 2 > data.len() || fixup_idx + 2 > data.len() { break; }
-        if data[sector_end] == update_seq_num[0] && data[sector_end+1] == update_seq_num[1] {
-            data[sector_end] = data[fixup_idx];
-            data[sector_end+1] = data[fixup_idx+1];
-        }
-    }
-    Ok(())
+/// 0~23번 엔트리는 NTFS 메타데이터 파일용으로 예약되어 있다.
+const FIRST_USER_ENTRY: u64 = 24;
+/// ADS 분류/Zone.Identifier 해석에 읽는 스트림 앞부분 크기
+const ADS_CONTENT_LIMIT: u64 = 4096;
+
+/// $Bitmap 메타데이터 파일에서 읽은 클러스터 할당 비트맵 (LCN당 1비트, LSB 우선)
+pub struct ClusterBitmap {
+    bits: Vec<u8>,
+}
+
+impl ClusterBitmap {
+    pub fn is_allocated(&self, lcn: u64) -> bool {
+        // 비트맵 범위를 벗어난 클러스터는 볼륨 밖이므로 할당된 것으로 취급하여 복구 대상에서 제외한다.
+        self.bits.get((lcn / 8) as usize).is_none_or(|byte| (byte >> (lcn % 8)) & 1 != 0)
+    }
+
+    /// 런리스트가 가리키는 클러스터 중 현재 다른 파일에 재할당된 클러스터 수
+    pub fn count_allocated(&self, runlist: &[DataRun]) -> u64 {
+        runlist.iter()
+            .filter(|run| run.start_lcn != u64::MAX)
+            .map(|run| (run.start_lcn..run.start_lcn + run.length).filter(|&lcn| self.is_allocated(lcn)).count() as u64)
+            .sum()
+    }
+
+    pub fn cluster_count(&self) -> u64 {
+        self.bits.len() as u64 * 8
+    }
+
+    /// 미할당 클러스터를 연속 구간(런) 단위로 반환한다.
+    pub fn free_runs(&self) -> Vec<DataRun> {
+        let mut runs = Vec::new();
+        let mut start: Option<u64> = None;
+        for (index, &byte) in self.bits.iter().enumerate() {
+            let base = index as u64 * 8;
+            // 바이트 전체가 같은 상태이면 비트 단위로 볼 필요가 없다.
+            if byte == 0xFF {
+                if let Some(s) = start.take() { runs.push(DataRun { start_lcn: s, length: base - s }); }
+                continue;
+            }
+            if byte == 0x00 {
+                start.get_or_insert(base);
+                continue;
+            }
+            for bit in 0..8 {
+                let lcn = base + bit;
+                if (byte >> bit) & 1 == 0 {
+                    start.get_or_insert(lcn);
+                } else if let Some(s) = start.take() {
+                    runs.push(DataRun { start_lcn: s, length: lcn - s });
+                }
+            }
+        }
+        if let Some(s) = start { runs.push(DataRun { start_lcn: s, length: self.cluster_count() - s }); }
+        runs
+    }
+}
+
+/// [추가] 미할당 클러스터에서 카빙하여 검증을 통과한 아티팩트
+#[derive(Debug, Clone)]
+pub struct CarvedArtifact {
+    pub kind: CarvedKind,
+    /// 볼륨 시작 기준 바이트 오프셋
+    pub volume_offset: u64,
+    pub data: Vec<u8>,
+}
+
+/// 삭제 파일 데이터 복구 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryStatus {
+    /// MFT 레코드 내부(상주 속성)에서 복구
+    Resident,
+    /// 모든 데이터 클러스터가 아직 미할당 상태여서 온전히 복구
+    Unallocated,
+    /// 일부 클러스터가 다른 파일에 재할당되어 복구하지 않음
+    Overwritten { reallocated_clusters: u64 },
+    /// 기본 $DATA 스트림이 없음 (디렉터리 등)
+    NoData,
 }
 
 pub struct MftReader {
-    file: File,                 
+    source: Box<dyn ReadSeek>,  
     cluster_size: u64,          
     record_size: u64,           
+    index_record_size: u64,
+    volume_serial_number: u64,
     mft_runlist: Vec<DataRun>,  
 }
 
 impl MftReader {
-    pub fn bootstrap(mut file: File) -> Result<Self> {
+    /// 볼륨 오프셋 0에 VBR이 위치한 임의의 소스(라이브 볼륨 또는 볼륨 이미지)로부터 $MFT 런리스트를 복원한다.
+    pub fn bootstrap(mut source: Box<dyn ReadSeek>) -> Result<Self> {
         let mut vbr = [0u8; 512];
-        file.seek(SeekFrom::Start(0))?;
-        file.read_exact(&mut vbr)?;
+        source.seek(SeekFrom::Start(0))?;
+        source.read_exact(&mut vbr)?;
         let boot = parse_boot_sector_manual(&vbr)?;
         let cluster_size = boot.cluster_size();
         let mft_offset = boot.mft_offset();
+        let record_size = boot.mft_record_size();
         
-        file.seek(SeekFrom::Start(mft_offset))?;
-        let mut mft_0 = vec![0u8; 1024];
-        file.read_exact(&mut mft_0)?;
+        // $MFT 첫 런은 최소 레코드 0~3을 연속으로 담으므로 레코드 0은 런 경계를 넘지 않는다.
+        source.seek(SeekFrom::Start(mft_offset))?;
+        let mut mft_0 = vec![0u8; record_size as usize];
+        source.read_exact(&mut mft_0)?;
         apply_fixup(&mut mft_0)?;
         
         let header = parse_file_record_header(&mft_0)?;
@@ -65,20 +133,19 @@ impl MftReader {
                         attr_list_data = mft_0[attr.offset+v_off .. end].to_vec();
                     }
                 }
-            } else if attr.type_code == 0x80 && attr.non_resident_flag == 1 {
-                if let Ok(nr) = parse_non_resident_header(&mft_0[attr.offset..]) {
-                    let start = attr.offset + nr.run_array_offset as usize;
-                    let end = std::cmp::min(attr.offset + attr.length as usize, mft_0.len());
-                    if start <= end {
-                        if let Ok(runs) = parse_runlist(&mft_0[start..end]) {
-                            initial_runlist = runs;
-                        }
-                    }
+            } else if attr.type_code == 0x80 && attr.non_resident_flag == 1
+                && let Ok(nr) = parse_non_resident_header(&mft_0[attr.offset..]) {
+                let start = attr.offset + nr.run_array_offset as usize;
+                let end = std::cmp::min(attr.offset + attr.length as usize, mft_0.len());
+                if start <= end
+                    && let Ok(runs) = parse_runlist(&mft_0[start..end]) {
+                    initial_runlist = runs;
                 }
             }
         }
 
-        let mut mft_runlist = initial_runlist.clone();
+        // [Fix] 소스 핸들을 복제할 수 없으므로, 확장 레코드 탐색 중인 리더가 런리스트를 직접 누적한다.
+        let mut reader = Self { source, cluster_size, record_size, index_record_size: boot.index_record_size(), volume_serial_number: boot.volume_serial_number, mft_runlist: initial_runlist };
         
         if !attr_list_data.is_empty() {
             let mut extents = Vec::new();
@@ -97,26 +164,20 @@ impl MftReader {
             }
 
             extents.sort_by_key(|e| e.0);
-            let mut temp_reader = Self { file: file.try_clone()?, cluster_size, record_size: 1024, mft_runlist: mft_runlist.clone() };
 
             for (lowest_vcn, mft_ref) in extents {
                 if lowest_vcn == 0 || mft_ref == 0 { continue; } 
-                if let Ok(child_data) = temp_reader.read_record(mft_ref) {
-                    if let Ok(child_header) = parse_file_record_header(&child_data) {
-                        if let Ok(child_attrs) = parse_attributes(&child_data, &child_header) {
-                            for c_attr in child_attrs {
-                                if c_attr.type_code == 0x80 && c_attr.non_resident_flag == 1 {
-                                    if let Ok(nr) = parse_non_resident_header(&child_data[c_attr.offset..]) {
-                                        let start = c_attr.offset + nr.run_array_offset as usize;
-                                        let end = std::cmp::min(c_attr.offset + c_attr.length as usize, child_data.len());
-                                        if start <= end {
-                                            if let Ok(runs) = parse_runlist(&child_data[start..end]) {
-                                                mft_runlist.extend(runs);
-                                                temp_reader.mft_runlist = mft_runlist.clone(); 
-                                            }
-                                        }
-                                    }
-                                }
+                if let Ok(child_data) = reader.read_record(mft_ref)
+                    && let Ok(child_header) = parse_file_record_header(&child_data)
+                    && let Ok(child_attrs) = parse_attributes(&child_data, &child_header) {
+                    for c_attr in child_attrs {
+                        if c_attr.type_code == 0x80 && c_attr.non_resident_flag == 1
+                            && let Ok(nr) = parse_non_resident_header(&child_data[c_attr.offset..]) {
+                            let start = c_attr.offset + nr.run_array_offset as usize;
+                            let end = std::cmp::min(c_attr.offset + c_attr.length as usize, child_data.len());
+                            if start <= end
+                                && let Ok(runs) = parse_runlist(&child_data[start..end]) {
+                                reader.mft_runlist.extend(runs);
                             }
                         }
                     }
@@ -124,8 +185,344 @@ impl MftReader {
             }
         }
 
-        if mft_runlist.is_empty() { bail!("Failed to locate $MFT Runlist"); }
-        Ok(Self { file, cluster_size, record_size: 1024, mft_runlist })
+        if reader.mft_runlist.is_empty() { bail!("Failed to locate $MFT Runlist"); }
+        Ok(reader)
+    }
+
+    pub fn cluster_size(&self) -> u64 {
+        self.cluster_size
+    }
+
+    /// VBR에 기록된 FILE 레코드 크기 (1KB 또는 4KB)
+    pub fn record_size(&self) -> u64 {
+        self.record_size
+    }
+
+    /// $LogFile의 대상 VCN을 MFT 엔트리로 환산하기 위한 볼륨 지오메트리
+    pub fn volume_geometry(&self) -> VolumeGeometry {
+        VolumeGeometry { cluster_size: self.cluster_size, record_size: self.record_size }
+    }
+
+    /// VBR에 기록된 INDX 레코드 크기. 디렉터리의 $INDEX_ROOT에 값이 있으면 그쪽이 우선한다.
+    pub fn index_record_size(&self) -> u64 {
+        self.index_record_size
+    }
+
+    /// VBR 0x48의 볼륨 일련번호 (트리아지 매니페스트 기록용)
+    pub fn volume_serial_number(&self) -> u64 {
+        self.volume_serial_number
+    }
+
+    /// $MFT 런리스트가 커버하는 전체 레코드 슬롯 수
+    pub fn record_count(&self) -> u64 {
+        self.mft_runlist.iter().map(|r| r.length).sum::<u64>() * self.cluster_size / self.record_size
+    }
+
+    /// 전체 MFT를 런 단위로 순차 읽기하여 사용 중/미사용을 가리지 않고 FILE 레코드를 하나씩 디코딩한다.
+    pub fn scan_records(&mut self) -> MftRecordReader<RunlistReader<'_>> {
+        let total = self.record_count() * self.record_size;
+        // 클러스터가 레코드보다 작으면 레코드가 런 경계에 걸칠 수 있으므로 런리스트를 연속 스트림으로 읽는다. (희소 런은 0으로 채워져 건너뛴다)
+        let stream = RunlistReader::new(&mut *self.source, self.cluster_size, &self.mft_runlist, total);
+        MftRecordReader::with_record_size(stream, self.record_size as usize)
+    }
+
+    /// 사용 중 플래그가 해제된 베이스 레코드를 찾아, 부모 시퀀스가 일치하는 범위까지 마지막 경로를 복원한다.
+    /// MFT는 한 번만 순회하며 경로 테이블과 삭제 레코드만 보관한다.
+    pub fn scan_deleted_records(&mut self) -> Result<Vec<DeletedFileRecord>> {
+        let mut resolver = MftPathResolver::new();
+        let mut records = Vec::new();
+        for record in self.scan_records() {
+            resolver.add_record(&record);
+            if !record.in_use && record.base_reference == 0 && !record.file_names.is_empty() {
+                records.push(record);
+            }
+        }
+
+        let deleted = records.into_iter()
+            .map(|record| {
+                let path = resolver.resolve(record.entry_number);
+                let parent_path_valid = !path.starts_with(ORPHAN_PATH_PREFIX);
+                DeletedFileRecord { record, path, parent_path_valid }
+            })
+            .collect();
+        Ok(deleted)
+    }
+
+    /// 사용 중인 모든 디렉터리의 $I30 INDX 레코드 slack을 카빙하여, 현재 인덱스에 없는 엔트리만 삭제 흔적으로 반환한다.
+    /// (속성 리스트로 분산된 $INDEX_ALLOCATION은 베이스 레코드에 있는 것만 대상으로 한다)
+    pub fn scan_index_slack(&mut self) -> Result<Vec<IndexSlackEntry>> {
+        let mut resolver = MftPathResolver::new();
+        let mut directories = Vec::new();
+        for record in self.scan_records() {
+            resolver.add_record(&record);
+            if record.in_use && record.is_directory && record.base_reference == 0 {
+                directories.push((record.entry_number, record.reference()));
+            }
+        }
+
+        let mut carved = Vec::new();
+        for (entry, reference) in directories {
+            let Ok(entries) = self.carve_directory_slack(entry, reference) else { continue };
+            if entries.is_empty() { continue; }
+            let directory_path = resolver.resolve(entry);
+            carved.extend(entries.into_iter().map(|mut e| { e.directory_path = directory_path.clone(); e }));
+        }
+        Ok(carved)
+    }
+
+    fn carve_directory_slack(&mut self, entry: u64, dir_reference: u64) -> Result<Vec<IndexSlackEntry>> {
+        let raw = self.read_record(entry)?;
+        let header = parse_file_record_header(&raw)?;
+        let attributes = parse_attributes(&raw, &header)?;
+
+        let mut block_size = self.index_record_size as usize;
+        let mut live: Vec<(u64, String)> = Vec::new();
+        let mut buffers = Vec::new();
+
+        for attr in attributes.iter().filter(|a| attribute_name(&raw, a) == "$I30") {
+            match attr.type_code {
+                0x90 => {
+                    // $INDEX_ROOT: 인덱스 레코드 크기(0x08)와 노드 헤더(0x10) 뒤의 현재 엔트리
+                    if let Some(root) = resident_content(&raw, attr).filter(|r| r.len() >= 32) {
+                        let size = u32::from_le_bytes(root[8..12].try_into().unwrap()) as usize;
+                        if size.is_power_of_two() && size >= 512 { block_size = size; }
+                        let first_entry = u32::from_le_bytes(root[16..20].try_into().unwrap()) as usize;
+                        if 16 + first_entry < root.len() {
+                            live.extend(parse_index_entries(&root[16 + first_entry..]).unwrap_or_default()
+                                .into_iter().map(|e| (e.file_reference, e.filename.to_lowercase())));
+                        }
+                    }
+                },
+                0xA0 if attr.non_resident_flag != 0 => {
+                    let nr = parse_non_resident_header(&raw[attr.offset..])?;
+                    let start = attr.offset + nr.run_array_offset as usize;
+                    let end = std::cmp::min(attr.offset + attr.length as usize, raw.len());
+                    if start < end {
+                        let runs = parse_runlist(&raw[start..end])?;
+                        buffers.push(self.read_data_from_runlist(&runs, nr.real_size)?);
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        let mut carved = Vec::new();
+        for buffer in &buffers {
+            for chunk in buffer.chunks_exact(block_size) {
+                let mut block = chunk.to_vec();
+                if &block[0..4] != b"INDX" || apply_fixup(&mut block).is_err() { continue; }
+                live.extend(parse_index_record(&block).unwrap_or_default()
+                    .into_iter().map(|e| (e.file_reference, e.filename.to_lowercase())));
+                carved.extend(carve_index_slack(&block, dir_reference));
+            }
+        }
+
+        // 엔트리 이동(B+ 트리 재배치)으로 slack에 복사본만 남은 현재 엔트리와, 같은 키의 중복 잔재를 제거한다.
+        let mut seen = HashSet::new();
+        carved.retain(|e| {
+            let name = e.file_name.name.to_lowercase();
+            let is_live = live.iter().any(|(reference, live_name)| {
+                *live_name == name && e.file_reference.is_none_or(|r| mft_entry_number(r) == *reference)
+            });
+            !is_live && seen.insert((name, e.file_reference, e.file_name.creation_time))
+        });
+        Ok(carved)
+    }
+
+    /// 사용 중인 모든 파일/디렉터리의 이름 있는 $DATA 스트림을 나열한다. 확장 레코드에 있는 스트림은
+    /// 베이스 레코드의 경로와 $SI 시각으로 보고하며, 내용은 앞 ADS_CONTENT_LIMIT 바이트만 읽는다.
+    pub fn scan_alternate_data_streams(&mut self) -> Result<Vec<AlternateDataStream>> {
+        let mut resolver = MftPathResolver::new();
+        let mut candidates = Vec::new();
+        for record in self.scan_records() {
+            resolver.add_record(&record);
+            let base_entry = if record.base_reference == 0 { record.entry_number } else { mft_entry_number(record.base_reference) };
+            // 예약 메타데이터 엔트리와 $UsnJrnl:$J 같은 '$' 시스템 스트림은 제외한다.
+            if !record.in_use || base_entry < FIRST_USER_ENTRY { continue; }
+            if record.data_streams.iter().any(|s| !s.name.is_empty() && !s.name.starts_with('$')) {
+                candidates.push((base_entry, record));
+            }
+        }
+
+        let mut streams = Vec::new();
+        for (base_entry, record) in candidates {
+            let base = if record.base_reference == 0 {
+                None
+            } else {
+                let Some(base) = self.read_record(base_entry).ok().and_then(|raw| parse_mft_record(&raw, base_entry).ok()) else { continue };
+                if base.base_reference != 0 { continue; }
+                Some(base)
+            };
+            let base = base.as_ref().unwrap_or(&record);
+            let path = resolver.resolve(base_entry);
+
+            for stream in record.data_streams.iter().filter(|s| !s.name.is_empty() && !s.name.starts_with('$')) {
+                let limit = std::cmp::min(stream.real_size, ADS_CONTENT_LIMIT);
+                let content = if stream.is_resident {
+                    self.read_record(record.entry_number).ok()
+                        .and_then(|raw| resident_stream(&raw, &stream.name))
+                        .map(|mut data| { data.truncate(limit as usize); data })
+                        .unwrap_or_default()
+                } else {
+                    self.read_data_from_runlist(&stream.runlist, limit).unwrap_or_default()
+                };
+
+                let si = base.standard_info.as_ref();
+                streams.push(AlternateDataStream {
+                    file_reference: base.reference(),
+                    path: path.clone(),
+                    stream_name: stream.name.clone(),
+                    size: stream.real_size,
+                    is_resident: stream.is_resident,
+                    is_directory: base.is_directory,
+                    si_creation_time: si.map(|s| s.creation_time).unwrap_or(0),
+                    si_mft_modified_time: si.map(|s| s.mft_modified_time).unwrap_or(0),
+                    content,
+                });
+            }
+        }
+        Ok(streams)
+    }
+
+    /// $Bitmap(엔트리 6)의 기본 데이터 스트림을 읽어 클러스터 할당 비트맵을 구성한다.
+    pub fn load_cluster_bitmap(&mut self) -> Result<ClusterBitmap> {
+        let raw = self.read_record(6)?;
+        let record = parse_mft_record(&raw, 6)?;
+        let stream = record.data_streams.iter().find(|s| s.name.is_empty()).context("$Bitmap has no $DATA stream")?;
+
+        let bits = if stream.is_resident {
+            let header = parse_file_record_header(&raw)?;
+            let attr = parse_attributes(&raw, &header)?.into_iter().find(|a| a.type_code == 0x80 && a.name_length == 0).context("$Bitmap $DATA missing")?;
+            resident_content(&raw, &attr).unwrap_or_default().to_vec()
+        } else {
+            self.read_data_from_runlist(&stream.runlist, stream.real_size)?
+        };
+        Ok(ClusterBitmap { bits })
+    }
+
+    /// [추가] $Bitmap에서 미할당으로 표시된 클러스터 구간을 순차로 읽으며 EVTX 청크, hbin, LNK, 프리페치를 카빙한다.
+    /// 조각난(연속되지 않은 미할당 구간에 걸친) 아티팩트는 복구하지 않는다.
+    pub fn carve_unallocated<F>(&mut self, bitmap: &ClusterBitmap, mut callback: F) -> Result<usize>
+    where
+        F: FnMut(CarvedArtifact),
+    {
+        const CARVE_WINDOW: usize = 8 * 1024 * 1024;
+        let cluster_size = self.cluster_size;
+        let mut carved = 0;
+
+        for run in bitmap.free_runs() {
+            let run_bytes = run.length.checked_mul(cluster_size).context("Free run overflow")?;
+            let run_offset = run.start_lcn.checked_mul(cluster_size).context("LCN overflow")?;
+            let mut reader = self.open_runlist(std::slice::from_ref(&run), run_bytes);
+            // 윈도 끝에 걸친 아티팩트도 검증할 수 있도록 MAX_CARVE_SIZE만큼 더 읽어 둔다.
+            let mut window = Vec::with_capacity(CARVE_WINDOW + MAX_CARVE_SIZE);
+            let mut window_start = 0u64;
+
+            loop {
+                let want = (CARVE_WINDOW + MAX_CARVE_SIZE - window.len()) as u64;
+                if let Err(e) = (&mut reader).take(want).read_to_end(&mut window) {
+                    // $Bitmap의 끝 비트가 볼륨 밖을 가리키는 경우 등
+                    tracing::debug!("    [-] Stopped carving free run at LCN {}: {}", run.start_lcn, e);
+                    break;
+                }
+                let at_end = window_start + window.len() as u64 >= run_bytes;
+                let scan_end = if at_end { window.len() } else { CARVE_WINDOW };
+
+                let (hits, next_offset) = find_artifacts(&window, scan_end);
+                for hit in hits {
+                    callback(CarvedArtifact {
+                        kind: hit.kind,
+                        volume_offset: run_offset + window_start + hit.offset as u64,
+                        data: window[hit.offset..hit.offset + hit.length].to_vec(),
+                    });
+                    carved += 1;
+                }
+                if at_end { break; }
+                // 섹터 정렬을 유지하기 위해, 다음 시작점이 윈도 밖이면 그만큼 스트림을 건너뛴다.
+                let consumed = std::cmp::min(next_offset, window.len());
+                window.drain(..consumed);
+                if next_offset > consumed {
+                    std::io::copy(&mut (&mut reader).take((next_offset - consumed) as u64), &mut std::io::sink())?;
+                }
+                window_start += next_offset as u64;
+            }
+        }
+        Ok(carved)
+    }
+
+    /// 삭제 레코드의 기본 $DATA 스트림을 복구한다. 속성 리스트로 확장 레코드에 나뉜 익스텐트까지 이어 붙이며,
+    /// 비상주 데이터는 모든 클러스터가 미할당일 때만 추출한다.
+    pub fn recover_deleted_data(&mut self, record: &MftRecord, bitmap: &ClusterBitmap, writer: &mut dyn Write) -> Result<RecoveryStatus> {
+        let raw = self.read_record(record.entry_number)?;
+        let header = parse_file_record_header(&raw)?;
+        let attrs = parse_attributes(&raw, &header)?;
+        let is_default_data = |attr: &AttributeHeader| attr.type_code == 0x80 && attr.name_length == 0;
+
+        if let Some(attr) = attrs.iter().find(|a| is_default_data(a) && a.non_resident_flag == 0) {
+            writer.write_all(resident_content(&raw, attr).unwrap_or_default())?;
+            return Ok(RecoveryStatus::Resident);
+        }
+
+        let mut extension_entries = Vec::new();
+        for (type_code, _, reference) in self.attribute_list_entries(&raw, &attrs) {
+            let entry = mft_entry_number(reference);
+            if type_code == 0x80 && entry != record.entry_number && !extension_entries.contains(&entry) {
+                extension_entries.push(entry);
+            }
+        }
+        let mut extents: Vec<(Vec<u8>, AttributeHeader)> = attrs.into_iter()
+            .filter(|a| is_default_data(a))
+            .map(|a| (raw.clone(), a))
+            .collect();
+        for entry in extension_entries {
+            let Ok(ext) = self.read_record(entry) else { continue };
+            let Ok(ext_header) = parse_file_record_header(&ext) else { continue };
+            // 확장 레코드가 이미 다른 파일에 재사용되었으면 이 파일의 익스텐트가 아니다.
+            if ext_header.flags & 0x01 != 0 || mft_entry_number(ext_header.base_file_record) != record.entry_number { continue; }
+            for attr in parse_attributes(&ext, &ext_header).unwrap_or_default().into_iter().filter(|a| is_default_data(a) && a.non_resident_flag != 0) {
+                extents.push((ext.clone(), attr));
+            }
+        }
+        if extents.is_empty() { return Ok(RecoveryStatus::NoData); }
+
+        let (runlist, real_size) = join_extents(&extents)?;
+        if runlist.is_empty() { return Ok(RecoveryStatus::NoData); }
+        let reallocated_clusters = bitmap.count_allocated(&runlist);
+        if reallocated_clusters > 0 {
+            return Ok(RecoveryStatus::Overwritten { reallocated_clusters });
+        }
+
+        self.extract_runlist_to_writer(&runlist, real_size, writer)?;
+        Ok(RecoveryStatus::Unallocated)
+    }
+
+    /// 레코드의 $ATTRIBUTE_LIST(상주 또는 비상주) 항목을 (속성 타입, 시작 VCN, 파일 참조)로 읽는다.
+    pub fn attribute_list_entries(&mut self, record: &[u8], attrs: &[AttributeHeader]) -> Vec<(u32, u64, u64)> {
+        let Some(attr) = attrs.iter().find(|a| a.type_code == 0x20) else { return Vec::new() };
+        let list = if attr.non_resident_flag == 0 {
+            resident_content(record, attr).map(<[u8]>::to_vec).unwrap_or_default()
+        } else {
+            let Ok(nr) = parse_non_resident_header(&record[attr.offset..]) else { return Vec::new() };
+            let start = attr.offset + nr.run_array_offset as usize;
+            let end = std::cmp::min(attr.offset + attr.length as usize, record.len());
+            if start > end { return Vec::new(); }
+            let Ok(runs) = parse_runlist(&record[start..end]) else { return Vec::new() };
+            self.read_data_from_runlist(&runs, nr.real_size).unwrap_or_default()
+        };
+
+        let mut entries = Vec::new();
+        let mut cur = 0;
+        while cur + 26 <= list.len() {
+            let length = u16::from_le_bytes(list[cur + 4..cur + 6].try_into().unwrap()) as usize;
+            if length == 0 { break; }
+            entries.push((
+                u32::from_le_bytes(list[cur..cur + 4].try_into().unwrap()),
+                u64::from_le_bytes(list[cur + 8..cur + 16].try_into().unwrap()),
+                u64::from_le_bytes(list[cur + 16..cur + 24].try_into().unwrap()),
+            ));
+            cur += length;
+        }
+        entries
     }
 
     pub fn read_record(&mut self, index: u64) -> Result<Vec<u8>> {
@@ -134,11 +531,18 @@ impl MftReader {
         let mut current_vcn = 0;
         for run in &self.mft_runlist {
             if target_vcn >= current_vcn && target_vcn < current_vcn + run.length {
-                let lcn = run.start_lcn + (target_vcn - current_vcn);
-                let phys_off = lcn.checked_mul(self.cluster_size).context("Phys Overflow")? + (v_off % self.cluster_size);
-                self.file.seek(SeekFrom::Start(phys_off))?;
                 let mut buf = vec![0u8; self.record_size as usize];
-                self.file.read_exact(&mut buf)?;
+                // 레코드가 런 안에 온전히 들어 있으면 바로 읽고, 런 경계에 걸치면(클러스터 < 레코드) 런리스트 스트림으로 읽는다.
+                if v_off + self.record_size <= (current_vcn + run.length) * self.cluster_size {
+                    let lcn = run.start_lcn + (target_vcn - current_vcn);
+                    let phys_off = lcn.checked_mul(self.cluster_size).context("Phys Overflow")? + (v_off % self.cluster_size);
+                    self.source.seek(SeekFrom::Start(phys_off))?;
+                    self.source.read_exact(&mut buf)?;
+                } else {
+                    let mut stream = RunlistReader::new(&mut *self.source, self.cluster_size, &self.mft_runlist, u64::MAX);
+                    stream.seek(SeekFrom::Start(v_off))?;
+                    stream.read_exact(&mut buf)?;
+                }
                 apply_fixup(&mut buf)?;
                 return Ok(buf);
             }
@@ -147,76 +551,436 @@ impl MftReader {
         bail!("Inode {} OOB", index)
     }
 
+    /// 런리스트를 size 바이트 길이의 Read + Seek 스트림으로 연다. (희소 런은 디스크를 읽지 않고 0으로 채운다)
+    pub fn open_runlist(&mut self, runlist: &[DataRun], size: u64) -> RunlistReader<'_> {
+        RunlistReader::new(&mut *self.source, self.cluster_size, runlist, size)
+    }
+
+    /// 속성 리스트, 인덱스 할당 등 작은 비상주 속성을 통째로 읽는다. 대용량 스트림은 open_runlist를 사용한다.
     pub fn read_data_from_runlist(&mut self, runlist: &[DataRun], max_size: u64) -> Result<Vec<u8>> {
         let mut buffer = Vec::new();
-        let mut total_read: u64 = 0;
-        let cluster_size = self.cluster_size;
-
-        for run in runlist {
-            if total_read >= max_size { break; }
-            let run_len = run.length.checked_mul(cluster_size).unwrap_or(u64::MAX);
-            let size = std::cmp::min(run_len, max_size - total_read);
-            
-            if run.start_lcn != u64::MAX {
-                if let Some(phys_off) = run.start_lcn.checked_mul(cluster_size) {
-                    self.file.seek(SeekFrom::Start(phys_off))?;
-                    let aligned_size = ((size + cluster_size - 1) / cluster_size) * cluster_size;
-                    let mut chunk = vec![0u8; aligned_size as usize];
-                    let _ = self.file.read_exact(&mut chunk);
-                    
-                    chunk.truncate(size as usize);
-                    buffer.extend(chunk);
-                }
-            } else {
-                buffer.extend(vec![0u8; size as usize]);
-            }
-            total_read += size;
-        }
+        self.open_runlist(runlist, max_size).read_to_end(&mut buffer).context("Runlist I/O Error")?;
         Ok(buffer)
     }
 
     pub fn extract_runlist_to_writer(&mut self, runlist: &[DataRun], max_size: u64, writer: &mut dyn Write) -> Result<u64> {
+        const MAX_CHUNK: u64 = 4 * 1024 * 1024;
+        let mut reader = self.open_runlist(runlist, max_size);
+        let mut buffer = vec![0u8; std::cmp::min(MAX_CHUNK, reader.len()) as usize];
         let mut total_written: u64 = 0;
+
+        loop {
+            let n = reader.read(&mut buffer).context("Runlist I/O Error")?;
+            if n == 0 { break; }
+            writer.write_all(&buffer[..n])?;
+            total_written += n as u64;
+        }
+        Ok(total_written)
+    }
+
+    /// NTFS 압축 속성(LZNT1)을 압축 단위(2^compression_unit 클러스터)별로 해제하여 기록한다.
+    /// 단위 전체가 할당되어 있으면 비압축 단위, 전부 희소면 0으로 채운 단위, 일부만 할당되어 있으면 LZNT1 압축 단위다.
+    pub fn extract_compressed_runlist_to_writer(&mut self, runlist: &[DataRun], compression_unit: u16, max_size: u64, writer: &mut dyn Write) -> Result<u64> {
+        if compression_unit == 0 || compression_unit > 16 { bail!("Invalid compression unit: {}", compression_unit); }
         let cluster_size = self.cluster_size;
+        let unit_clusters = 1u64 << compression_unit;
+        let unit_bytes = unit_clusters.checked_mul(cluster_size).context("Compression unit overflow")?;
 
+        // VCN 순서대로 (시작 VCN, 런) 목록을 만든다.
+        let mut vcn_runs = Vec::with_capacity(runlist.len());
+        let mut next_vcn = 0u64;
         for run in runlist {
-            if total_written >= max_size { break; }
-            if run.start_lcn == u64::MAX { 
-                let run_len = run.length.checked_mul(cluster_size).unwrap_or(u64::MAX);
-                let size = std::cmp::min(run_len, max_size - total_written);
-                total_written += size;
-                continue;
+            vcn_runs.push((next_vcn, run));
+            next_vcn += run.length;
+        }
+
+        let mut total_written: u64 = 0;
+        let mut unit_vcn = 0u64;
+        while unit_vcn < next_vcn && total_written < max_size {
+            let unit_end = unit_vcn + unit_clusters;
+            let mut allocated: Vec<(u64, u64)> = Vec::new();
+            for &(vcn, run) in &vcn_runs {
+                let (start, end) = (vcn.max(unit_vcn), (vcn + run.length).min(unit_end));
+                if start >= end || run.start_lcn == u64::MAX { continue; }
+                allocated.push((run.start_lcn + (start - vcn), end - start));
+            }
+            let allocated_clusters: u64 = allocated.iter().map(|(_, count)| count).sum();
+
+            let mut unit = Vec::with_capacity(unit_bytes as usize);
+            for (lcn, count) in allocated {
+                self.source.seek(SeekFrom::Start(lcn.checked_mul(cluster_size).context("LCN overflow")?))?;
+                let start = unit.len();
+                unit.resize(start + (count * cluster_size) as usize, 0);
+                self.source.read_exact(&mut unit[start..]).context("Compression unit I/O Error")?;
             }
 
-            let run_bytes = run.length.checked_mul(cluster_size).context("Runlist overflow")?;
-            // [Fix] mut 키워드 제거
-            let run_remaining = std::cmp::min(run_bytes, max_size - total_written);
-            let phys_off = run.start_lcn.checked_mul(cluster_size).context("LCN overflow")?;
-            
-            self.file.seek(SeekFrom::Start(phys_off))?;
+            if allocated_clusters > 0 && allocated_clusters < unit_clusters {
+                unit = parser::compression::decompress_lznt1(&unit)?;
+            }
+            unit.resize(unit_bytes as usize, 0);
 
-            let clusters_to_read = (run_remaining + cluster_size - 1) / cluster_size;
-            let aligned_read_size = clusters_to_read * cluster_size;
-            const MAX_CHUNK: u64 = 4 * 1024 * 1024;
-            let mut buffer = vec![0u8; MAX_CHUNK as usize];
+            let size = std::cmp::min(unit_bytes, max_size - total_written);
+            writer.write_all(&unit[..size as usize])?;
+            total_written += size;
+            unit_vcn = unit_end;
+        }
+        Ok(total_written)
+    }
+}
 
-            let mut aligned_remaining = aligned_read_size;
-            let mut exact_remaining = run_remaining;
+/// FILE 레코드에서 이름이 일치하는 상주 $DATA 속성의 내용을 찾는다.
+/// 속성 리스트로 여러 레코드에 나뉜 $DATA 익스텐트의 런리스트를 VCN 순으로 잇는다. 빈 구간은 희소 런으로 채운다.
+/// 논리 크기는 시작 VCN이 0인 익스텐트 헤더에만 기록되어 있다.
+pub(crate) fn join_extents(extents: &[(Vec<u8>, AttributeHeader)]) -> Result<(Vec<DataRun>, u64)> {
+    let mut parts = Vec::new();
+    let mut real_size = 0;
+    for (r, attr) in extents {
+        let nr = parse_non_resident_header(&r[attr.offset..])?;
+        let start = attr.offset + nr.run_array_offset as usize;
+        let end = std::cmp::min(attr.offset + attr.length as usize, r.len());
+        if start > end { continue; }
+        if nr.starting_vcn == 0 { real_size = nr.real_size; }
+        parts.push((nr.starting_vcn, parse_runlist(&r[start..end])?));
+    }
+    parts.sort_by_key(|(vcn, _)| *vcn);
 
-            while aligned_remaining > 0 {
-                let current_aligned_read = std::cmp::min(MAX_CHUNK, aligned_remaining);
-                let slice = &mut buffer[..current_aligned_read as usize];
-                
-                self.file.read_exact(slice).context("Sector-aligned Block I/O Error")?;
+    let mut runlist = Vec::new();
+    let mut next_vcn = 0u64;
+    for (vcn, runs) in parts {
+        if vcn < next_vcn { continue; }
+        if vcn > next_vcn {
+            runlist.push(DataRun { start_lcn: u64::MAX, length: vcn - next_vcn });
+        }
+        next_vcn = vcn + runs.iter().map(|run| run.length).sum::<u64>();
+        runlist.extend(runs);
+    }
+    Ok((runlist, real_size))
+}
 
-                let current_exact_write = std::cmp::min(current_aligned_read, exact_remaining);
-                writer.write_all(&slice[..current_exact_write as usize])?;
+fn resident_stream(raw: &[u8], name: &str) -> Option<Vec<u8>> {
+    let header = parse_file_record_header(raw).ok()?;
+    let attributes = parse_attributes(raw, &header).ok()?;
+    attributes.iter()
+        .find(|a| a.type_code == 0x80 && a.non_resident_flag == 0 && attribute_name(raw, a) == name)
+        .and_then(|a| resident_content(raw, a))
+        .map(|content| content.to_vec())
+}
 
-                aligned_remaining -= current_aligned_read;
-                exact_remaining -= current_exact_write;
-                total_written += current_exact_write;
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_volume::*;
+
+    #[test]
+    fn compressed_runlist_decodes_each_compression_unit() {
+        // 압축 단위 16클러스터(8KB): 0번은 전부 할당(비압축), 1번은 1클러스터만 할당(LZNT1), 2번은 전부 희소
+        let stored: Vec<u8> = (0..16 * CLUSTER_SIZE).map(|i| (i % 251) as u8).collect();
+        let lznt1 = [0x05, 0xB0, 0x08, b'a', b'b', b'c', 0x26, 0x21, 0x00, 0x00];
+        let mut volume = Volume::new();
+        volume.write(40, 0, &stored);
+        volume.write(60, 0, &lznt1);
+        let mut reader = volume.reader();
+
+        let runlist = [
+            DataRun { start_lcn: 40, length: 16 },
+            DataRun { start_lcn: 60, length: 1 },
+            DataRun { start_lcn: u64::MAX, length: 15 },
+            DataRun { start_lcn: u64::MAX, length: 16 },
+        ];
+        let unit = 16 * CLUSTER_SIZE;
+        let max_size = (2 * unit + 100) as u64;
+        let mut out = Vec::new();
+        assert_eq!(reader.extract_compressed_runlist_to_writer(&runlist, 4, max_size, &mut out).unwrap(), max_size);
+
+        let mut expected = stored.clone();
+        let mut decoded = b"abc".repeat(100);
+        decoded.resize(unit, 0);
+        expected.extend(decoded);
+        expected.extend([0u8; 100]);
+        assert_eq!(out, expected);
+
+        assert!(reader.extract_compressed_runlist_to_writer(&runlist, 0, max_size, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn corrupt_compression_unit_is_an_error() {
+        let mut volume = Volume::new();
+        // 첫 토큰이 역참조인 LZNT1 청크
+        volume.write(40, 0, &[0x02, 0xB0, 0x01, 0x00, 0x10]);
+        let mut reader = volume.reader();
+        let runlist = [DataRun { start_lcn: 40, length: 1 }, DataRun { start_lcn: u64::MAX, length: 15 }];
+        assert!(reader.extract_compressed_runlist_to_writer(&runlist, 4, u64::MAX, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn directory_slack_reports_stale_names_and_drops_relocated_live_entries() {
+        const DOCS: u64 = 11;
+        const DOCS_REFERENCE: u64 = (1 << 48) | DOCS;
+        const INDX_LCN: u64 = 100;
+        let keep = index_entry((1 << 48) | 70, Some(file_name(DOCS_REFERENCE, "keep.txt", 0x20)));
+        let notes = index_entry((1 << 48) | 71, Some(file_name(DOCS_REFERENCE, "notes.txt", 0x20)));
+        let deleted = index_entry((3 << 48) | 72, Some(file_name(DOCS_REFERENCE, "deleted.txt", 0x20)));
+
+        // $INDEX_ROOT에는 keep.txt만 있고 하위 노드(INDX 레코드)를 가진다.
+        let mut root = index_root(std::slice::from_ref(&keep));
+        root[28] = 0x01;
+
+        // INDX 레코드: 사용 영역에 notes.txt, slack에 재배치된 keep.txt/notes.txt 복사본과 삭제된 deleted.txt 두 벌
+        let mut block = vec![0u8; 4096];
+        block[0..4].copy_from_slice(b"INDX");
+        block[4..6].copy_from_slice(&0x28u16.to_le_bytes());
+        block[6..8].copy_from_slice(&9u16.to_le_bytes());
+        block[24..28].copy_from_slice(&(0x40u32 - 24).to_le_bytes());
+        let mut offset = 0x40;
+        for entry in [&notes, &index_entry(0, None)] {
+            block[offset..offset + entry.len()].copy_from_slice(entry);
+            offset += entry.len();
         }
-        Ok(total_written)
+        block[28..32].copy_from_slice(&((offset - 24) as u32).to_le_bytes());
+        block[32..36].copy_from_slice(&(4096u32 - 24).to_le_bytes());
+        for entry in [&keep, &deleted, &notes, &deleted] {
+            block[offset..offset + entry.len()].copy_from_slice(entry);
+            offset += entry.len();
+        }
+        protect_sectors(&mut block);
+
+        let mut volume = Volume::new();
+        volume.record(5, file_record(5, 0x03, &[resident(0x30, "", &file_name((5 << 48) | 5, ".", 0x1000_0000))]));
+        volume.record(DOCS, file_record(1, 0x03, &[
+            resident(0x30, "", &file_name((5 << 48) | 5, "Docs", 0x1000_0000)),
+            resident(0x90, "$I30", &root),
+            non_resident(0xA0, "$I30", &[(Some(INDX_LCN), 8)], 4096),
+        ]));
+        volume.write(INDX_LCN, 0, &block);
+
+        let carved = volume.reader().scan_index_slack().unwrap();
+        assert_eq!(carved.len(), 1, "{:#?}", carved);
+        assert_eq!(carved[0].file_name.name, "deleted.txt");
+        assert_eq!(carved[0].file_reference, Some((3 << 48) | 72));
+        assert_eq!(carved[0].directory_reference, DOCS_REFERENCE);
+        assert_eq!(carved[0].directory_path, "\\Docs");
+    }
+
+    #[test]
+    fn free_runs_split_at_bit_boundaries_inside_mixed_bytes() {
+        // 0~11 할당, 12~27 미할당(바이트 경계를 넘는 런), 32와 39 사이 6개, 마지막 바이트의 최상위 비트 1개
+        let bitmap = ClusterBitmap { bits: vec![0xFF, 0x0F, 0x00, 0xF0, 0x81, 0xFF, 0x7F] };
+        let runs: Vec<(u64, u64)> = bitmap.free_runs().iter().map(|run| (run.start_lcn, run.length)).collect();
+        assert_eq!(runs, [(12, 16), (33, 6), (55, 1)]);
+        assert!(bitmap.is_allocated(32) && !bitmap.is_allocated(33) && bitmap.is_allocated(56));
+    }
+
+    /// 헤더(0x4C) + 유니코드 Name 문자열 + TerminalBlock
+    fn lnk(name: &str) -> Vec<u8> {
+        let mut data = vec![0u8; 0x4C];
+        data[0..20].copy_from_slice(&[0x4C, 0, 0, 0, 0x01, 0x14, 0x02, 0, 0, 0, 0, 0, 0xC0, 0, 0, 0, 0, 0, 0, 0x46]);
+        data[0x14..0x18].copy_from_slice(&(0x80u32 | 0x04).to_le_bytes());
+        data.extend((name.encode_utf16().count() as u16).to_le_bytes());
+        data.extend(name.encode_utf16().flat_map(u16::to_le_bytes));
+        data.extend(0u32.to_le_bytes());
+        data
+    }
+
+    /// 할당 셀 하나로 채워진 4KB hbin
+    fn hbin() -> Vec<u8> {
+        let mut hbin = vec![0u8; 4096];
+        hbin[0..4].copy_from_slice(b"hbin");
+        hbin[8..12].copy_from_slice(&4096u32.to_le_bytes());
+        hbin[0x20..0x24].copy_from_slice(&(-(4096i32 - 0x20)).to_le_bytes());
+        hbin
+    }
+
+    #[test]
+    fn carving_reads_only_free_runs_and_rejects_artifacts_cut_by_allocated_clusters() {
+        // 할당: 0~47(부트 섹터, $MFT), 60, 144~159 → 미할당 런 48~59, 61~143, 160~255
+        let mut bits = vec![0u8; VOLUME_CLUSTERS / 8];
+        bits[..6].fill(0xFF);
+        bits[7] = 0x10;
+        bits[18..20].fill(0xFF);
+
+        let mut volume = Volume::new();
+        volume.record(6, file_record(1, 0x01, &[resident(0x80, "", &bits)]));
+        volume.write(60, 0, &lnk("allocated.docx"));
+        volume.write(100, 0, &lnk("report.docx"));
+        volume.write(120, 0, &hbin());
+        // 144번 클러스터부터 할당되어 런 안에는 앞 4클러스터만 남는다.
+        volume.write(140, 0, &hbin());
+        volume.write(200, 0, &lnk("notes.txt"));
+        let mut reader = volume.reader();
+        let bitmap = reader.load_cluster_bitmap().unwrap();
+
+        let mut carved = Vec::new();
+        let count = reader.carve_unallocated(&bitmap, |artifact| carved.push(artifact)).unwrap();
+        let hits: Vec<(CarvedKind, u64)> = carved.iter().map(|a| (a.kind, a.volume_offset)).collect();
+        let cluster = CLUSTER_SIZE as u64;
+        assert_eq!(count, 3);
+        assert_eq!(hits, [(CarvedKind::Lnk, 100 * cluster), (CarvedKind::RegistryHbin, 120 * cluster), (CarvedKind::Lnk, 200 * cluster)]);
+        assert_eq!(carved[0].data, lnk("report.docx"));
+    }
+
+    const ROOT_REFERENCE: u64 = (5 << 48) | 5;
+
+    /// 속성 리스트 항목 (타입, 길이 32, 시작 VCN, 파일 참조)
+    fn attribute_list(entries: &[(u32, u64, u64)]) -> Vec<u8> {
+        let mut list = Vec::new();
+        for &(type_code, vcn, reference) in entries {
+            let mut entry = vec![0u8; 32];
+            entry[0..4].copy_from_slice(&type_code.to_le_bytes());
+            entry[4..6].copy_from_slice(&32u16.to_le_bytes());
+            entry[8..16].copy_from_slice(&vcn.to_le_bytes());
+            entry[16..24].copy_from_slice(&reference.to_le_bytes());
+            list.extend(entry);
+        }
+        list
+    }
+
+    /// 시작 VCN이 0이 아닌 비상주 익스텐트
+    fn extent(runs: &[(Option<u64>, u64)], starting_vcn: u64) -> Vec<u8> {
+        let mut attr = non_resident(0x80, "", runs, 0);
+        let clusters: u64 = runs.iter().map(|(_, length)| length).sum();
+        attr[16..24].copy_from_slice(&starting_vcn.to_le_bytes());
+        attr[24..32].copy_from_slice(&(starting_vcn + clusters - 1).to_le_bytes());
+        attr
+    }
+
+    /// 삭제된 report.bin(엔트리 10): VCN 0~1은 LCN 100, VCN 2~3은 확장 레코드(엔트리 11)의 LCN 150에 있다.
+    fn fragmented_deleted_file(volume: &mut Volume, extension_flags: u16) -> Vec<u8> {
+        let content: Vec<u8> = (0..4 * CLUSTER_SIZE - 100).map(|i| (i % 253) as u8).collect();
+        volume.record(5, file_record(5, 0x03, &[resident(0x30, "", &file_name(ROOT_REFERENCE, ".", 0x1000_0000))]));
+        volume.record(10, file_record(3, 0x00, &[
+            resident(0x20, "", &attribute_list(&[(0x30, 0, (3 << 48) | 10), (0x80, 0, (3 << 48) | 10), (0x80, 2, (1 << 48) | 11)])),
+            resident(0x30, "", &file_name(ROOT_REFERENCE, "report.bin", 0x20)),
+            non_resident(0x80, "", &[(Some(100), 2)], content.len() as u64),
+        ]));
+        let mut extension = file_record(2, extension_flags, &[extent(&[(Some(150), 2)], 2)]);
+        extension[32..40].copy_from_slice(&((2u64 << 48) | 10).to_le_bytes());
+        volume.record(11, extension);
+        volume.write(100, 0, &content[..2 * CLUSTER_SIZE]);
+        volume.write(150, 0, &content[2 * CLUSTER_SIZE..]);
+        content
+    }
+
+    fn free_bitmap(allocated: &[u64]) -> ClusterBitmap {
+        let mut bits = vec![0u8; VOLUME_CLUSTERS / 8];
+        for &lcn in allocated {
+            bits[lcn as usize / 8] |= 1 << (lcn % 8);
+        }
+        ClusterBitmap { bits }
+    }
+
+    #[test]
+    fn join_extents_orders_by_vcn_and_fills_gaps_with_sparse_runs() {
+        let base = file_record(1, 0x01, &[non_resident(0x80, "", &[(Some(100), 2)], 3000)]);
+        let far = file_record(1, 0x01, &[extent(&[(Some(200), 1)], 5)]);
+        let near = file_record(1, 0x01, &[extent(&[(Some(150), 1), (None, 1)], 2)]);
+        let first_attr = |record: &Vec<u8>| {
+            let header = parse_file_record_header(record).unwrap();
+            (record.clone(), parse_attributes(record, &header).unwrap().remove(0))
+        };
+
+        let (runlist, real_size) = join_extents(&[first_attr(&far), first_attr(&base), first_attr(&near)]).unwrap();
+        let runs: Vec<(u64, u64)> = runlist.iter().map(|run| (run.start_lcn, run.length)).collect();
+        assert_eq!(runs, [(100, 2), (150, 1), (u64::MAX, 1), (u64::MAX, 1), (200, 1)]);
+        assert_eq!(real_size, 3000);
+    }
+
+    #[test]
+    fn deleted_resident_data_is_recovered_from_the_record() {
+        let mut volume = Volume::new();
+        volume.record(5, file_record(5, 0x03, &[resident(0x30, "", &file_name(ROOT_REFERENCE, ".", 0x1000_0000))]));
+        volume.record(12, file_record(4, 0x00, &[
+            resident(0x30, "", &file_name(ROOT_REFERENCE, "note.txt", 0x20)),
+            resident(0x80, "", b"meet at noon"),
+        ]));
+        volume.record(13, file_record(4, 0x02, &[resident(0x30, "", &file_name(ROOT_REFERENCE, "Old", 0x1000_0000))]));
+        let mut reader = volume.reader();
+
+        let deleted = reader.scan_deleted_records().unwrap();
+        let paths: Vec<&str> = deleted.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, ["\\note.txt", "\\Old"]);
+
+        let mut out = Vec::new();
+        assert_eq!(reader.recover_deleted_data(&deleted[0].record, &free_bitmap(&[]), &mut out).unwrap(), RecoveryStatus::Resident);
+        assert_eq!(out, b"meet at noon");
+        assert_eq!(reader.recover_deleted_data(&deleted[1].record, &free_bitmap(&[]), &mut Vec::new()).unwrap(), RecoveryStatus::NoData);
+    }
+
+    #[test]
+    fn deleted_data_spanning_attribute_list_extents_is_recovered() {
+        let mut volume = Volume::new();
+        let content = fragmented_deleted_file(&mut volume, 0x00);
+        let mut reader = volume.reader();
+        let record = reader.scan_deleted_records().unwrap().remove(0).record;
+
+        let mut out = Vec::new();
+        assert_eq!(reader.recover_deleted_data(&record, &free_bitmap(&[]), &mut out).unwrap(), RecoveryStatus::Unallocated);
+        assert_eq!(out, content);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn reallocated_clusters_in_any_extent_block_recovery() {
+        let mut volume = Volume::new();
+        fragmented_deleted_file(&mut volume, 0x00);
+        let mut reader = volume.reader();
+        let record = reader.scan_deleted_records().unwrap().remove(0).record;
+
+        // 확장 레코드 쪽 클러스터 하나가 다른 파일에 재할당되었다.
+        let mut out = Vec::new();
+        let status = reader.recover_deleted_data(&record, &free_bitmap(&[151]), &mut out).unwrap();
+        assert_eq!(status, RecoveryStatus::Overwritten { reallocated_clusters: 1 });
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn reused_extension_record_is_not_joined() {
+        let mut volume = Volume::new();
+        let content = fragmented_deleted_file(&mut volume, 0x01);
+        let mut reader = volume.reader();
+        let record = reader.scan_deleted_records().unwrap().remove(0).record;
+
+        // 다른 파일이 사용 중인 확장 레코드의 런(LCN 150)은 읽지 않는다.
+        let mut out = Vec::new();
+        assert_eq!(reader.recover_deleted_data(&record, &free_bitmap(&[150, 151]), &mut out).unwrap(), RecoveryStatus::Unallocated);
+        assert_eq!(out, content[..2 * CLUSTER_SIZE]);
+    }
+
+    #[test]
+    fn alternate_data_streams_in_extension_records_are_reported_with_the_base_record() {
+        let mut si = vec![0u8; 72];
+        si[0..8].copy_from_slice(&111u64.to_le_bytes());
+        si[16..24].copy_from_slice(&222u64.to_le_bytes());
+        let payload = b"MZ hidden payload".repeat(300);
+
+        let mut volume = Volume::with_mft_records(32);
+        volume.record(5, file_record(5, 0x03, &[resident(0x30, "", &file_name(ROOT_REFERENCE, ".", 0x1000_0000))]));
+        // 베이스 레코드(30)보다 앞선 확장 레코드(25)에 비상주 ADS가 있다.
+        let mut extension = file_record(1, 0x01, &[non_resident(0x80, "payload.exe", &[(Some(100), 11)], payload.len() as u64)]);
+        extension[32..40].copy_from_slice(&((2u64 << 48) | 30).to_le_bytes());
+        volume.record(25, extension);
+        volume.record(30, file_record(2, 0x01, &[
+            resident(0x10, "", &si),
+            resident(0x30, "", &file_name(ROOT_REFERENCE, "readme.txt", 0x20)),
+            resident(0x80, "", b"hello"),
+            resident(0x80, "Zone.Identifier", b"[ZoneTransfer]\r\nZoneId=3\r\n"),
+            resident(0x80, "$Config", b"system"),
+        ]));
+        // 예약 엔트리와 삭제된 레코드의 ADS는 보고하지 않는다.
+        volume.record(9, file_record(1, 0x01, &[resident(0x80, "$SDS", b"secure"), resident(0x80, "extra", b"x")]));
+        volume.record(26, file_record(1, 0x00, &[resident(0x30, "", &file_name(ROOT_REFERENCE, "gone.txt", 0x20)), resident(0x80, "old", b"x")]));
+        volume.write(100, 0, &payload);
+        let mut reader = volume.reader();
+
+        let streams = reader.scan_alternate_data_streams().unwrap();
+        let names: Vec<&str> = streams.iter().map(|s| s.stream_name.as_str()).collect();
+        assert_eq!(names, ["payload.exe", "Zone.Identifier"]);
+        for stream in &streams {
+            assert_eq!(stream.path, "\\readme.txt");
+            assert_eq!(stream.file_reference, (2 << 48) | 30);
+            assert_eq!((stream.si_creation_time, stream.si_mft_modified_time), (111, 222));
+        }
+        assert!(!streams[0].is_resident);
+        assert_eq!(streams[0].size, payload.len() as u64);
+        assert_eq!(streams[0].content, payload[..ADS_CONTENT_LIMIT as usize]);
+        assert_eq!(streams[1].content, b"[ZoneTransfer]\r\nZoneId=3\r\n");
+    }
+}
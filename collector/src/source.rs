@@ -0,0 +1,37 @@
+use crate::artifacts::{CollectedFile, ForensicCollector};
+use anyhow::Result;
+use models::io::ReadSeek;
+use models::profile::CollectionTarget;
+use models::vss::ShadowCopyInfo;
+
+/// [추가] 수집 백엔드 공통 인터페이스. CLI는 NTFS 볼륨(ForensicCollector)과 이미 수집된 트리아지 폴더/ZIP(TriageFolderCollector)을
+/// 구분하지 않고 같은 콜백으로 아티팩트를 받는다.
+pub trait ArtifactSource {
+    /// 타겟과 일치하는 파일을 하나씩 콜백에 넘기고 (파일 수, 바이트 수)를 반환한다.
+    fn collect_files(&mut self, target: &CollectionTarget, callback: &mut dyn FnMut(&CollectedFile, &mut dyn ReadSeek)) -> Result<(usize, u64)>;
+
+    /// 수집 대상 시스템의 컴퓨터 이름
+    fn computer_name(&mut self) -> Option<String>;
+
+    /// 볼륨 일련번호 (알 수 없으면 0)
+    fn volume_serial_number(&self) -> u64;
+
+    /// 섀도 복사본에서 수집된 소스이면 그 스냅숏 정보
+    fn snapshot(&self) -> Option<&ShadowCopyInfo> {
+        None
+    }
+}
+
+impl ArtifactSource for ForensicCollector<'_> {
+    fn collect_files(&mut self, target: &CollectionTarget, callback: &mut dyn FnMut(&CollectedFile, &mut dyn ReadSeek)) -> Result<(usize, u64)> {
+        ForensicCollector::collect_files(self, target, callback)
+    }
+
+    fn computer_name(&mut self) -> Option<String> {
+        ForensicCollector::computer_name(self)
+    }
+
+    fn volume_serial_number(&self) -> u64 {
+        ForensicCollector::volume_serial_number(self)
+    }
+}
@@ -0,0 +1,145 @@
+use crate::image::ReadSeek;
+use models::mft::DataRun;
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// 비상주 속성의 런리스트를 파일 오프셋 기준 Read + Seek 스트림으로 보여준다.
+/// 희소(sparse) 런과 런리스트가 끝난 뒤의 영역은 디스크를 읽지 않고 0으로 채운다.
+pub struct RunlistReader<'a> {
+    source: &'a mut dyn ReadSeek,
+    cluster_size: u64,
+    /// (시작 VCN, 런) — VCN 오름차순
+    runs: Vec<(u64, DataRun)>,
+    size: u64,
+    pos: u64,
+}
+
+impl<'a> RunlistReader<'a> {
+    /// size는 스트림의 논리 크기(real_size)이며, 런리스트가 덮는 범위를 넘으면 런리스트 크기로 줄인다.
+    pub fn new(source: &'a mut dyn ReadSeek, cluster_size: u64, runlist: &[DataRun], size: u64) -> Self {
+        let mut runs = Vec::with_capacity(runlist.len());
+        let mut vcn = 0u64;
+        for run in runlist {
+            runs.push((vcn, run.clone()));
+            vcn = vcn.saturating_add(run.length);
+        }
+        let size = std::cmp::min(size, vcn.saturating_mul(cluster_size));
+        Self { source, cluster_size, runs, size, pos: 0 }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+impl Read for RunlistReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.size || buf.is_empty() { return Ok(0); }
+        let vcn = self.pos / self.cluster_size;
+        let index = self.runs.partition_point(|(start, _)| *start <= vcn).saturating_sub(1);
+        let (start_vcn, run) = &self.runs[index];
+
+        let run_start = start_vcn * self.cluster_size;
+        let run_end = std::cmp::min((start_vcn + run.length) * self.cluster_size, self.size);
+        let n = std::cmp::min(buf.len() as u64, run_end - self.pos) as usize;
+
+        if run.start_lcn == u64::MAX {
+            buf[..n].fill(0);
+        } else {
+            let offset = run.start_lcn.checked_mul(self.cluster_size)
+                .and_then(|o| o.checked_add(self.pos - run_start))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Runlist LCN overflow"))?;
+            self.source.seek(SeekFrom::Start(offset))?;
+            self.source.read_exact(&mut buf[..n])?;
+        }
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for RunlistReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(p) => p as i128,
+            SeekFrom::End(off) => self.size as i128 + off as i128,
+            SeekFrom::Current(off) => self.pos as i128 + off as i128,
+        };
+        if new_pos < 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Seek before start of stream"));
+        }
+        self.pos = new_pos as u64;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const CLUSTER_SIZE: u64 = 16;
+
+    /// 클러스터 n은 모두 n 값의 바이트로 채운 디스크
+    fn disk() -> Cursor<Vec<u8>> {
+        Cursor::new((0..32u8).flat_map(|n| [n; CLUSTER_SIZE as usize]).collect())
+    }
+
+    fn runs() -> [DataRun; 3] {
+        [
+            DataRun { start_lcn: 5, length: 2 },
+            DataRun { start_lcn: u64::MAX, length: 1 },
+            DataRun { start_lcn: 20, length: 1 },
+        ]
+    }
+
+    #[test]
+    fn reads_runs_in_vcn_order_and_fills_sparse_runs_with_zeros() {
+        let mut disk = disk();
+        let mut reader = RunlistReader::new(&mut disk, CLUSTER_SIZE, &runs(), 4 * CLUSTER_SIZE - 4);
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+
+        let mut expected = [[5u8; 16], [6; 16], [0; 16]].concat();
+        expected.extend([20u8; 12]);
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn seeks_within_and_past_the_stream() {
+        let mut disk = disk();
+        let mut reader = RunlistReader::new(&mut disk, CLUSTER_SIZE, &runs(), 4 * CLUSTER_SIZE);
+        let mut buf = [0u8; 4];
+
+        // 첫 런 끝에서 읽으면 런 경계까지만 돌려준다.
+        reader.seek(SeekFrom::Start(CLUSTER_SIZE * 2 - 2)).unwrap();
+        assert_eq!(reader.read(&mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], [6, 6]);
+
+        reader.seek(SeekFrom::End(-2)).unwrap();
+        reader.read_exact(&mut buf[..2]).unwrap();
+        assert_eq!(&buf[..2], [20, 20]);
+
+        assert_eq!(reader.seek(SeekFrom::Current(10)).unwrap(), 4 * CLUSTER_SIZE + 10);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+        assert!(reader.seek(SeekFrom::Current(-1000)).is_err());
+    }
+
+    #[test]
+    fn size_is_clamped_to_the_runlist() {
+        let mut disk = disk();
+        let reader = RunlistReader::new(&mut disk, CLUSTER_SIZE, &runs(), u64::MAX);
+        assert_eq!(reader.len(), 4 * CLUSTER_SIZE);
+        assert!(RunlistReader::new(&mut disk, CLUSTER_SIZE, &[], 100).is_empty());
+    }
+
+    #[test]
+    fn overflowing_lcn_is_an_error() {
+        let mut disk = disk();
+        let runs = [DataRun { start_lcn: u64::MAX / 2, length: 1 }];
+        let mut reader = RunlistReader::new(&mut disk, CLUSTER_SIZE, &runs, CLUSTER_SIZE);
+        assert!(reader.read(&mut [0u8; 4]).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! 테스트용 NTFS 볼륨 작성기. 부트 섹터와 $MFT 런 하나로 된 작은 볼륨에 FILE 레코드와 클러스터 데이터를 배치한다.
+
+use crate::mft::MftReader;
+use std::io::Cursor;
+
+pub(crate) const CLUSTER_SIZE: usize = 512;
+const RECORD_SIZE: usize = 1024;
+const MFT_LCN: u64 = 16;
+const MFT_RECORDS: u64 = 16;
+pub(crate) const VOLUME_CLUSTERS: usize = 256;
+
+/// 런리스트 인코딩: (Some(LCN) | None(희소), 길이). 오프셋은 직전 LCN 기준 부호 있는 4바이트
+fn encode_runlist(runs: &[(Option<u64>, u64)]) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut previous = 0i64;
+    for &(lcn, length) in runs {
+        match lcn {
+            Some(lcn) => {
+                data.push(0x44);
+                data.extend((length as u32).to_le_bytes());
+                data.extend(((lcn as i64 - previous) as i32).to_le_bytes());
+                previous = lcn as i64;
+            },
+            None => {
+                data.push(0x04);
+                data.extend((length as u32).to_le_bytes());
+            },
+        }
+    }
+    data.push(0);
+    data
+}
+
+fn attribute_header(type_code: u32, name: &str, non_resident: bool, header_size: usize) -> Vec<u8> {
+    let name: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let mut attr = vec![0u8; header_size];
+    attr[0..4].copy_from_slice(&type_code.to_le_bytes());
+    attr[8] = non_resident as u8;
+    attr[9] = (name.len() / 2) as u8;
+    attr[10..12].copy_from_slice(&(header_size as u16).to_le_bytes());
+    attr.extend(name);
+    attr.resize(attr.len().next_multiple_of(8), 0);
+    attr
+}
+
+fn finish_attribute(mut attr: Vec<u8>) -> Vec<u8> {
+    attr.resize(attr.len().next_multiple_of(8), 0);
+    let length = attr.len() as u32;
+    attr[4..8].copy_from_slice(&length.to_le_bytes());
+    attr
+}
+
+pub(crate) fn resident(type_code: u32, name: &str, content: &[u8]) -> Vec<u8> {
+    let mut attr = attribute_header(type_code, name, false, 24);
+    let content_offset = attr.len() as u16;
+    attr[16..20].copy_from_slice(&(content.len() as u32).to_le_bytes());
+    attr[20..22].copy_from_slice(&content_offset.to_le_bytes());
+    attr.extend(content);
+    finish_attribute(attr)
+}
+
+pub(crate) fn non_resident(type_code: u32, name: &str, runs: &[(Option<u64>, u64)], real_size: u64) -> Vec<u8> {
+    let mut attr = attribute_header(type_code, name, true, 64);
+    let clusters: u64 = runs.iter().map(|(_, length)| length).sum();
+    let run_offset = attr.len() as u16;
+    attr[24..32].copy_from_slice(&clusters.saturating_sub(1).to_le_bytes());
+    attr[32..34].copy_from_slice(&run_offset.to_le_bytes());
+    attr[40..48].copy_from_slice(&(clusters * CLUSTER_SIZE as u64).to_le_bytes());
+    attr[48..56].copy_from_slice(&real_size.to_le_bytes());
+    attr[56..64].copy_from_slice(&real_size.to_le_bytes());
+    attr.extend(encode_runlist(runs));
+    finish_attribute(attr)
+}
+
+/// 2024-03-01T09:00 (FILETIME)
+const FILE_TIME: u64 = (1_709_283_600 + 11_644_473_600) * 10_000_000;
+
+pub(crate) fn file_name(parent: u64, name: &str, flags: u32) -> Vec<u8> {
+    let name: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let mut content = vec![0u8; 66];
+    content[0..8].copy_from_slice(&parent.to_le_bytes());
+    for field in 0..4 {
+        content[8 + field * 8..16 + field * 8].copy_from_slice(&FILE_TIME.to_le_bytes());
+    }
+    content[56..60].copy_from_slice(&flags.to_le_bytes());
+    content[64] = (name.len() / 2) as u8;
+    content[65] = 1;
+    content.extend(name);
+    content
+}
+
+/// 인덱스 엔트리 헤더(파일 참조, 길이, 키 길이, 플래그) + $FILE_NAME 키. key가 None이면 마지막 엔트리
+pub(crate) fn index_entry(reference: u64, key: Option<Vec<u8>>) -> Vec<u8> {
+    let mut entry = vec![0u8; 16];
+    match key {
+        Some(key) => {
+            entry[0..8].copy_from_slice(&reference.to_le_bytes());
+            entry[10..12].copy_from_slice(&(key.len() as u16).to_le_bytes());
+            entry.extend(key);
+            entry.resize(entry.len().next_multiple_of(8), 0);
+        },
+        None => entry[12] = 0x02,
+    }
+    let length = entry.len() as u16;
+    entry[8..10].copy_from_slice(&length.to_le_bytes());
+    entry
+}
+
+/// flags: 0x01 사용 중, 0x02 디렉터리. 섹터 끝 2바이트는 업데이트 시퀀스로 보호한다.
+pub(crate) fn file_record(sequence: u16, flags: u16, attributes: &[Vec<u8>]) -> Vec<u8> {
+    let mut record = vec![0u8; RECORD_SIZE];
+    record[0..4].copy_from_slice(b"FILE");
+    record[4..6].copy_from_slice(&0x30u16.to_le_bytes());
+    record[6..8].copy_from_slice(&((RECORD_SIZE / 512 + 1) as u16).to_le_bytes());
+    record[16..18].copy_from_slice(&sequence.to_le_bytes());
+    record[18..20].copy_from_slice(&1u16.to_le_bytes());
+    record[20..22].copy_from_slice(&0x38u16.to_le_bytes());
+    record[22..24].copy_from_slice(&flags.to_le_bytes());
+    let mut offset = 0x38;
+    for attr in attributes {
+        record[offset..offset + attr.len()].copy_from_slice(attr);
+        offset += attr.len();
+    }
+    record[offset..offset + 4].copy_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+    record[24..28].copy_from_slice(&((offset + 8) as u32).to_le_bytes());
+    record[28..32].copy_from_slice(&(RECORD_SIZE as u32).to_le_bytes());
+    protect_sectors(&mut record);
+    record
+}
+
+/// 업데이트 시퀀스 번호 1을 각 512바이트 섹터 끝에 기록하고 원래 값을 USA에 옮긴다.
+pub(crate) fn protect_sectors(block: &mut [u8]) {
+    let usa_offset = u16::from_le_bytes([block[4], block[5]]) as usize;
+    block[usa_offset..usa_offset + 2].copy_from_slice(&1u16.to_le_bytes());
+    for sector in 0..block.len() / 512 {
+        let end = (sector + 1) * 512 - 2;
+        let slot = usa_offset + 2 + sector * 2;
+        block.copy_within(end..end + 2, slot);
+        block[end..end + 2].copy_from_slice(&1u16.to_le_bytes());
+    }
+}
+
+/// 512바이트 클러스터, 1KB FILE 레코드, 4KB INDX 레코드인 볼륨. $MFT는 LCN 16부터 기본 16개 레코드를 담는다.
+pub(crate) struct Volume(Vec<u8>);
+
+impl Volume {
+    pub(crate) fn new() -> Self {
+        Self::with_mft_records(MFT_RECORDS)
+    }
+
+    /// 사용자 엔트리(24번 이후)가 필요한 테스트용. $MFT가 LCN 16부터 레코드당 2클러스터를 차지한다.
+    pub(crate) fn with_mft_records(mft_records: u64) -> Self {
+        let mut data = vec![0u8; VOLUME_CLUSTERS * CLUSTER_SIZE];
+        data[3..11].copy_from_slice(b"NTFS    ");
+        data[11..13].copy_from_slice(&(CLUSTER_SIZE as u16).to_le_bytes());
+        data[13] = 1;
+        data[48..56].copy_from_slice(&MFT_LCN.to_le_bytes());
+        data[0x40] = (-10i8) as u8;
+        data[0x44] = (-12i8) as u8;
+        let mut volume = Self(data);
+        let mft_clusters = mft_records * (RECORD_SIZE / CLUSTER_SIZE) as u64;
+        volume.record(0, file_record(1, 0x01, &[
+            non_resident(0x80, "", &[(Some(MFT_LCN), mft_clusters)], mft_records * RECORD_SIZE as u64),
+        ]));
+        volume
+    }
+
+    pub(crate) fn record(&mut self, entry: u64, record: Vec<u8>) {
+        self.write(MFT_LCN, entry as usize * RECORD_SIZE, &record);
+    }
+
+    pub(crate) fn write(&mut self, lcn: u64, offset: usize, data: &[u8]) {
+        let start = lcn as usize * CLUSTER_SIZE + offset;
+        self.0[start..start + data.len()].copy_from_slice(data);
+    }
+
+    pub(crate) fn reader(self) -> MftReader {
+        MftReader::bootstrap(Box::new(Cursor::new(self.0))).unwrap()
+    }
+}
+
+/// $INDEX_ROOT 내용: 인덱스 레코드 크기 4KB, 노드 헤더(0x10) 뒤의 엔트리들과 마지막 엔트리
+pub(crate) fn index_root(entries: &[Vec<u8>]) -> Vec<u8> {
+    let mut root = vec![0u8; 32];
+    root[0..4].copy_from_slice(&0x30u32.to_le_bytes());
+    root[8..12].copy_from_slice(&4096u32.to_le_bytes());
+    root[12] = 8;
+    root[16..20].copy_from_slice(&16u32.to_le_bytes());
+    for entry in entries {
+        root.extend(entry);
+    }
+    root.extend(index_entry(0, None));
+    let entries_size = (root.len() - 16) as u32;
+    root[20..24].copy_from_slice(&entries_size.to_le_bytes());
+    root[24..28].copy_from_slice(&entries_size.to_le_bytes());
+    root
+}
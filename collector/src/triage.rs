@@ -0,0 +1,375 @@
+use crate::artifacts::CollectedFile;
+use crate::image::ewf::to_hex;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use md5::{Digest, Md5};
+use models::mft::{MftRecord, StandardInformation};
+use models::triage::{MacbTimes, ManifestEntry, TriageManifest, VolumeManifest};
+use models::vss::ShadowCopyInfo;
+use sha1::Sha1;
+use sha2::Sha256;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// 패키지 안에 매니페스트를 기록하는 경로
+pub const MANIFEST_NAME: &str = "manifest.json";
+
+/// 트리아지 패키지의 저장 형식. 경로가 .zip으로 끝나면 ZIP, 아니면 디렉터리 트리로 기록한다.
+pub trait PackageSink {
+    /// reader의 내용을 archive_path('/' 구분)에 기록한다. modified는 원본 파일의 수정 시각이다.
+    fn add_file(&mut self, archive_path: &str, modified: Option<DateTime<Utc>>, size: u64, reader: &mut dyn Read) -> Result<u64>;
+    fn finish(self: Box<Self>) -> Result<()>;
+}
+
+/// 디렉터리 트리 형식 (원본 경로 구조를 그대로 재현)
+pub struct DirectorySink {
+    root: PathBuf,
+}
+
+impl DirectorySink {
+    pub fn create(root: &Path) -> Result<Self> {
+        fs::create_dir_all(root).with_context(|| format!("Failed to create {}", root.display()))?;
+        Ok(Self { root: root.to_path_buf() })
+    }
+}
+
+impl PackageSink for DirectorySink {
+    fn add_file(&mut self, archive_path: &str, modified: Option<DateTime<Utc>>, _size: u64, reader: &mut dyn Read) -> Result<u64> {
+        let path = archive_path.split('/').fold(self.root.clone(), |p, part| p.join(part));
+        if let Some(parent) = path.parent() { fs::create_dir_all(parent)?; }
+        let mut file = File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+        let written = io::copy(reader, &mut file)?;
+        if let Some(modified) = modified {
+            let _ = file.set_modified(modified.into());
+        }
+        Ok(written)
+    }
+
+    fn finish(self: Box<Self>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// ZIP 형식. 4GB를 넘는 스트림($UsnJrnl:$J, $MFT 등)은 ZIP64 항목으로 기록한다.
+pub struct ZipSink {
+    writer: ZipWriter<File>,
+}
+
+impl ZipSink {
+    /// overwrite가 false이면 기존 파일을 덮어쓰지 않는다. (이전 수집 결과 보존)
+    pub fn create(path: &Path, overwrite: bool) -> Result<Self> {
+        if let Some(parent) = path.parent() && !parent.as_os_str().is_empty() { fs::create_dir_all(parent)?; }
+        let mut options = OpenOptions::new();
+        options.write(true);
+        if overwrite { options.create(true).truncate(true); } else { options.create_new(true); }
+        let file = match options.open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => bail!("Triage package {} already exists", path.display()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to create {}", path.display())),
+        };
+        Ok(Self { writer: ZipWriter::new(file) })
+    }
+}
+
+impl PackageSink for ZipSink {
+    fn add_file(&mut self, archive_path: &str, modified: Option<DateTime<Utc>>, size: u64, reader: &mut dyn Read) -> Result<u64> {
+        let mut options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(size >= u32::MAX as u64);
+        // ZIP 타임스탬프는 1980~2107년만 표현할 수 있으므로 범위를 벗어나면 현재 시각으로 둔다.
+        if let Some(time) = modified.and_then(|m| zip::DateTime::try_from(m.naive_utc()).ok()) {
+            options = options.last_modified_time(time);
+        }
+        self.writer.start_file(archive_path, options)?;
+        Ok(io::copy(reader, &mut self.writer)?)
+    }
+
+    fn finish(self: Box<Self>) -> Result<()> {
+        self.writer.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+/// 읽는 동안 MD5/SHA-1/SHA-256을 함께 계산하는 리더. 스트림을 한 번만 읽어 기록과 해시를 동시에 처리한다.
+struct HashingReader<'a> {
+    inner: &'a mut dyn Read,
+    md5: Md5,
+    sha1: Sha1,
+    sha256: Sha256,
+}
+
+impl Read for HashingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.md5.update(&buf[..n]);
+        self.sha1.update(&buf[..n]);
+        self.sha256.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// [추가] 수집한 원본 아티팩트를 원래 경로 구조 그대로 ZIP/디렉터리에 기록하고, 파일별 해시와
+/// MFT 메타데이터(엔트리/시퀀스 번호, SI/FN 타임스탬프)를 담은 보관 연속성(chain-of-custody) 매니페스트를 남긴다.
+pub struct TriagePackage {
+    sink: Box<dyn PackageSink>,
+    manifest: TriageManifest,
+    archive_paths: HashSet<String>,
+    location: PathBuf,
+}
+
+impl TriagePackage {
+    /// overwrite는 기존 ZIP 파일을 덮어쓸지 여부이다. 디렉터리 형식은 비어 있지 않으면 항상 거부한다.
+    pub fn create(path: &Path, overwrite: bool) -> Result<Self> {
+        let is_zip = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("zip"));
+        if !is_zip && path.exists() && fs::read_dir(path).map(|mut d| d.next().is_some()).unwrap_or(true) {
+            bail!("Triage directory {} already exists and is not empty", path.display());
+        }
+        let sink: Box<dyn PackageSink> = if is_zip { Box::new(ZipSink::create(path, overwrite)?) } else { Box::new(DirectorySink::create(path)?) };
+        Ok(Self {
+            sink,
+            manifest: TriageManifest {
+                collector: "FACT".to_string(),
+                collector_version: env!("CARGO_PKG_VERSION").to_string(),
+                host_name: None,
+                collection_started: Utc::now(),
+                collection_finished: None,
+                volumes: Vec::new(),
+                files: Vec::new(),
+            },
+            archive_paths: HashSet::new(),
+            location: path.to_path_buf(),
+        })
+    }
+
+    /// 볼륨(또는 섀도 복사본)을 등록한다. 처음 확인된 호스트 이름이 패키지 전체의 호스트 이름이 된다.
+    pub fn add_volume(&mut self, label: &str, serial_number: u64, host_name: Option<String>, snapshot: Option<&ShadowCopyInfo>) {
+        if self.manifest.host_name.is_none() { self.manifest.host_name = host_name.clone(); }
+        self.manifest.volumes.push(VolumeManifest {
+            label: label.to_string(),
+            serial_number: format!("{:016X}", serial_number),
+            host_name,
+            snapshot: snapshot.cloned(),
+        });
+    }
+
+    /// 수집한 스트림 하나를 <볼륨 라벨>/<원본 경로>에 기록한다. 같은 스트림을 여러 타겟이 수집하면 한 번만 기록한다.
+    pub fn add_file(&mut self, volume: &str, target: &str, file: &CollectedFile, reader: &mut dyn Read) -> Result<()> {
+        let mut archive_path = format!("{}/{}", volume, archive_components(&file.path).join("/"));
+        if let Some(stream) = &file.stream { archive_path = format!("{}_{}", archive_path, sanitize(stream)); }
+        if !self.archive_paths.insert(archive_path.clone()) { return Ok(()); }
+
+        let standard_information = file.record.as_ref().and_then(|r| r.standard_info.as_ref()).map(|si| MacbTimes {
+            created: StandardInformation::to_datetime(si.creation_time),
+            modified: StandardInformation::to_datetime(si.modification_time),
+            mft_modified: StandardInformation::to_datetime(si.mft_modified_time),
+            accessed: StandardInformation::to_datetime(si.access_time),
+        });
+        let file_name = file.record.as_ref().and_then(preferred_file_name_times);
+
+        let mut hashing = HashingReader { inner: reader, md5: Md5::new(), sha1: Sha1::new(), sha256: Sha256::new() };
+        let modified = standard_information.as_ref().map(|t| t.modified);
+        let size = self.sink.add_file(&archive_path, modified, file.size, &mut hashing)
+            .with_context(|| format!("Failed to write {} to triage package", archive_path))?;
+
+        self.manifest.files.push(ManifestEntry {
+            archive_path,
+            original_path: file.path.clone(),
+            stream: file.stream.clone(),
+            volume: volume.to_string(),
+            target: target.to_string(),
+            mft_entry: file.inode,
+            mft_sequence: file.record.as_ref().map(|r| r.sequence_number).unwrap_or(0),
+            size,
+            md5: to_hex(&hashing.md5.finalize()),
+            sha1: to_hex(&hashing.sha1.finalize()),
+            sha256: to_hex(&hashing.sha256.finalize()),
+            collected_at: Utc::now(),
+            standard_information,
+            file_name,
+        });
+        Ok(())
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.manifest.files.len()
+    }
+
+    /// 매니페스트를 패키지에 기록하고 닫는다. 반환값은 매니페스트 자체의 SHA-256이다.
+    pub fn finish(mut self) -> Result<String> {
+        self.manifest.collection_finished = Some(Utc::now());
+        let json = serde_json::to_vec_pretty(&self.manifest)?;
+        let digest = to_hex(&Sha256::digest(&json));
+        self.sink.add_file(MANIFEST_NAME, self.manifest.collection_finished, json.len() as u64, &mut json.as_slice())?;
+        self.sink.finish().with_context(|| format!("Failed to finalize {}", self.location.display()))?;
+        Ok(digest)
+    }
+}
+
+/// Win32 이름을 우선하는 $FILE_NAME 타임스탬프
+fn preferred_file_name_times(record: &MftRecord) -> Option<MacbTimes> {
+    let fn_attr = record.preferred_file_name()?;
+    Some(MacbTimes {
+        created: StandardInformation::to_datetime(fn_attr.creation_time),
+        modified: StandardInformation::to_datetime(fn_attr.modification_time),
+        mft_modified: StandardInformation::to_datetime(fn_attr.mft_modified_time),
+        accessed: StandardInformation::to_datetime(fn_attr.access_time),
+    })
+}
+
+/// 원본 경로를 패키지 안의 경로 구성요소로 바꾼다. 드라이브 접두(C:), '.', '..'와 빈 구성요소는 버려
+/// 패키지 루트 밖을 가리키지 않게 한다.
+fn archive_components(path: &str) -> Vec<String> {
+    let mut components = Vec::new();
+    for part in path.split(['\\', '/']) {
+        if part.is_empty() || part == "." || part == ".." { continue; }
+        // 드라이브 문자와 \\?\ 접두는 경로 맨 앞에서만 버린다.
+        if components.is_empty() && (part == "?" || (part.len() == 2 && part.ends_with(':'))) { continue; }
+        components.push(sanitize(part));
+    }
+    components
+}
+
+/// Windows/ZIP 경로에 쓸 수 없는 문자를 '_'로 바꾼다.
+fn sanitize(component: &str) -> String {
+    component.chars().map(|c| if "\\/:*?\"<>|".contains(c) || c.is_control() { '_' } else { c }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::mft::FileNameAttribute;
+    use std::io::Cursor;
+    use zip::ZipArchive;
+
+    /// 2024-03-01T09:00 / 10:00 (FILETIME)
+    const T1: u64 = (1_709_283_600 + 11_644_473_600) * 10_000_000;
+    const T2: u64 = (1_709_287_200 + 11_644_473_600) * 10_000_000;
+
+    fn record(entry: u64, sequence: u16) -> MftRecord {
+        let name = |namespace: u8, name: &str, time: u64| FileNameAttribute {
+            parent_directory: 5, creation_time: time, modification_time: time, mft_modified_time: time, access_time: time,
+            allocated_size: 0, real_size: 0, flags: 0x20, name_length: name.len() as u8, namespace, name: name.to_string(),
+        };
+        MftRecord {
+            entry_number: entry,
+            sequence_number: sequence,
+            base_reference: 0,
+            in_use: true,
+            is_directory: false,
+            standard_info: Some(StandardInformation {
+                creation_time: T1, modification_time: T2, mft_modified_time: T2, access_time: T2,
+                file_flags: 0x20, max_versions: 0, version_number: 0, class_id: 0,
+            }),
+            // 8.3 이름보다 Win32 이름의 시각을 기록한다.
+            file_names: vec![name(2, "SECURI~1.EVT", T2), name(1, "Security.evtx", T1)],
+            data_streams: Vec::new(),
+        }
+    }
+
+    fn file(path: &str, stream: Option<&str>, inode: u64, size: u64) -> CollectedFile {
+        CollectedFile {
+            name: path.rsplit('\\').next().unwrap().to_string(),
+            path: path.to_string(),
+            stream: stream.map(str::to_string),
+            inode,
+            size,
+            record: Some(record(inode, 3)),
+        }
+    }
+
+    fn hex_sha256(data: &[u8]) -> String {
+        to_hex(&Sha256::digest(data))
+    }
+
+    #[test]
+    fn archive_paths_cannot_escape_the_package() {
+        assert_eq!(archive_components("Windows\\System32\\config\\SYSTEM"), ["Windows", "System32", "config", "SYSTEM"]);
+        assert_eq!(archive_components("C:\\Windows\\..\\..\\evil.exe"), ["Windows", "evil.exe"]);
+        assert_eq!(archive_components("\\\\?\\D:\\.\\Users/a:b"), ["Users", "a_b"]);
+        assert_eq!(archive_components("Logs\\C:"), ["Logs", "C_"]);
+    }
+
+    #[test]
+    fn zip_package_round_trips_manifest_and_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.zip");
+        let security = b"ElfFile\0 security log".to_vec();
+        let zone = b"[ZoneTransfer]\r\nZoneId=3\r\n".to_vec();
+
+        let mut package = TriagePackage::create(&path, false).unwrap();
+        package.add_volume("vol0", 0x1234_5678_9ABC_DEF0, Some("WS01".into()), None);
+        package.add_volume("vol0/vss1", 0x1234_5678_9ABC_DEF0, None, None);
+        let security_file = file("Windows\\System32\\winevt\\Logs\\Security.evtx", None, 70, security.len() as u64);
+        package.add_file("vol0", "EventLogs", &security_file, &mut security.as_slice()).unwrap();
+        // 다른 타겟이 같은 스트림을 다시 수집해도 한 번만 기록한다.
+        package.add_file("vol0", "Other", &security_file, &mut security.as_slice()).unwrap();
+        package.add_file("vol0/vss1", "Downloads", &file("..\\Users\\a\\setup.exe", Some("Zone.Identifier"), 71, zone.len() as u64), &mut zone.as_slice()).unwrap();
+        assert_eq!(package.file_count(), 2);
+        let digest = package.finish().unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut manifest_json = Vec::new();
+        archive.by_name(MANIFEST_NAME).unwrap().read_to_end(&mut manifest_json).unwrap();
+        assert_eq!(digest, hex_sha256(&manifest_json));
+
+        let manifest: TriageManifest = serde_json::from_slice(&manifest_json).unwrap();
+        assert_eq!(manifest.host_name.as_deref(), Some("WS01"));
+        assert_eq!(manifest.volumes[1].label, "vol0/vss1");
+        assert_eq!(manifest.volumes[0].serial_number, "123456789ABCDEF0");
+        assert!(manifest.collection_finished.is_some());
+
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.archive_path.as_str()).collect();
+        assert_eq!(paths, ["vol0/Windows/System32/winevt/Logs/Security.evtx", "vol0/vss1/Users/a/setup.exe_Zone.Identifier"]);
+        for (entry, expected) in manifest.files.iter().zip([&security, &zone]) {
+            let mut data = Vec::new();
+            archive.by_name(&entry.archive_path).unwrap().read_to_end(&mut data).unwrap();
+            assert_eq!(&data, expected);
+            assert_eq!(entry.sha256, hex_sha256(&data));
+            assert_eq!(entry.md5, to_hex(&Md5::digest(&data)));
+            assert_eq!(entry.sha1, to_hex(&Sha1::digest(&data)));
+            assert_eq!(entry.size, data.len() as u64);
+        }
+
+        let security_entry = &manifest.files[0];
+        assert_eq!((security_entry.mft_entry, security_entry.mft_sequence), (70, 3));
+        assert_eq!(security_entry.target, "EventLogs");
+        assert_eq!(security_entry.standard_information.as_ref().unwrap().modified, StandardInformation::to_datetime(T2));
+        assert_eq!(security_entry.file_name.as_ref().unwrap().modified, StandardInformation::to_datetime(T1));
+        assert_eq!(manifest.files[1].stream.as_deref(), Some("Zone.Identifier"));
+        assert_eq!(manifest.files[1].original_path, "..\\Users\\a\\setup.exe");
+    }
+
+    #[test]
+    fn existing_zip_is_kept_unless_overwrite_is_requested() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.zip");
+        fs::write(&path, b"previous collection").unwrap();
+
+        assert!(TriagePackage::create(&path, false).is_err());
+        assert_eq!(fs::read(&path).unwrap(), b"previous collection");
+
+        TriagePackage::create(&path, true).unwrap().finish().unwrap();
+        let archive = ZipArchive::new(Cursor::new(fs::read(&path).unwrap())).unwrap();
+        assert_eq!(archive.file_names().collect::<Vec<_>>(), [MANIFEST_NAME]);
+    }
+
+    #[test]
+    fn directory_package_writes_the_original_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("existing.txt"), b"x").unwrap();
+        assert!(TriagePackage::create(dir.path(), true).is_err());
+
+        let root = dir.path().join("package");
+        let mut package = TriagePackage::create(&root, false).unwrap();
+        package.add_file("vol0", "Prefetch", &file("C:\\Windows\\Prefetch\\CMD.EXE-1.pf", None, 80, 3), &mut b"SCC".as_slice()).unwrap();
+        package.finish().unwrap();
+
+        assert_eq!(fs::read(root.join("vol0/Windows/Prefetch/CMD.EXE-1.pf")).unwrap(), b"SCC");
+        let manifest: TriageManifest = serde_json::from_slice(&fs::read(root.join(MANIFEST_NAME)).unwrap()).unwrap();
+        assert_eq!(manifest.files[0].sha256, hex_sha256(b"SCC"));
+    }
+}
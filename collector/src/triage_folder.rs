@@ -0,0 +1,491 @@
+use crate::artifacts::CollectedFile;
+use crate::source::ArtifactSource;
+use crate::triage::MANIFEST_NAME;
+use anyhow::{Context, Result, bail};
+use models::io::ReadSeek;
+use models::profile::CollectionTarget;
+use models::triage::{ManifestEntry, TriageManifest};
+use models::vss::ShadowCopyInfo;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+/// 볼륨 루트 바로 아래에서만 나타나는 이름. 수집 도구마다 다른 접두 경로(C/, uploads/auto/C%3A/, vol0/ ...)를 걷어낼 때 쓴다.
+const VOLUME_ROOT_MARKERS: &[&str] = &["Windows", "Users", "ProgramData", "$MFT", "$LogFile", "$Extend", "$Recycle.Bin", "$Boot"];
+/// 이 크기를 넘는 ZIP 항목은 메모리 대신 임시 파일로 풀어 스트림으로 넘긴다.
+const ZIP_MEMORY_LIMIT: u64 = 64 * 1024 * 1024;
+/// 접두 경로 없이 볼륨 루트가 폴더 최상위인 경우의 볼륨 라벨
+const DEFAULT_VOLUME_LABEL: &str = "vol0";
+
+enum Storage {
+    Directory(PathBuf),
+    Zip(ZipArchive<File>),
+}
+
+/// 트리아지 폴더/ZIP 안의 파일 하나 (볼륨 루트 기준 논리 경로로 정규화)
+#[derive(Debug, Clone)]
+struct StoredStream {
+    /// 파일이 속한 볼륨 라벨 (매니페스트의 volume 또는 걷어낸 접두 경로)
+    volume: String,
+    components: Vec<String>,
+    /// 파일명에 ':'(또는 %3A)로 표기된 ADS 이름
+    stream: Option<String>,
+    /// 디렉터리 기준 상대 경로 또는 ZIP 항목 이름
+    location: String,
+    size: u64,
+}
+
+/// 트리아지 폴더 안의 볼륨(또는 섀도 복사본) 하나
+#[derive(Debug, Clone)]
+pub struct TriageVolume {
+    /// 패키지 내 볼륨 디렉터리 (예: "vol0", "vol0/vss1", KAPE의 "C")
+    pub label: String,
+    /// 매니페스트에 기록된 볼륨 일련번호 (알 수 없으면 0)
+    pub serial_number: u64,
+    pub host_name: Option<String>,
+    pub snapshot: Option<ShadowCopyInfo>,
+}
+
+/// [추가] KAPE/Velociraptor 또는 --export로 만든 트리아지 폴더(ZIP)를 NTFS 접근 없이 수집 백엔드로 쓴다.
+/// 프로파일의 경로 패턴을 폴더 안의 파일 경로에 그대로 매칭하므로 같은 분석기/상관 분석을 어느 OS에서나 돌릴 수 있다.
+/// 여러 볼륨이나 섀도 복사본이 들어 있으면 select_volume으로 고른 볼륨의 파일만 수집한다.
+pub struct TriageFolderCollector {
+    storage: Storage,
+    streams: Vec<StoredStream>,
+    manifest: Option<TriageManifest>,
+    volumes: Vec<TriageVolume>,
+    selected: usize,
+}
+
+impl TriageFolderCollector {
+    /// 디렉터리 또는 .zip 파일을 연다.
+    pub fn open(path: &Path) -> Result<Self> {
+        let (mut storage, files) = if path.is_dir() {
+            let mut files = Vec::new();
+            list_directory_files(path, String::new(), &mut files)?;
+            (Storage::Directory(path.to_path_buf()), files)
+        } else {
+            let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+            let mut archive = ZipArchive::new(file).with_context(|| format!("{} is neither a directory nor a ZIP archive", path.display()))?;
+            let mut files = Vec::new();
+            for i in 0..archive.len() {
+                let entry = archive.by_index_raw(i)?;
+                if entry.is_file() { files.push((entry.name().to_string(), entry.size())); }
+            }
+            (Storage::Zip(archive), files)
+        };
+        if files.is_empty() { bail!("No files found in {}", path.display()); }
+
+        let manifest = files.iter().any(|(location, _)| location == MANIFEST_NAME)
+            .then(|| read_location(&mut storage, MANIFEST_NAME).ok())
+            .flatten()
+            .and_then(|data| serde_json::from_slice::<TriageManifest>(&data).ok());
+
+        let streams = normalize_streams(files, manifest.as_ref());
+        let volumes = group_volumes(&streams, manifest.as_ref());
+        tracing::info!("  [*] Triage source {}: {} files in {} volume(s){}", path.display(), streams.len(), volumes.len(),
+            if manifest.is_some() { " (FACT manifest found)" } else { "" });
+        Ok(Self { storage, streams, manifest, volumes, selected: 0 })
+    }
+
+    /// 폴더 안의 볼륨 목록 (매니페스트 순서, 이어서 접두 경로가 처음 나온 순서)
+    pub fn volumes(&self) -> &[TriageVolume] {
+        &self.volumes
+    }
+
+    /// 이후 수집할 볼륨을 고른다.
+    pub fn select_volume(&mut self, label: &str) -> Result<()> {
+        self.selected = self.volumes.iter().position(|v| v.label == label)
+            .with_context(|| format!("Volume {} not found in triage source", label))?;
+        Ok(())
+    }
+
+    fn selected_volume(&self) -> &TriageVolume {
+        &self.volumes[self.selected]
+    }
+
+    /// 저장된 파일 하나를 Read + Seek 스트림으로 연다. ZIP 항목은 압축을 풀어야 하므로 크기에 따라 메모리 또는 임시 파일을 쓴다.
+    fn open_stream(&mut self, stream: &StoredStream) -> Result<Box<dyn ReadSeek>> {
+        match &mut self.storage {
+            Storage::Directory(root) => {
+                let path = stream.location.split('/').fold(root.clone(), |p, part| p.join(part));
+                Ok(Box::new(File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?))
+            },
+            Storage::Zip(archive) => {
+                let mut entry = archive.by_name(&stream.location)?;
+                if stream.size <= ZIP_MEMORY_LIMIT {
+                    let mut data = Vec::with_capacity(stream.size as usize);
+                    entry.read_to_end(&mut data)?;
+                    Ok(Box::new(Cursor::new(data)))
+                } else {
+                    let mut spool = tempfile::tempfile().context("Failed to create spool file")?;
+                    io::copy(&mut entry, &mut spool)?;
+                    spool.seek(SeekFrom::Start(0))?;
+                    Ok(Box::new(spool))
+                }
+            },
+        }
+    }
+}
+
+impl ArtifactSource for TriageFolderCollector {
+    fn collect_files(&mut self, target: &CollectionTarget, callback: &mut dyn FnMut(&CollectedFile, &mut dyn ReadSeek)) -> Result<(usize, u64)> {
+        let (mut processed_count, mut total_bytes_streamed) = (0, 0);
+        let patterns = target.path_patterns();
+
+        let volume = self.selected_volume().label.clone();
+        for stream in self.streams.clone() {
+            if stream.volume != volume || !matches_stream(&stream, target.ads.as_deref()) { continue; }
+            let parts: Vec<&str> = stream.components.iter().map(String::as_str).collect();
+            let Some(pattern) = patterns.iter().find(|p| p.matches(&parts)) else { continue };
+            if !target.accepts_extension(parts.last().copied().unwrap_or_default()) { continue; }
+            if stream.size == 0 { continue; }
+
+            let name = pattern.collection_name(&parts);
+            if let Some(max_size) = target.max_size
+                && stream.size > max_size {
+                tracing::info!("    [-] Skipping {} ({} bytes exceeds max_size {})", name, stream.size, max_size);
+                continue;
+            }
+            match self.open_stream(&stream) {
+                Ok(mut reader) => {
+                    let file = CollectedFile {
+                        name, path: parts.join("\\"), stream: target.ads.clone(),
+                        inode: 0, size: stream.size, record: None,
+                    };
+                    callback(&file, &mut *reader);
+                    processed_count += 1;
+                    total_bytes_streamed += stream.size;
+                },
+                Err(e) => tracing::debug!("    [-] Failed to open {}: {}", stream.location, e),
+            }
+        }
+        Ok((processed_count, total_bytes_streamed))
+    }
+
+    fn computer_name(&mut self) -> Option<String> {
+        let volume = self.selected_volume().label.clone();
+        let system = self.streams.iter().find(|s| s.volume == volume && s.stream.is_none()
+            && s.components.len() == 4
+            && ["Windows", "System32", "config", "SYSTEM"].iter().zip(&s.components).all(|(a, b)| a.eq_ignore_ascii_case(b)))
+            .cloned();
+        let from_hive = system.and_then(|s| {
+            let mut data = Vec::new();
+            self.open_stream(&s).ok()?.read_to_end(&mut data).ok()?;
+            parser::system_hive::computer_name(&data)
+        });
+        from_hive
+            .or_else(|| self.selected_volume().host_name.clone())
+            .or_else(|| self.manifest.as_ref().and_then(|m| m.host_name.clone()))
+    }
+
+    fn volume_serial_number(&self) -> u64 {
+        self.selected_volume().serial_number
+    }
+
+    fn snapshot(&self) -> Option<&ShadowCopyInfo> {
+        self.selected_volume().snapshot.as_ref()
+    }
+}
+
+fn list_directory_files(root: &Path, prefix: String, files: &mut Vec<(String, u64)>) -> Result<()> {
+    for entry in fs::read_dir(root.join(&prefix))? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let location = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+        let file_type = entry.file_type()?;
+        // 심볼릭 링크는 따라가지 않는다. (폴더 밖을 가리킬 수 있음)
+        if file_type.is_dir() {
+            list_directory_files(root, location, files)?;
+        } else if file_type.is_file() {
+            files.push((location, entry.metadata()?.len()));
+        }
+    }
+    Ok(())
+}
+
+fn read_location(storage: &mut Storage, location: &str) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    match storage {
+        Storage::Directory(root) => { File::open(root.join(location))?.read_to_end(&mut data)?; },
+        Storage::Zip(archive) => { archive.by_name(location)?.read_to_end(&mut data)?; },
+    }
+    Ok(data)
+}
+
+/// 저장 경로를 볼륨 루트 기준 논리 경로로 바꾼다. FACT 매니페스트에 기록된 파일은 원본 경로/스트림을 그대로 쓰고,
+/// 나머지는 경로 구성요소를 퍼센트 디코딩한 뒤 처음 나오는 볼륨 루트 표식(Windows, Users, $MFT ...) 앞의 접두 경로를 버린다.
+fn normalize_streams(files: Vec<(String, u64)>, manifest: Option<&TriageManifest>) -> Vec<StoredStream> {
+    let recorded: HashMap<&str, &ManifestEntry> = manifest
+        .map(|m| m.files.iter().map(|e| (e.archive_path.as_str(), e)).collect())
+        .unwrap_or_default();
+    let (known, files): (Vec<_>, Vec<_>) = files.into_iter().partition(|(location, _)| recorded.contains_key(location.as_str()));
+    let mut streams: Vec<StoredStream> = known.into_iter().map(|(location, size)| {
+        let entry = recorded[location.as_str()];
+        StoredStream {
+            volume: entry.volume.clone(),
+            components: entry.original_path.split('\\').filter(|c| !c.is_empty()).map(str::to_string).collect(),
+            stream: entry.stream.clone(),
+            location,
+            size,
+        }
+    }).collect();
+
+    let decoded: Vec<(Vec<String>, String, u64)> = files.into_iter()
+        .filter(|(location, _)| location != MANIFEST_NAME)
+        .map(|(location, size)| (location.split(['/', '\\']).filter(|c| !c.is_empty()).map(percent_decode).collect(), location, size))
+        .collect();
+
+    let mut roots: Vec<Vec<String>> = Vec::new();
+    for (components, _, _) in &decoded {
+        if let Some(i) = components.iter().take(components.len().saturating_sub(1)).position(|c| is_root_marker(c))
+            .or_else(|| components.last().filter(|c| is_root_marker(c)).map(|_| components.len() - 1))
+            && !roots.contains(&components[..i].to_vec()) {
+            roots.push(components[..i].to_vec());
+        }
+    }
+
+    streams.extend(decoded.into_iter().map(|(components, location, size)| {
+        // 표식이 없는 파일은 가장 긴 일치 루트 기준 (없으면 폴더 최상위 기준)
+        let root = roots.iter().filter(|r| components.starts_with(r)).max_by_key(|r| r.len()).map(Vec::as_slice).unwrap_or_default();
+        let volume = volume_label(root);
+        let mut components = components[root.len().min(components.len())..].to_vec();
+        let mut stream = None;
+        if let Some(last) = components.last_mut()
+            && let Some((name, ads)) = last.split_once(':')
+            && !name.is_empty() {
+            stream = Some(ads.to_string());
+            *last = name.to_string();
+        }
+        // KAPE는 $UsnJrnl의 $J/$Max 스트림을 $Extend\$J 파일로 저장한다.
+        if stream.is_none() && components.len() == 2 && components[0].eq_ignore_ascii_case("$Extend")
+            && (components[1].eq_ignore_ascii_case("$J") || components[1].eq_ignore_ascii_case("$Max")) {
+            stream = Some(components[1].clone());
+            components[1] = "$UsnJrnl".to_string();
+        }
+        StoredStream { volume, components, stream, location, size }
+    }));
+    streams
+}
+
+/// 걷어낸 접두 경로를 볼륨 라벨로 쓴다. (C/ -> "C", uploads/auto/C%3A/ -> "uploads/auto/C", vol0/vss1/ -> "vol0/vss1")
+fn volume_label(root: &[String]) -> String {
+    if root.is_empty() { return DEFAULT_VOLUME_LABEL.to_string(); }
+    root.iter()
+        .map(|c| c.trim_end_matches(':').chars().map(|ch| if "\\/:*?\"<>|".contains(ch) { '_' } else { ch }).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 파일이 있는 볼륨만 목록으로 만든다. 매니페스트에 기록된 볼륨은 일련번호/호스트/스냅숏 정보를 함께 쓴다.
+fn group_volumes(streams: &[StoredStream], manifest: Option<&TriageManifest>) -> Vec<TriageVolume> {
+    let mut labels: Vec<&str> = manifest.map(|m| m.volumes.iter().map(|v| v.label.as_str()).collect()).unwrap_or_default();
+    for stream in streams {
+        if !labels.contains(&stream.volume.as_str()) { labels.push(&stream.volume); }
+    }
+    labels.into_iter()
+        .filter(|label| streams.iter().any(|s| s.volume == *label))
+        .map(|label| {
+            let recorded = manifest.and_then(|m| m.volumes.iter().find(|v| v.label == label));
+            TriageVolume {
+                label: label.to_string(),
+                serial_number: recorded.and_then(|v| u64::from_str_radix(&v.serial_number, 16).ok()).unwrap_or(0),
+                host_name: recorded.and_then(|v| v.host_name.clone()),
+                snapshot: recorded.and_then(|v| v.snapshot.clone()),
+            }
+        })
+        .collect()
+}
+
+fn is_root_marker(component: &str) -> bool {
+    VOLUME_ROOT_MARKERS.iter().any(|m| m.eq_ignore_ascii_case(component))
+}
+
+/// 타겟이 요청한 스트림(기본 스트림 또는 ADS)과 일치하는지 검사한다.
+fn matches_stream(stream: &StoredStream, requested_ads: Option<&str>) -> bool {
+    match (requested_ads, &stream.stream) {
+        (None, None) => true,
+        (Some(ads), Some(name)) => ads.eq_ignore_ascii_case(name),
+        _ => false,
+    }
+}
+
+/// Velociraptor 등이 파일명의 ':', '$' 등을 %XX로 인코딩한 것을 되돌린다.
+fn percent_decode(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = component.get(i + 1..i + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16) {
+            out.push(byte);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8(out).unwrap_or_else(|_| component.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::triage::TriagePackage;
+    use chrono::{TimeZone, Utc};
+    use models::artifact::ArtifactTarget;
+
+    fn files(locations: &[&str]) -> Vec<(String, u64)> {
+        locations.iter().map(|l| (l.to_string(), 10)).collect()
+    }
+
+    fn logical(streams: &[StoredStream]) -> Vec<(String, String, Option<String>)> {
+        streams.iter().map(|s| (s.volume.clone(), s.components.join("\\"), s.stream.clone())).collect()
+    }
+
+    fn expected(volume: &str, path: &str, stream: Option<&str>) -> (String, String, Option<String>) {
+        (volume.to_string(), path.to_string(), stream.map(str::to_string))
+    }
+
+    fn target(paths: &[&str], ads: Option<&str>) -> CollectionTarget {
+        CollectionTarget {
+            name: "Test".into(),
+            parser: ArtifactTarget::RegistryNTUSER,
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+            extensions: Vec::new(),
+            ads: ads.map(str::to_string),
+            max_size: None,
+        }
+    }
+
+    fn collect(source: &mut TriageFolderCollector, target: &CollectionTarget) -> Vec<(String, Vec<u8>)> {
+        let mut collected = Vec::new();
+        source.collect_files(target, &mut |file, reader| {
+            let mut data = Vec::new();
+            reader.read_to_end(&mut data).unwrap();
+            collected.push((file.path.clone(), data));
+        }).unwrap();
+        collected
+    }
+
+    #[test]
+    fn percent_encoded_components_are_decoded() {
+        assert_eq!(percent_decode("C%3A"), "C:");
+        assert_eq!(percent_decode("%24MFT"), "$MFT");
+        assert_eq!(percent_decode("%E2%82%AC.txt"), "€.txt");
+        // 잘못된 인코딩은 그대로 둔다.
+        assert_eq!(percent_decode("100%zz"), "100%zz");
+        assert_eq!(percent_decode("end%4"), "end%4");
+        assert_eq!(percent_decode("%FF%FE"), "%FF%FE");
+    }
+
+    #[test]
+    fn kape_roots_become_separate_volumes() {
+        let streams = normalize_streams(files(&[
+            "C/Windows/System32/config/SYSTEM",
+            "C/$Extend/$J",
+            "C/$Extend/$Max",
+            "C/$MFT",
+            "C/notes.txt",
+            "D/Users/bob/NTUSER.DAT",
+            "uploads/auto/C%3A/Users/a/setup.exe%3AZone.Identifier",
+            "$LogFile",
+        ]), None);
+        assert_eq!(logical(&streams), [
+            expected("C", "Windows\\System32\\config\\SYSTEM", None),
+            expected("C", "$Extend\\$UsnJrnl", Some("$J")),
+            expected("C", "$Extend\\$UsnJrnl", Some("$Max")),
+            expected("C", "$MFT", None),
+            expected("C", "notes.txt", None),
+            expected("D", "Users\\bob\\NTUSER.DAT", None),
+            expected("uploads/auto/C", "Users\\a\\setup.exe", Some("Zone.Identifier")),
+            expected("vol0", "$LogFile", None),
+        ]);
+
+        let volumes: Vec<String> = group_volumes(&streams, None).into_iter().map(|v| v.label).collect();
+        assert_eq!(volumes, ["C", "D", "uploads/auto/C", "vol0"]);
+    }
+
+    #[test]
+    fn manifest_entries_keep_their_recorded_volume() {
+        let recorded = |volume: &str, archive_path: &str, original_path: &str, stream: Option<&str>| ManifestEntry {
+            archive_path: archive_path.into(), original_path: original_path.into(), stream: stream.map(str::to_string),
+            volume: volume.into(), target: "T".into(), mft_entry: 0, mft_sequence: 0, size: 10,
+            md5: String::new(), sha1: String::new(), sha256: String::new(), collected_at: Utc::now(),
+            standard_information: None, file_name: None,
+        };
+        let manifest = TriageManifest {
+            collector: "FACT".into(), collector_version: "0".into(), host_name: None,
+            collection_started: Utc::now(), collection_finished: None,
+            volumes: Vec::new(),
+            files: vec![
+                recorded("vol0", "vol0/$Extend/$UsnJrnl_$J", "$Extend\\$UsnJrnl", Some("$J")),
+                recorded("vol0/vss1", "vol0/vss1/$Extend/$UsnJrnl_$J", "$Extend\\$UsnJrnl", Some("$J")),
+            ],
+        };
+        let streams = normalize_streams(files(&[MANIFEST_NAME, "vol0/$Extend/$UsnJrnl_$J", "vol0/vss1/$Extend/$UsnJrnl_$J", "vol1/$MFT"]), Some(&manifest));
+        assert_eq!(logical(&streams), [
+            expected("vol0", "$Extend\\$UsnJrnl", Some("$J")),
+            expected("vol0/vss1", "$Extend\\$UsnJrnl", Some("$J")),
+            expected("vol1", "$MFT", None),
+        ]);
+    }
+
+    #[test]
+    fn each_kape_volume_is_collected_separately() {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, content) in [("C/Users/alice/NTUSER.DAT", "alice"), ("D/Users/bob/NTUSER.DAT", "bob"), ("C/$Extend/$J", "usn")] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let mut source = TriageFolderCollector::open(dir.path()).unwrap();
+        let mut labels: Vec<String> = source.volumes().iter().map(|v| v.label.clone()).collect();
+        labels.sort();
+        assert_eq!(labels, ["C", "D"]);
+
+        let hives = target(&["Users\\*\\NTUSER.DAT"], None);
+        source.select_volume("D").unwrap();
+        assert_eq!(collect(&mut source, &hives), [("Users\\bob\\NTUSER.DAT".to_string(), b"bob".to_vec())]);
+        source.select_volume("C").unwrap();
+        assert_eq!(collect(&mut source, &hives), [("Users\\alice\\NTUSER.DAT".to_string(), b"alice".to_vec())]);
+        assert_eq!(collect(&mut source, &target(&["$Extend\\$UsnJrnl"], Some("$J"))), [("$Extend\\$UsnJrnl".to_string(), b"usn".to_vec())]);
+        assert!(source.select_volume("E").is_err());
+    }
+
+    #[test]
+    fn exported_snapshots_are_reopened_as_tagged_volumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.zip");
+        let snapshot = ShadowCopyInfo {
+            index: 1, store_id: "store".into(), shadow_copy_id: "shadow".into(), shadow_copy_set_id: "set".into(),
+            creation_time: Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap(), volume_size: 1 << 30,
+        };
+        let hive = |data: &'static [u8]| CollectedFile {
+            name: "NTUSER.DAT".into(), path: "Users\\alice\\NTUSER.DAT".into(), stream: None, inode: 70, size: data.len() as u64, record: None,
+        };
+
+        let mut package = TriagePackage::create(&path, false).unwrap();
+        package.add_volume("vol0", 0xAAAA, Some("WS01".into()), None);
+        package.add_file("vol0", "NTUSER", &hive(b"current"), &mut &b"current"[..]).unwrap();
+        package.add_volume("vol0/vss1", 0xAAAA, None, Some(&snapshot));
+        package.add_file("vol0/vss1", "NTUSER", &hive(b"older"), &mut &b"older"[..]).unwrap();
+        package.finish().unwrap();
+
+        let mut source = TriageFolderCollector::open(&path).unwrap();
+        let labels: Vec<&str> = source.volumes().iter().map(|v| v.label.as_str()).collect();
+        assert_eq!(labels, ["vol0", "vol0/vss1"]);
+        assert_eq!(source.volume_serial_number(), 0xAAAA);
+        assert!(source.snapshot().is_none());
+        let hives = target(&["Users\\**\\NTUSER.DAT"], None);
+        assert_eq!(collect(&mut source, &hives)[0].1, b"current");
+
+        source.select_volume("vol0/vss1").unwrap();
+        assert_eq!(source.snapshot().map(|s| s.index), Some(1));
+        assert_eq!(source.computer_name().as_deref(), Some("WS01"));
+        assert_eq!(collect(&mut source, &hives), [("Users\\alice\\NTUSER.DAT".to_string(), b"older".to_vec())]);
+    }
+}
@@ -1,4 +1,7 @@
-#[derive(Debug, Clone, PartialEq, Eq)]
+use serde::Deserialize;
+
+/// 수집한 아티팩트를 해석할 파서 종류. 어떤 경로를 수집할지는 수집 프로파일(profile.rs)이 정한다.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub enum ArtifactTarget {
     MFT,
     Prefetch,
@@ -10,37 +13,19 @@ pub enum ArtifactTarget {
     RegistrySAM,
     RegistryNTUSER,
     UsnJrnl,
+    LogFile, // [신규] NTFS 트랜잭션 저널
     RecycleBin,
     USBLog,
     LNK, // 신규 추가
     WMI, // 신규 추가
 }
 
-#[derive(Debug, Clone)]
-pub enum TargetType {
-    SingleFile { path: &'static str },
-    Directory { path: &'static str, extension: Option<&'static str>, recursive: bool },
-}
-
 impl ArtifactTarget {
-    pub fn get_details(&self) -> Vec<TargetType> {
-        match self {
-            Self::MFT => vec![TargetType::SingleFile { path: "$MFT" }],
-            Self::Prefetch => vec![TargetType::Directory { path: "Windows\\Prefetch", extension: Some("pf"), recursive: false }],
-            Self::EventLogs => vec![TargetType::Directory { path: "Windows\\System32\\winevt\\Logs", extension: Some("evtx"), recursive: false }],
-            Self::ScheduledTasks => vec![TargetType::Directory { path: "Windows\\System32\\Tasks", extension: None, recursive: true }],
-            Self::Amcache => vec![TargetType::SingleFile { path: "Windows\\AppCompat\\Programs\\Amcache.hve" }],
-            Self::RegistrySOFTWARE => vec![TargetType::SingleFile { path: "Windows\\System32\\config\\SOFTWARE" }],
-            Self::RegistrySYSTEM => vec![TargetType::SingleFile { path: "Windows\\System32\\config\\SYSTEM" }],
-            Self::RegistrySAM => vec![TargetType::SingleFile { path: "Windows\\System32\\config\\SAM" }],
-            Self::RegistryNTUSER => vec![TargetType::Directory { path: "Users", extension: Some("DAT"), recursive: true }],
-            Self::UsnJrnl => vec![TargetType::SingleFile { path: "$Extend\\$UsnJrnl:$J" }],
-            Self::RecycleBin => vec![TargetType::Directory { path: "$Recycle.Bin", extension: None, recursive: true }],
-            Self::USBLog => vec![TargetType::SingleFile { path: "Windows\\inf\\setupapi.dev.log" }],
-            // [신규] 모든 사용자의 바탕화면, 다운로드, 최근 실행 폴더의 바로가기 파일 수집
-            Self::LNK => vec![TargetType::Directory { path: "Users", extension: Some("lnk"), recursive: true }],
-            // [신규] WMI 리포지토리 수집
-            Self::WMI => vec![TargetType::SingleFile { path: "Windows\\System32\\wbem\\Repository\\OBJECTS.DATA" }],
-        }
+    /// [추가] regf 하이브를 수집하는 타겟 (트랜잭션 로그 .LOG1/.LOG2를 함께 수집하여 재생한다)
+    pub fn is_registry_hive(&self) -> bool {
+        matches!(
+            self,
+            Self::Amcache | Self::RegistrySOFTWARE | Self::RegistrySYSTEM | Self::RegistrySAM | Self::RegistryNTUSER
+        )
     }
-}
\ No newline at end of file
+}
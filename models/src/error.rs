@@ -17,4 +17,7 @@ pub enum FactError {
     
     #[error("Database Error: {0}")]
     DatabaseError(String),
+
+    #[error("Integrity check failed for '{artifact_name}': stored {algorithm} {expected}, computed {actual}")]
+    HashMismatch { artifact_name: String, algorithm: String, expected: String, actual: String },
 }
\ No newline at end of file
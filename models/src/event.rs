@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
+use crate::vss::ShadowCopyInfo;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionEvent {
@@ -32,6 +33,9 @@ pub struct PersistenceEvent {
     pub target_name: String,
     pub target_path: String,
     pub source_artifact: String,
+    // [추가] 사용자 하이브(NTUSER.DAT)처럼 특정 사용자에게 속한 자동 실행 항목의 소유 사용자
+    #[serde(default)]
+    pub user: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +57,18 @@ pub struct SystemEvent {
     pub source_artifact: String,
 }
 
+/// 파일 시스템 이벤트를 만든 NTFS 아티팩트 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FileSystemArtifact {
+    Mft,
+    UsnJrnl,
+    LogFile,
+    IndexSlack,
+    AlternateDataStream,
+    #[default]
+    Other,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileSystemEvent {
     pub timestamp: DateTime<Utc>,
@@ -64,6 +80,66 @@ pub struct FileSystemEvent {
     pub fn_mtime: Option<DateTime<Utc>>, 
     pub is_timestomped: bool,            
     pub source_artifact: String,
+    // [추가] 출처 아티팩트 종류. source_artifact는 VSS/카빙 태그가 덧붙는 표시용 문자열이므로 규칙 매칭에는 이 값을 쓴다.
+    #[serde(default)]
+    pub artifact: FileSystemArtifact,
+    // [추가] MFT 기반 이벤트의 파일 참조 (엔트리 + 시퀀스). 다른 아티팩트와의 파일 단위 조인에 사용
+    #[serde(default)]
+    pub mft_reference: Option<u64>,
+    #[serde(default)]
+    pub parent_reference: Option<u64>,
+    // [추가] 타임스톰핑 판정 근거가 된 규칙 목록 (비어 있으면 is_timestomped = false)
+    #[serde(default)]
+    pub timestomp_rules: Vec<String>,
+    // [추가] 사용 중 플래그가 해제된(삭제된) MFT 레코드에서 복원된 이벤트 여부
+    #[serde(default)]
+    pub is_deleted: bool,
+    // [추가] 실행 파일/스크립트 은닉으로 판정된 ADS의 근거 목록 (비어 있으면 의심 없음)
+    #[serde(default)]
+    pub stream_indicators: Vec<String>,
+}
+
+/// Zone.Identifier(Mark-of-the-Web) 스트림으로 확인된 파일의 다운로드 출처
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadEvent {
+    pub timestamp: DateTime<Utc>,
+    pub file_path: String,
+    /// URLZONE 값 (0 로컬, 1 인트라넷, 2 신뢰, 3 인터넷, 4 제한)
+    pub zone_id: u32,
+    pub referrer_url: Option<String>,
+    pub host_url: Option<String>,
+    pub mft_reference: Option<u64>,
+    pub source_artifact: String,
+}
+
+/// 휴지통($Recycle.Bin)으로 삭제된 파일. $I 메타데이터의 원래 경로/크기/삭제 시각과 짝이 되는 $R 내용
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletionEvent {
+    pub timestamp: DateTime<Utc>,
+    pub original_path: String,
+    /// 삭제 당시 원본 크기 ($I 기록값)
+    pub file_size: u64,
+    /// 휴지통 하위 폴더 이름으로 확인한 삭제 사용자 SID
+    pub user_sid: String,
+    /// 내용이 남아 있는 $R 경로 (휴지통을 비웠으면 None)
+    pub recycled_path: Option<String>,
+    pub recycled_size: u64,
+    pub source_artifact: String,
+}
+
+/// USB 저장 장치의 연결/해제/사용자 마운트 기록 (장치 전체 이력은 usb::UsbDevice)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsbEvent {
+    pub timestamp: DateTime<Utc>,
+    /// "First Connected", "Last Connected", "Last Removed", "Mounted"
+    pub action: String,
+    pub device_name: String,
+    pub serial_number: String,
+    pub drive_letter: Option<String>,
+    pub volume_guid: Option<String>,
+    /// 마운트한 사용자 (MountPoints2 기반 이벤트만)
+    pub user: Option<String>,
+    pub source_artifact: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,4 +150,35 @@ pub enum ForensicEvent {
     Logon(LogonEvent),
     SystemActivity(SystemEvent),
     FileSystemActivity(FileSystemEvent),
-}
\ No newline at end of file
+    Download(DownloadEvent),
+    Deletion(DeletionEvent),
+    Usb(UsbEvent),
+}
+
+impl ForensicEvent {
+    pub fn source_artifact_mut(&mut self) -> &mut String {
+        match self {
+            Self::Execution(e) => &mut e.source_artifact,
+            Self::NetworkActivity(e) => &mut e.source_artifact,
+            Self::Persistence(e) => &mut e.source_artifact,
+            Self::Logon(e) => &mut e.source_artifact,
+            Self::SystemActivity(e) => &mut e.source_artifact,
+            Self::FileSystemActivity(e) => &mut e.source_artifact,
+            Self::Download(e) => &mut e.source_artifact,
+            Self::Deletion(e) => &mut e.source_artifact,
+            Self::Usb(e) => &mut e.source_artifact,
+        }
+    }
+
+    /// 섀도 복사본에서 수집된 이벤트의 출처에 스냅숏 번호와 생성 시각을 덧붙인다. (예: "$MFT [VSS #1 2024-03-01T09:00:00+00:00]")
+    pub fn tag_snapshot(&mut self, snapshot: &ShadowCopyInfo) {
+        let source = self.source_artifact_mut();
+        *source = format!("{} [VSS #{} {}]", source, snapshot.index, snapshot.creation_time.to_rfc3339());
+    }
+
+    /// 미할당 영역에서 카빙된 이벤트의 출처에 디스크상의 물리 오프셋을 덧붙인다. (예: "Security.evtx (EID: 5156) [Carved @ 0x1A2B000]")
+    pub fn tag_carved(&mut self, physical_offset: u64) {
+        let source = self.source_artifact_mut();
+        *source = format!("{} [Carved @ {:#X}]", source, physical_offset);
+    }
+}
@@ -0,0 +1,6 @@
+use std::io::{Read, Seek};
+
+/// 파서가 소비할 수 있는 임의 탐색 가능한 바이트 스트림 (볼륨/이미지 핸들, 파일 런리스트 뷰, 메모리 버퍼 등)
+pub trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek + ?Sized> ReadSeek for T {}
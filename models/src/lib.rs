@@ -3,7 +3,15 @@ pub mod mft;
 pub mod error;
 pub mod artifact;
 pub mod event;
+pub mod partition;
+pub mod vss;
+pub mod io;
+pub mod profile;
+pub mod triage;
+pub mod usb;
 
 pub use error::FactError;
+pub use io::ReadSeek;
 // 필요하다면 아래처럼 명시적으로 Export 할 수 있습니다.
-pub use artifact::{ArtifactTarget, TargetType};
\ No newline at end of file
+pub use artifact::ArtifactTarget;
+pub use profile::{CollectionProfile, CollectionTarget};
\ No newline at end of file
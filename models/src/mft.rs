@@ -90,6 +90,72 @@ pub struct FileNameAttribute {
     pub name: String,
 }
 
+impl FileNameAttribute {
+    /// 파일명 네임스페이스: 0 = POSIX, 1 = Win32, 2 = DOS(8.3), 3 = Win32 & DOS
+    pub fn is_dos_only(&self) -> bool {
+        self.namespace == 2
+    }
+}
+
+/// MFT 파일 참조(하위 48비트 엔트리 번호 + 상위 16비트 시퀀스 번호)에서 엔트리 번호를 추출한다.
+pub fn mft_entry_number(reference: u64) -> u64 {
+    reference & 0x0000_FFFF_FFFF_FFFF
+}
+
+/// MFT 파일 참조에서 시퀀스 번호를 추출한다.
+pub fn mft_sequence_number(reference: u64) -> u16 {
+    (reference >> 48) as u16
+}
+
+/// $DATA 속성 메타데이터 (이름 없는 기본 스트림 또는 ADS). 상주 내용은 레코드에서 다시 읽는다.
+#[derive(Debug, Clone)]
+pub struct DataStream {
+    pub name: String,
+    pub is_resident: bool,
+    /// 비상주 속성의 런리스트 (starting VCN 0 조각만 포함)
+    pub runlist: Vec<DataRun>,
+    pub real_size: u64,
+}
+
+/// 사용 중 플래그가 해제된 FILE 레코드와 마지막으로 알려진 경로
+#[derive(Debug, Clone)]
+pub struct DeletedFileRecord {
+    pub record: MftRecord,
+    pub path: String,
+    /// 부모 디렉터리의 시퀀스 번호가 참조와 일치하여 경로를 신뢰할 수 있는지 여부
+    pub parent_path_valid: bool,
+}
+
+/// $STANDARD_INFORMATION과 모든 $FILE_NAME 속성을 디코딩한 FILE 레코드
+#[derive(Debug, Clone)]
+pub struct MftRecord {
+    pub entry_number: u64,
+    pub sequence_number: u16,
+    /// 확장 레코드인 경우 베이스 레코드의 파일 참조, 베이스 레코드면 0
+    pub base_reference: u64,
+    pub in_use: bool,
+    pub is_directory: bool,
+    pub standard_info: Option<StandardInformation>,
+    pub file_names: Vec<FileNameAttribute>,
+    pub data_streams: Vec<DataStream>,
+}
+
+impl MftRecord {
+    /// 이 레코드를 가리키는 파일 참조 값
+    pub fn reference(&self) -> u64 {
+        ((self.sequence_number as u64) << 48) | self.entry_number
+    }
+
+    /// 경로 재구성에 사용할 대표 파일명 (Win32 > POSIX > DOS 순으로 선호)
+    pub fn preferred_file_name(&self) -> Option<&FileNameAttribute> {
+        self.file_names.iter().min_by_key(|f| match f.namespace {
+            1 | 3 => 0,
+            0 => 1,
+            _ => 2,
+        })
+    }
+}
+
 #[derive(BinRead, Debug, Clone)]
 #[br(little)]
 pub struct IndexHeader {
@@ -122,6 +188,36 @@ pub struct IndexEntry {
     pub is_directory: bool, // [Fix] 디렉토리 식별자 완벽 분리
 }
 
+/// INDX 레코드의 사용 영역 뒤 slack에서 카빙한 $FILE_NAME 키 (삭제 또는 이름 변경 전 엔트리의 잔재)
+#[derive(Debug, Clone)]
+pub struct IndexSlackEntry {
+    pub directory_reference: u64,
+    /// 디렉터리 전체 경로 (수집기가 경로 테이블로 채운다)
+    pub directory_path: String,
+    /// 엔트리 헤더가 slack 안에 온전히 남아 있는 경우의 파일 참조
+    pub file_reference: Option<u64>,
+    pub file_name: FileNameAttribute,
+    /// 카빙 위치: INDX 레코드의 VCN과 레코드 내 오프셋
+    pub index_vcn: u64,
+    pub offset: usize,
+}
+
+/// 이름 있는 $DATA 속성(대체 데이터 스트림). 내용은 분류와 Zone.Identifier 해석에 필요한 앞부분만 보관한다.
+#[derive(Debug, Clone)]
+pub struct AlternateDataStream {
+    /// 스트림이 붙은 베이스 파일의 참조 (확장 레코드에 있던 스트림도 베이스 기준)
+    pub file_reference: u64,
+    pub path: String,
+    pub stream_name: String,
+    pub size: u64,
+    pub is_resident: bool,
+    pub is_directory: bool,
+    /// 베이스 파일 $STANDARD_INFORMATION의 생성 / MFT 변경 시각 (FILETIME)
+    pub si_creation_time: u64,
+    pub si_mft_modified_time: u64,
+    pub content: Vec<u8>,
+}
+
 #[derive(BinRead, Debug, Clone)]
 #[br(little)]
 pub struct IndexRecordHeader {
@@ -21,6 +21,13 @@ pub struct BootSector {
     pub total_sectors: u64,         // 볼륨 전체 크기
     pub mft_lcn: u64,               // $MFT의 시작 클러스터 번호 (Logical Cluster Number)
     pub mft_mirr_lcn: u64,          // $MFTMirr 위치
+    pub clusters_per_mft_record: i8,   // 0x40: 음수이면 2^(-값) 바이트 (보통 -10 -> 1KB)
+    
+    #[br(pad_before = 3)]
+    pub clusters_per_index_record: i8, // 0x44: 인덱스 레코드(INDX) 크기, 인코딩은 위와 동일
+
+    #[br(pad_before = 3)]
+    pub volume_serial_number: u64,     // 0x48: 볼륨 일련번호
     
     // ... 나머지 필드는 당장 필요 없으므로 생략
 }
@@ -35,4 +42,24 @@ impl BootSector {
     pub fn mft_offset(&self) -> u64 {
         self.mft_lcn * self.cluster_size()
     }
+
+    /// FILE 레코드 크기(Byte 단위)
+    pub fn mft_record_size(&self) -> u64 {
+        record_size_from_clusters(self.clusters_per_mft_record, self.cluster_size())
+    }
+
+    /// INDX 레코드 크기(Byte 단위)
+    pub fn index_record_size(&self) -> u64 {
+        record_size_from_clusters(self.clusters_per_index_record, self.cluster_size())
+    }
+}
+
+/// VBR의 "레코드당 클러스터 수" 필드를 바이트 크기로 변환한다.
+/// 레코드가 클러스터보다 작으면 음수 n으로 기록되며 이때 크기는 2^(-n) 바이트다.
+pub fn record_size_from_clusters(clusters_per_record: i8, cluster_size: u64) -> u64 {
+    if clusters_per_record < 0 {
+        1u64.checked_shl(clusters_per_record.unsigned_abs() as u32).unwrap_or(0)
+    } else {
+        clusters_per_record as u64 * cluster_size
+    }
 }
\ No newline at end of file
@@ -0,0 +1,22 @@
+use serde::{Serialize, Deserialize};
+
+/// 파티션 테이블 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PartitionScheme {
+    /// 파티션 테이블 없이 오프셋 0에 VBR이 바로 위치하는 볼륨 이미지
+    Volume,
+    Mbr,
+    Gpt,
+}
+
+/// 디스크 이미지에서 발견된 단일 파티션(볼륨) 정보
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitionEntry {
+    pub index: usize,
+    pub scheme: PartitionScheme,
+    pub type_id: String,       // MBR: "0x07", GPT: 파티션 타입 GUID
+    pub name: String,          // GPT 파티션 이름 또는 타입 설명
+    pub start_offset: u64,     // 디스크 시작 기준 바이트 오프셋
+    pub length: u64,           // 바이트 단위 크기
+    pub is_ntfs: bool,         // VBR 시그니처("NTFS    ")로 확인된 NTFS 볼륨 여부
+}
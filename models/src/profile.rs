@@ -0,0 +1,243 @@
+use crate::artifact::ArtifactTarget;
+use crate::error::FactError;
+use serde::Deserialize;
+use std::path::Path;
+
+/// 바이너리에 내장되는 기본 프로파일. 기존 하드코딩 타겟 목록에 LogFile, RecycleBin, USBLog 타겟과
+/// 레지스트리 하이브 트랜잭션 로그(.LOG1/.LOG2) 경로를 더했다.
+const DEFAULT_PROFILE: &str = include_str!("../profiles/default.toml");
+
+/// 볼륨마다 무엇을 수집하여 어느 파서로 넘길지 정의하는 수집 프로파일 (TOML)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CollectionProfile {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(rename = "target", default)]
+    pub targets: Vec<CollectionTarget>,
+}
+
+/// 프로파일의 수집 타겟 하나
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CollectionTarget {
+    pub name: String,
+    /// 수집한 스트림을 해석할 파서
+    pub parser: ArtifactTarget,
+    /// 볼륨 루트 기준 경로 패턴 ('*', '?', '**' 지원)
+    pub paths: Vec<String>,
+    /// 허용할 확장자 (비어 있으면 모두 허용)
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// 기본 스트림 대신 수집할 ADS 이름 (예: UsnJrnl의 "$J")
+    pub ads: Option<String>,
+    /// 이 크기(바이트)를 넘는 스트림은 건너뛴다.
+    pub max_size: Option<u64>,
+}
+
+/// '\' 단위로 나눈 경로 패턴
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPattern {
+    pub segments: Vec<String>,
+}
+
+impl Default for CollectionProfile {
+    fn default() -> Self {
+        Self::from_toml(DEFAULT_PROFILE, "default profile").expect("built-in collection profile must be valid")
+    }
+}
+
+impl CollectionProfile {
+    pub fn from_toml(text: &str, source_name: &str) -> Result<Self, FactError> {
+        let profile: Self = toml::from_str(text).map_err(|e| FactError::ParseError {
+            artifact_name: source_name.to_string(),
+            details: e.to_string(),
+        })?;
+        profile.validate(source_name)?;
+        Ok(profile)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FactError> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_toml(&text, &path.display().to_string())
+    }
+
+    fn validate(&self, source_name: &str) -> Result<(), FactError> {
+        let invalid = |details: String| FactError::ParseError { artifact_name: source_name.to_string(), details };
+        if self.targets.is_empty() {
+            return Err(invalid("profile defines no [[target]]".to_string()));
+        }
+        for target in &self.targets {
+            if target.paths.is_empty() {
+                return Err(invalid(format!("target '{}' has no paths", target.name)));
+            }
+            for path in &target.paths {
+                let pattern = PathPattern::parse(path);
+                if pattern.segments.is_empty() || pattern.segments.iter().any(|s| s.is_empty() || s == "." || s == "..") {
+                    return Err(invalid(format!("target '{}': invalid path pattern '{}'", target.name, path)));
+                }
+                if pattern.segments.last().is_some_and(|s| s == "**") {
+                    return Err(invalid(format!("target '{}': '**' must be followed by a file name pattern in '{}'", target.name, path)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CollectionTarget {
+    pub fn path_patterns(&self) -> Vec<PathPattern> {
+        self.paths.iter().map(|p| PathPattern::parse(p)).collect()
+    }
+
+    /// 확장자 필터 검사 (대소문자 무시)
+    pub fn accepts_extension(&self, file_name: &str) -> bool {
+        if self.extensions.is_empty() { return true; }
+        let Some((_, ext)) = file_name.rsplit_once('.') else { return false };
+        self.extensions.iter().any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(ext))
+    }
+}
+
+impl PathPattern {
+    /// 앞뒤 구분자는 무시하고 '/'는 '\'와 동일하게 취급한다.
+    pub fn parse(path: &str) -> Self {
+        let normalized = path.replace('/', "\\");
+        Self { segments: normalized.trim_matches('\\').split('\\').map(str::to_string).collect() }
+    }
+
+    /// 와일드카드가 처음 나오기 전까지의 고정 구성요소 수
+    pub fn literal_len(&self) -> usize {
+        self.segments.iter().take_while(|s| !is_wildcard(s)).count()
+    }
+
+    pub fn is_literal(&self) -> bool {
+        self.literal_len() == self.segments.len()
+    }
+
+    /// 경로 구성요소 전체가 패턴과 일치하는지 검사한다. (디렉터리를 순회하지 않는 수집 백엔드용)
+    pub fn matches(&self, components: &[&str]) -> bool {
+        fn walk(segments: &[String], components: &[&str]) -> bool {
+            match segments.split_first() {
+                None => components.is_empty(),
+                Some((segment, rest)) if segment == "**" => {
+                    (0..=components.len()).any(|skip| walk(rest, &components[skip..]))
+                },
+                Some((segment, rest)) => components.split_first()
+                    .is_some_and(|(name, tail)| wildcard_match(segment, name) && walk(rest, tail)),
+            }
+        }
+        walk(&self.segments, components)
+    }
+
+    /// ForensicCollector와 같은 규칙의 수집 이름: 고정 경로는 파일명, 와일드카드 아래는 고정 접두 이후 경로를 '_'로 이은 이름
+    pub fn collection_name(&self, components: &[&str]) -> String {
+        if self.is_literal() {
+            return components.last().copied().unwrap_or_default().to_string();
+        }
+        components[self.literal_len().min(components.len())..].join("_")
+    }
+}
+
+pub fn is_wildcard(segment: &str) -> bool {
+    segment.contains(['*', '?'])
+}
+
+/// 한 경로 구성요소에 대한 '*'/'?' 와일드카드 매칭 (NTFS와 같이 대소문자 무시)
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let p: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    let n: Vec<char> = name.chars().flat_map(char::to_lowercase).collect();
+    let (mut pi, mut ni) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            backtrack = Some((pi, ni));
+            pi += 1;
+        } else if let Some((star, matched)) = backtrack {
+            pi = star + 1;
+            ni = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn components(path: &str) -> Vec<&str> {
+        path.split('\\').collect()
+    }
+
+    fn profile(targets: &str) -> Result<CollectionProfile, FactError> {
+        CollectionProfile::from_toml(&format!("name = \"test\"\n{}", targets), "test")
+    }
+
+    #[test]
+    fn wildcards_match_case_insensitively_within_one_component() {
+        assert!(wildcard_match("*.pf", "CMD.EXE-4A81B364.PF"));
+        assert!(wildcard_match("SYSTEM.LOG?", "system.log1"));
+        assert!(!wildcard_match("SYSTEM.LOG?", "SYSTEM.LOG"));
+        assert!(!wildcard_match("SYSTEM.LOG?", "SYSTEM.LOG12"));
+        assert!(wildcard_match("a*b*c", "aXXbYYbZc"));
+        assert!(!wildcard_match("a*b*c", "aXXbYY"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("**", "anything"));
+    }
+
+    #[test]
+    fn single_star_does_not_cross_separators_but_double_star_does() {
+        let pattern = PathPattern::parse("Windows/Prefetch/*");
+        assert_eq!(pattern.segments, ["Windows", "Prefetch", "*"]);
+        assert!(pattern.matches(&components("WINDOWS\\prefetch\\CMD.EXE-1.pf")));
+        assert!(!pattern.matches(&components("Windows\\Prefetch\\Old\\CMD.EXE-1.pf")));
+        assert!(!pattern.matches(&components("Windows\\Prefetch")));
+
+        let pattern = PathPattern::parse("\\Users\\**\\NTUSER.DAT\\");
+        assert!(pattern.matches(&components("Users\\NTUSER.DAT")));
+        assert!(pattern.matches(&components("users\\alice\\AppData\\ntuser.dat")));
+        assert!(!pattern.matches(&components("Users\\alice\\NTUSER.DAT.LOG1")));
+    }
+
+    #[test]
+    fn collection_names_follow_the_literal_prefix() {
+        let literal = PathPattern::parse("Windows\\System32\\config\\SYSTEM");
+        assert!(literal.is_literal());
+        assert_eq!(literal.collection_name(&components("Windows\\System32\\config\\SYSTEM")), "SYSTEM");
+
+        let tasks = PathPattern::parse("Windows\\System32\\Tasks\\**\\*");
+        assert_eq!(tasks.literal_len(), 3);
+        assert_eq!(tasks.collection_name(&components("Windows\\System32\\Tasks\\Microsoft\\Update")), "Microsoft_Update");
+    }
+
+    #[test]
+    fn invalid_profiles_are_rejected() {
+        assert!(profile("").is_err());
+        assert!(profile("[[target]]\nname = \"A\"\nparser = \"MFT\"\npaths = []\n").is_err());
+        assert!(profile("[[target]]\nname = \"A\"\nparser = \"MFT\"\npaths = ['Windows\\..\\x']\n").is_err());
+        assert!(profile("[[target]]\nname = \"A\"\nparser = \"MFT\"\npaths = ['Users\\**']\n").is_err());
+        assert!(profile("[[target]]\nname = \"A\"\nparser = \"Nope\"\npaths = ['$MFT']\n").is_err());
+        assert!(profile("[[target]]\nname = \"A\"\nparser = \"MFT\"\npaths = ['$MFT']\nunknown = 1\n").is_err());
+
+        let valid = profile("[[target]]\nname = \"A\"\nparser = \"EventLogs\"\npaths = ['Logs\\*']\nextensions = ['.evtx']\n").unwrap();
+        assert!(valid.targets[0].accepts_extension("Security.EVTX"));
+        assert!(!valid.targets[0].accepts_extension("Security.evtx.bak"));
+        assert!(!valid.targets[0].accepts_extension("README"));
+    }
+
+    #[test]
+    fn default_profile_is_valid() {
+        let profile = CollectionProfile::default();
+        let names: Vec<&str> = profile.targets.iter().map(|t| t.name.as_str()).collect();
+        assert!(names.contains(&"LogFile") && names.contains(&"RecycleBin") && names.contains(&"USBLog"));
+        let system = profile.targets.iter().find(|t| t.parser == ArtifactTarget::RegistrySYSTEM).unwrap();
+        assert!(system.path_patterns().iter().any(|p| p.matches(&components("Windows\\System32\\config\\SYSTEM.LOG2"))));
+    }
+}
@@ -0,0 +1,61 @@
+use crate::vss::ShadowCopyInfo;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+/// 트리아지 패키지(ZIP 또는 디렉터리)의 manifest.json. 수집 도구/호스트/볼륨 정보와 파일별 해시를 기록한다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriageManifest {
+    pub collector: String,
+    pub collector_version: String,
+    /// 수집 대상 시스템 이름 (SYSTEM 하이브의 ComputerName)
+    pub host_name: Option<String>,
+    pub collection_started: DateTime<Utc>,
+    pub collection_finished: Option<DateTime<Utc>>,
+    pub volumes: Vec<VolumeManifest>,
+    pub files: Vec<ManifestEntry>,
+}
+
+/// 패키지 안의 볼륨 하나 (섀도 복사본은 별도 항목)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeManifest {
+    /// 패키지 내 최상위 디렉터리 이름 (예: "vol0", "vol0/vss2")
+    pub label: String,
+    /// NTFS VBR의 볼륨 일련번호 (16진수)
+    pub serial_number: String,
+    pub host_name: Option<String>,
+    /// 섀도 복사본이면 스냅숏 메타데이터 (재분석 시 이벤트 출처에 스냅숏을 표시한다)
+    #[serde(default)]
+    pub snapshot: Option<ShadowCopyInfo>,
+}
+
+/// 수집한 스트림 하나
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// 패키지 내 경로 ('/' 구분)
+    pub archive_path: String,
+    /// 볼륨 루트 기준 원본 경로
+    pub original_path: String,
+    /// 수집한 ADS 이름 (기본 스트림이면 None)
+    pub stream: Option<String>,
+    pub volume: String,
+    /// 이 파일을 수집한 프로파일 타겟 이름
+    pub target: String,
+    pub mft_entry: u64,
+    pub mft_sequence: u16,
+    pub size: u64,
+    pub md5: String,
+    pub sha1: String,
+    pub sha256: String,
+    pub collected_at: DateTime<Utc>,
+    pub standard_information: Option<MacbTimes>,
+    pub file_name: Option<MacbTimes>,
+}
+
+/// $STANDARD_INFORMATION / $FILE_NAME의 MACB 타임스탬프
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacbTimes {
+    pub created: DateTime<Utc>,
+    pub modified: DateTime<Utc>,
+    pub mft_modified: DateTime<Utc>,
+    pub accessed: DateTime<Utc>,
+}
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+/// USB 저장 장치 하나의 연결 이력. setupapi.dev.log, SYSTEM 하이브(Enum\USBSTOR, Enum\USB, MountedDevices,
+/// DeviceContainers), Partition/Diagnostic 1006, NTUSER MountPoints2를 일련번호 기준으로 합친 결과이다.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsbDevice {
+    /// 장치 인스턴스 일련번호 (USBSTOR 인스턴스 ID의 "&0" 접미사 제외)
+    pub serial_number: String,
+    pub vendor: String,
+    pub product: String,
+    pub revision: String,
+    /// USB\VID_xxxx&PID_xxxx의 VID/PID (16진수 4자리)
+    pub vid: Option<String>,
+    pub pid: Option<String>,
+    pub friendly_name: Option<String>,
+    pub container_id: Option<String>,
+    /// setupapi 최초 설치 섹션 또는 장치 속성 0064/0065
+    pub first_connected: Option<DateTime<Utc>>,
+    /// 장치 속성 0066, DeviceContainers/인스턴스 키 수정 시각, 1006 이벤트 중 가장 늦은 시각
+    pub last_connected: Option<DateTime<Utc>>,
+    /// 장치 속성 0067
+    pub last_removed: Option<DateTime<Utc>>,
+    /// MountedDevices에서 마지막으로 이 장치에 할당된 드라이브 문자 (예: "E:")
+    pub drive_letter: Option<String>,
+    /// MountedDevices의 볼륨 GUID (예: "{a1b2...}")
+    pub volume_guid: Option<String>,
+    /// Partition/Diagnostic 1006의 디스크 용량 (바이트)
+    pub capacity: Option<u64>,
+    /// MountPoints2에 이 볼륨 GUID가 남은 사용자
+    pub users: Vec<UsbDeviceUser>,
+    /// 이 장치 정보를 제공한 아티팩트
+    pub sources: Vec<String>,
+}
+
+/// 장치 볼륨을 마운트한 사용자 (NTUSER.DAT MountPoints2)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsbDeviceUser {
+    pub user: String,
+    /// MountPoints2\{GUID} 키의 마지막 수정 시각
+    pub last_mounted: Option<DateTime<Utc>>,
+}
+
+impl UsbDevice {
+    /// 타임라인에 표시할 장치 이름 (FriendlyName 우선)
+    pub fn display_name(&self) -> String {
+        match &self.friendly_name {
+            Some(name) if !name.is_empty() => name.clone(),
+            _ => format!("{} {}", self.vendor, self.product).trim().to_string(),
+        }
+    }
+}
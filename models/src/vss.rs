@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+/// 볼륨 섀도 복사본(VSS 스냅숏) 하나의 메타데이터. index 0이 가장 오래된 스냅숏이다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadowCopyInfo {
+    pub index: usize,
+    pub store_id: String,          // 카탈로그의 저장소 GUID
+    pub shadow_copy_id: String,    // vssadmin에 표시되는 섀도 복사본 ID
+    pub shadow_copy_set_id: String,
+    pub creation_time: DateTime<Utc>,
+    pub volume_size: u64,          // 스냅숏 시점의 볼륨 크기(바이트)
+}
@@ -0,0 +1,147 @@
+/// Mark-of-the-Web 스트림 이름
+pub const ZONE_IDENTIFIER_STREAM: &str = "Zone.Identifier";
+
+/// Zone.Identifier 스트림의 [ZoneTransfer] 섹션
+#[derive(Debug, Clone, Default)]
+pub struct ZoneIdentifier {
+    pub zone_id: u32,
+    pub referrer_url: Option<String>,
+    pub host_url: Option<String>,
+}
+
+/// 실행 파일/스크립트로 취급하는 ADS 이름 확장자
+const EXECUTABLE_EXTENSIONS: [&str; 14] = [
+    ".exe", ".dll", ".scr", ".sys", ".com", ".ps1", ".psm1", ".vbs", ".vbe", ".js", ".jse", ".wsf", ".bat", ".cmd",
+];
+
+/// ADS 내용에서 스크립트로 판단하는 문자열 (소문자 비교)
+const SCRIPT_MARKERS: [&str; 9] = [
+    "powershell", "invoke-expression", "iex(", "frombase64string", "wscript.shell",
+    "createobject(", "@echo off", "<script", "#!/",
+];
+
+/// UTF-16LE(BOM 또는 0x00 교차 패턴) / UTF-8 텍스트를 문자열로 변환한다.
+fn decode_text(data: &[u8]) -> String {
+    let utf16 = data.starts_with(&[0xFF, 0xFE]) || (data.len() >= 4 && data[1] == 0 && data[3] == 0);
+    if utf16 {
+        let body = data.strip_prefix(&[0xFF, 0xFE]).unwrap_or(data);
+        let units: Vec<u16> = body.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+        return String::from_utf16_lossy(&units);
+    }
+    let body = data.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(data);
+    String::from_utf8_lossy(body).into_owned()
+}
+
+/// Zone.Identifier INI 텍스트를 해석한다. ZoneId가 없으면 MOTW로 보지 않는다.
+pub fn parse_zone_identifier(data: &[u8]) -> Option<ZoneIdentifier> {
+    let text = decode_text(data);
+    let mut zone = ZoneIdentifier::default();
+    let mut has_zone = false;
+    let mut in_section = false;
+
+    for line in text.lines().map(|l| l.trim_matches(|c: char| c.is_whitespace() || c == '\0')) {
+        if line.starts_with('[') {
+            in_section = line.eq_ignore_ascii_case("[ZoneTransfer]");
+            continue;
+        }
+        if !in_section { continue; }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let value = value.trim();
+
+        match key.trim().to_ascii_lowercase().as_str() {
+            "zoneid" => {
+                if let Ok(id) = value.parse() {
+                    zone.zone_id = id;
+                    has_zone = true;
+                }
+            },
+            "referrerurl" if !value.is_empty() => zone.referrer_url = Some(value.to_string()),
+            "hosturl" if !value.is_empty() => zone.host_url = Some(value.to_string()),
+            _ => {},
+        }
+    }
+    has_zone.then_some(zone)
+}
+
+pub fn zone_name(zone_id: u32) -> &'static str {
+    match zone_id {
+        0 => "Local Machine",
+        1 => "Local Intranet",
+        2 => "Trusted Sites",
+        3 => "Internet",
+        4 => "Restricted Sites",
+        _ => "Unknown",
+    }
+}
+
+/// 실행 파일이나 스크립트를 숨긴 것으로 보이는 ADS인지 판단하고, 그 근거를 반환한다.
+pub fn classify_stream(stream_name: &str, content: &[u8]) -> Vec<String> {
+    let mut reasons = Vec::new();
+    let lower_name = stream_name.to_lowercase();
+
+    if let Some(ext) = EXECUTABLE_EXTENSIONS.iter().find(|ext| lower_name.ends_with(*ext)) {
+        reasons.push(format!("executable name ({})", ext));
+    }
+    if content.starts_with(b"MZ") && content.len() >= 0x40 {
+        let pe_offset = u32::from_le_bytes(content[0x3C..0x40].try_into().unwrap()) as usize;
+        let is_pe = content.get(pe_offset..pe_offset + 4).is_some_and(|sig| sig == b"PE\0\0");
+        reasons.push(if is_pe { "PE executable content".to_string() } else { "MZ header".to_string() });
+    } else if lower_name != ZONE_IDENTIFIER_STREAM.to_lowercase() {
+        let text = decode_text(content).to_lowercase();
+        if let Some(marker) = SCRIPT_MARKERS.iter().find(|m| text.contains(*m)) {
+            reasons.push(format!("script content ('{}')", marker));
+        }
+    }
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str) -> Vec<u8> {
+        [0xFF, 0xFE].into_iter().chain(text.encode_utf16().flat_map(u16::to_le_bytes)).collect()
+    }
+
+    #[test]
+    fn zone_identifier_fields_are_parsed_from_utf8_and_utf16() {
+        let text = "[ZoneTransfer]\r\nZoneId=3\r\nReferrerUrl=https://example.com/\r\nHostUrl=https://cdn.example.com/a.exe\r\n";
+        for data in [text.as_bytes().to_vec(), utf16(text)] {
+            let zone = parse_zone_identifier(&data).unwrap();
+            assert_eq!(zone.zone_id, 3);
+            assert_eq!(zone.referrer_url.as_deref(), Some("https://example.com/"));
+            assert_eq!(zone.host_url.as_deref(), Some("https://cdn.example.com/a.exe"));
+        }
+        assert_eq!(zone_name(3), "Internet");
+    }
+
+    #[test]
+    fn zone_identifier_requires_zone_id_in_zone_transfer_section() {
+        assert!(parse_zone_identifier(b"[Other]\r\nZoneId=3\r\n").is_none());
+        assert!(parse_zone_identifier(b"[ZoneTransfer]\r\nZoneId=abc\r\n").is_none());
+
+        let zone = parse_zone_identifier(b"[zonetransfer]\nZoneId = 4\nHostUrl=\n").unwrap();
+        assert_eq!(zone.zone_id, 4);
+        assert!(zone.host_url.is_none());
+    }
+
+    #[test]
+    fn executable_names_and_pe_content_are_flagged() {
+        let mut pe = vec![0u8; 0x84];
+        pe[0..2].copy_from_slice(b"MZ");
+        pe[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        pe[0x80..0x84].copy_from_slice(b"PE\0\0");
+        assert_eq!(classify_stream("payload.EXE", &pe), ["executable name (.exe)", "PE executable content"]);
+
+        let mut mz_only = pe.clone();
+        mz_only[0x80..0x84].copy_from_slice(b"XXXX");
+        assert_eq!(classify_stream("data", &mz_only), ["MZ header"]);
+    }
+
+    #[test]
+    fn script_content_is_flagged_except_in_zone_identifier() {
+        assert_eq!(classify_stream("cfg", &utf16("$x = 'a'; IEX(New-Object Net.WebClient)")), ["script content ('iex(')"]);
+        assert!(classify_stream(ZONE_IDENTIFIER_STREAM, b"[ZoneTransfer]\r\nHostUrl=http://x/powershell\r\n").is_empty());
+        assert!(classify_stream("SummaryInformation", b"plain document metadata").is_empty());
+    }
+}
@@ -16,7 +16,7 @@ pub fn parse_amcache_carve(data: &[u8]) -> Result<Vec<AmcacheRecord>> {
         if (data[i] == b'c' || data[i] == b'C') && data[i+1] == 0 
             && data[i+2] == b':' && data[i+3] == 0 
             && data[i+4] == b'\\' && data[i+5] == 0 
-        {
+            {
             let mut j = i;
             let mut path_u16 = Vec::new();
             
@@ -0,0 +1,237 @@
+use crate::lnk::{lnk_length, MAX_LNK_SIZE};
+use crate::prefetch::parse_prefetch_info;
+use crate::registry::{hbin_size, MAX_HBIN_SIZE};
+use evtx::EvtxChunkData;
+
+/// 파일의 시작(클러스터 경계)은 항상 섹터 경계이므로 시그니처는 512바이트 간격으로만 검사한다.
+pub const CARVE_ALIGNMENT: usize = 512;
+/// EVTX 청크 크기
+pub const EVTX_CHUNK_SIZE: usize = 64 * 1024;
+/// 카빙 시 허용하는 프리페치 최대 크기 (MAM 압축본은 압축 크기를 알 수 없으므로 이만큼을 넘겨 해제를 시도한다)
+pub const MAX_PREFETCH_SIZE: usize = 1024 * 1024;
+/// 한 번의 검증에 필요한 최대 바이트 수. 스캔 윈도는 최소 이만큼 겹쳐야 경계에 걸친 아티팩트를 놓치지 않는다.
+pub const MAX_CARVE_SIZE: usize = MAX_PREFETCH_SIZE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CarvedKind {
+    EvtxChunk,
+    RegistryHbin,
+    Lnk,
+    Prefetch,
+}
+
+impl CarvedKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::EvtxChunk => "EVTX chunk",
+            Self::RegistryHbin => "Registry hbin",
+            Self::Lnk => "LNK",
+            Self::Prefetch => "Prefetch",
+        }
+    }
+}
+
+/// 버퍼 내 검증된 아티팩트의 위치
+#[derive(Debug, Clone, Copy)]
+pub struct CarveHit {
+    pub kind: CarvedKind,
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// data[..scan_end] 범위의 섹터 경계에서 시그니처를 찾고, 기존 파서로 구조를 검증한 것만 반환한다.
+/// 검증에는 scan_end 뒤의 바이트(최대 MAX_CARVE_SIZE)도 사용한다.
+/// 두 번째 값은 다음 스캔을 시작할 오프셋이다. (마지막 아티팩트가 scan_end를 넘으면 그 뒤)
+pub fn find_artifacts(data: &[u8], scan_end: usize) -> (Vec<CarveHit>, usize) {
+    let mut hits = Vec::new();
+    let mut offset = 0;
+    while offset < std::cmp::min(scan_end, data.len()) {
+        match validate(&data[offset..]) {
+            Some((kind, length)) => {
+                hits.push(CarveHit { kind, offset, length });
+                // MAM 프리페치는 실제 끝을 모르므로 다음 섹터부터, 나머지는 아티팩트 뒤부터 스캔한다.
+                offset += if data[offset..].starts_with(b"MAM") { CARVE_ALIGNMENT } else { length.next_multiple_of(CARVE_ALIGNMENT) };
+            },
+            None => offset += CARVE_ALIGNMENT,
+        }
+    }
+    (hits, offset)
+}
+
+fn validate(data: &[u8]) -> Option<(CarvedKind, usize)> {
+    if data.starts_with(b"ElfChnk\0") {
+        let chunk = data.get(..EVTX_CHUNK_SIZE)?;
+        // 헤더/레코드 CRC32 검증까지 통과해야 한다.
+        return EvtxChunkData::new(chunk.to_vec(), true).ok().map(|_| (CarvedKind::EvtxChunk, EVTX_CHUNK_SIZE));
+    }
+    if data.starts_with(b"hbin") {
+        return hbin_size(&data[..std::cmp::min(data.len(), MAX_HBIN_SIZE)]).map(|size| (CarvedKind::RegistryHbin, size));
+    }
+    if data.starts_with(&[0x4C, 0x00, 0x00, 0x00]) {
+        return lnk_length(&data[..std::cmp::min(data.len(), MAX_LNK_SIZE)]).map(|size| (CarvedKind::Lnk, size));
+    }
+    if data.len() >= 8 && &data[4..8] == b"SCCA" {
+        let size = data.get(0x0C..0x10).map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)?;
+        if !(84..=MAX_PREFETCH_SIZE).contains(&size) { return None; }
+        let file = data.get(..size)?;
+        return valid_prefetch(file).then_some((CarvedKind::Prefetch, size));
+    }
+    if data.len() >= 8 && &data[0..3] == b"MAM" {
+        // 압축 크기 필드가 없으므로 최대 크기까지를 아티팩트로 넘긴다. (해제기는 원본 크기만큼만 출력한다)
+        let uncompressed_size = u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
+        if !(84..=MAX_PREFETCH_SIZE).contains(&uncompressed_size) { return None; }
+        let file = &data[..std::cmp::min(data.len(), MAX_PREFETCH_SIZE)];
+        return valid_prefetch(file).then_some((CarvedKind::Prefetch, file.len()));
+    }
+    None
+}
+
+fn valid_prefetch(data: &[u8]) -> bool {
+    parse_prefetch_info(data).is_ok_and(|info| {
+        !info.executable_name.is_empty() && info.executable_name.chars().all(|c| !c.is_control())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::HiveParser;
+
+    /// 레코드 하나(헤더 서명 + 크기)를 담고 헤더/레코드 CRC32가 맞는 EVTX 청크
+    fn evtx_chunk() -> Vec<u8> {
+        let mut chunk = vec![0u8; EVTX_CHUNK_SIZE];
+        chunk[0..8].copy_from_slice(b"ElfChnk\0");
+        for (pos, value) in [(8, 1u64), (16, 1), (24, 1), (32, 1)] {
+            chunk[pos..pos + 8].copy_from_slice(&value.to_le_bytes());
+        }
+        let record = 512;
+        chunk[record..record + 4].copy_from_slice(b"\x2a\x2a\x00\x00");
+        chunk[record + 4..record + 8].copy_from_slice(&0x28u32.to_le_bytes());
+        chunk[record + 0x24..record + 0x28].copy_from_slice(&0x28u32.to_le_bytes());
+        chunk[40..44].copy_from_slice(&128u32.to_le_bytes());
+        chunk[44..48].copy_from_slice(&(record as u32).to_le_bytes());
+        chunk[48..52].copy_from_slice(&((record + 0x28) as u32).to_le_bytes());
+        let events = evtx::checksum_ieee(&chunk[512..record + 0x28]);
+        chunk[52..56].copy_from_slice(&events.to_le_bytes());
+        let header: Vec<u8> = chunk[..120].iter().chain(&chunk[128..512]).copied().collect();
+        let header = evtx::checksum_ieee(&header);
+        chunk[124..128].copy_from_slice(&header.to_le_bytes());
+        chunk
+    }
+
+    /// 헤더(0x4C) + 유니코드 Name 문자열 + TerminalBlock
+    fn lnk(name: &str) -> Vec<u8> {
+        let mut data = vec![0u8; 0x4C];
+        data[0..20].copy_from_slice(&[0x4C, 0, 0, 0, 0x01, 0x14, 0x02, 0, 0, 0, 0, 0, 0xC0, 0, 0, 0, 0, 0, 0, 0x46]);
+        data[0x14..0x18].copy_from_slice(&(0x80u32 | 0x04).to_le_bytes());
+        data.extend((name.encode_utf16().count() as u16).to_le_bytes());
+        data.extend(name.encode_utf16().flat_map(u16::to_le_bytes));
+        data.extend(0u32.to_le_bytes());
+        data
+    }
+
+    /// 할당 셀을 추가하고 hbin 기준 셀 오프셋을 반환한다.
+    fn cell(hbin: &mut Vec<u8>, content: &[u8]) -> u32 {
+        let offset = hbin.len();
+        let size = (4 + content.len()).next_multiple_of(8);
+        hbin.extend((-(size as i32)).to_le_bytes());
+        hbin.extend(content);
+        hbin.resize(offset + size, 0);
+        offset as u32
+    }
+
+    /// Run 키(nk) 하나와 REG_SZ 값 하나를 담은 4KB hbin. 나머지는 해제된 셀 하나로 채운다.
+    fn hbin() -> Vec<u8> {
+        let mut hbin = vec![0u8; 0x20];
+        hbin[0..4].copy_from_slice(b"hbin");
+        hbin[8..12].copy_from_slice(&4096u32.to_le_bytes());
+
+        let data: Vec<u8> = "C:\\ProgramData\\updater.exe".encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect();
+        let data_offset = cell(&mut hbin, &data);
+        let mut vk = vec![0u8; 0x14];
+        vk[0..2].copy_from_slice(b"vk");
+        vk[0x02..0x04].copy_from_slice(&7u16.to_le_bytes());
+        vk[0x04..0x08].copy_from_slice(&(data.len() as u32).to_le_bytes());
+        vk[0x08..0x0C].copy_from_slice(&data_offset.to_le_bytes());
+        vk[0x0C..0x10].copy_from_slice(&1u32.to_le_bytes());
+        vk[0x10..0x12].copy_from_slice(&1u16.to_le_bytes());
+        vk.extend(b"Updater");
+        let vk_offset = cell(&mut hbin, &vk);
+        let list_offset = cell(&mut hbin, &vk_offset.to_le_bytes());
+
+        let mut nk = vec![0u8; 0x4C];
+        nk[0..2].copy_from_slice(b"nk");
+        nk[0x02..0x04].copy_from_slice(&0x0020u16.to_le_bytes());
+        for field in [0x1C, 0x20, 0x2C, 0x30] {
+            nk[field..field + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        }
+        nk[0x24..0x28].copy_from_slice(&1u32.to_le_bytes());
+        nk[0x28..0x2C].copy_from_slice(&list_offset.to_le_bytes());
+        nk[0x48..0x4A].copy_from_slice(&3u16.to_le_bytes());
+        nk.extend(b"Run");
+        cell(&mut hbin, &nk);
+
+        let free = 4096 - hbin.len();
+        hbin.extend((free as i32).to_le_bytes());
+        hbin.resize(4096, 0);
+        hbin
+    }
+
+    fn place(data: &mut Vec<u8>, offset: usize, bytes: &[u8]) {
+        if data.len() < offset + bytes.len() { data.resize(offset + bytes.len(), 0); }
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    #[test]
+    fn finds_validated_artifacts_at_sector_offsets() {
+        let lnk = lnk("report.docx");
+        let hbin = hbin();
+        let mut corrupt = evtx_chunk();
+        corrupt[520] ^= 0xFF;
+        let mut broken_hbin = hbin.clone();
+        broken_hbin[0x20..0x24].copy_from_slice(&(-12i32).to_le_bytes());
+
+        let evtx_at = 512 + 512 + hbin.len() + EVTX_CHUNK_SIZE;
+        let mut data = Vec::new();
+        place(&mut data, 512, &lnk);
+        place(&mut data, 1024, &hbin);
+        // 레코드 CRC가 맞지 않는 청크는 건너뛰고 청크 안쪽 섹터도 계속 검사한다.
+        place(&mut data, 1024 + hbin.len(), &corrupt);
+        place(&mut data, evtx_at, &evtx_chunk());
+        // 섹터 경계가 아닌 위치의 시그니처와 셀 체인이 깨진 hbin은 찾지 않는다.
+        place(&mut data, evtx_at + EVTX_CHUNK_SIZE + 100, &lnk);
+        place(&mut data, evtx_at + EVTX_CHUNK_SIZE + 512, &broken_hbin);
+
+        let (hits, next) = find_artifacts(&data, data.len());
+        let found: Vec<(CarvedKind, usize, usize)> = hits.iter().map(|h| (h.kind, h.offset, h.length)).collect();
+        assert_eq!(found, [
+            (CarvedKind::Lnk, 512, lnk.len()),
+            (CarvedKind::RegistryHbin, 1024, hbin.len()),
+            (CarvedKind::EvtxChunk, evtx_at, EVTX_CHUNK_SIZE),
+        ]);
+        assert!(next >= data.len());
+
+        // 카빙한 hbin은 regf 헤더 없이도 키와 값을 읽을 수 있다.
+        let keys = HiveParser::from_hbin(&data[1024..1024 + hbin.len()]).unwrap().carved_keys();
+        let run = keys.iter().find(|k| k.name == "Run").unwrap();
+        assert_eq!(run.values[0].data_string, "C:\\ProgramData\\updater.exe");
+    }
+
+    #[test]
+    fn artifact_crossing_scan_end_moves_next_offset_past_it() {
+        let mut data = vec![0u8; 1024];
+        place(&mut data, 1024, &evtx_chunk());
+        place(&mut data, 1024 + EVTX_CHUNK_SIZE + 512, &lnk("a.txt"));
+
+        // scan_end 이전에서 시작한 청크는 scan_end 뒤의 바이트까지 검증에 쓰고, 다음 스캔은 청크 뒤에서 시작한다.
+        let (hits, next) = find_artifacts(&data, 1536);
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].kind, hits[0].offset), (CarvedKind::EvtxChunk, 1024));
+        assert_eq!(next, 1024 + EVTX_CHUNK_SIZE);
+
+        let (hits, _) = find_artifacts(&data[next..], data.len() - next);
+        assert_eq!((hits[0].kind, hits[0].offset), (CarvedKind::Lnk, 512));
+        // 청크가 잘려 있으면 검증할 수 없다.
+        assert!(find_artifacts(&data[..1024 + EVTX_CHUNK_SIZE - 1], 1536).0.is_empty());
+    }
+}
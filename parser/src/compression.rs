@@ -0,0 +1,821 @@
+use models::FactError;
+
+fn corrupt(format: &str, details: impl Into<String>) -> FactError {
+    FactError::ParseError { artifact_name: format.into(), details: details.into() }
+}
+
+/// 이미 출력된 데이터에서 LZ77 역참조를 복사한다. (겹치는 복사 허용)
+fn copy_match(out: &mut Vec<u8>, offset: usize, length: usize, format: &str) -> Result<(), FactError> {
+    if offset == 0 || offset > out.len() {
+        return Err(corrupt(format, format!("Match offset {} exceeds output {}", offset, out.len())));
+    }
+    let start = out.len() - offset;
+    for i in 0..length {
+        let byte = out[start + i];
+        out.push(byte);
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// LZNT1 (NTFS 압축 단위, COMPRESSION_FORMAT_LZNT1)
+// ---------------------------------------------------------------------------
+
+const LZNT1_CHUNK_SIZE: usize = 4096;
+
+fn decompress_lznt1_chunk(data: &[u8], out: &mut Vec<u8>) -> Result<(), FactError> {
+    let chunk_start = out.len();
+    let mut i = 0;
+
+    while i < data.len() {
+        let flags = data[i];
+        i += 1;
+        for bit in 0..8 {
+            if i >= data.len() { break; }
+            if (flags >> bit) & 1 == 0 {
+                out.push(data[i]);
+                i += 1;
+                continue;
+            }
+            if i + 2 > data.len() { return Err(corrupt("LZNT1", "Truncated back-reference")); }
+            let token = u16::from_le_bytes([data[i], data[i + 1]]) as usize;
+            i += 2;
+
+            // 청크 내 현재 위치가 클수록 오프셋 비트가 늘어난다. (최소 4비트 오프셋 / 12비트 길이)
+            let position = out.len() - chunk_start;
+            if position == 0 { return Err(corrupt("LZNT1", "Back-reference at chunk start")); }
+            let mut length_bits = 12;
+            let mut p = position - 1;
+            while p >= 0x10 {
+                p >>= 1;
+                length_bits -= 1;
+            }
+            let length = (token & ((1 << length_bits) - 1)) + 3;
+            let offset = (token >> length_bits) + 1;
+            if offset > position { return Err(corrupt("LZNT1", "Back-reference before chunk start")); }
+            copy_match(out, offset, length, "LZNT1")?;
+        }
+    }
+    if out.len() - chunk_start > LZNT1_CHUNK_SIZE {
+        return Err(corrupt("LZNT1", "Chunk expands beyond 4096 bytes"));
+    }
+    Ok(())
+}
+
+/// LZNT1 스트림(2바이트 청크 헤더 + 최대 4KB 청크의 연속)을 해제한다. 헤더 0은 스트림 종료.
+pub fn decompress_lznt1(input: &[u8]) -> Result<Vec<u8>, FactError> {
+    let mut out = Vec::with_capacity(input.len() * 2);
+    let mut pos = 0;
+
+    while pos + 2 <= input.len() {
+        let header = u16::from_le_bytes([input[pos], input[pos + 1]]);
+        if header == 0 { break; }
+        let chunk_len = (header & 0x0FFF) as usize + 1;
+        let start = pos + 2;
+        let end = std::cmp::min(start + chunk_len, input.len());
+
+        if header & 0x8000 != 0 {
+            decompress_lznt1_chunk(&input[start..end], &mut out)?;
+        } else {
+            out.extend_from_slice(&input[start..end]);
+        }
+        pos = start + chunk_len;
+    }
+    Ok(out)
+}
+
+// ---------------------------------------------------------------------------
+// XPRESS (MS-XCA Plain LZ77, COMPRESSION_FORMAT_XPRESS)
+// ---------------------------------------------------------------------------
+
+/// Plain LZ77 스트림을 output_size 바이트까지 해제한다. 32비트 플래그 워드(MSB부터)가 리터럴/매치를 구분하고,
+/// 7 이상의 매치 길이는 두 매치가 한 바이트를 4비트씩 나눠 쓰는 니블부터 단계적으로 늘어난다.
+pub fn decompress_xpress(input: &[u8], output_size: usize) -> Result<Vec<u8>, FactError> {
+    let mut out = Vec::with_capacity(output_size);
+    let mut pos = 0usize;
+    let mut flags = 0u32;
+    let mut flag_count = 0u32;
+    let mut shared_nibble: Option<usize> = None;
+
+    let read = |pos: &mut usize, n: usize| -> Result<u32, FactError> {
+        let bytes = input.get(*pos..*pos + n).ok_or_else(|| corrupt("XPRESS", "Truncated match length"))?;
+        *pos += n;
+        Ok(bytes.iter().rev().fold(0u32, |acc, &b| (acc << 8) | b as u32))
+    };
+
+    while out.len() < output_size {
+        if flag_count == 0 {
+            if pos + 4 > input.len() { break; }
+            flags = read(&mut pos, 4)?;
+            flag_count = 32;
+        }
+        flag_count -= 1;
+
+        if flags & (1 << flag_count) == 0 {
+            let Some(&literal) = input.get(pos) else { break };
+            out.push(literal);
+            pos += 1;
+            continue;
+        }
+
+        // 입력이 매치 플래그 위치에서 끝나면 스트림 종료다.
+        if pos == input.len() { break; }
+        let token = read(&mut pos, 2)? as usize;
+        let offset = (token >> 3) + 1;
+        let mut length = token & 7;
+        if length == 7 {
+            length = match shared_nibble.take() {
+                Some(at) => (input[at] >> 4) as usize,
+                None => {
+                    shared_nibble = Some(pos);
+                    (read(&mut pos, 1)? & 0x0F) as usize
+                },
+            };
+            if length == 15 {
+                length = read(&mut pos, 1)? as usize;
+                if length == 255 {
+                    length = read(&mut pos, 2)? as usize;
+                    if length == 0 { length = read(&mut pos, 4)? as usize; }
+                    if length < 15 + 7 { return Err(corrupt("XPRESS", "Invalid extended match length")); }
+                    length -= 15 + 7;
+                }
+                length += 15;
+            }
+            length += 7;
+        }
+        length += 3;
+
+        let length = std::cmp::min(length, output_size - out.len());
+        copy_match(&mut out, offset, length, "XPRESS")?;
+    }
+    Ok(out)
+}
+
+// ---------------------------------------------------------------------------
+// XPRESS Huffman (MS-XCA LZ77+Huffman, COMPRESSION_FORMAT_XPRESS_HUFF)
+// ---------------------------------------------------------------------------
+
+const XPRESS_SYMBOLS: usize = 512;
+const XPRESS_MAX_CODE_LENGTH: u32 = 15;
+const XPRESS_BLOCK_SIZE: usize = 65536;
+
+/// 256바이트(512개 4비트 길이) 테이블로 15비트 룩업 테이블을 만든다. 항목 = (심볼 << 4) | 코드 길이
+fn build_xpress_table(lengths: &[u8]) -> Result<Vec<u16>, FactError> {
+    let length_of = |symbol: usize| -> u32 {
+        let byte = lengths[symbol / 2];
+        (if symbol.is_multiple_of(2) { byte & 0x0F } else { byte >> 4 }) as u32
+    };
+
+    let mut table = vec![0u16; 1 << XPRESS_MAX_CODE_LENGTH];
+    let mut position = 0usize;
+    for bit_length in 1..=XPRESS_MAX_CODE_LENGTH {
+        let span = 1usize << (XPRESS_MAX_CODE_LENGTH - bit_length);
+        for symbol in (0..XPRESS_SYMBOLS).filter(|&s| length_of(s) == bit_length) {
+            if position + span > table.len() { return Err(corrupt("XPRESS", "Over-subscribed Huffman table")); }
+            table[position..position + span].fill(((symbol as u16) << 4) | bit_length as u16);
+            position += span;
+        }
+    }
+    if position == 0 { return Err(corrupt("XPRESS", "Empty Huffman table")); }
+    Ok(table)
+}
+
+/// XPRESS Huffman 스트림을 output_size 바이트까지 해제한다. 출력 64KB마다 새 허프만 테이블이 온다.
+pub fn decompress_xpress_huffman(input: &[u8], output_size: usize) -> Result<Vec<u8>, FactError> {
+    let read16 = |pos: usize| -> u32 {
+        if pos + 2 <= input.len() { u16::from_le_bytes([input[pos], input[pos + 1]]) as u32 } else { 0 }
+    };
+    let mut out = Vec::with_capacity(output_size);
+    let mut in_pos = 0usize;
+
+    while out.len() < output_size {
+        if in_pos + 256 > input.len() { return Err(corrupt("XPRESS", "Truncated Huffman table")); }
+        let table = build_xpress_table(&input[in_pos..in_pos + 256])?;
+        let mut cur = in_pos + 256;
+        let mut next_bits = (read16(cur) << 16) | read16(cur + 2);
+        cur += 4;
+        let mut extra_bits: i32 = 16;
+        let block_end = std::cmp::min(out.len() + XPRESS_BLOCK_SIZE, output_size);
+
+        while out.len() < block_end {
+            if cur > input.len() + 4 { return Err(corrupt("XPRESS", "Read past end of input")); }
+            let entry = table[(next_bits >> (32 - XPRESS_MAX_CODE_LENGTH)) as usize];
+            let (symbol, bit_length) = ((entry >> 4) as usize, (entry & 0x0F) as u32);
+            if bit_length == 0 { return Err(corrupt("XPRESS", "Invalid Huffman code")); }
+
+            next_bits <<= bit_length;
+            extra_bits -= bit_length as i32;
+            if extra_bits < 0 {
+                next_bits |= read16(cur) << (-extra_bits);
+                extra_bits += 16;
+                cur += 2;
+            }
+
+            if symbol < 256 {
+                out.push(symbol as u8);
+                continue;
+            }
+
+            let symbol = symbol - 256;
+            let mut length = symbol & 0x0F;
+            let offset_bits = (symbol >> 4) as u32;
+            if length == 15 {
+                length = *input.get(cur).ok_or_else(|| corrupt("XPRESS", "Truncated match length"))? as usize;
+                cur += 1;
+                if length == 255 {
+                    length = read16(cur) as usize;
+                    cur += 2;
+                    if length == 0 {
+                        if cur + 4 > input.len() { return Err(corrupt("XPRESS", "Truncated match length")); }
+                        length = u32::from_le_bytes(input[cur..cur + 4].try_into().unwrap()) as usize;
+                        cur += 4;
+                    }
+                    if length < 15 { return Err(corrupt("XPRESS", "Invalid extended match length")); }
+                    length -= 15;
+                }
+                length += 15;
+            }
+            length += 3;
+
+            let mut offset = if offset_bits == 0 { 0 } else { (next_bits >> (32 - offset_bits)) as usize };
+            offset += 1 << offset_bits;
+            if offset_bits > 0 {
+                next_bits <<= offset_bits;
+                extra_bits -= offset_bits as i32;
+                if extra_bits < 0 {
+                    next_bits |= read16(cur) << (-extra_bits);
+                    extra_bits += 16;
+                    cur += 2;
+                }
+            }
+
+            let length = std::cmp::min(length, output_size - out.len());
+            copy_match(&mut out, offset, length, "XPRESS")?;
+        }
+        in_pos = cur;
+    }
+    Ok(out)
+}
+
+// ---------------------------------------------------------------------------
+// LZX (WIM/WOF 변형: 32KB 윈도, 청크마다 독립, E8 변환 파일 크기 12000000)
+// ---------------------------------------------------------------------------
+
+const LZX_NUM_CHARS: usize = 256;
+const LZX_NUM_POSITION_SLOTS: usize = 30;
+const LZX_MAIN_SYMBOLS: usize = LZX_NUM_CHARS + LZX_NUM_POSITION_SLOTS * 8;
+const LZX_LENGTH_SYMBOLS: usize = 249;
+const LZX_PRETREE_SYMBOLS: usize = 20;
+const LZX_ALIGNED_SYMBOLS: usize = 8;
+const LZX_MAX_CODE_LENGTH: usize = 16;
+const LZX_DEFAULT_BLOCK_SIZE: usize = 32768;
+const LZX_E8_FILE_SIZE: i32 = 12_000_000;
+
+const LZX_BLOCKTYPE_VERBATIM: u32 = 1;
+const LZX_BLOCKTYPE_ALIGNED: u32 = 2;
+const LZX_BLOCKTYPE_UNCOMPRESSED: u32 = 3;
+
+/// 16비트 LE 워드 단위, MSB 우선 비트 스트림
+struct LzxBitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buffer: u64,
+    count: u32,
+}
+
+impl<'a> LzxBitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0, buffer: 0, count: 0 }
+    }
+
+    fn ensure(&mut self, n: u32) {
+        while self.count < n {
+            let word = if self.pos + 2 <= self.data.len() {
+                u16::from_le_bytes([self.data[self.pos], self.data[self.pos + 1]]) as u64
+            } else { 0 };
+            self.pos += 2;
+            self.buffer |= word << (48 - self.count);
+            self.count += 16;
+        }
+    }
+
+    fn read(&mut self, n: u32) -> u32 {
+        if n == 0 { return 0; }
+        self.ensure(n);
+        let value = (self.buffer >> (64 - n)) as u32;
+        self.buffer <<= n;
+        self.count -= n;
+        value
+    }
+
+    /// 비압축 블록 직전: 16비트 경계로 정렬한다. 이미 정렬되어 있으면 16비트를 버린다.
+    fn align(&mut self) {
+        self.ensure(1);
+        self.buffer = 0;
+        self.count = 0;
+    }
+
+    fn overrun(&self) -> bool {
+        self.pos > self.data.len() + 4
+    }
+}
+
+/// 정규(canonical) 허프만 코드: 길이별 코드 수와 (길이, 심볼) 순으로 정렬된 심볼
+struct CanonicalHuffman {
+    counts: [u16; LZX_MAX_CODE_LENGTH + 1],
+    symbols: Vec<u16>,
+}
+
+impl CanonicalHuffman {
+    fn new(lengths: &[u8]) -> Result<Self, FactError> {
+        let mut counts = [0u16; LZX_MAX_CODE_LENGTH + 1];
+        for &length in lengths {
+            if length as usize > LZX_MAX_CODE_LENGTH { return Err(corrupt("LZX", "Code length exceeds 16")); }
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        // 크래프트 부등식 위반(과다 할당) 검사
+        let mut remaining: i32 = 1;
+        for &count in counts.iter().skip(1) {
+            remaining = (remaining << 1) - count as i32;
+            if remaining < 0 { return Err(corrupt("LZX", "Over-subscribed Huffman code")); }
+        }
+
+        let mut symbols = Vec::new();
+        for length in 1..=LZX_MAX_CODE_LENGTH as u8 {
+            symbols.extend((0..lengths.len()).filter(|&s| lengths[s] == length).map(|s| s as u16));
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, bits: &mut LzxBitReader) -> Result<usize, FactError> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for length in 1..=LZX_MAX_CODE_LENGTH {
+            code |= bits.read(1) as i32;
+            let count = self.counts[length] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize] as usize);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(corrupt("LZX", "Invalid Huffman code"))
+    }
+}
+
+/// 프리트리로 부호화된 코드 길이 목록을 이전 블록 길이와의 델타로 읽는다.
+fn read_lzx_lengths(bits: &mut LzxBitReader, lengths: &mut [u8]) -> Result<(), FactError> {
+    let mut pretree_lengths = [0u8; LZX_PRETREE_SYMBOLS];
+    for length in pretree_lengths.iter_mut() {
+        *length = bits.read(4) as u8;
+    }
+    let pretree = CanonicalHuffman::new(&pretree_lengths)?;
+    let delta = |previous: u8, symbol: usize| ((previous as usize + 17 - symbol) % 17) as u8;
+
+    let mut i = 0;
+    while i < lengths.len() {
+        let symbol = pretree.decode(bits)?;
+        match symbol {
+            0..=16 => {
+                lengths[i] = delta(lengths[i], symbol);
+                i += 1;
+            },
+            17 | 18 => {
+                let run = if symbol == 17 { bits.read(4) as usize + 4 } else { bits.read(5) as usize + 20 };
+                let end = std::cmp::min(i + run, lengths.len());
+                lengths[i..end].fill(0);
+                i = end;
+            },
+            _ => {
+                let run = bits.read(1) as usize + 4;
+                let symbol = pretree.decode(bits)?;
+                if symbol > 16 { return Err(corrupt("LZX", "Invalid pretree run symbol")); }
+                let value = delta(lengths[i], symbol);
+                let end = std::cmp::min(i + run, lengths.len());
+                lengths[i..end].fill(value);
+                i = end;
+            },
+        }
+        if bits.overrun() { return Err(corrupt("LZX", "Truncated code lengths")); }
+    }
+    Ok(())
+}
+
+fn lzx_position_tables() -> ([u32; LZX_NUM_POSITION_SLOTS], [u32; LZX_NUM_POSITION_SLOTS]) {
+    let mut extra_bits = [0u32; LZX_NUM_POSITION_SLOTS];
+    let mut base = [0u32; LZX_NUM_POSITION_SLOTS];
+    let mut next = 0u32;
+    for slot in 0..LZX_NUM_POSITION_SLOTS {
+        extra_bits[slot] = if slot < 4 { 0 } else { std::cmp::min(slot as u32 / 2 - 1, 17) };
+        base[slot] = next;
+        next += 1 << extra_bits[slot];
+    }
+    (extra_bits, base)
+}
+
+/// 압축 시 적용된 x86 CALL(E8) 상대 주소 → 절대 주소 변환을 되돌린다.
+fn undo_e8_translation(data: &mut [u8]) {
+    if data.len() <= 10 { return; }
+    let tail = data.len() - 10;
+    let mut i = 0;
+    while i < tail {
+        if data[i] != 0xE8 {
+            i += 1;
+            continue;
+        }
+        let position = i as i32;
+        let absolute = i32::from_le_bytes(data[i + 1..i + 5].try_into().unwrap());
+        if absolute >= 0 {
+            if absolute < LZX_E8_FILE_SIZE {
+                data[i + 1..i + 5].copy_from_slice(&(absolute - position).to_le_bytes());
+            }
+        } else if absolute >= -position {
+            data[i + 1..i + 5].copy_from_slice(&(absolute + LZX_E8_FILE_SIZE).to_le_bytes());
+        }
+        i += 5;
+    }
+}
+
+/// WIM/WOF 방식의 독립 LZX 청크 하나를 output_size 바이트로 해제한다.
+pub fn decompress_lzx(input: &[u8], output_size: usize) -> Result<Vec<u8>, FactError> {
+    let (extra_bits, position_base) = lzx_position_tables();
+    let mut bits = LzxBitReader::new(input);
+    let mut out = Vec::with_capacity(output_size);
+    let mut main_lengths = [0u8; LZX_MAIN_SYMBOLS];
+    let mut length_lengths = [0u8; LZX_LENGTH_SYMBOLS];
+    let mut recent = [1usize; 3];
+
+    while out.len() < output_size {
+        let block_type = bits.read(3);
+        let block_size = if bits.read(1) == 1 { LZX_DEFAULT_BLOCK_SIZE } else { bits.read(16) as usize };
+        if block_size == 0 { return Err(corrupt("LZX", "Zero-sized block")); }
+        let block_end = std::cmp::min(out.len() + block_size, output_size);
+
+        if block_type == LZX_BLOCKTYPE_UNCOMPRESSED {
+            bits.align();
+            let mut pos = bits.pos;
+            if pos + 12 > input.len() { return Err(corrupt("LZX", "Truncated uncompressed block header")); }
+            for (i, offset) in recent.iter_mut().enumerate() {
+                *offset = u32::from_le_bytes(input[pos + i * 4..pos + i * 4 + 4].try_into().unwrap()) as usize;
+            }
+            pos += 12;
+            let length = block_end - out.len();
+            if pos + length > input.len() { return Err(corrupt("LZX", "Truncated uncompressed block")); }
+            out.extend_from_slice(&input[pos..pos + length]);
+            pos += length;
+            if block_size % 2 == 1 { pos += 1; }
+            bits = LzxBitReader { data: input, pos, buffer: 0, count: 0 };
+            continue;
+        }
+
+        if block_type != LZX_BLOCKTYPE_VERBATIM && block_type != LZX_BLOCKTYPE_ALIGNED {
+            return Err(corrupt("LZX", format!("Invalid block type {}", block_type)));
+        }
+
+        let aligned_tree = if block_type == LZX_BLOCKTYPE_ALIGNED {
+            let mut lengths = [0u8; LZX_ALIGNED_SYMBOLS];
+            for length in lengths.iter_mut() {
+                *length = bits.read(3) as u8;
+            }
+            Some(CanonicalHuffman::new(&lengths)?)
+        } else { None };
+
+        read_lzx_lengths(&mut bits, &mut main_lengths[..LZX_NUM_CHARS])?;
+        read_lzx_lengths(&mut bits, &mut main_lengths[LZX_NUM_CHARS..])?;
+        let main_tree = CanonicalHuffman::new(&main_lengths)?;
+        read_lzx_lengths(&mut bits, &mut length_lengths)?;
+        let length_tree = CanonicalHuffman::new(&length_lengths)?;
+
+        while out.len() < block_end {
+            if bits.overrun() { return Err(corrupt("LZX", "Read past end of input")); }
+            let symbol = main_tree.decode(&mut bits)?;
+            if symbol < LZX_NUM_CHARS {
+                out.push(symbol as u8);
+                continue;
+            }
+
+            let symbol = symbol - LZX_NUM_CHARS;
+            let slot = symbol >> 3;
+            let mut length = symbol & 7;
+            if length == 7 { length += length_tree.decode(&mut bits)?; }
+            length += 2;
+
+            let offset = match slot {
+                0 => recent[0],
+                1 | 2 => {
+                    recent.swap(0, slot);
+                    recent[0]
+                },
+                _ => {
+                    let extra = extra_bits[slot];
+                    let formatted = match &aligned_tree {
+                        Some(aligned) if extra >= 3 => {
+                            let verbatim = bits.read(extra - 3) << 3;
+                            position_base[slot] + verbatim + aligned.decode(&mut bits)? as u32
+                        },
+                        _ => position_base[slot] + bits.read(extra),
+                    };
+                    let offset = formatted as usize - 2;
+                    recent[2] = recent[1];
+                    recent[1] = recent[0];
+                    recent[0] = offset;
+                    offset
+                },
+            };
+
+            let length = std::cmp::min(length, output_size - out.len());
+            copy_match(&mut out, offset, length, "LZX")?;
+        }
+    }
+
+    undo_e8_translation(&mut out);
+    Ok(out)
+}
+
+// ---------------------------------------------------------------------------
+// WOF (Windows Overlay Filter) 시스템 압축: WofCompressedData 스트림 = 청크 오프셋 테이블 + 청크들
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WofAlgorithm {
+    Xpress4K,
+    Lzx,
+    Xpress8K,
+    Xpress16K,
+}
+
+impl WofAlgorithm {
+    /// FILE_PROVIDER_EXTERNAL_INFO_V1.Algorithm 값
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Xpress4K),
+            1 => Some(Self::Lzx),
+            2 => Some(Self::Xpress8K),
+            3 => Some(Self::Xpress16K),
+            _ => None,
+        }
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        match self {
+            Self::Xpress4K => 4096,
+            Self::Xpress8K => 8192,
+            Self::Xpress16K => 16384,
+            Self::Lzx => 32768,
+        }
+    }
+}
+
+/// WofCompressedData 스트림을 원래 크기로 해제한다. 압축 크기가 원본 청크 크기와 같으면 비압축 청크다.
+pub fn decompress_wof(data: &[u8], algorithm: WofAlgorithm, uncompressed_size: u64) -> Result<Vec<u8>, FactError> {
+    let chunk_size = algorithm.chunk_size() as u64;
+    let chunk_count = uncompressed_size.div_ceil(chunk_size) as usize;
+    if chunk_count == 0 { return Ok(Vec::new()); }
+
+    // 첫 청크를 제외한 각 청크의 시작 오프셋 (4GB 초과 파일은 64비트 엔트리)
+    let entry_size = if uncompressed_size > u32::MAX as u64 { 8 } else { 4 };
+    let table_size = (chunk_count - 1) * entry_size;
+    if data.len() < table_size { return Err(corrupt("WOF", "Truncated chunk table")); }
+    let mut offsets = Vec::with_capacity(chunk_count + 1);
+    offsets.push(0u64);
+    for i in 0..chunk_count - 1 {
+        let entry = &data[i * entry_size..(i + 1) * entry_size];
+        offsets.push(if entry_size == 8 { u64::from_le_bytes(entry.try_into().unwrap()) } else { u32::from_le_bytes(entry.try_into().unwrap()) as u64 });
+    }
+    offsets.push((data.len() - table_size) as u64);
+
+    let mut out = Vec::with_capacity(uncompressed_size as usize);
+    for i in 0..chunk_count {
+        let (start, end) = (table_size + offsets[i] as usize, table_size + offsets[i + 1] as usize);
+        if start > end || end > data.len() { return Err(corrupt("WOF", format!("Chunk {} out of range", i))); }
+        let chunk = &data[start..end];
+        let expected = std::cmp::min(chunk_size, uncompressed_size - out.len() as u64) as usize;
+
+        if chunk.len() == expected {
+            out.extend_from_slice(chunk);
+            continue;
+        }
+        let decoded = match algorithm {
+            WofAlgorithm::Lzx => decompress_lzx(chunk, expected)?,
+            _ => decompress_xpress_huffman(chunk, expected)?,
+        };
+        out.extend_from_slice(&decoded);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abc_100() -> Vec<u8> {
+        b"abc".repeat(100)
+    }
+
+    #[test]
+    fn lznt1_compressed_and_uncompressed_chunks() {
+        // 압축 청크: 플래그 0x08 (리터럴 a b c, 역참조 하나), 토큰 0x2126 = 오프셋 3, 길이 297
+        let mut stream = vec![0x05, 0xB0, 0x08, b'a', b'b', b'c', 0x26, 0x21];
+        // 비압축 청크 (헤더 0x3000 | 길이 - 1)
+        stream.extend([0x03, 0x30, b'x', b'y', b'z', b'!']);
+        stream.extend([0x00, 0x00]);
+
+        let mut expected = abc_100();
+        expected.extend(b"xyz!");
+        assert_eq!(decompress_lznt1(&stream).unwrap(), expected);
+    }
+
+    #[test]
+    fn lznt1_rejects_corrupt_back_references() {
+        // 청크 첫 토큰이 역참조
+        assert!(decompress_lznt1(&[0x02, 0xB0, 0x01, 0x00, 0x10]).is_err());
+        // 역참조 토큰이 1바이트만 남음
+        assert!(decompress_lznt1(&[0x02, 0xB0, 0x02, b'a', 0x00]).is_err());
+        // 오프셋이 청크 시작 이전을 가리킴 (위치 1에서 오프셋 2)
+        assert!(decompress_lznt1(&[0x03, 0xB0, 0x02, b'a', 0x00, 0x10]).is_err());
+    }
+
+    #[test]
+    fn xpress_plain_matches_ms_xca_examples() {
+        // MS-XCA 3.1 예제
+        let mut alphabet = vec![0x3F, 0x00, 0x00, 0x00];
+        alphabet.extend(b'a'..=b'z');
+        assert_eq!(decompress_xpress(&alphabet, 26).unwrap(), (b'a'..=b'z').collect::<Vec<u8>>());
+
+        let repeated = [0xFF, 0xFF, 0xFF, 0x1F, b'a', b'b', b'c', 0x17, 0x00, 0x0F, 0xFF, 0x26, 0x01];
+        assert_eq!(decompress_xpress(&repeated, 300).unwrap(), abc_100());
+    }
+
+    #[test]
+    fn xpress_plain_shares_length_nibbles_between_matches() {
+        // 두 매치(오프셋 1, 길이 7 + 니블)가 한 바이트 0x21의 하위/상위 니블을 나눠 쓴다: 길이 11, 12
+        let stream = [0xFF, 0xFF, 0xFF, 0x7F, b'z', 0x07, 0x00, 0x21, 0x07, 0x00];
+        assert_eq!(decompress_xpress(&stream, 24).unwrap(), vec![b'z'; 24]);
+    }
+
+    #[test]
+    fn xpress_plain_rejects_corrupt_input() {
+        // 첫 토큰이 매치이면 참조할 출력이 없다.
+        assert!(decompress_xpress(&[0x00, 0x00, 0x00, 0x80, 0x00, 0x00], 16).is_err());
+        // 확장 길이 바이트가 잘림
+        assert!(decompress_xpress(&[0x00, 0x00, 0x00, 0x40, b'a', 0x07, 0x00, 0x0F], 64).is_err());
+    }
+
+    /// 'a' 'b' 'c'와 매치 심볼 287(오프셋 비트 1, 길이 니블 15)에 길이 2 코드를 준 테이블로 "abc" x 100을 부호화한다.
+    fn xpress_huffman_abc() -> Vec<u8> {
+        let mut stream = vec![0u8; 256];
+        stream[0x61 / 2] |= 2 << 4;
+        stream[0x62 / 2] |= 2;
+        stream[0x63 / 2] |= 2 << 4;
+        stream[287 / 2] |= 2 << 4;
+        // 비트: a(00) b(01) c(10) 매치(11) 오프셋 추가 비트(1) / 길이 바이트 255, 길이 294
+        stream.extend([0x80, 0x1B, 0x00, 0x00, 0xFF, 0x26, 0x01]);
+        stream
+    }
+
+    #[test]
+    fn xpress_huffman_decodes_literals_and_long_match() {
+        assert_eq!(decompress_xpress_huffman(&xpress_huffman_abc(), 300).unwrap(), abc_100());
+    }
+
+    #[test]
+    fn xpress_huffman_rejects_corrupt_input() {
+        let stream = xpress_huffman_abc();
+        assert!(decompress_xpress_huffman(&stream[..200], 300).is_err());
+        // 코드 길이가 모두 0인 테이블
+        assert!(decompress_xpress_huffman(&[0u8; 260], 16).is_err());
+        // 첫 심볼이 매치
+        let mut leading_match = stream.clone();
+        leading_match[256..260].copy_from_slice(&[0x00, 0xC0, 0x00, 0x00]);
+        assert!(decompress_xpress_huffman(&leading_match, 300).is_err());
+        // 입력이 끝나도 출력 크기에 도달하지 못함
+        assert!(decompress_xpress_huffman(&stream, 1 << 20).is_err());
+    }
+
+    /// LZX 비트 스트림 작성기 (16비트 LE 워드, MSB 우선)
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        word: u16,
+        count: u32,
+    }
+
+    impl BitWriter {
+        fn write(&mut self, value: u32, bits: u32) {
+            for i in (0..bits).rev() {
+                self.word = (self.word << 1) | ((value >> i) & 1) as u16;
+                self.count += 1;
+                if self.count == 16 { self.flush(); }
+            }
+        }
+
+        fn flush(&mut self) {
+            if self.count == 0 { return; }
+            self.word <<= 16 - self.count;
+            self.bytes.extend(self.word.to_le_bytes());
+            self.word = 0;
+            self.count = 0;
+        }
+
+        /// 프리트리(심볼 0 = 길이 유지, 15 = 길이 2)로 코드 길이 목록을 기록한다.
+        fn lengths(&mut self, count: usize, two_bit: &[usize]) {
+            for symbol in 0..LZX_PRETREE_SYMBOLS {
+                self.write(if symbol == 0 || symbol == 15 { 1 } else { 0 }, 4);
+            }
+            for i in 0..count {
+                self.write(two_bit.contains(&i) as u32, 1);
+            }
+        }
+    }
+
+    #[test]
+    fn lzx_verbatim_block_with_match() {
+        let mut bits = BitWriter::default();
+        bits.write(LZX_BLOCKTYPE_VERBATIM, 3);
+        bits.write(0, 1);
+        bits.write(9, 16);
+        // 주 트리: 'a' 'b' 'c'와 매치 심볼 292(슬롯 4, 길이 헤더 4)만 길이 2. 길이 트리는 비어 있다.
+        bits.lengths(LZX_NUM_CHARS, &[0x61, 0x62, 0x63]);
+        bits.lengths(LZX_MAIN_SYMBOLS - LZX_NUM_CHARS, &[292 - LZX_NUM_CHARS]);
+        bits.lengths(LZX_LENGTH_SYMBOLS, &[]);
+        // a(00) b(01) c(10) 매치(11) + 슬롯 4의 추가 비트 1 -> 오프셋 5 - 2 = 3, 길이 6
+        for (code, length) in [(0b00, 2), (0b01, 2), (0b10, 2), (0b11, 2), (1, 1)] {
+            bits.write(code, length);
+        }
+        bits.flush();
+
+        assert_eq!(decompress_lzx(&bits.bytes, 9).unwrap(), b"abcabcabc");
+    }
+
+    #[test]
+    fn lzx_uncompressed_block_undoes_e8_translation() {
+        let mut bits = BitWriter::default();
+        bits.write(LZX_BLOCKTYPE_UNCOMPRESSED, 3);
+        bits.write(0, 1);
+        bits.write(16, 16);
+        bits.flush();
+        let mut stream = bits.bytes;
+        stream.extend([1u32, 1, 1].iter().flat_map(|r| r.to_le_bytes()));
+        // 위치 5의 CALL: 절대 주소 105 -> 상대 주소 100
+        let block = [0x90, 0x90, 0x90, 0x90, 0x90, 0xE8, 105, 0, 0, 0, 0x90, 0x90, 0x90, 0x90, 0x90, 0x90];
+        stream.extend(block);
+
+        let mut expected = block.to_vec();
+        expected[6] = 100;
+        assert_eq!(decompress_lzx(&stream, 16).unwrap(), expected);
+    }
+
+    #[test]
+    fn lzx_rejects_corrupt_input() {
+        // 블록 유형 0
+        assert!(decompress_lzx(&[0x00, 0x00, 0x00, 0x00], 16).is_err());
+        // 비압축 블록 데이터가 잘림
+        let mut bits = BitWriter::default();
+        bits.write(LZX_BLOCKTYPE_UNCOMPRESSED, 3);
+        bits.write(1, 1);
+        bits.flush();
+        let mut truncated = bits.bytes;
+        truncated.extend([0u8; 12 + 100]);
+        assert!(decompress_lzx(&truncated, LZX_DEFAULT_BLOCK_SIZE).is_err());
+        // 코드 길이 없이 끝난 verbatim 블록
+        assert!(decompress_lzx(&[0x20, 0x00], 64).is_err());
+    }
+
+    #[test]
+    fn wof_stream_mixes_stored_and_compressed_chunks() {
+        let stored: Vec<u8> = (0..4096).map(|i| (i % 251) as u8).collect();
+        let compressed = xpress_huffman_abc();
+        let mut data = (stored.len() as u32).to_le_bytes().to_vec();
+        data.extend(&stored);
+        data.extend(&compressed);
+
+        let mut expected = stored.clone();
+        expected.extend(abc_100());
+        assert_eq!(decompress_wof(&data, WofAlgorithm::Xpress4K, expected.len() as u64).unwrap(), expected);
+
+        // 청크 오프셋이 스트림 밖을 가리킴
+        data[0..4].copy_from_slice(&0xFFFF_u32.to_le_bytes());
+        assert!(decompress_wof(&data, WofAlgorithm::Xpress4K, expected.len() as u64).is_err());
+    }
+
+    #[test]
+    fn garbage_input_never_panics() {
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        for round in 0..2000 {
+            let len = (round * 7) % 700;
+            let data: Vec<u8> = (0..len).map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            }).collect();
+            let _ = decompress_lznt1(&data);
+            let _ = decompress_xpress(&data, 4096);
+            let _ = decompress_xpress_huffman(&data, 4096);
+            let _ = decompress_lzx(&data, 4096);
+            let _ = decompress_wof(&data, WofAlgorithm::Lzx, 40000);
+        }
+    }
+}
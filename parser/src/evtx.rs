@@ -1,51 +1,71 @@
 use anyhow::Result;
-use chrono::{Utc, NaiveDateTime};
-use evtx::EvtxParser;
+use chrono::{DateTime, Utc, NaiveDateTime};
+use evtx::{EvtxChunkData, EvtxParser, ParserSettings};
 use serde_json::Value;
-use std::io::Cursor;
+use std::io::{Cursor, Read, Seek};
+use std::sync::Arc;
 use models::event::{ForensicEvent, ExecutionEvent, NetworkEvent, SystemEvent};
 
 pub fn parse_security_evtx_buffer(data: &[u8], filename: &str) -> Result<Vec<ForensicEvent>> {
-    let cursor = Cursor::new(data.to_vec());
-    let mut parser = EvtxParser::from_read_seek(cursor)?;
+    parse_security_evtx_stream(Cursor::new(data), filename)
+}
+
+/// EVTX를 64KB 청크 단위로 읽으며 파싱한다. 로그 전체를 메모리에 올리지 않는다.
+pub fn parse_security_evtx_stream<R: Read + Seek>(reader: R, filename: &str) -> Result<Vec<ForensicEvent>> {
+    let mut parser = EvtxParser::from_read_seek(reader)?;
     let mut events = Vec::new();
 
-    for record in parser.records_json() {
-        if let Ok(r) = record {
-            let v: Option<Value> = serde_json::from_str(&r.data).ok();
-            if let Some(json_val) = v {
-                let event_id = json_val.pointer("/Event/System/EventID").and_then(|id| id.as_u64()).unwrap_or(0) as u32;
-                
-                // 1. 실행 이벤트 파싱 (EID 4688, Sysmon 1)
-                if event_id == 4688 || event_id == 1 {
-                    if let Some(event) = extract_execution_data(&json_val, filename) {
-                        events.push(event);
-                    }
-                }
-                
-                // 2. 네트워크 연결 이벤트 파싱 (WFP EID 5156, Sysmon 3)
-                else if event_id == 5156 || event_id == 3 {
-                    if let Some(event) = extract_network_data(&json_val, filename, event_id) {
-                        events.push(event);
-                    }
-                }
-
-                // 3. [추가] 방어 회피 및 안티포렌식 이벤트 파싱 (EID 1102, 104, 5001, 1116)
-                else if event_id == 1102 || event_id == 104 || event_id == 5001 || event_id == 1116 {
-                    if let Some(event) = extract_evasion_data(&json_val, filename, event_id) {
-                        events.push(event);
-                    }
-                }
-            }
+    for r in parser.records_json().flatten() {
+        let v: Option<Value> = serde_json::from_str(&r.data).ok();
+        if let Some(json_val) = v
+            && let Some(event) = record_event(&json_val, filename) {
+            events.push(event);
         }
     }
     Ok(events)
 }
 
+/// [추가] 파일 헤더 없이 발견된 64KB ElfChnk 청크 하나를 해석한다. (미할당 영역 카빙용)
+/// 헤더/레코드 CRC32가 맞지 않으면 오탐으로 보고 에러를 반환한다.
+pub fn parse_evtx_chunk(chunk: &[u8], filename: &str) -> Result<Vec<ForensicEvent>> {
+    let mut chunk_data = EvtxChunkData::new(chunk.to_vec(), true)?;
+    let mut parsed = chunk_data.parse(Arc::new(ParserSettings::default()))?;
+    let mut events = Vec::new();
+
+    for record in parsed.iter().flatten() {
+        if let Ok(r) = record.into_json_value()
+            && let Some(event) = record_event(&r.data, filename) {
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
+
+fn record_event(json_val: &Value, filename: &str) -> Option<ForensicEvent> {
+    let event_id = json_val.pointer("/Event/System/EventID").and_then(|id| id.as_u64()).unwrap_or(0) as u32;
+
+    match event_id {
+        // 1. 실행 이벤트 파싱 (EID 4688, Sysmon 1)
+        4688 | 1 => extract_execution_data(json_val, filename),
+        // 2. 네트워크 연결 이벤트 파싱 (WFP EID 5156, Sysmon 3)
+        5156 | 3 => extract_network_data(json_val, filename, event_id),
+        // 3. [추가] 방어 회피 및 안티포렌식 이벤트 파싱 (EID 1102, 104, 5001, 1116)
+        1102 | 104 | 5001 | 1116 => extract_evasion_data(json_val, filename, event_id),
+        _ => None,
+    }
+}
+
+/// [Fix] evtx 크레이트는 속성을 "#attributes" 아래에 둔다. (분석기 EvtxAnalyzer와 동일한 경로)
+fn record_timestamp(v: &Value) -> Option<DateTime<Utc>> {
+    let timestamp_str = v.pointer("/Event/System/TimeCreated/#attributes/SystemTime")
+        .or_else(|| v.pointer("/Event/System/TimeCreated/SystemTime"))
+        .and_then(|t| t.as_str())?;
+    Some(NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%dT%H:%M:%S%.fZ")
+        .map(|dt| dt.and_utc()).unwrap_or_else(|_| Utc::now()))
+}
+
 fn extract_execution_data(v: &Value, filename: &str) -> Option<ForensicEvent> {
-    let timestamp_str = v.pointer("/Event/System/TimeCreated/SystemTime").and_then(|t| t.as_str())?;
-    let timestamp = NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%dT%H:%M:%S%.fZ")
-        .map(|dt| dt.and_utc()).unwrap_or_else(|_| Utc::now());
+    let timestamp = record_timestamp(v)?;
 
     let event_data = v.pointer("/Event/EventData")?;
     let process_name = extract_event_data_field(event_data, "NewProcessName")
@@ -70,9 +90,7 @@ fn extract_execution_data(v: &Value, filename: &str) -> Option<ForensicEvent> {
 }
 
 fn extract_network_data(v: &Value, filename: &str, event_id: u32) -> Option<ForensicEvent> {
-    let timestamp_str = v.pointer("/Event/System/TimeCreated/SystemTime").and_then(|t| t.as_str())?;
-    let timestamp = NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%dT%H:%M:%S%.fZ")
-        .map(|dt| dt.and_utc()).unwrap_or_else(|_| Utc::now());
+    let timestamp = record_timestamp(v)?;
 
     let event_data = v.pointer("/Event/EventData")?;
     
@@ -115,9 +133,7 @@ fn extract_network_data(v: &Value, filename: &str, event_id: u32) -> Option<Fore
 
 // [핵심 로직] 안티포렌식 및 방어 회피 탐지 추출
 fn extract_evasion_data(v: &Value, filename: &str, event_id: u32) -> Option<ForensicEvent> {
-    let timestamp_str = v.pointer("/Event/System/TimeCreated/SystemTime").and_then(|t| t.as_str())?;
-    let timestamp = NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%dT%H:%M:%S%.fZ")
-        .map(|dt| dt.and_utc()).unwrap_or_else(|_| Utc::now());
+    let timestamp = record_timestamp(v)?;
 
     let (activity_type, description) = match event_id {
         1102 | 104 => (
@@ -150,10 +166,9 @@ fn extract_event_data_field(event_data: &Value, field_name: &str) -> Option<Stri
                 return item.get("#text").and_then(|t| t.as_str()).map(|s| s.to_string());
             }
         }
-    } else if let Value::Object(data_map) = event_data {
-        if let Some(val) = data_map.get(field_name) {
-             return val.as_str().map(|s| s.to_string());
-        }
+    } else if let Value::Object(data_map) = event_data
+        && let Some(val) = data_map.get(field_name) {
+        return val.as_str().map(|s| s.to_string());
     }
     None
 }
\ No newline at end of file
@@ -4,9 +4,20 @@ pub mod prefetch;
 pub mod registry;
 pub mod evtx;
 pub mod usnjrnl;
+pub mod logfile;
 pub mod amcache;
 pub mod tasks;
 pub mod ntuser;
 pub mod lnk;
 pub mod wmi;
-pub mod system_hive;
\ No newline at end of file
+pub mod system_hive;
+pub mod partition;
+pub mod compression;
+pub mod vss;
+pub mod ads;
+pub mod carve;
+pub mod recycle_bin;
+pub mod usb;
+pub mod registry_log;
+#[cfg(test)]
+mod test_hive;
@@ -3,6 +3,45 @@ use chrono::Utc;
 use models::event::{ForensicEvent, ExecutionEvent};
 use std::collections::HashSet;
 
+/// ShellLinkHeader: HeaderSize(0x4C) + LinkCLSID {00021401-0000-0000-C000-000000000046}
+const LNK_HEADER: [u8; 20] = [
+    0x4C, 0x00, 0x00, 0x00, 0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
+];
+/// 카빙 시 허용하는 LNK 최대 크기
+pub const MAX_LNK_SIZE: usize = 64 * 1024;
+
+/// [추가] 헤더 뒤의 IDList / LinkInfo / StringData / ExtraData 구조를 따라가며 LNK 파일의 실제 길이를 구한다. (MS-SHLLINK)
+/// 구조가 어긋나면 None을 반환하므로 미할당 영역 카빙의 검증에 사용한다.
+pub fn lnk_length(data: &[u8]) -> Option<usize> {
+    if data.len() < 0x4C || data[0..20] != LNK_HEADER { return None; }
+    // Reserved1/2/3 (0x42~0x4B)은 항상 0
+    if data[0x42..0x4C].iter().any(|&b| b != 0) { return None; }
+    let flags = u32::from_le_bytes(data[0x14..0x18].try_into().unwrap());
+    let read_u16 = |pos: usize| data.get(pos..pos + 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as usize);
+    let read_u32 = |pos: usize| data.get(pos..pos + 4).map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize);
+
+    let mut pos = 0x4C;
+    if flags & 0x01 != 0 { pos += 2 + read_u16(pos)?; }         // HasLinkTargetIDList
+    if flags & 0x02 != 0 {                                        // HasLinkInfo (크기 필드 포함)
+        let size = read_u32(pos)?;
+        if size < 0x1C { return None; }
+        pos += size;
+    }
+    let char_size = if flags & 0x80 != 0 { 2 } else { 1 };      // IsUnicode
+    for bit in [0x04, 0x08, 0x10, 0x20, 0x40] {                   // Name, RelativePath, WorkingDir, Arguments, IconLocation
+        if flags & bit != 0 { pos += 2 + read_u16(pos)? * char_size; }
+    }
+    loop {                                                        // ExtraData: 4바이트 미만 크기의 TerminalBlock으로 끝난다
+        let size = read_u32(pos)?;
+        if size < 4 { pos += 4; break; }
+        if size < 8 { return None; }
+        pos += size;
+        if pos > MAX_LNK_SIZE { return None; }
+    }
+    (pos <= data.len() && pos <= MAX_LNK_SIZE).then_some(pos)
+}
+
 pub fn parse_lnk_carve(data: &[u8], filename: &str) -> Result<Vec<ForensicEvent>> {
     let mut events = Vec::new();
     let mut extracted = HashSet::new();
@@ -30,7 +69,7 @@ pub fn parse_lnk_carve(data: &[u8], filename: &str) -> Result<Vec<ForensicEvent>
     for path in extracted {
         events.push(ForensicEvent::Execution(ExecutionEvent {
             timestamp: Utc::now(),
-            process_name: filename.split('\\').last().unwrap_or(filename).to_string(),
+            process_name: filename.split('\\').next_back().unwrap_or(filename).to_string(),
             file_path: filename.to_string(),
             command_line: path,
             parent_process_name: "explorer.exe".to_string(),
@@ -0,0 +1,777 @@
+use chrono::{DateTime, Utc};
+use models::FactError;
+use models::mft::{mft_entry_number, StandardInformation};
+use std::collections::{HashMap, HashSet};
+use crate::mft::{apply_fixup, parse_file_name_attribute, parse_mft_record};
+
+// NTFS 클라이언트 로그 레코드의 Redo/Undo 오퍼레이션 코드 (재구성에 필요한 것만)
+pub const OP_INITIALIZE_FILE_RECORD_SEGMENT: u16 = 0x02;
+pub const OP_DEALLOCATE_FILE_RECORD_SEGMENT: u16 = 0x03;
+pub const OP_UPDATE_RESIDENT_VALUE: u16 = 0x07;
+pub const OP_ADD_INDEX_ENTRY_ROOT: u16 = 0x0C;
+pub const OP_DELETE_INDEX_ENTRY_ROOT: u16 = 0x0D;
+pub const OP_ADD_INDEX_ENTRY_ALLOCATION: u16 = 0x0E;
+pub const OP_DELETE_INDEX_ENTRY_ALLOCATION: u16 = 0x0F;
+
+const LFS_RECORD_HEADER_SIZE: usize = 0x30;
+const CLIENT_HEADER_SIZE: usize = 0x20;
+/// 레코드 한 개가 가질 수 있는 클라이언트 데이터 상한 (손상된 헤더로 인한 과도한 할당 방지)
+const MAX_CLIENT_DATA_LENGTH: usize = 0x10000;
+/// RSTR 2페이지 + 버퍼(tail) 2페이지 뒤부터 순환 로그 영역이 시작된다.
+const LOG_AREA_FIRST_PAGE: usize = 4;
+/// FILE 레코드의 첫 속성($STANDARD_INFORMATION) 위치 (NTFS 3.0 / 3.1)
+const FIRST_ATTRIBUTE_OFFSETS: [u16; 2] = [0x30, 0x38];
+/// 상주 $STANDARD_INFORMATION 값의 속성 내 시작 오프셋
+const SI_VALUE_OFFSET: u16 = 0x18;
+
+/// RSTR 페이지의 재시작 영역
+#[derive(Debug, Clone)]
+pub struct LogFileRestart {
+    pub current_lsn: u64,
+    pub system_page_size: u32,
+    pub log_page_size: u32,
+    pub seq_number_bits: u32,
+    pub file_size: u64,
+    pub log_record_header_length: u16,
+    pub log_page_data_offset: u16,
+}
+
+impl LogFileRestart {
+    /// LSN이 가리키는 $LogFile 내 바이트 오프셋 (상위 seq_number_bits는 순환 횟수)
+    pub fn lsn_to_offset(&self, lsn: u64) -> u64 {
+        let offset_bits = 64u32.saturating_sub(self.seq_number_bits);
+        let mask = if offset_bits >= 64 { u64::MAX } else { (1u64 << offset_bits) - 1 };
+        (lsn & mask) << 3
+    }
+}
+
+/// RCRD 페이지에서 읽은 LFS 클라이언트 레코드 (NTFS 클라이언트 헤더 포함)
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub lsn: u64,
+    pub previous_lsn: u64,
+    pub undo_next_lsn: u64,
+    pub transaction_id: u32,
+    pub redo_operation: u16,
+    pub undo_operation: u16,
+    pub target_attribute: u16,
+    pub record_offset: u16,
+    pub attribute_offset: u16,
+    pub cluster_block_offset: u16,
+    pub target_vcn: u64,
+    pub redo_data: Vec<u8>,
+    pub undo_data: Vec<u8>,
+}
+
+/// 트랜잭션 단위로 재구성한 파일 시스템 조작
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogFileOperationKind {
+    Create,
+    Delete,
+    Rename { old_name: String },
+    /// $STANDARD_INFORMATION 시각을 직접 덮어쓴 경우 (생성 시각 변경 또는 수정 시각 역행)
+    TimestampChange {
+        old_creation: Option<DateTime<Utc>>,
+        new_creation: Option<DateTime<Utc>>,
+        old_modification: Option<DateTime<Utc>>,
+        new_modification: Option<DateTime<Utc>>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct LogFileOperation {
+    /// 조작이 속한 트랜잭션의 첫 LSN
+    pub lsn: u64,
+    /// 같은 트랜잭션에서 기록된 $SI 갱신 시각. 없으면 직전 트랜잭션의 시각을 이어받는다.
+    pub timestamp: DateTime<Utc>,
+    pub kind: LogFileOperationKind,
+    pub file_name: String,
+    pub mft_reference: Option<u64>,
+    pub parent_reference: Option<u64>,
+    pub is_dir: bool,
+}
+
+/// 대상 VCN을 MFT 엔트리 번호로 환산하는 데 필요한 볼륨 지오메트리
+#[derive(Debug, Clone, Copy)]
+pub struct VolumeGeometry {
+    pub cluster_size: u64,
+    pub record_size: u64,
+}
+
+impl Default for VolumeGeometry {
+    fn default() -> Self {
+        Self { cluster_size: 4096, record_size: 1024 }
+    }
+}
+
+impl VolumeGeometry {
+    /// 손상된 VCN이나 레코드 크기 0처럼 환산할 수 없으면 None
+    fn mft_entry(&self, record: &LogRecord) -> Option<u64> {
+        let offset = record.target_vcn.checked_mul(self.cluster_size)?
+            .checked_add(record.cluster_block_offset as u64 * 512)?;
+        offset.checked_div(self.record_size)
+    }
+}
+
+fn parse_error(details: impl Into<String>) -> FactError {
+    FactError::ParseError { artifact_name: "$LogFile".into(), details: details.into() }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn parse_restart_page(data: &[u8], page_offset: usize) -> Option<LogFileRestart> {
+    if data.len() < page_offset + 0x30 || &data[page_offset..page_offset + 4] != b"RSTR" { return None; }
+    let system_page_size = read_u32(data, page_offset + 0x10);
+    if !system_page_size.is_power_of_two() || !(512..=65536).contains(&system_page_size) { return None; }
+
+    let page_end = page_offset + system_page_size as usize;
+    if data.len() < page_end { return None; }
+    let mut page = data[page_offset..page_end].to_vec();
+    apply_fixup(&mut page).ok()?;
+
+    let log_page_size = read_u32(&page, 0x14);
+    let area = read_u16(&page, 0x18) as usize;
+    if area + 0x30 > page.len() || !log_page_size.is_power_of_two() || log_page_size < 512 { return None; }
+
+    let restart = LogFileRestart {
+        current_lsn: read_u64(&page, area),
+        system_page_size,
+        log_page_size,
+        seq_number_bits: read_u32(&page, area + 0x10),
+        file_size: read_u64(&page, area + 0x18),
+        log_record_header_length: read_u16(&page, area + 0x24),
+        log_page_data_offset: read_u16(&page, area + 0x26),
+    };
+    let valid = restart.seq_number_bits < 64
+        && (restart.log_page_data_offset as u32) < log_page_size
+        && restart.log_record_header_length as usize >= LFS_RECORD_HEADER_SIZE;
+    valid.then_some(restart)
+}
+
+/// 두 개의 RSTR 사본 중 current_lsn이 더 큰(최신) 재시작 영역을 반환한다.
+pub fn parse_restart_area(data: &[u8]) -> Result<LogFileRestart, FactError> {
+    let first = parse_restart_page(data, 0);
+    let second_offset = first.as_ref().map(|r| r.system_page_size as usize).unwrap_or(4096);
+    let second = parse_restart_page(data, second_offset);
+
+    match (first, second) {
+        (Some(a), Some(b)) => Ok(if b.current_lsn > a.current_lsn { b } else { a }),
+        (Some(a), None) | (None, Some(a)) => Ok(a),
+        (None, None) => Err(parse_error("No valid RSTR restart page")),
+    }
+}
+
+/// 픽스업을 적용한 RCRD 페이지 목록. 사용되지 않았거나 손상된 페이지는 None.
+fn load_record_pages(data: &[u8], restart: &LogFileRestart) -> Vec<Option<Vec<u8>>> {
+    let page_size = restart.log_page_size as usize;
+    data.chunks_exact(page_size)
+        .enumerate()
+        .map(|(index, chunk)| {
+            if index < LOG_AREA_FIRST_PAGE || &chunk[0..4] != b"RCRD" { return None; }
+            let mut page = chunk.to_vec();
+            apply_fixup(&mut page).ok().map(|_| page)
+        })
+        .collect()
+}
+
+/// $LogFile 전체에서 LFS 클라이언트 레코드를 추출한다. 페이지 경계를 넘는 레코드는 다음 페이지 데이터 영역에서 이어 붙이고,
+/// 헤더의 LSN이 현재 파일 오프셋과 일치하는지로 레코드 경계를 검증하여 순환 로그의 잔여 데이터를 걸러낸다.
+pub fn parse_log_records(data: &[u8]) -> Result<Vec<LogRecord>, FactError> {
+    let restart = parse_restart_area(data)?;
+    let pages = load_record_pages(data, &restart);
+    let page_size = restart.log_page_size as usize;
+    let data_offset = restart.log_page_data_offset as usize;
+    let header_length = restart.log_record_header_length as usize;
+
+    let mut records = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (page_index, page) in pages.iter().enumerate() {
+        let Some(page) = page else { continue };
+        let mut cursor = data_offset;
+
+        while cursor + header_length <= page_size {
+            let lsn = read_u64(page, cursor);
+            let file_offset = (page_index * page_size + cursor) as u64;
+            if lsn == 0 || restart.lsn_to_offset(lsn) != file_offset {
+                // 앞 페이지에서 넘어온 레코드 꼬리이거나 빈 공간: 8바이트 단위로 다음 헤더를 탐색한다.
+                cursor += 8;
+                continue;
+            }
+
+            let client_length = read_u32(page, cursor + 0x18) as usize;
+            if client_length > MAX_CLIENT_DATA_LENGTH { cursor += 8; continue; }
+
+            // 클라이언트 데이터를 현재 페이지 및 후속 페이지의 데이터 영역에서 모은다.
+            let mut client = Vec::with_capacity(client_length);
+            let mut src_page = page_index;
+            let mut src_offset = cursor + header_length;
+            let mut complete = true;
+            while client.len() < client_length {
+                if src_offset >= page_size {
+                    src_page += 1;
+                    src_offset = data_offset;
+                }
+                let Some(Some(src)) = pages.get(src_page) else { complete = false; break };
+                let take = std::cmp::min(client_length - client.len(), page_size - src_offset);
+                client.extend_from_slice(&src[src_offset..src_offset + take]);
+                src_offset += take;
+            }
+
+            if complete && seen.insert(lsn) && read_u32(page, cursor + 0x20) == 1
+                && let Some(record) = decode_client_record(page, cursor, &client) {
+                records.push(record);
+            }
+
+            // 다음 레코드는 8바이트 정렬. 다른 페이지에서 끝난 레코드 뒤쪽은 해당 페이지 순회 시 LSN 검증으로 찾는다.
+            if src_page != page_index { break; }
+            cursor = (cursor + header_length + client_length).next_multiple_of(8);
+        }
+    }
+
+    records.sort_by_key(|r| r.lsn);
+    Ok(records)
+}
+
+fn decode_client_record(page: &[u8], header: usize, client: &[u8]) -> Option<LogRecord> {
+    if client.len() < CLIENT_HEADER_SIZE { return None; }
+    let slice = |offset: u16, length: u16| -> Vec<u8> {
+        let (start, end) = (offset as usize, offset as usize + length as usize);
+        if length == 0 || end > client.len() { Vec::new() } else { client[start..end].to_vec() }
+    };
+
+    Some(LogRecord {
+        lsn: read_u64(page, header),
+        previous_lsn: read_u64(page, header + 0x08),
+        undo_next_lsn: read_u64(page, header + 0x10),
+        transaction_id: read_u32(page, header + 0x24),
+        redo_operation: read_u16(client, 0x00),
+        undo_operation: read_u16(client, 0x02),
+        target_attribute: read_u16(client, 0x0C),
+        record_offset: read_u16(client, 0x10),
+        attribute_offset: read_u16(client, 0x12),
+        cluster_block_offset: read_u16(client, 0x14),
+        target_vcn: read_u64(client, 0x18),
+        redo_data: slice(read_u16(client, 0x04), read_u16(client, 0x06)),
+        undo_data: slice(read_u16(client, 0x08), read_u16(client, 0x0A)),
+    })
+}
+
+/// 인덱스 엔트리(파일 참조 + $FILE_NAME 키)
+struct IndexKey {
+    reference: u64,
+    parent: u64,
+    name: String,
+    is_dir: bool,
+}
+
+fn parse_index_key(data: &[u8]) -> Option<IndexKey> {
+    if data.len() < 0x10 { return None; }
+    let key_length = read_u16(data, 0x0A) as usize;
+    if key_length < 66 || 0x10 + key_length > data.len() { return None; }
+    let fn_attr = parse_file_name_attribute(&data[0x10..0x10 + key_length]).ok()?;
+    Some(IndexKey {
+        reference: read_u64(data, 0),
+        parent: fn_attr.parent_directory,
+        name: fn_attr.name,
+        is_dir: fn_attr.flags & 0x1000_0000 != 0,
+    })
+}
+
+/// (필드 인덱스, FILETIME) 목록
+type SiTimeFields = Vec<(usize, u64)>;
+
+/// UpdateResidentValue가 $SI 값 범위를 덮어쓴 경우, 바뀐 8바이트 시각 필드를 (필드 인덱스, 값)으로 반환한다.
+/// 필드 인덱스: 0 = 생성, 1 = 수정, 2 = MFT 변경, 3 = 접근
+fn si_time_fields(record: &LogRecord, data: &[u8]) -> SiTimeFields {
+    if !FIRST_ATTRIBUTE_OFFSETS.contains(&record.record_offset) || record.attribute_offset < SI_VALUE_OFFSET {
+        return Vec::new();
+    }
+    let start = (record.attribute_offset - SI_VALUE_OFFSET) as usize;
+    (0..4usize)
+        .filter(|field| field * 8 >= start && field * 8 + 8 <= start + data.len())
+        .map(|field| (field, read_u64(data, field * 8 - start)))
+        .collect()
+}
+
+#[derive(Default)]
+struct Transaction<'a> {
+    records: Vec<&'a LogRecord>,
+}
+
+/// previous_lsn 체인으로 같은 트랜잭션의 레코드를 묶는다. (transaction_id는 트랜잭션 테이블 슬롯이라 재사용된다)
+fn group_transactions(records: &[LogRecord]) -> Vec<Transaction<'_>> {
+    let mut transactions: Vec<Transaction> = Vec::new();
+    let mut owner: HashMap<u64, usize> = HashMap::new();
+
+    for record in records {
+        let index = match owner.get(&record.previous_lsn) {
+            Some(&index) if record.previous_lsn != 0 => index,
+            _ => {
+                transactions.push(Transaction::default());
+                transactions.len() - 1
+            }
+        };
+        owner.insert(record.lsn, index);
+        transactions[index].records.push(record);
+    }
+    transactions
+}
+
+/// LFS 레코드를 트랜잭션 단위로 분석하여 생성/이름 변경/삭제/시각 변경 조작으로 재구성한다.
+pub fn reconstruct_operations(records: &[LogRecord], geometry: VolumeGeometry) -> Vec<LogFileOperation> {
+    // 1차: 로그 전체에서 엔트리 번호별 마지막으로 알려진 이름을 수집 (시각 변경 레코드에는 이름이 없다)
+    let mut names: HashMap<u64, &IndexKey> = HashMap::new();
+    let keys: Vec<IndexKey> = records.iter()
+        .filter(|r| matches!(r.redo_operation, OP_ADD_INDEX_ENTRY_ROOT | OP_ADD_INDEX_ENTRY_ALLOCATION
+            | OP_DELETE_INDEX_ENTRY_ROOT | OP_DELETE_INDEX_ENTRY_ALLOCATION))
+        .flat_map(|r| [parse_index_key(&r.redo_data), parse_index_key(&r.undo_data)])
+        .flatten()
+        .collect();
+    for key in &keys {
+        names.insert(mft_entry_number(key.reference), key);
+    }
+    let describe = |entry: u64| match names.get(&entry) {
+        Some(key) => (key.name.clone(), Some(key.reference), Some(key.parent), key.is_dir),
+        None => (format!("MFT Entry #{}", entry), None, None, false),
+    };
+
+    let mut operations = Vec::new();
+    let mut last_timestamp: Option<DateTime<Utc>> = None;
+
+    for transaction in group_transactions(records) {
+        let mut added: Vec<IndexKey> = Vec::new();
+        let mut removed: Vec<IndexKey> = Vec::new();
+        let mut created: HashMap<u64, (u16, Option<StandardInformation>)> = HashMap::new();
+        let mut si_changes: Vec<(u64, SiTimeFields, SiTimeFields)> = Vec::new();
+        let mut newest_time = 0u64;
+
+        for record in &transaction.records {
+            match record.redo_operation {
+                OP_ADD_INDEX_ENTRY_ROOT | OP_ADD_INDEX_ENTRY_ALLOCATION => {
+                    if let Some(key) = parse_index_key(&record.redo_data) { added.push(key); }
+                },
+                OP_DELETE_INDEX_ENTRY_ROOT | OP_DELETE_INDEX_ENTRY_ALLOCATION => {
+                    // 삭제 Redo에는 키만 남는 경우가 있으므로 Undo(재추가용 전체 엔트리)를 우선한다.
+                    if let Some(key) = parse_index_key(&record.undo_data).or_else(|| parse_index_key(&record.redo_data)) {
+                        removed.push(key);
+                    }
+                },
+                OP_INITIALIZE_FILE_RECORD_SEGMENT => {
+                    // Redo 데이터는 초기화된 FILE 레코드 이미지이며, 0x2C에 자신의 레코드 번호를 가진다.
+                    let image = &record.redo_data;
+                    if image.len() >= 0x30 && &image[0..4] == b"FILE" {
+                        let entry = read_u32(image, 0x2C) as u64;
+                        let Some(entry) = (if entry != 0 { Some(entry) } else { geometry.mft_entry(record) }) else { continue };
+                        if let Ok(parsed) = parse_mft_record(image, entry) {
+                            if let Some(si) = &parsed.standard_info { newest_time = newest_time.max(si.creation_time); }
+                            created.insert(entry, (parsed.sequence_number, parsed.standard_info));
+                        }
+                    }
+                },
+                OP_UPDATE_RESIDENT_VALUE => {
+                    let new_fields = si_time_fields(record, &record.redo_data);
+                    if new_fields.is_empty() { continue; }
+                    let Some(entry) = geometry.mft_entry(record) else { continue };
+                    // MFT 변경(C) 시각은 SetFileTime 호출 시에도 현재 시각으로 갱신되므로 트랜잭션 시각 추정에 사용한다.
+                    for (_, time) in &new_fields { newest_time = newest_time.max(*time); }
+                    let old_fields = si_time_fields(record, &record.undo_data);
+                    si_changes.push((entry, old_fields, new_fields));
+                },
+                _ => {},
+            }
+        }
+
+        let timestamp = if newest_time != 0 { Some(StandardInformation::to_datetime(newest_time)) } else { last_timestamp };
+        last_timestamp = timestamp;
+        let Some(timestamp) = timestamp else { continue };
+        let lsn = transaction.records[0].lsn;
+
+        for key in &added {
+            let kind = match removed.iter().find(|r| r.reference == key.reference) {
+                Some(old) => LogFileOperationKind::Rename { old_name: old.name.clone() },
+                None => LogFileOperationKind::Create,
+            };
+            operations.push(LogFileOperation {
+                lsn, timestamp, kind,
+                file_name: key.name.clone(),
+                mft_reference: Some(key.reference),
+                parent_reference: Some(key.parent),
+                is_dir: key.is_dir,
+            });
+        }
+
+        for key in removed.iter().filter(|r| !added.iter().any(|a| a.reference == r.reference)) {
+            operations.push(LogFileOperation {
+                lsn, timestamp,
+                kind: LogFileOperationKind::Delete,
+                file_name: key.name.clone(),
+                mft_reference: Some(key.reference),
+                parent_reference: Some(key.parent),
+                is_dir: key.is_dir,
+            });
+        }
+
+        // 인덱스 추가 없이 레코드만 초기화된 경우 (인덱스 갱신이 다른 페이지로 밀려난 생성)
+        for (entry, (sequence, _)) in &created {
+            let reference = ((*sequence as u64) << 48) | entry;
+            if added.iter().any(|a| a.reference == reference) { continue; }
+            let (file_name, _, parent_reference, is_dir) = describe(*entry);
+            operations.push(LogFileOperation {
+                lsn, timestamp,
+                kind: LogFileOperationKind::Create,
+                file_name,
+                mft_reference: Some(reference),
+                parent_reference,
+                is_dir,
+            });
+        }
+
+        for (entry, old_fields, new_fields) in si_changes {
+            // 새로 만든 레코드의 최초 $SI 기록은 시각 변경이 아니다.
+            if created.contains_key(&entry) { continue; }
+            let field = |fields: &[(usize, u64)], index: usize| fields.iter().find(|(i, _)| *i == index).map(|(_, t)| *t);
+            let (old_b, new_b) = (field(&old_fields, 0), field(&new_fields, 0));
+            let (old_m, new_m) = (field(&old_fields, 1), field(&new_fields, 1));
+
+            let creation_rewritten = matches!((old_b, new_b), (Some(o), Some(n)) if o != n);
+            let modification_rewound = matches!((old_m, new_m), (Some(o), Some(n)) if n < o);
+            if !creation_rewritten && !modification_rewound { continue; }
+
+            let to_dt = |t: Option<u64>| t.map(StandardInformation::to_datetime);
+            let (file_name, mft_reference, parent_reference, is_dir) = describe(entry);
+            operations.push(LogFileOperation {
+                lsn, timestamp,
+                kind: LogFileOperationKind::TimestampChange {
+                    old_creation: to_dt(old_b), new_creation: to_dt(new_b),
+                    old_modification: to_dt(old_m), new_modification: to_dt(new_m),
+                },
+                file_name,
+                mft_reference,
+                parent_reference,
+                is_dir,
+            });
+        }
+    }
+    operations
+}
+
+/// $LogFile 스트림을 파싱하여 파일 조작 목록을 반환한다. (4KB 클러스터, 1KB FILE 레코드 가정)
+pub fn parse_logfile(data: &[u8]) -> Result<Vec<LogFileOperation>, FactError> {
+    parse_logfile_with_geometry(data, VolumeGeometry::default())
+}
+
+/// 볼륨 VBR에서 읽은 클러스터/FILE 레코드 크기로 대상 VCN을 MFT 엔트리 번호로 환산한다.
+pub fn parse_logfile_with_geometry(data: &[u8], geometry: VolumeGeometry) -> Result<Vec<LogFileOperation>, FactError> {
+    let records = parse_log_records(data)?;
+    Ok(reconstruct_operations(&records, geometry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE_SIZE: usize = 4096;
+    const DATA_OFFSET: usize = 0x40;
+    const SEQ_NUMBER_BITS: u32 = 44;
+    const RECORD_PAGES: usize = 3;
+    /// 2019-01-01, 2024-03-01T09:00, 2024-03-01T10:00 (FILETIME)
+    const T0: u64 = (1_546_300_800 + 11_644_473_600) * 10_000_000;
+    const T1: u64 = (1_709_283_600 + 11_644_473_600) * 10_000_000;
+    const T2: u64 = (1_709_287_200 + 11_644_473_600) * 10_000_000;
+    const ENTRY: u64 = 0x40;
+    const REFERENCE: u64 = (3 << 48) | ENTRY;
+    /// 4KB 클러스터, 1KB 레코드에서 엔트리 0x40이 위치한 VCN
+    const ENTRY_VCN: u64 = ENTRY * 1024 / 4096;
+
+    fn put_u16(data: &mut [u8], offset: usize, value: u16) {
+        data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u64(data: &mut [u8], offset: usize, value: u64) {
+        data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// 업데이트 시퀀스 번호 1을 각 512바이트 섹터 끝에 기록하고 원래 값을 USA에 옮긴다.
+    fn protect(page: &mut [u8], usa_offset: usize) {
+        let sectors = page.len() / 512;
+        put_u16(page, 4, usa_offset as u16);
+        put_u16(page, 6, (sectors + 1) as u16);
+        put_u16(page, usa_offset, 1);
+        for sector in 0..sectors {
+            let end = (sector + 1) * 512 - 2;
+            page.copy_within(end..end + 2, usa_offset + 2 + sector * 2);
+            put_u16(page, end, 1);
+        }
+    }
+
+    fn restart_page(current_lsn: u64) -> Vec<u8> {
+        let mut page = vec![0u8; PAGE_SIZE];
+        page[0..4].copy_from_slice(b"RSTR");
+        put_u32(&mut page, 0x10, PAGE_SIZE as u32);
+        put_u32(&mut page, 0x14, PAGE_SIZE as u32);
+        put_u16(&mut page, 0x18, 0x30);
+        put_u64(&mut page, 0x30, current_lsn);
+        put_u32(&mut page, 0x30 + 0x10, SEQ_NUMBER_BITS);
+        put_u64(&mut page, 0x30 + 0x18, ((4 + RECORD_PAGES) * PAGE_SIZE) as u64);
+        put_u16(&mut page, 0x30 + 0x24, LFS_RECORD_HEADER_SIZE as u16);
+        put_u16(&mut page, 0x30 + 0x26, DATA_OFFSET as u16);
+        protect(&mut page, 0x1E);
+        page
+    }
+
+    /// RSTR 2페이지, 빈 버퍼 2페이지, RCRD 페이지들로 된 $LogFile 작성기
+    struct LogFile {
+        pages: Vec<Vec<u8>>,
+        page: usize,
+        offset: usize,
+    }
+
+    impl LogFile {
+        fn new() -> Self {
+            let mut pages = vec![vec![0u8; PAGE_SIZE]; LOG_AREA_FIRST_PAGE + RECORD_PAGES];
+            for page in pages.iter_mut().skip(LOG_AREA_FIRST_PAGE) {
+                page[0..4].copy_from_slice(b"RCRD");
+            }
+            Self { pages, page: LOG_AREA_FIRST_PAGE, offset: DATA_OFFSET }
+        }
+
+        /// 클라이언트 레코드를 추가하고 LSN을 반환한다. 데이터가 페이지 끝을 넘으면 다음 페이지 데이터 영역으로 이어진다.
+        fn push(&mut self, previous_lsn: u64, client: &[u8]) -> u64 {
+            if self.offset + LFS_RECORD_HEADER_SIZE > PAGE_SIZE { self.next_page(); }
+            let lsn = ((self.page * PAGE_SIZE + self.offset) >> 3) as u64 | (1 << (64 - SEQ_NUMBER_BITS));
+            let header = &mut self.pages[self.page][self.offset..self.offset + LFS_RECORD_HEADER_SIZE];
+            put_u64(header, 0x00, lsn);
+            put_u64(header, 0x08, previous_lsn);
+            put_u32(header, 0x18, client.len() as u32);
+            put_u32(header, 0x20, 1);
+            self.offset += LFS_RECORD_HEADER_SIZE;
+
+            let mut rest = client;
+            while !rest.is_empty() {
+                if self.offset >= PAGE_SIZE { self.next_page(); }
+                let take = std::cmp::min(rest.len(), PAGE_SIZE - self.offset);
+                self.pages[self.page][self.offset..self.offset + take].copy_from_slice(&rest[..take]);
+                self.offset += take;
+                rest = &rest[take..];
+            }
+            self.offset = self.offset.next_multiple_of(8);
+            lsn
+        }
+
+        fn next_page(&mut self) {
+            self.page += 1;
+            self.offset = DATA_OFFSET;
+        }
+
+        fn bytes(mut self, current_lsn: u64) -> Vec<u8> {
+            self.pages[0] = restart_page(current_lsn);
+            self.pages[1] = restart_page(current_lsn.saturating_sub(1));
+            for page in self.pages.iter_mut().skip(LOG_AREA_FIRST_PAGE) {
+                protect(page, 0x28);
+            }
+            self.pages.concat()
+        }
+    }
+
+    /// NTFS 클라이언트 헤더(0x20) + LCN 하나 뒤에 Redo, Undo 데이터가 온다.
+    fn client(redo_op: u16, undo_op: u16, redo: &[u8], undo: &[u8], record_offset: u16, attribute_offset: u16) -> Vec<u8> {
+        let redo_offset = 0x28;
+        let undo_offset = (redo_offset + redo.len()).next_multiple_of(8);
+        let mut data = vec![0u8; undo_offset + undo.len()];
+        put_u16(&mut data, 0x00, redo_op);
+        put_u16(&mut data, 0x02, undo_op);
+        put_u16(&mut data, 0x04, redo_offset as u16);
+        put_u16(&mut data, 0x06, redo.len() as u16);
+        put_u16(&mut data, 0x08, undo_offset as u16);
+        put_u16(&mut data, 0x0A, undo.len() as u16);
+        put_u16(&mut data, 0x0E, 1);
+        put_u16(&mut data, 0x10, record_offset);
+        put_u16(&mut data, 0x12, attribute_offset);
+        put_u64(&mut data, 0x18, ENTRY_VCN);
+        data[redo_offset..redo_offset + redo.len()].copy_from_slice(redo);
+        data[undo_offset..].copy_from_slice(undo);
+        data
+    }
+
+    /// 초기화된 FILE 레코드 이미지: $STANDARD_INFORMATION 하나와 0x2C의 레코드 번호
+    fn file_record_image(creation: u64) -> Vec<u8> {
+        let mut image = vec![0u8; 0x100];
+        image[0..4].copy_from_slice(b"FILE");
+        put_u16(&mut image, 0x10, (REFERENCE >> 48) as u16);
+        put_u16(&mut image, 0x14, 0x38);
+        put_u16(&mut image, 0x16, 0x01);
+        put_u32(&mut image, 0x2C, ENTRY as u32);
+        let si = 0x38;
+        put_u32(&mut image, si, 0x10);
+        put_u32(&mut image, si + 4, 0x60);
+        put_u32(&mut image, si + 16, 0x48);
+        put_u16(&mut image, si + 20, 0x18);
+        for field in 0..4 {
+            put_u64(&mut image, si + 0x18 + field * 8, creation);
+        }
+        put_u32(&mut image, si + 0x60, 0xFFFF_FFFF);
+        image
+    }
+
+    /// $I30 인덱스 엔트리 (파일 참조 + $FILE_NAME 키)
+    fn index_entry(name: &str) -> Vec<u8> {
+        let name: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let key_length = 66 + name.len();
+        let mut entry = vec![0u8; (0x10 + key_length).next_multiple_of(8)];
+        let length = entry.len() as u16;
+        put_u64(&mut entry, 0, REFERENCE);
+        put_u16(&mut entry, 8, length);
+        put_u16(&mut entry, 0x0A, key_length as u16);
+        put_u64(&mut entry, 0x10, (5 << 48) | 5);
+        entry[0x10 + 64] = (name.len() / 2) as u8;
+        entry[0x10 + 65] = 1;
+        entry[0x10 + 66..0x10 + 66 + name.len()].copy_from_slice(&name);
+        entry
+    }
+
+    fn si_times(creation: u64, modification: u64, changed: u64) -> Vec<u8> {
+        [creation, modification, changed, changed].iter().flat_map(|t| t.to_le_bytes()).collect()
+    }
+
+    /// 생성(evil.exe) -> 이름 변경(svc.exe) -> $SI 생성/수정 시각 역행 -> 삭제. 시각 변경 레코드는 페이지 경계에 걸친다.
+    fn lifecycle_log() -> (Vec<u8>, [u64; 4]) {
+        let mut log = LogFile::new();
+        let init = log.push(0, &client(OP_INITIALIZE_FILE_RECORD_SEGMENT, 0, &file_record_image(T1), &[], 0, 0));
+        log.push(init, &client(OP_ADD_INDEX_ENTRY_ROOT, OP_DELETE_INDEX_ENTRY_ROOT, &index_entry("evil.exe"), &[], 0x98, 0x20));
+
+        let unlink = log.push(0, &client(OP_DELETE_INDEX_ENTRY_ROOT, OP_ADD_INDEX_ENTRY_ROOT, &[], &index_entry("evil.exe"), 0x98, 0x20));
+        log.push(unlink, &client(OP_ADD_INDEX_ENTRY_ROOT, OP_DELETE_INDEX_ENTRY_ROOT, &index_entry("svc.exe"), &[], 0x98, 0x20));
+
+        log.offset = PAGE_SIZE - LFS_RECORD_HEADER_SIZE - 0x30;
+        let touch = log.push(0, &client(OP_UPDATE_RESIDENT_VALUE, OP_UPDATE_RESIDENT_VALUE,
+            &si_times(T0, T0, T2), &si_times(T1, T1, T1), 0x38, SI_VALUE_OFFSET));
+
+        let delete = log.push(0, &client(OP_DELETE_INDEX_ENTRY_ROOT, OP_ADD_INDEX_ENTRY_ROOT, &[], &index_entry("svc.exe"), 0x98, 0x20));
+        (log.bytes(delete), [init, unlink, touch, delete])
+    }
+
+    fn dt(filetime: u64) -> DateTime<Utc> {
+        StandardInformation::to_datetime(filetime)
+    }
+
+    #[test]
+    fn restart_area_prefers_newer_copy() {
+        let (data, [.., last]) = lifecycle_log();
+        let restart = parse_restart_area(&data).unwrap();
+        assert_eq!(restart.current_lsn, last);
+        assert_eq!(restart.log_page_size, PAGE_SIZE as u32);
+        assert_eq!(restart.lsn_to_offset(last) % 8, 0);
+
+        let mut broken = data.clone();
+        broken[0..4].copy_from_slice(b"BAAD");
+        assert_eq!(parse_restart_area(&broken).unwrap().current_lsn, last - 1);
+        broken[PAGE_SIZE..PAGE_SIZE + 4].copy_from_slice(b"BAAD");
+        assert!(parse_restart_area(&broken).is_err());
+    }
+
+    #[test]
+    fn records_are_read_across_page_boundaries() {
+        let (data, [init, unlink, touch, delete]) = lifecycle_log();
+        let records = parse_log_records(&data).unwrap();
+        assert_eq!(records.len(), 6);
+        assert_eq!(records[0].lsn, init);
+        assert!(records.iter().any(|r| r.lsn == unlink));
+        assert_eq!(records.last().unwrap().lsn, delete);
+
+        let spanning = records.iter().find(|r| r.lsn == touch).unwrap();
+        assert_eq!(spanning.redo_operation, OP_UPDATE_RESIDENT_VALUE);
+        assert_eq!(spanning.redo_data, si_times(T0, T0, T2));
+        assert_eq!(spanning.undo_data, si_times(T1, T1, T1));
+        assert_eq!(spanning.target_vcn, ENTRY_VCN);
+    }
+
+    #[test]
+    fn rebuilds_create_rename_timestamp_change_and_delete() {
+        let (data, [init, unlink, touch, delete]) = lifecycle_log();
+        let operations = parse_logfile(&data).unwrap();
+        assert_eq!(operations.len(), 4, "{:#?}", operations);
+
+        assert_eq!(operations[0].kind, LogFileOperationKind::Create);
+        assert_eq!((operations[0].lsn, operations[0].file_name.as_str()), (init, "evil.exe"));
+        assert_eq!(operations[0].timestamp, dt(T1));
+        assert_eq!(operations[0].mft_reference, Some(REFERENCE));
+        assert_eq!(operations[0].parent_reference, Some((5 << 48) | 5));
+
+        assert_eq!(operations[1].kind, LogFileOperationKind::Rename { old_name: "evil.exe".into() });
+        assert_eq!((operations[1].lsn, operations[1].file_name.as_str()), (unlink, "svc.exe"));
+        // $SI 기록이 없는 트랜잭션은 직전 트랜잭션의 시각을 이어받는다.
+        assert_eq!(operations[1].timestamp, dt(T1));
+
+        assert_eq!(operations[2].kind, LogFileOperationKind::TimestampChange {
+            old_creation: Some(dt(T1)), new_creation: Some(dt(T0)),
+            old_modification: Some(dt(T1)), new_modification: Some(dt(T0)),
+        });
+        assert_eq!((operations[2].lsn, operations[2].file_name.as_str()), (touch, "svc.exe"));
+        assert_eq!(operations[2].timestamp, dt(T2));
+        assert_eq!(operations[2].mft_reference, Some(REFERENCE));
+
+        assert_eq!(operations[3].kind, LogFileOperationKind::Delete);
+        assert_eq!((operations[3].lsn, operations[3].file_name.as_str()), (delete, "svc.exe"));
+    }
+
+    #[test]
+    fn malformed_record_page_is_skipped() {
+        let (mut data, [_, unlink, ..]) = lifecycle_log();
+        // 두 번째 RCRD 페이지(삭제 레코드 포함)의 USA 항목 수가 페이지 크기와 맞지 않는다.
+        let page = (LOG_AREA_FIRST_PAGE + 1) * PAGE_SIZE;
+        put_u16(&mut data, page + 6, 6);
+
+        let records = parse_log_records(&data).unwrap();
+        assert!(records.iter().any(|r| r.lsn == unlink));
+        // 페이지 경계에 걸친 레코드도 꼬리 페이지를 잃었으므로 버린다.
+        assert!(records.iter().all(|r| r.redo_operation != OP_UPDATE_RESIDENT_VALUE));
+        assert_eq!(records.len(), 4);
+
+        let operations = parse_logfile(&data).unwrap();
+        assert!(operations.iter().all(|o| o.kind != LogFileOperationKind::Delete));
+    }
+
+    #[test]
+    fn stale_record_with_wrong_lsn_is_ignored() {
+        let (mut data, [init, ..]) = lifecycle_log();
+        // 순환 로그의 이전 회차 잔여 레코드처럼 LSN이 현재 위치와 맞지 않게 만든다.
+        let header = LOG_AREA_FIRST_PAGE * PAGE_SIZE + DATA_OFFSET;
+        put_u64(&mut data, header, init + 0x1000);
+
+        let records = parse_log_records(&data).unwrap();
+        assert!(records.iter().all(|r| r.redo_operation != OP_INITIALIZE_FILE_RECORD_SEGMENT));
+    }
+
+    #[test]
+    fn unmappable_target_vcn_skips_the_record() {
+        let mut log = LogFile::new();
+        let mut touch = client(OP_UPDATE_RESIDENT_VALUE, OP_UPDATE_RESIDENT_VALUE,
+            &si_times(T0, T0, T2), &si_times(T1, T1, T1), 0x38, SI_VALUE_OFFSET);
+        put_u64(&mut touch, 0x18, u64::MAX);
+        let last = log.push(0, &touch);
+        let data = log.bytes(last);
+
+        assert!(parse_logfile(&data).unwrap().is_empty());
+        let (data, _) = lifecycle_log();
+        let zero = VolumeGeometry { cluster_size: 4096, record_size: 0 };
+        let operations = parse_logfile_with_geometry(&data, zero).unwrap();
+        // 인덱스 기반 생성/이름 변경/삭제는 남고, 엔트리를 알 수 없는 시각 변경만 빠진다.
+        assert_eq!(operations.len(), 3);
+        assert!(operations.iter().all(|o| !matches!(o.kind, LogFileOperationKind::TimestampChange { .. })));
+    }
+}
@@ -1,8 +1,41 @@
 use models::mft::{
     FileRecordHeader, AttributeHeader, NonResidentAttributeHeader, 
-    DataRun, IndexEntry
+    DataRun, IndexEntry, IndexSlackEntry, StandardInformation, FileNameAttribute, MftRecord, DataStream,
+    mft_entry_number, mft_sequence_number
 };
 use models::FactError;
+use models::ntfs::record_size_from_clusters;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+
+const MFT_READ_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
+/// Update Sequence Array를 적용하여 각 섹터 끝 2바이트를 원래 값으로 복원한다. (FILE/INDX 레코드)
+/// [Fix] 섹터 크기를 512로 가정하지 않고 레코드 크기 / (USA 항목 수 - 1)로 보호 간격을 구한다. (4Kn 디스크 대응)
+pub fn apply_fixup(data: &mut [u8]) -> Result<(), FactError> {
+    if data.len() < 512 { return Ok(()); }
+    let signature = &data[0..4];
+    if !matches!(signature, b"FILE" | b"INDX" | b"RSTR" | b"RCRD") { return Ok(()); }
+    let usa_offset = u16::from_le_bytes([data[4], data[5]]) as usize;
+    let usa_count = u16::from_le_bytes([data[6], data[7]]) as usize;
+    if usa_offset == 0 || usa_count <= 1 || usa_offset + (usa_count * 2) > data.len() { return Ok(()); }
+    let update_seq_num = [data[usa_offset], data[usa_offset+1]];
+    let sector_count = usa_count - 1;
+    let sector_size = data.len() / sector_count;
+    if !sector_size.is_power_of_two() || sector_size < 256 {
+        return Err(FactError::ParseError { artifact_name: "Fixup".into(), details: format!("{} update sequence entries do not fit a {}-byte record", usa_count, data.len()) });
+    }
+    for i in 0..sector_count {
+        let sector_end = (i + 1) * sector_size - 2;
+        let fixup_idx = usa_offset + 2 + (i * 2);
+        if sector_end + 2 > data.len() || fixup_idx + 2 > data.len() { break; }
+        if data[sector_end] == update_seq_num[0] && data[sector_end+1] == update_seq_num[1] {
+            data[sector_end] = data[fixup_idx];
+            data[sector_end+1] = data[fixup_idx+1];
+        }
+    }
+    Ok(())
+}
 
 pub fn parse_file_record_header(data: &[u8]) -> Result<FileRecordHeader, FactError> {
     if data.len() < 48 { 
@@ -155,16 +188,647 @@ pub fn parse_index_record(data: &[u8]) -> Result<Vec<IndexEntry>, FactError> {
     Ok(Vec::new())
 }
 
-pub struct BootSector { pub bytes_per_sector: u16, pub sectors_per_cluster: u8, pub mft_lcn: u64 }
+/// 카빙한 $FILE_NAME 타임스탬프의 허용 범위 (1980-01-01 ~ 2100-01-01 FILETIME)
+const CARVE_MIN_FILETIME: u64 = 119_600_064_000_000_000;
+const CARVE_MAX_FILETIME: u64 = 157_469_184_000_000_000;
+
+/// slack 위치의 바이트가 이 디렉터리에 속한 온전한 $FILE_NAME 키인지 검증한다.
+fn carve_file_name(data: &[u8], offset: usize, end: usize, dir_reference: u64) -> Option<FileNameAttribute> {
+    if offset + 66 > end { return None; }
+    let name_length = data[offset + 64] as usize;
+    let namespace = data[offset + 65];
+    if name_length == 0 || namespace > 3 || offset + 66 + name_length * 2 > end { return None; }
+
+    // 부모 참조는 반드시 이 디렉터리여야 한다. (시퀀스 번호는 양쪽 모두 알려진 경우에만 비교)
+    let parent = u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    if mft_entry_number(parent) != mft_entry_number(dir_reference) { return None; }
+    let (parent_seq, dir_seq) = (mft_sequence_number(parent), mft_sequence_number(dir_reference));
+    if parent_seq != 0 && dir_seq != 0 && parent_seq != dir_seq { return None; }
+
+    let fn_attr = parse_file_name_attribute(&data[offset..offset + 66 + name_length * 2]).ok()?;
+    let times = [fn_attr.creation_time, fn_attr.modification_time, fn_attr.mft_modified_time, fn_attr.access_time];
+    if !times.iter().all(|t| (CARVE_MIN_FILETIME..CARVE_MAX_FILETIME).contains(t)) { return None; }
+    if fn_attr.name.chars().any(|c| c.is_control() || c == char::REPLACEMENT_CHARACTER || "\\/:*?\"<>|".contains(c)) { return None; }
+    Some(fn_attr)
+}
+
+/// 픽스업이 적용된 INDX 레코드에서 사용 영역 끝(total_size_of_entries)과 할당 크기 사이의 slack을 8바이트 단위로 스캔하여
+/// 이 디렉터리를 부모로 갖는 $FILE_NAME 키를 카빙한다.
+pub fn carve_index_slack(data: &[u8], dir_reference: u64) -> Vec<IndexSlackEntry> {
+    let mut carved = Vec::new();
+    if data.len() < 40 || &data[0..4] != b"INDX" { return carved; }
+
+    // 인덱스 노드 헤더(0x18)의 오프셋들은 헤더 시작 기준이다.
+    const NODE_HEADER: usize = 24;
+    let index_vcn = u64::from_le_bytes(data[16..24].try_into().unwrap());
+    let used = u32::from_le_bytes(data[28..32].try_into().unwrap()) as usize;
+    let allocated = u32::from_le_bytes(data[32..36].try_into().unwrap()) as usize;
+    let slack_start = (NODE_HEADER + used).next_multiple_of(8);
+    let slack_end = std::cmp::min(NODE_HEADER + allocated, data.len());
+
+    // 키는 엔트리 헤더(16바이트) 뒤에 오므로 8바이트 정렬 위치만 검사한다.
+    let mut offset = slack_start;
+    while offset + 66 <= slack_end {
+        let Some(file_name) = carve_file_name(data, offset, slack_end, dir_reference) else {
+            offset += 8;
+            continue;
+        };
+        let file_reference = (offset >= slack_start + 16)
+            .then(|| u64::from_le_bytes(data[offset - 16..offset - 8].try_into().unwrap()))
+            .filter(|r| mft_entry_number(*r) != 0);
+        let key_end = offset + 66 + file_name.name.encode_utf16().count() * 2;
+
+        carved.push(IndexSlackEntry {
+            directory_reference: dir_reference,
+            directory_path: String::new(),
+            file_reference,
+            file_name,
+            index_vcn,
+            offset,
+        });
+        offset = key_end.next_multiple_of(8);
+    }
+    carved
+}
+
+pub struct BootSector { pub bytes_per_sector: u16, pub sectors_per_cluster: u8, pub mft_lcn: u64, pub clusters_per_mft_record: i8, pub clusters_per_index_record: i8, pub volume_serial_number: u64 }
 impl BootSector {
     pub fn cluster_size(&self) -> u64 { (self.bytes_per_sector as u64) * (self.sectors_per_cluster as u64) }
     pub fn mft_offset(&self) -> u64 { self.mft_lcn * self.cluster_size() }
+    pub fn mft_record_size(&self) -> u64 { record_size_from_clusters(self.clusters_per_mft_record, self.cluster_size()) }
+    pub fn index_record_size(&self) -> u64 { record_size_from_clusters(self.clusters_per_index_record, self.cluster_size()) }
 }
+/// [Fix] 레코드 크기를 1024/4096으로 가정하지 않고 VBR 0x40/0x44 필드에서 읽는다. (4K FILE 레코드, 4Kn 디스크 대응)
 pub fn parse_boot_sector_manual(data: &[u8]) -> Result<BootSector, FactError> {
     if data.len() < 512 { return Err(FactError::ParseError { artifact_name: "VBR".into(), details: "Too small".into() }); }
-    Ok(BootSector { 
+    let boot = BootSector { 
         bytes_per_sector: u16::from_le_bytes([data[11], data[12]]), 
         sectors_per_cluster: data[13], 
-        mft_lcn: u64::from_le_bytes(data[48..56].try_into().unwrap()) 
+        mft_lcn: u64::from_le_bytes(data[48..56].try_into().unwrap()),
+        clusters_per_mft_record: data[0x40] as i8,
+        clusters_per_index_record: data[0x44] as i8,
+        volume_serial_number: u64::from_le_bytes(data[0x48..0x50].try_into().unwrap()),
+    };
+    let valid_size = |size: u64| size.is_power_of_two() && (256..=65536).contains(&size);
+    if !boot.bytes_per_sector.is_power_of_two() || boot.bytes_per_sector < 256 || boot.cluster_size() == 0 {
+        return Err(FactError::ParseError { artifact_name: "VBR".into(), details: format!("Invalid geometry ({} bytes/sector, {} sectors/cluster)", boot.bytes_per_sector, boot.sectors_per_cluster) });
+    }
+    if !valid_size(boot.mft_record_size()) || !valid_size(boot.index_record_size()) {
+        return Err(FactError::ParseError { artifact_name: "VBR".into(), details: format!("Invalid record size (MFT {}, INDX {})", boot.mft_record_size(), boot.index_record_size()) });
+    }
+    Ok(boot)
+}
+
+/// 상주(resident) 속성의 콘텐츠 영역을 반환한다.
+pub fn resident_content<'a>(record: &'a [u8], attr: &AttributeHeader) -> Option<&'a [u8]> {
+    if attr.non_resident_flag != 0 || attr.offset + 22 > record.len() { return None; }
+    let size = u32::from_le_bytes(record[attr.offset+16..attr.offset+20].try_into().unwrap()) as usize;
+    let offset = u16::from_le_bytes([record[attr.offset+20], record[attr.offset+21]]) as usize;
+    let start = attr.offset + offset;
+    let end = std::cmp::min(start + size, attr.offset + attr.length as usize);
+    if start > end || end > record.len() { return None; }
+    Some(&record[start..end])
+}
+
+pub fn parse_standard_information(data: &[u8]) -> Result<StandardInformation, FactError> {
+    if data.len() < 48 {
+        return Err(FactError::ParseError { artifact_name: "$STANDARD_INFORMATION".into(), details: "Data too small".into() });
+    }
+    Ok(StandardInformation {
+        creation_time: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+        modification_time: u64::from_le_bytes(data[8..16].try_into().unwrap()),
+        mft_modified_time: u64::from_le_bytes(data[16..24].try_into().unwrap()),
+        access_time: u64::from_le_bytes(data[24..32].try_into().unwrap()),
+        file_flags: u32::from_le_bytes(data[32..36].try_into().unwrap()),
+        max_versions: u32::from_le_bytes(data[36..40].try_into().unwrap()),
+        version_number: u32::from_le_bytes(data[40..44].try_into().unwrap()),
+        class_id: u32::from_le_bytes(data[44..48].try_into().unwrap()),
+    })
+}
+
+pub fn parse_file_name_attribute(data: &[u8]) -> Result<FileNameAttribute, FactError> {
+    if data.len() < 66 {
+        return Err(FactError::ParseError { artifact_name: "$FILE_NAME".into(), details: "Data too small".into() });
+    }
+    let name_length = data[64];
+    let name_end = 66 + name_length as usize * 2;
+    if name_end > data.len() {
+        return Err(FactError::ParseError { artifact_name: "$FILE_NAME".into(), details: "Name exceeds attribute".into() });
+    }
+    let u16_vec: Vec<u16> = data[66..name_end].chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+
+    Ok(FileNameAttribute {
+        parent_directory: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+        creation_time: u64::from_le_bytes(data[8..16].try_into().unwrap()),
+        modification_time: u64::from_le_bytes(data[16..24].try_into().unwrap()),
+        mft_modified_time: u64::from_le_bytes(data[24..32].try_into().unwrap()),
+        access_time: u64::from_le_bytes(data[32..40].try_into().unwrap()),
+        allocated_size: u64::from_le_bytes(data[40..48].try_into().unwrap()),
+        real_size: u64::from_le_bytes(data[48..56].try_into().unwrap()),
+        flags: u32::from_le_bytes(data[56..60].try_into().unwrap()),
+        name_length,
+        namespace: data[65],
+        name: String::from_utf16_lossy(&u16_vec),
     })
-}
\ No newline at end of file
+}
+
+/// 속성 헤더 뒤에 기록된 UTF-16 속성 이름 (이름 없는 속성이면 빈 문자열)
+pub fn attribute_name(record: &[u8], attr: &AttributeHeader) -> String {
+    if attr.name_length == 0 { return String::new(); }
+    let start = attr.offset + attr.name_offset as usize;
+    let end = std::cmp::min(start + attr.name_length as usize * 2, record.len());
+    if start >= end { return String::new(); }
+    let u16_vec: Vec<u16> = record[start..end].chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    String::from_utf16_lossy(&u16_vec)
+}
+
+fn parse_data_stream(record: &[u8], attr: &AttributeHeader) -> Option<DataStream> {
+    let name = attribute_name(record, attr);
+    if attr.non_resident_flag == 0 {
+        let content = resident_content(record, attr)?;
+        return Some(DataStream { name, is_resident: true, runlist: Vec::new(), real_size: content.len() as u64 });
+    }
+
+    let nr = parse_non_resident_header(&record[attr.offset..]).ok()?;
+    let start = attr.offset + nr.run_array_offset as usize;
+    let end = std::cmp::min(attr.offset + attr.length as usize, record.len());
+    let runlist = if nr.starting_vcn == 0 && start <= end { parse_runlist(&record[start..end]).unwrap_or_default() } else { Vec::new() };
+    Some(DataStream { name, is_resident: false, runlist, real_size: nr.real_size })
+}
+
+/// 픽스업이 적용된 FILE 레코드 하나를 디코딩한다.
+pub fn parse_mft_record(data: &[u8], entry_number: u64) -> Result<MftRecord, FactError> {
+    let header = parse_file_record_header(data)?;
+    if header.signature != "FILE" {
+        return Err(FactError::ParseError { artifact_name: "MFT".into(), details: format!("Bad signature '{}' at entry {}", header.signature, entry_number) });
+    }
+
+    let mut record = MftRecord {
+        entry_number,
+        sequence_number: header.sequence_number,
+        base_reference: header.base_file_record,
+        in_use: (header.flags & 0x01) != 0,
+        is_directory: (header.flags & 0x02) != 0,
+        standard_info: None,
+        file_names: Vec::new(),
+        data_streams: Vec::new(),
+    };
+
+    for attr in parse_attributes(data, &header)? {
+        match attr.type_code {
+            0x10 => {
+                if let Some(content) = resident_content(data, &attr) {
+                    record.standard_info = parse_standard_information(content).ok();
+                }
+            },
+            0x30 => {
+                if let Some(content) = resident_content(data, &attr)
+                    && let Ok(fn_attr) = parse_file_name_attribute(content) {
+                    record.file_names.push(fn_attr);
+                }
+            },
+            0x80 => {
+                if let Some(stream) = parse_data_stream(data, &attr) {
+                    record.data_streams.push(stream);
+                }
+            },
+            _ => {},
+        }
+    }
+    Ok(record)
+}
+
+/// $MFT 버퍼의 첫 레코드 헤더(bytes_allocated)에서 레코드 크기를 추정한다. 판단 불가 시 1024바이트.
+pub fn detect_record_size(data: &[u8]) -> usize {
+    if data.len() >= 32 && &data[0..4] == b"FILE" {
+        let allocated = u32::from_le_bytes(data[28..32].try_into().unwrap()) as usize;
+        if allocated.is_power_of_two() && (256..=65536).contains(&allocated) { return allocated; }
+    }
+    1024
+}
+
+/// 전체 $MFT 스트림을 순회하며 유효한 FILE 레코드를 디코딩하는 이터레이터 (빈 슬롯/BAAD 레코드는 건너뛴다)
+pub struct MftRecordIter<'a> {
+    data: &'a [u8],
+    record_size: usize,
+    index: u64,
+}
+
+impl<'a> MftRecordIter<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, record_size: detect_record_size(data), index: 0 }
+    }
+
+    pub fn with_record_size(data: &'a [u8], record_size: usize) -> Self {
+        Self { data, record_size, index: 0 }
+    }
+}
+
+impl Iterator for MftRecordIter<'_> {
+    type Item = MftRecord;
+
+    fn next(&mut self) -> Option<MftRecord> {
+        loop {
+            let start = (self.index as usize).checked_mul(self.record_size)?;
+            if start + self.record_size > self.data.len() { return None; }
+            let entry_number = self.index;
+            self.index += 1;
+
+            let raw = &self.data[start..start + self.record_size];
+            if &raw[0..4] != b"FILE" { continue; }
+
+            let mut buf = raw.to_vec();
+            if apply_fixup(&mut buf).is_err() { continue; }
+            if let Ok(record) = parse_mft_record(&buf, entry_number) {
+                return Some(record);
+            }
+        }
+    }
+}
+
+/// $MFT 스트림을 레코드 단위로 읽는 이터레이터. 레코드 크기는 첫 레코드 헤더로 판단하며, 전체 $MFT를 메모리에 올리지 않는다.
+pub struct MftRecordReader<R: Read> {
+    reader: BufReader<R>,
+    record_size: Option<usize>,
+    index: u64,
+}
+
+impl<R: Read> MftRecordReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader: BufReader::with_capacity(MFT_READ_BUFFER_SIZE, reader), record_size: None, index: 0 }
+    }
+
+    pub fn with_record_size(reader: R, record_size: usize) -> Self {
+        Self { reader: BufReader::with_capacity(MFT_READ_BUFFER_SIZE, reader), record_size: Some(record_size), index: 0 }
+    }
+
+    fn read_slot(&mut self) -> Option<Vec<u8>> {
+        let record_size = match self.record_size {
+            Some(size) => size,
+            None => {
+                let size = detect_record_size(self.reader.fill_buf().ok()?);
+                self.record_size = Some(size);
+                size
+            },
+        };
+        let mut buf = vec![0u8; record_size];
+        self.reader.read_exact(&mut buf).ok()?;
+        Some(buf)
+    }
+}
+
+impl<R: Read> Iterator for MftRecordReader<R> {
+    type Item = MftRecord;
+
+    fn next(&mut self) -> Option<MftRecord> {
+        loop {
+            let mut buf = self.read_slot()?;
+            let entry_number = self.index;
+            self.index += 1;
+
+            if &buf[0..4] != b"FILE" || apply_fixup(&mut buf).is_err() { continue; }
+            if let Ok(record) = parse_mft_record(&buf, entry_number) {
+                return Some(record);
+            }
+        }
+    }
+}
+
+const MFT_ROOT_ENTRY: u64 = 5;
+const MAX_PATH_DEPTH: usize = 255;
+
+/// 부모 디렉터리가 재사용되었거나(시퀀스 불일치) 존재하지 않는 파일의 경로 접두어
+pub const ORPHAN_PATH_PREFIX: &str = "\\$OrphanFiles";
+
+struct PathNode {
+    sequence_number: u16,
+    name: String,
+    parent_reference: u64,
+}
+
+/// 부모 참조를 따라 전체 경로를 재구성하는 테이블. 부모의 시퀀스 번호가 참조와 다르면 고아 경로로 처리한다.
+pub struct MftPathResolver {
+    nodes: HashMap<u64, PathNode>,
+    /// 베이스 레코드보다 먼저 나온 확장 레코드의 (이름, 부모 참조)
+    extension_names: HashMap<u64, (String, u64)>,
+    cache: HashMap<u64, String>,
+}
+
+impl Default for MftPathResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MftPathResolver {
+    pub fn new() -> Self {
+        Self { nodes: HashMap::new(), extension_names: HashMap::new(), cache: HashMap::new() }
+    }
+
+    /// 베이스 레코드의 대표 파일명으로 노드를 구성한다. 확장 레코드에만 존재하는 $FILE_NAME은 베이스 레코드로 병합한다.
+    pub fn from_records(records: &[MftRecord]) -> Self {
+        let mut resolver = Self::new();
+        for record in records {
+            resolver.add_record(record);
+        }
+        resolver
+    }
+
+    /// 레코드 하나를 경로 테이블에 추가한다. 스트림을 두 번 읽을 때 첫 번째 순회에서 호출하며,
+    /// 확장 레코드가 베이스 레코드보다 먼저 나와도 같은 결과가 되도록 이름을 보류해 둔다.
+    pub fn add_record(&mut self, record: &MftRecord) {
+        if record.base_reference != 0 {
+            let Some(fn_attr) = record.preferred_file_name() else { return };
+            let base_entry = mft_entry_number(record.base_reference);
+            match self.nodes.get_mut(&base_entry) {
+                Some(node) if node.name.is_empty() => {
+                    node.name = fn_attr.name.clone();
+                    node.parent_reference = fn_attr.parent_directory;
+                },
+                Some(_) => {},
+                None => {
+                    self.extension_names.entry(base_entry).or_insert_with(|| (fn_attr.name.clone(), fn_attr.parent_directory));
+                },
+            }
+            return;
+        }
+
+        let pending = self.extension_names.remove(&record.entry_number);
+        let (name, parent_reference) = match record.preferred_file_name() {
+            Some(fn_attr) => (fn_attr.name.clone(), fn_attr.parent_directory),
+            None => pending.unwrap_or_default(),
+        };
+        self.nodes.insert(record.entry_number, PathNode { sequence_number: record.sequence_number, name, parent_reference });
+    }
+
+    /// 엔트리 번호의 전체 경로 (예: \Windows\System32\cmd.exe)
+    pub fn resolve(&mut self, entry_number: u64) -> String {
+        if entry_number == MFT_ROOT_ENTRY { return String::new(); }
+        if let Some(path) = self.cache.get(&entry_number) { return path.clone(); }
+
+        // 루트까지 부모를 따라 올라가며 이름을 수집한다.
+        let mut components = Vec::new();
+        let mut current = entry_number;
+        let mut prefix = String::new();
+
+        for _ in 0..MAX_PATH_DEPTH {
+            if current == MFT_ROOT_ENTRY { break; }
+            if let Some(cached) = self.cache.get(&current) {
+                prefix = cached.clone();
+                break;
+            }
+            let Some(node) = self.nodes.get(&current) else {
+                prefix = ORPHAN_PATH_PREFIX.to_string();
+                break;
+            };
+            components.push(if node.name.is_empty() { format!("$Entry{}", current) } else { node.name.clone() });
+
+            let parent_entry = mft_entry_number(node.parent_reference);
+            if !self.parent_is_valid(node.parent_reference) || parent_entry == current {
+                prefix = ORPHAN_PATH_PREFIX.to_string();
+                break;
+            }
+            current = parent_entry;
+        }
+
+        let mut path = prefix;
+        for component in components.iter().rev() {
+            path.push('\\');
+            path.push_str(component);
+        }
+        self.cache.insert(entry_number, path.clone());
+        path
+    }
+
+    /// 특정 $FILE_NAME 속성(하드링크 포함)이 가리키는 경로
+    pub fn resolve_file_name(&mut self, fn_attr: &FileNameAttribute) -> String {
+        let parent_path = if self.parent_is_valid(fn_attr.parent_directory) {
+            self.resolve(mft_entry_number(fn_attr.parent_directory))
+        } else {
+            ORPHAN_PATH_PREFIX.to_string()
+        };
+        format!("{}\\{}", parent_path, fn_attr.name)
+    }
+
+    fn parent_is_valid(&self, parent_reference: u64) -> bool {
+        let sequence = mft_sequence_number(parent_reference);
+        match self.nodes.get(&mft_entry_number(parent_reference)) {
+            Some(parent) => sequence == 0 || parent.sequence_number == sequence,
+            None => mft_entry_number(parent_reference) == MFT_ROOT_ENTRY,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIR_REFERENCE: u64 = (2 << 48) | 40;
+    /// 2024-03-01T09:00 (FILETIME)
+    const FILE_TIME: u64 = (1_709_283_600 + 11_644_473_600) * 10_000_000;
+
+    fn put_u16(data: &mut [u8], offset: usize, value: u16) {
+        data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn file_name_key(parent: u64, name: &str) -> Vec<u8> {
+        let name: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let mut key = vec![0u8; 66];
+        key[0..8].copy_from_slice(&parent.to_le_bytes());
+        for field in 0..4 {
+            key[8 + field * 8..16 + field * 8].copy_from_slice(&FILE_TIME.to_le_bytes());
+        }
+        key[64] = (name.len() / 2) as u8;
+        key[65] = 1;
+        key.extend(name);
+        key
+    }
+
+    /// 인덱스 엔트리 (파일 참조, 엔트리 길이, 키 길이, 플래그 + $FILE_NAME 키)
+    fn index_entry(reference: u64, name: &str) -> Vec<u8> {
+        let key = file_name_key(DIR_REFERENCE, name);
+        let mut entry = vec![0u8; 16];
+        entry[0..8].copy_from_slice(&reference.to_le_bytes());
+        put_u16(&mut entry, 10, key.len() as u16);
+        entry.extend(key);
+        entry.resize(entry.len().next_multiple_of(8), 0);
+        let length = entry.len() as u16;
+        put_u16(&mut entry, 8, length);
+        entry
+    }
+
+    fn end_entry() -> Vec<u8> {
+        let mut entry = vec![0u8; 16];
+        put_u16(&mut entry, 8, 16);
+        entry[12] = 0x02;
+        entry
+    }
+
+    /// 4KB INDX 레코드: 노드 헤더(0x18) 뒤 0x28에 USA, 0x40부터 현재 엔트리, 사용 영역 뒤 slack
+    fn indx_block(live: &[Vec<u8>], slack: &[Vec<u8>]) -> Vec<u8> {
+        let mut block = vec![0u8; 4096];
+        block[0..4].copy_from_slice(b"INDX");
+        put_u16(&mut block, 4, 0x28);
+        put_u16(&mut block, 6, 9);
+        block[16..24].copy_from_slice(&3u64.to_le_bytes());
+        put_u32(&mut block, 24, 0x40 - 24);
+        let mut offset = 0x40;
+        for entry in live.iter().chain([&end_entry()]) {
+            block[offset..offset + entry.len()].copy_from_slice(entry);
+            offset += entry.len();
+        }
+        put_u32(&mut block, 28, (offset - 24) as u32);
+        put_u32(&mut block, 32, (4096 - 24) as u32);
+        for bytes in slack {
+            block[offset..offset + bytes.len()].copy_from_slice(bytes);
+            offset += bytes.len();
+        }
+        block
+    }
+
+    #[test]
+    fn index_slack_carves_only_keys_of_this_directory() {
+        let live = index_entry((1 << 48) | 70, "keep.txt");
+        let stale = index_entry((4 << 48) | 80, "deleted.txt");
+        let other_parent = {
+            let mut entry = index_entry((1 << 48) | 81, "elsewhere.txt");
+            entry[16..24].copy_from_slice(&((2u64 << 48) | 41).to_le_bytes());
+            entry
+        };
+        let bare_key = file_name_key(DIR_REFERENCE, "orphan.tmp");
+        let block = indx_block(std::slice::from_ref(&live), &[stale, other_parent, vec![0; 40], bare_key]);
+
+        // 사용 영역의 엔트리는 현재 엔트리이며 카빙 대상이 아니다.
+        let entries = parse_index_record(&block).unwrap();
+        assert_eq!(entries.iter().map(|e| e.filename.as_str()).collect::<Vec<_>>(), ["keep.txt"]);
+
+        let carved = carve_index_slack(&block, DIR_REFERENCE);
+        let names: Vec<&str> = carved.iter().map(|e| e.file_name.name.as_str()).collect();
+        assert_eq!(names, ["deleted.txt", "orphan.tmp"]);
+        assert_eq!(carved[0].file_reference, Some((4 << 48) | 80));
+        assert_eq!(carved[0].offset, 0x40 + live.len() + end_entry().len() + 16);
+        assert_eq!(carved[0].index_vcn, 3);
+        assert_eq!(carved[0].file_name.creation_time, FILE_TIME);
+        // 엔트리 헤더가 남지 않은 키는 참조를 알 수 없다.
+        assert_eq!(carved[1].file_reference, None);
+    }
+
+    #[test]
+    fn index_slack_reports_relocated_live_copy_for_the_caller_to_filter() {
+        // B+ 트리 재배치로 slack에 남은 현재 엔트리의 복사본은 레코드 단독으로는 구분할 수 없다.
+        let live = index_entry((1 << 48) | 70, "keep.txt");
+        let block = indx_block(std::slice::from_ref(&live), std::slice::from_ref(&live));
+        let carved = carve_index_slack(&block, DIR_REFERENCE);
+        assert_eq!(carved.len(), 1);
+        assert_eq!(carved[0].file_reference, Some((1 << 48) | 70));
+
+        // 다른 시퀀스의 디렉터리(재사용된 엔트리)로는 카빙하지 않는다.
+        assert!(carve_index_slack(&block, (3 << 48) | 40).is_empty());
+        assert!(carve_index_slack(&block[..32], DIR_REFERENCE).is_empty());
+    }
+
+    /// FILE 레코드: USA는 0x30, 속성은 USA 뒤 8바이트 정렬 위치부터. names는 (부모 참조, 이름) 목록
+    fn file_record(record_size: usize, sequence: u16, base: u64, names: &[(u64, &str)]) -> Vec<u8> {
+        let sectors = record_size / 512;
+        let attr_offset = (0x30 + (sectors + 1) * 2).next_multiple_of(8);
+        let mut record = vec![0u8; record_size];
+        record[0..4].copy_from_slice(b"FILE");
+        put_u16(&mut record, 4, 0x30);
+        put_u16(&mut record, 6, (sectors + 1) as u16);
+        put_u16(&mut record, 16, sequence);
+        put_u16(&mut record, 20, attr_offset as u16);
+        put_u16(&mut record, 22, 0x01);
+        put_u32(&mut record, 28, record_size as u32);
+        record[32..40].copy_from_slice(&base.to_le_bytes());
+
+        let mut offset = attr_offset;
+        for &(parent, name) in names {
+            let content = file_name_key(parent, name);
+            let length = (24 + content.len()).next_multiple_of(8);
+            put_u32(&mut record, offset, 0x30);
+            put_u32(&mut record, offset + 4, length as u32);
+            put_u32(&mut record, offset + 16, content.len() as u32);
+            put_u16(&mut record, offset + 20, 24);
+            record[offset + 24..offset + 24 + content.len()].copy_from_slice(&content);
+            offset += length;
+        }
+        put_u32(&mut record, offset, 0xFFFF_FFFF);
+        put_u32(&mut record, 24, (offset + 8) as u32);
+
+        put_u16(&mut record, 0x30, 1);
+        for sector in 0..sectors {
+            let end = (sector + 1) * 512 - 2;
+            record.copy_within(end..end + 2, 0x32 + sector * 2);
+            put_u16(&mut record, end, 1);
+        }
+        record
+    }
+
+    #[test]
+    fn record_reader_detects_4k_records_and_skips_unusable_slots() {
+        const SIZE: usize = 4096;
+        // 이름이 섹터 경계(0x1FE)를 가로지르도록 긴 파일명을 쓴다.
+        let long_name = "x".repeat(200);
+        let mut stream = file_record(SIZE, 1, 0, &[((5 << 48) | 5, "$MFT")]);
+        stream.extend(vec![0u8; SIZE]);
+        let mut bad = file_record(SIZE, 1, 0, &[]);
+        bad[0..4].copy_from_slice(b"BAAD");
+        stream.extend(bad);
+        stream.extend(file_record(SIZE, 7, 0, &[((5 << 48) | 5, &long_name)]));
+        stream.extend(file_record(SIZE, 1, 0, &[])[..SIZE / 2].to_vec());
+
+        let records: Vec<MftRecord> = MftRecordReader::new(std::io::Cursor::new(&stream)).collect();
+        assert_eq!(records.iter().map(|r| r.entry_number).collect::<Vec<_>>(), [0, 3]);
+        assert_eq!(records[1].sequence_number, 7);
+        assert_eq!(records[1].file_names[0].name, long_name);
+        assert_eq!(records[1].reference(), (7 << 48) | 3);
+
+        let iterated: Vec<u64> = MftRecordIter::new(&stream).map(|r| r.entry_number).collect();
+        assert_eq!(iterated, [0, 3]);
+        // 1KB로 잘못 지정하면 4KB 레코드의 보호 간격이 맞지 않아 어떤 슬롯도 디코딩되지 않는다.
+        assert_eq!(MftRecordReader::with_record_size(std::io::Cursor::new(&stream), 1024).count(), 0);
+    }
+
+    #[test]
+    fn path_resolver_marks_missing_and_reused_parents_as_orphans() {
+        let root = (5 << 48) | 5;
+        let users = (2 << 48) | 30;
+        let mut stream = vec![0u8; 5 * 1024];
+        let mut slot = |entry: usize, record: Vec<u8>| {
+            let end = (entry + 1) * 1024;
+            if stream.len() < end { stream.resize(end, 0); }
+            stream[entry * 1024..end].copy_from_slice(&record);
+        };
+        slot(5, file_record(1024, 5, 0, &[(root, ".")]));
+        slot(30, file_record(1024, 2, 0, &[(root, "Users")]));
+        slot(31, file_record(1024, 1, 0, &[(users, "a.txt")]));
+        // 존재하지 않는 부모, 재사용되어 시퀀스가 다른 부모, 자기 자신을 부모로 가리키는 손상 레코드
+        slot(32, file_record(1024, 1, 0, &[((1 << 48) | 99, "b.txt")]));
+        slot(33, file_record(1024, 1, 0, &[((1 << 48) | 30, "c.txt")]));
+        slot(35, file_record(1024, 1, 0, &[((1 << 48) | 35, "loop.txt")]));
+        // 확장 레코드(엔트리 40)가 $FILE_NAME을 갖고, 베이스 레코드(34)보다 먼저 추가된다.
+        slot(40, file_record(1024, 1, (1 << 48) | 34, &[(users, "ext.bin")]));
+        slot(34, file_record(1024, 1, 0, &[]));
+
+        let mut records: Vec<MftRecord> = MftRecordReader::with_record_size(std::io::Cursor::new(&stream), 1024).collect();
+        records.sort_by_key(|r| std::cmp::Reverse(r.base_reference));
+        let mut resolver = MftPathResolver::from_records(&records);
+
+        assert_eq!(resolver.resolve(5), "");
+        assert_eq!(resolver.resolve(31), "\\Users\\a.txt");
+        assert_eq!(resolver.resolve(32), "\\$OrphanFiles\\b.txt");
+        assert_eq!(resolver.resolve(33), "\\$OrphanFiles\\c.txt");
+        assert_eq!(resolver.resolve(35), "\\$OrphanFiles\\loop.txt");
+        assert_eq!(resolver.resolve(34), "\\Users\\ext.bin");
+        assert_eq!(resolver.resolve(77), "\\$OrphanFiles");
+
+        let stale = records.iter().find(|r| r.entry_number == 33).unwrap().file_names[0].clone();
+        assert_eq!(resolver.resolve_file_name(&stale), "\\$OrphanFiles\\c.txt");
+        let live = records.iter().find(|r| r.entry_number == 31).unwrap().file_names[0].clone();
+        assert_eq!(resolver.resolve_file_name(&live), "\\Users\\a.txt");
+    }
+}
@@ -1,84 +1,160 @@
 use anyhow::Result;
-use chrono::Utc;
 use models::event::{ForensicEvent, PersistenceEvent};
-use std::collections::HashSet;
+use crate::registry::HiveParser;
 
-pub fn parse_ntuser_run_keys(data: &[u8], filename: &str) -> Result<Vec<ForensicEvent>> {
+/// 사용자 하이브의 자동 실행 위치: (HKCU 기준 키 경로, 값 이름 (None이면 키의 모든 값), 표시 이름)
+const AUTOSTART_LOCATIONS: &[(&str, Option<&str>, &str)] = &[
+    ("Software\\Microsoft\\Windows\\CurrentVersion\\Run", None, "Run Key"),
+    ("Software\\Microsoft\\Windows\\CurrentVersion\\RunOnce", None, "RunOnce Key"),
+    ("Software\\Wow6432Node\\Microsoft\\Windows\\CurrentVersion\\Run", None, "Run Key (32-bit)"),
+    ("Software\\Wow6432Node\\Microsoft\\Windows\\CurrentVersion\\RunOnce", None, "RunOnce Key (32-bit)"),
+    ("Software\\Microsoft\\Windows\\CurrentVersion\\Policies\\Explorer\\Run", None, "Policies Explorer Run"),
+    ("Software\\Microsoft\\Windows NT\\CurrentVersion\\Windows", Some("Load"), "Windows Load"),
+    ("Software\\Microsoft\\Windows NT\\CurrentVersion\\Windows", Some("Run"), "Windows Run"),
+    ("Software\\Microsoft\\Windows NT\\CurrentVersion\\Winlogon", Some("Shell"), "Winlogon Shell"),
+    ("Environment", Some("UserInitMprLogonScript"), "Logon Script"),
+    ("Software\\Microsoft\\Command Processor", Some("AutoRun"), "Command Processor AutoRun"),
+];
+
+/// 프로필 경로를 기록한 Shell Folders 값 (예: AppData = C:\Users\bob\AppData\Roaming)
+const SHELL_FOLDERS: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\Shell Folders";
+
+/// 프로필 경로에서 사용자 이름을 꺼낸다. 예: "Users\bob\NTUSER.DAT", "C:\Users\bob\AppData\Roaming" -> "bob"
+pub fn profile_user(path: &str) -> Option<String> {
+    let components: Vec<&str> = path.split('\\').collect();
+    components.iter().position(|c| c.eq_ignore_ascii_case("Users"))
+        .and_then(|i| components.get(i + 1))
+        .filter(|user| !user.is_empty() && !user.eq_ignore_ascii_case("NTUSER.DAT"))
+        .map(|user| user.to_string())
+}
+
+/// 하이브 소유 사용자: 수집 이름("bob_NTUSER.DAT" 또는 프로필 경로)을 우선하고,
+/// 알 수 없으면 하이브의 Shell Folders에 기록된 프로필 경로를 쓴다.
+fn hive_owner(filename: &str, hive: &HiveParser) -> Option<String> {
+    let from_name = filename.len().checked_sub("_NTUSER.DAT".len())
+        .filter(|&split| filename.is_char_boundary(split) && filename[split..].eq_ignore_ascii_case("_NTUSER.DAT"))
+        .map(|split| filename[..split].to_string())
+        .filter(|user| !user.is_empty() && !user.contains('\\'));
+    from_name
+        .or_else(|| profile_user(filename))
+        .or_else(|| {
+            let values = hive.get_values(hive.find_key(SHELL_FOLDERS)?);
+            ["AppData", "Desktop", "Personal"].iter()
+                .filter_map(|name| values.iter().find(|v| v.name.eq_ignore_ascii_case(name)))
+                .find_map(|v| profile_user(&v.data_string))
+        })
+}
+
+/// [추가] NTUSER.DAT를 하이브로 해석하여 사용자 자동 실행 항목(Run/RunOnce, Windows\Load, Winlogon\Shell,
+/// Environment\UserInitMprLogonScript 등)을 추출한다. 값별 기록 시각은 없으므로 키의 마지막 수정 시각을 쓴다.
+pub fn parse_ntuser_autostarts(data: &[u8], filename: &str) -> Result<Vec<ForensicEvent>> {
+    let hive = HiveParser::new(data)?;
+    let user = hive_owner(filename, &hive);
     let mut events = Vec::new();
-    
-    // 1. 바이트 슬라이싱 패닉을 방지하기 위해 데이터를 순수 char 벡터로 변환
-    let u16_data: Vec<u16> = data.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
-    let chars: Vec<char> = std::char::decode_utf16(u16_data)
-        .map(|r| r.unwrap_or('\u{FFFD}'))
-        .collect();
-    
-    let lower_chars: Vec<char> = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
-    
-    // 검색할 확장자 타겟을 char 배열로 정의
-    let targets = [
-        vec!['.', 'e', 'x', 'e'],
-        vec!['.', 'b', 'a', 't'],
-        vec!['.', 'p', 's', '1'],
-        vec!['.', 'v', 'b', 's'],
-    ];
-    
-    let mut extracted = HashSet::new();
-    let mut i = 0;
-
-    // 2. 바이트가 아닌 char 인덱스 기반 탐색 (100% 안전)
-    while i < chars.len() {
-        let mut matched_len = 0;
-        for target in &targets {
-            if i + target.len() <= chars.len() && &lower_chars[i..i + target.len()] == target.as_slice() {
-                matched_len = target.len();
-                break;
-            }
-        }
 
-        if matched_len > 0 {
-            // 일치하는 확장자를 찾았으므로 경로의 시작점(뒤로 가기) 찾기
-            let mut start = i;
-            while start > 0 {
-                let ch = chars[start - 1];
-                // 경로에 사용될 수 있는 아스키 문자와 공백만 허용
-                if !ch.is_ascii_graphic() && ch != ' ' {
-                    break;
-                }
-                start -= 1;
-            }
-
-            // 인자(Arguments)를 포함하기 위해 경로의 끝점(앞으로 가기) 찾기
-            let mut end = i + matched_len;
-            while end < chars.len() {
-                let ch = chars[end];
-                if ch == '\0' || ch == '\n' || ch == '\r' || (!ch.is_ascii_graphic() && ch != ' ') {
-                    break;
-                }
-                end += 1;
-            }
-
-            if start < end {
-                // char 벡터를 안전하게 String으로 묶어냄
-                let extracted_path: String = chars[start..end].iter().collect();
-                let trimmed = extracted_path.trim().to_string();
-                
-                // C:\ 와 같은 드라이브 문자열이 포함된 정상적인 경로인지 검증
-                if trimmed.contains(":\\") && !extracted.contains(&trimmed) {
-                    extracted.insert(trimmed.clone());
-                    events.push(ForensicEvent::Persistence(PersistenceEvent {
-                        timestamp: Utc::now(),
-                        persistence_type: "Registry Run Key (NTUSER.DAT)".to_string(),
-                        target_name: filename.split('\\').last().unwrap_or(filename).to_string(),
-                        target_path: trimmed,
-                        source_artifact: format!("Registry: {}", filename),
-                    }));
-                }
-            }
-            i += matched_len;
-        } else {
-            i += 1;
+    for (path, value_name, description) in AUTOSTART_LOCATIONS {
+        let Some(key_off) = hive.find_key(path) else { continue };
+        let Some(timestamp) = hive.get_key_last_write(key_off).or_else(|| hive.last_written()) else { continue };
+
+        for val in hive.get_values(key_off) {
+            if value_name.is_some_and(|name| !val.name.eq_ignore_ascii_case(name)) { continue; }
+            // Load/Shell 등은 기본값이 빈 문자열로 존재한다.
+            if val.data_string.is_empty() { continue; }
+            events.push(ForensicEvent::Persistence(PersistenceEvent {
+                timestamp,
+                persistence_type: format!("{} (NTUSER.DAT)", description),
+                target_name: val.name,
+                target_path: val.data_string,
+                source_artifact: format!("NTUSER.DAT ({}): HKCU\\{}", user.as_deref().unwrap_or(filename), path),
+                user: user.clone(),
+            }));
         }
     }
-    
+
     Ok(events)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::REG_EXPAND_SZ;
+    use crate::test_hive::{HiveBuilder, filetime, utf16z};
+    use models::mft::StandardInformation;
+
+    const RUN: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+    const WINLOGON: &str = "Software\\Microsoft\\Windows NT\\CurrentVersion\\Winlogon";
+    const WINDOWS: &str = "Software\\Microsoft\\Windows NT\\CurrentVersion\\Windows";
+
+    fn ntuser_hive(profile: Option<&str>) -> Vec<u8> {
+        let mut hive = HiveBuilder::new(filetime("2024-03-02T00:00:00Z"));
+        hive.key(RUN, filetime("2024-03-01T09:00:00Z"))
+            .string(RUN, "OneDrive", "\"C:\\Users\\kim\\AppData\\Local\\Microsoft\\OneDrive\\OneDrive.exe\" /background")
+            .value(RUN, "Updater", REG_EXPAND_SZ, &utf16z("%APPDATA%\\updater.exe"))
+            .key(WINLOGON, filetime("2024-03-01T09:05:00Z"))
+            .string(WINLOGON, "Shell", "explorer.exe, C:\\ProgramData\\shell.exe")
+            .string(WINLOGON, "ExcludeProfileDirs", "AppData\\Local")
+            // 기본으로 존재하는 빈 Load 값은 보고하지 않는다.
+            .string(WINDOWS, "Load", "")
+            .string("Environment", "UserInitMprLogonScript", "C:\\Users\\kim\\logon.bat")
+            .string("Environment", "TEMP", "%USERPROFILE%\\AppData\\Local\\Temp")
+            // 실행 파일 경로가 들어 있어도 자동 실행 위치가 아니면 무시한다. (이전 문자열 카빙의 오탐)
+            .string("Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\RunMRU", "a", "C:\\Tools\\mimikatz.exe\\1");
+        if let Some(profile) = profile {
+            hive.string(SHELL_FOLDERS, "AppData", &format!("{}\\AppData\\Roaming", profile));
+        }
+        hive.build()
+    }
+
+    fn persistence(events: &[ForensicEvent]) -> Vec<&PersistenceEvent> {
+        events.iter().filter_map(|e| match e {
+            ForensicEvent::Persistence(p) => Some(p),
+            _ => None,
+        }).collect()
+    }
+
+    #[test]
+    fn autostarts_carry_key_time_and_owning_user() {
+        let events = parse_ntuser_autostarts(&ntuser_hive(None), "kim_NTUSER.DAT").unwrap();
+        let events = persistence(&events);
+        let names: Vec<(&str, &str)> = events.iter().map(|e| (e.persistence_type.as_str(), e.target_name.as_str())).collect();
+        assert_eq!(names, [
+            ("Run Key (NTUSER.DAT)", "OneDrive"),
+            ("Run Key (NTUSER.DAT)", "Updater"),
+            ("Winlogon Shell (NTUSER.DAT)", "Shell"),
+            ("Logon Script (NTUSER.DAT)", "UserInitMprLogonScript"),
+        ]);
+        assert!(events.iter().all(|e| e.user.as_deref() == Some("kim")));
+
+        let updater = events[1];
+        assert_eq!(updater.target_path, "%APPDATA%\\updater.exe");
+        assert_eq!(updater.timestamp, StandardInformation::to_datetime(filetime("2024-03-01T09:00:00Z")));
+        assert_eq!(updater.source_artifact, format!("NTUSER.DAT (kim): HKCU\\{}", RUN));
+        let shell = events[2];
+        assert_eq!(shell.target_path, "explorer.exe, C:\\ProgramData\\shell.exe");
+        assert_eq!(shell.timestamp, StandardInformation::to_datetime(filetime("2024-03-01T09:05:00Z")));
+    }
+
+    #[test]
+    fn owning_user_falls_back_to_profile_path_and_shell_folders() {
+        let from_path = parse_ntuser_autostarts(&ntuser_hive(None), "C:\\Users\\lee\\NTUSER.DAT").unwrap();
+        assert!(persistence(&from_path).iter().all(|e| e.user.as_deref() == Some("lee")));
+
+        let from_hive = parse_ntuser_autostarts(&ntuser_hive(Some("C:\\Users\\park")), "NTUSER.DAT").unwrap();
+        let from_hive = persistence(&from_hive);
+        assert!(from_hive.iter().all(|e| e.user.as_deref() == Some("park")));
+        assert!(from_hive[0].source_artifact.starts_with("NTUSER.DAT (park): "));
+
+        let unknown = parse_ntuser_autostarts(&ntuser_hive(None), "NTUSER.DAT").unwrap();
+        assert!(persistence(&unknown).iter().all(|e| e.user.is_none() && e.source_artifact.starts_with("NTUSER.DAT (NTUSER.DAT): ")));
+
+        assert!(parse_ntuser_autostarts(b"not a hive", "kim_NTUSER.DAT").is_err());
+    }
+
+    #[test]
+    fn profile_user_from_paths() {
+        assert_eq!(profile_user("Users\\bob\\NTUSER.DAT").as_deref(), Some("bob"));
+        assert_eq!(profile_user("C:\\users\\bob\\AppData\\Roaming").as_deref(), Some("bob"));
+        assert_eq!(profile_user("Users\\NTUSER.DAT"), None);
+        assert_eq!(profile_user("Windows\\System32\\config\\SYSTEM"), None);
+    }
+}
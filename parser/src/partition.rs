@@ -0,0 +1,151 @@
+use models::FactError;
+
+/// MBR 파티션 테이블의 단일 엔트리 (LBA는 디스크 논리 섹터 단위)
+#[derive(Debug, Clone)]
+pub struct MbrEntry {
+    pub slot: usize,
+    pub status: u8,
+    pub partition_type: u8,
+    pub start_lba: u32,
+    pub sector_count: u32,
+}
+
+impl MbrEntry {
+    pub fn is_extended(&self) -> bool {
+        matches!(self.partition_type, 0x05 | 0x0F | 0x85)
+    }
+
+    pub fn is_protective(&self) -> bool {
+        self.partition_type == 0xEE
+    }
+
+    pub fn type_description(&self) -> &'static str {
+        match self.partition_type {
+            0x07 => "NTFS/exFAT/HPFS",
+            0x0B | 0x0C => "FAT32",
+            0x27 => "Windows Recovery (Hidden NTFS)",
+            0x05 | 0x0F | 0x85 => "Extended",
+            0x82 => "Linux Swap",
+            0x83 => "Linux",
+            0xEE => "GPT Protective",
+            0xEF => "EFI System",
+            _ => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GptHeader {
+    pub current_lba: u64,
+    pub first_usable_lba: u64,
+    pub last_usable_lba: u64,
+    pub partition_entry_lba: u64,
+    pub num_partition_entries: u32,
+    pub partition_entry_size: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct GptEntry {
+    pub slot: usize,
+    pub type_guid: String,
+    pub unique_guid: String,
+    pub first_lba: u64,
+    pub last_lba: u64,
+    pub attributes: u64,
+    pub name: String,
+}
+
+impl GptEntry {
+    pub fn type_description(&self) -> &'static str {
+        match self.type_guid.as_str() {
+            "EBD0A0A2-B9E5-4433-87C0-68B6B72699C7" => "Microsoft Basic Data",
+            "C12A7328-F81F-11D2-BA4B-00A0C93EC93B" => "EFI System",
+            "E3C9E316-0B5C-4DB8-817D-F92DF00215AE" => "Microsoft Reserved",
+            "DE94BBA4-06D1-4D40-A16A-BFD50179D6AC" => "Windows Recovery",
+            "5808C8AA-7E8F-42E0-85D2-E1E90434CFB3" => "LDM Metadata",
+            "AF9B60A0-1431-4F62-BC68-3311714A69AD" => "LDM Data",
+            _ => "Unknown",
+        }
+    }
+}
+
+/// 첫 섹터(MBR 또는 EBR)의 파티션 테이블 4개 슬롯을 파싱한다. 비어있는 슬롯은 제외한다.
+pub fn parse_mbr(sector: &[u8]) -> Result<Vec<MbrEntry>, FactError> {
+    if sector.len() < 512 {
+        return Err(FactError::ParseError { artifact_name: "MBR".into(), details: "Sector too small".into() });
+    }
+    if sector[510] != 0x55 || sector[511] != 0xAA {
+        return Err(FactError::ParseError { artifact_name: "MBR".into(), details: "Missing 0x55AA boot signature".into() });
+    }
+
+    let mut entries = Vec::new();
+    for slot in 0..4 {
+        let base = 446 + slot * 16;
+        let partition_type = sector[base + 4];
+        let start_lba = u32::from_le_bytes(sector[base+8..base+12].try_into().unwrap());
+        let sector_count = u32::from_le_bytes(sector[base+12..base+16].try_into().unwrap());
+        if partition_type == 0 || sector_count == 0 { continue; }
+
+        entries.push(MbrEntry { slot, status: sector[base], partition_type, start_lba, sector_count });
+    }
+    Ok(entries)
+}
+
+/// LBA 1에 위치한 GPT 헤더("EFI PART")를 파싱한다.
+pub fn parse_gpt_header(data: &[u8]) -> Result<GptHeader, FactError> {
+    if data.len() < 92 || &data[0..8] != b"EFI PART" {
+        return Err(FactError::ParseError { artifact_name: "GPT".into(), details: "Missing EFI PART signature".into() });
+    }
+    Ok(GptHeader {
+        current_lba: u64::from_le_bytes(data[24..32].try_into().unwrap()),
+        first_usable_lba: u64::from_le_bytes(data[40..48].try_into().unwrap()),
+        last_usable_lba: u64::from_le_bytes(data[48..56].try_into().unwrap()),
+        partition_entry_lba: u64::from_le_bytes(data[72..80].try_into().unwrap()),
+        num_partition_entries: u32::from_le_bytes(data[80..84].try_into().unwrap()),
+        partition_entry_size: u32::from_le_bytes(data[84..88].try_into().unwrap()),
+    })
+}
+
+/// GPT 파티션 엔트리 배열을 파싱한다. 타입 GUID가 0인 미사용 엔트리는 제외한다.
+pub fn parse_gpt_entries(data: &[u8], entry_size: usize) -> Result<Vec<GptEntry>, FactError> {
+    if entry_size < 128 {
+        return Err(FactError::ParseError { artifact_name: "GPT".into(), details: format!("Invalid entry size {}", entry_size) });
+    }
+
+    let mut entries = Vec::new();
+    for (slot, raw) in data.chunks_exact(entry_size).enumerate() {
+        if raw[0..16].iter().all(|&b| b == 0) { continue; }
+
+        let u16_name: Vec<u16> = raw[56..128].chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&c| c != 0)
+            .collect();
+
+        entries.push(GptEntry {
+            slot,
+            type_guid: format_guid(&raw[0..16]),
+            unique_guid: format_guid(&raw[16..32]),
+            first_lba: u64::from_le_bytes(raw[32..40].try_into().unwrap()),
+            last_lba: u64::from_le_bytes(raw[40..48].try_into().unwrap()),
+            attributes: u64::from_le_bytes(raw[48..56].try_into().unwrap()),
+            name: String::from_utf16_lossy(&u16_name),
+        });
+    }
+    Ok(entries)
+}
+
+/// OEM ID 필드("NTFS    ")로 NTFS VBR 여부를 판별한다.
+pub fn is_ntfs_vbr(sector: &[u8]) -> bool {
+    sector.len() >= 512 && &sector[3..11] == b"NTFS    " && sector[510] == 0x55 && sector[511] == 0xAA
+}
+
+/// 혼합 엔디안(Data1~3 리틀, Data4 빅) GUID를 표준 문자열로 변환한다.
+pub fn format_guid(b: &[u8]) -> String {
+    format!(
+        "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+        u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        u16::from_le_bytes([b[4], b[5]]),
+        u16::from_le_bytes([b[6], b[7]]),
+        b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]
+    )
+}
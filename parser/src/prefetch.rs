@@ -1,12 +1,11 @@
 use anyhow::{Result, bail};
 use chrono::{DateTime, Utc};
 use models::mft::StandardInformation;
+use crate::compression;
 
-#[link(name = "ntdll")]
-unsafe extern "system" {
-    fn RtlGetCompressionWorkSpaceSize(CompressionFormatAndEngine: u16, CompressBufferWorkSpaceSize: *mut u32, CompressFragmentWorkSpaceSize: *mut u32) -> i32;
-    fn RtlDecompressBufferEx(CompressionFormat: u16, UncompressedBuffer: *mut u8, UncompressedBufferSize: u32, CompressedBuffer: *const u8, CompressedBufferSize: u32, FinalUncompressedSize: *mut u32, WorkSpace: *mut u8) -> i32;
-}
+const COMPRESSION_FORMAT_LZNT1: u8 = 2;
+const COMPRESSION_FORMAT_XPRESS: u8 = 3;
+const COMPRESSION_FORMAT_XPRESS_HUFF: u8 = 4;
 
 #[derive(Debug, Clone)]
 pub struct PrefetchInfo {
@@ -16,29 +15,26 @@ pub struct PrefetchInfo {
     pub referenced_files: Vec<String>,
 }
 
+/// MAM 컨테이너: 형식(data[3] & 0x7F, 0x80 = 체크섬 포함)과 원본 크기 뒤에 압축 데이터가 온다.
+/// [Fix] ntdll(RtlDecompressBufferEx) 의존을 제거하여 오프라인 분석 환경에서도 해제한다.
 fn decompress_mam(data: &[u8]) -> Result<Vec<u8>> {
     if data.len() < 8 { bail!("Data too small"); }
-    let mut compression_format = data[3] as u16;
-    let has_checksum = (compression_format & 0x80) != 0;
-    compression_format &= 0x7F;
+    let has_checksum = (data[3] & 0x80) != 0;
+    let compression_format = data[3] & 0x7F;
 
-    let uncompressed_size = u32::from_le_bytes(data[4..8].try_into().unwrap());
+    let uncompressed_size = u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
     let compressed_data_offset = if has_checksum { 12 } else { 8 };
     if data.len() < compressed_data_offset { bail!("Truncated"); }
+    let compressed = &data[compressed_data_offset..];
 
-    let (mut comp_ws_size, mut decomp_ws_size) = (0u32, 0u32);
-    let status_ws = unsafe { RtlGetCompressionWorkSpaceSize(compression_format, &mut comp_ws_size, &mut decomp_ws_size) };
-    if status_ws < 0 { bail!("NTSTATUS: {:#X}", status_ws); }
-
-    let mut workspace = vec![0u8; decomp_ws_size as usize];
-    let mut uncompressed_buffer = vec![0u8; uncompressed_size as usize];
-    let mut final_size = 0u32;
-
-    let status = unsafe { RtlDecompressBufferEx(compression_format, uncompressed_buffer.as_mut_ptr(), uncompressed_size, data[compressed_data_offset..].as_ptr(), (data.len() - compressed_data_offset) as u32, &mut final_size, workspace.as_mut_ptr()) };
-    if status >= 0 {
-        uncompressed_buffer.truncate(final_size as usize);
-        Ok(uncompressed_buffer)
-    } else { bail!("NTSTATUS: {:#X}", status); }
+    let mut decompressed = match compression_format {
+        COMPRESSION_FORMAT_LZNT1 => compression::decompress_lznt1(compressed)?,
+        COMPRESSION_FORMAT_XPRESS => compression::decompress_xpress(compressed, uncompressed_size)?,
+        COMPRESSION_FORMAT_XPRESS_HUFF => compression::decompress_xpress_huffman(compressed, uncompressed_size)?,
+        other => bail!("Unsupported MAM compression format: {}", other),
+    };
+    decompressed.truncate(uncompressed_size);
+    Ok(decompressed)
 }
 
 pub fn parse_prefetch_info(data: &[u8]) -> Result<PrefetchInfo> {
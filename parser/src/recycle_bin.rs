@@ -0,0 +1,264 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use models::event::{DeletionEvent, ForensicEvent};
+use models::mft::StandardInformation;
+use std::collections::BTreeMap;
+
+/// 휴지통 루트 디렉터리 이름 (Vista 이후)
+pub const RECYCLE_BIN_DIR: &str = "$Recycle.Bin";
+/// v1 $I 파일의 고정 길이 원본 경로 필드 (MAX_PATH UTF-16)
+const V1_PATH_BYTES: usize = 520;
+/// $I 파일로 읽어 들이는 최대 크기 (v2도 경로 길이 필드 + 최대 32767자 경로를 넘지 않는다)
+pub const MAX_INDEX_FILE_SIZE: u64 = 64 * 1024;
+
+/// $I 메타데이터 파일 하나 (삭제된 파일의 원래 경로/크기/삭제 시각)
+#[derive(Debug, Clone)]
+pub struct RecycleBinIndex {
+    /// 1: Vista~8.1 (경로 520바이트 고정), 2: Windows 10 이후 (경로 길이 필드 + 가변 경로)
+    pub version: u64,
+    pub file_size: u64,
+    pub deletion_time: DateTime<Utc>,
+    pub original_path: String,
+}
+
+/// $I 파일 형식:
+///   0x00 u64 버전, 0x08 u64 원본 크기, 0x10 FILETIME 삭제 시각,
+///   v1: 0x18 UTF-16 경로 (520바이트, NUL 패딩) / v2: 0x18 u32 경로 문자 수(NUL 포함), 0x1C UTF-16 경로
+pub fn parse_index_file(data: &[u8]) -> Result<RecycleBinIndex> {
+    if data.len() < 0x1C { bail!("$I file too small ({} bytes)", data.len()); }
+    let read_u64 = |pos: usize| u64::from_le_bytes(data[pos..pos + 8].try_into().unwrap());
+    let version = read_u64(0);
+    let path_bytes = match version {
+        1 => data.get(0x18..0x18 + V1_PATH_BYTES).unwrap_or(&data[0x18..]),
+        2 => {
+            let chars = u32::from_le_bytes(data[0x18..0x1C].try_into().unwrap()) as usize;
+            match data.get(0x1C..0x1C + chars * 2) {
+                Some(bytes) => bytes,
+                None => bail!("$I path length {} exceeds file size", chars),
+            }
+        },
+        other => bail!("Unsupported $I version: {}", other),
+    };
+    let units: Vec<u16> = path_bytes.chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+    let original_path = String::from_utf16_lossy(&units);
+    if original_path.is_empty() { bail!("$I file has no original path"); }
+
+    Ok(RecycleBinIndex {
+        version,
+        file_size: read_u64(0x08),
+        deletion_time: StandardInformation::to_datetime(read_u64(0x10)),
+        original_path,
+    })
+}
+
+/// $Recycle.Bin 아래 경로에서 (사용자 SID, 항목 이름, 항목 경로)를 꺼낸다.
+/// 예: "$Recycle.Bin\S-1-5-21-...-1001\$IAB12CD.txt" -> ("S-1-5-21-...-1001", "$IAB12CD.txt", 전체 경로)
+///     "$Recycle.Bin\S-1-5-21-...-1001\$RAB12CD\sub\a.txt" -> ("S-1-5-21-...-1001", "$RAB12CD", "$Recycle.Bin\...\$RAB12CD")
+fn split_recycle_path(path: &str) -> Option<(&str, &str, &str)> {
+    let components: Vec<&str> = path.split('\\').collect();
+    let root = components.iter().position(|c| c.eq_ignore_ascii_case(RECYCLE_BIN_DIR))?;
+    let (sid, item) = (*components.get(root + 1)?, *components.get(root + 2)?);
+    let item_len = components[..=root + 2].iter().map(|c| c.len() + 1).sum::<usize>() - 1;
+    Some((sid, item, &path[..item_len]))
+}
+
+/// $I/$R 이름에서 공통 식별자(접두사 뒤 6자 + 확장자)를 대소문자 무시 키로 만든다.
+fn item_key(item: &str, prefix: char) -> Option<String> {
+    let mut chars = item.chars();
+    (chars.next()? == '$' && chars.next()?.eq_ignore_ascii_case(&prefix)).then(|| chars.as_str().to_ascii_uppercase())
+}
+
+/// 휴지통 항목 하나: $I 메타데이터와 짝이 되는 $R 내용(파일 또는 삭제된 폴더)
+#[derive(Debug, Clone)]
+pub struct RecycledItem {
+    pub user_sid: String,
+    /// 볼륨 루트 기준 $I 경로
+    pub index_path: String,
+    pub index: RecycleBinIndex,
+    /// $R 경로 (비워졌거나 수집되지 않았으면 None)
+    pub content_path: Option<String>,
+    /// $R에 남아 있는 바이트 수 (폴더는 하위 파일 합계)
+    pub content_size: u64,
+}
+
+#[derive(Default)]
+struct PendingItem {
+    index: Option<(String, RecycleBinIndex)>,
+    content: Option<String>,
+    content_size: u64,
+}
+
+/// [추가] 수집 중 전달되는 $I/$R 파일을 (SID, 항목 식별자)별로 모아 짝짓는다.
+/// $R은 크기만 기록하므로 휴지통에 남은 대용량 파일도 읽지 않는다.
+#[derive(Default)]
+pub struct RecycleBin {
+    items: BTreeMap<(String, String), PendingItem>,
+}
+
+impl RecycleBin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// $I 파일 내용을 등록한다. 휴지통 경로가 아니면 무시하고, 해석할 수 없으면 오류를 반환한다.
+    pub fn add_index(&mut self, path: &str, data: &[u8]) -> Result<()> {
+        let Some((sid, item, _)) = split_recycle_path(path) else { return Ok(()) };
+        let Some(key) = item_key(item, 'I') else { return Ok(()) };
+        let index = parse_index_file(data)?;
+        self.items.entry((sid.to_string(), key)).or_default().index = Some((path.to_string(), index));
+        Ok(())
+    }
+
+    /// $R 파일(또는 삭제된 폴더 $R 아래의 파일)을 등록한다.
+    pub fn add_content(&mut self, path: &str, size: u64) {
+        let Some((sid, item, item_path)) = split_recycle_path(path) else { return };
+        let Some(key) = item_key(item, 'R') else { return };
+        let pending = self.items.entry((sid.to_string(), key)).or_default();
+        pending.content.get_or_insert_with(|| item_path.to_string());
+        pending.content_size += size;
+    }
+
+    /// 휴지통의 $I 파일 경로인지 검사한다. (호출자는 $I만 내용을 읽고 $R은 크기만 넘긴다.)
+    pub fn is_index_path(path: &str) -> bool {
+        split_recycle_path(path).and_then(|(_, item, _)| item_key(item, 'I')).is_some()
+    }
+
+    /// $I가 있는 항목만 반환한다. ($R만 남은 항목은 원래 경로를 알 수 없다.)
+    pub fn items(&self) -> Vec<RecycledItem> {
+        self.items.iter().filter_map(|((sid, _), pending)| {
+            let (index_path, index) = pending.index.clone()?;
+            Some(RecycledItem {
+                user_sid: sid.clone(),
+                index_path,
+                index,
+                content_path: pending.content.clone(),
+                content_size: pending.content_size,
+            })
+        }).collect()
+    }
+
+    /// 사용자별 삭제 이벤트
+    pub fn events(&self) -> Vec<ForensicEvent> {
+        self.items().into_iter().map(|item| ForensicEvent::Deletion(DeletionEvent {
+            timestamp: item.index.deletion_time,
+            original_path: item.index.original_path,
+            file_size: item.index.file_size,
+            user_sid: item.user_sid,
+            recycled_path: item.content_path,
+            recycled_size: item.content_size,
+            source_artifact: format!("Recycle Bin: {}", item.index_path),
+        })).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SID: &str = "S-1-5-21-1111111111-2222222222-3333333333-1001";
+    /// 2024-03-01T09:00:00Z (FILETIME)
+    const DELETED_AT: u64 = (1_709_283_600 + 11_644_473_600) * 10_000_000;
+
+    fn utf16z(text: &str) -> Vec<u8> {
+        text.encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect()
+    }
+
+    fn index_v1(path: &str, size: u64) -> Vec<u8> {
+        let mut data = [1u64, size, DELETED_AT].iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<u8>>();
+        let mut name = utf16z(path);
+        name.resize(V1_PATH_BYTES, 0);
+        data.extend(name);
+        data
+    }
+
+    fn index_v2(path: &str, size: u64) -> Vec<u8> {
+        let mut data = [2u64, size, DELETED_AT].iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<u8>>();
+        data.extend(((path.encode_utf16().count() + 1) as u32).to_le_bytes());
+        data.extend(utf16z(path));
+        data
+    }
+
+    fn recycle_path(item: &str) -> String {
+        format!("{}\\{}\\{}", RECYCLE_BIN_DIR, SID, item)
+    }
+
+    #[test]
+    fn parses_v1_and_v2_index_files() {
+        let v1 = parse_index_file(&index_v1("C:\\Users\\kim\\secret.docx", 12_345)).unwrap();
+        assert_eq!(v1.version, 1);
+        assert_eq!(v1.file_size, 12_345);
+        assert_eq!(v1.original_path, "C:\\Users\\kim\\secret.docx");
+        assert_eq!(v1.deletion_time.to_rfc3339(), "2024-03-01T09:00:00+00:00");
+
+        let long_path = format!("C:\\Data\\{}\\report.pdf", "nested\\".repeat(60));
+        let v2 = parse_index_file(&index_v2(&long_path, 1 << 33)).unwrap();
+        assert_eq!(v2.version, 2);
+        assert_eq!(v2.file_size, 1 << 33);
+        assert_eq!(v2.original_path, long_path);
+    }
+
+    #[test]
+    fn rejects_malformed_index_files() {
+        assert!(parse_index_file(&[0u8; 0x10]).is_err());
+        let mut unknown = index_v2("C:\\a.txt", 1);
+        unknown[0] = 3;
+        assert!(parse_index_file(&unknown).is_err());
+        // 경로 문자 수가 파일 끝을 넘는다.
+        let mut truncated = index_v2("C:\\a.txt", 1);
+        truncated[0x18..0x1C].copy_from_slice(&1000u32.to_le_bytes());
+        assert!(parse_index_file(&truncated).is_err());
+        assert!(parse_index_file(&index_v2("", 1)).is_err());
+    }
+
+    #[test]
+    fn pairs_index_with_file_and_folder_content() {
+        let mut bin = RecycleBin::new();
+        // 파일: $I/$R 짝 (확장자 대소문자가 달라도 같은 항목)
+        bin.add_index(&recycle_path("$IAB12CD.txt"), &index_v2("C:\\Users\\kim\\notes.txt", 42)).unwrap();
+        bin.add_content(&recycle_path("$RAB12CD.TXT"), 42);
+        // 폴더: $R 디렉터리 아래 파일 크기를 합산하고 경로는 $R 폴더로 보고한다.
+        bin.add_content(&recycle_path("$RXY98ZW\\a.bin"), 100);
+        bin.add_content(&recycle_path("$RXY98ZW\\sub\\b.bin"), 23);
+        bin.add_index(&recycle_path("$IXY98ZW"), &index_v1("C:\\Users\\kim\\Project", 0)).unwrap();
+        // 휴지통을 비워 $R이 없는 항목, $I 없이 남은 $R, 휴지통 밖 파일, 다른 사용자의 같은 식별자
+        bin.add_index(&recycle_path("$IEMPTY1.jpg"), &index_v2("D:\\photo.jpg", 7)).unwrap();
+        bin.add_content(&recycle_path("$RLOST01.dll"), 9);
+        bin.add_content("Users\\kim\\$RAB12CD.txt", 5);
+        bin.add_content(&format!("{}\\S-1-5-18\\$RAB12CD.txt", RECYCLE_BIN_DIR), 1);
+        bin.add_index("Windows\\Temp\\$IAB12CD.txt", &index_v2("C:\\x", 1)).unwrap();
+        assert!(bin.add_index(&recycle_path("$IBROKEN.txt"), &[0u8; 8]).is_err());
+
+        let items = bin.items();
+        let by_path = |original: &str| items.iter().find(|i| i.index.original_path == original).unwrap();
+        assert_eq!(items.len(), 3);
+
+        let file = by_path("C:\\Users\\kim\\notes.txt");
+        assert_eq!(file.user_sid, SID);
+        assert_eq!(file.index_path, recycle_path("$IAB12CD.txt"));
+        assert_eq!(file.content_path.as_deref(), Some(recycle_path("$RAB12CD.TXT").as_str()));
+        assert_eq!(file.content_size, 42);
+
+        let folder = by_path("C:\\Users\\kim\\Project");
+        assert_eq!(folder.content_path.as_deref(), Some(recycle_path("$RXY98ZW").as_str()));
+        assert_eq!(folder.content_size, 123);
+
+        let emptied = by_path("D:\\photo.jpg");
+        assert_eq!((emptied.content_path.as_deref(), emptied.content_size), (None, 0));
+
+        let events = bin.events();
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|e| matches!(e, ForensicEvent::Deletion(d) if d.user_sid == SID && d.source_artifact.starts_with("Recycle Bin: "))));
+    }
+
+    #[test]
+    fn index_path_detection() {
+        assert!(RecycleBin::is_index_path(&recycle_path("$IAB12CD.txt")));
+        assert!(RecycleBin::is_index_path(&format!("$RECYCLE.BIN\\{}\\$iab12cd.txt", SID)));
+        assert!(!RecycleBin::is_index_path(&recycle_path("$RAB12CD.txt")));
+        assert!(!RecycleBin::is_index_path(&recycle_path("desktop.ini")));
+        assert!(!RecycleBin::is_index_path("$Recycle.Bin\\$IAB12CD.txt"));
+    }
+}
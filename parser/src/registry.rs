@@ -1,15 +1,248 @@
 use anyhow::{Result, bail};
+use chrono::{DateTime, Datelike, Utc};
+use models::mft::StandardInformation;
+use std::collections::HashSet;
+use std::fmt;
+
+/// 카빙 시 허용하는 hbin 최대 크기 (큰 값 셀을 담은 hbin도 보통 수백 KB 이하)
+pub const MAX_HBIN_SIZE: usize = 1024 * 1024;
+/// 이보다 큰 값 데이터는 db(big data) 셀에 세그먼트 목록으로 나뉘어 저장된다. (하이브 버전 1.4 이후)
+const BIG_DATA_SEGMENT_SIZE: usize = 16344;
+/// 셀 오프셋이 없음을 나타내는 값
+const NO_CELL: u32 = 0xFFFFFFFF;
+/// nk 플래그: 하이브 루트 키
+const KEY_HIVE_ENTRY: u16 = 0x0004;
+/// 부모 체인을 따라갈 최대 깊이 (손상된 하이브의 순환 방지)
+const MAX_KEY_DEPTH: usize = 512;
+
+pub const REG_NONE: u32 = 0;
+pub const REG_SZ: u32 = 1;
+pub const REG_EXPAND_SZ: u32 = 2;
+pub const REG_BINARY: u32 = 3;
+pub const REG_DWORD: u32 = 4;
+pub const REG_DWORD_BIG_ENDIAN: u32 = 5;
+pub const REG_LINK: u32 = 6;
+pub const REG_MULTI_SZ: u32 = 7;
+pub const REG_QWORD: u32 = 11;
 
 #[derive(Debug, Clone)]
 pub struct RegistryValue {
     pub name: String,
     pub data_type: u32,
+    /// 타입별 표시 문자열 (RegistryData의 Display)
     pub data_string: String,
+    /// 값 데이터 원본 (big data는 세그먼트를 이어 붙인 결과)
     pub data_raw: Vec<u8>,
+    pub data: RegistryData,
+}
+
+/// [추가] 값 타입에 따라 해석한 데이터. 크기가 타입과 맞지 않거나 알 수 없는 타입은 Binary로 남긴다.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryData {
+    None,
+    String(String),
+    ExpandString(String),
+    Binary(Vec<u8>),
+    Dword(u32),
+    DwordBigEndian(u32),
+    Link(String),
+    MultiString(Vec<String>),
+    Qword(u64),
+}
+
+/// UTF-16LE 문자열을 첫 NUL까지 읽는다.
+fn utf16_string(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+impl RegistryData {
+    pub fn from_raw(data_type: u32, bytes: &[u8]) -> Self {
+        match data_type {
+            REG_NONE if bytes.is_empty() => Self::None,
+            REG_SZ => Self::String(utf16_string(bytes)),
+            REG_EXPAND_SZ => Self::ExpandString(utf16_string(bytes)),
+            REG_LINK => Self::Link(utf16_string(bytes)),
+            REG_MULTI_SZ => {
+                let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+                Self::MultiString(units.split(|&u| u == 0)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from_utf16_lossy)
+                    .collect())
+            },
+            REG_DWORD if bytes.len() == 4 => Self::Dword(u32::from_le_bytes(bytes.try_into().unwrap())),
+            REG_DWORD_BIG_ENDIAN if bytes.len() == 4 => Self::DwordBigEndian(u32::from_be_bytes(bytes.try_into().unwrap())),
+            REG_QWORD if bytes.len() == 8 => Self::Qword(u64::from_le_bytes(bytes.try_into().unwrap())),
+            _ => Self::Binary(bytes.to_vec()),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) | Self::ExpandString(s) | Self::Link(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// DWORD 계열 값. (4바이트 REG_BINARY도 DWORD로 기록하는 프로그램이 있어 함께 허용한다.)
+    pub fn as_u32(&self) -> Option<u32> {
+        match self {
+            Self::Dword(v) | Self::DwordBigEndian(v) => Some(*v),
+            Self::Binary(b) if b.len() == 4 => Some(u32::from_le_bytes(b[..].try_into().unwrap())),
+            _ => None,
+        }
+    }
+
+    /// QWORD 값 또는 8바이트 REG_BINARY (FILETIME을 바이너리로 저장하는 경우가 많다)
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Qword(v) => Some(*v),
+            Self::Binary(b) if b.len() == 8 => Some(u64::from_le_bytes(b[..].try_into().unwrap())),
+            _ => None,
+        }
+    }
+
+    /// FILETIME으로 해석한 값 (0은 시각 없음)
+    pub fn as_filetime(&self) -> Option<DateTime<Utc>> {
+        self.as_u64().filter(|&ft| ft > 0).map(StandardInformation::to_datetime)
+    }
+}
+
+/// 바이너리는 앞 64바이트만 16진수로 표시한다.
+const BINARY_DISPLAY_BYTES: usize = 64;
+
+impl fmt::Display for RegistryData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => Ok(()),
+            Self::String(s) | Self::ExpandString(s) | Self::Link(s) => write!(f, "{}", s.trim()),
+            Self::MultiString(items) => write!(f, "{}", items.join("; ")),
+            Self::Dword(v) | Self::DwordBigEndian(v) => write!(f, "0x{:08X}", v),
+            Self::Qword(v) => write!(f, "0x{:016X}", v),
+            Self::Binary(bytes) => {
+                for b in bytes.iter().take(BINARY_DISPLAY_BYTES) { write!(f, "{:02X}", b)?; }
+                if bytes.len() > BINARY_DISPLAY_BYTES { write!(f, "... ({} bytes)", bytes.len())?; }
+                Ok(())
+            },
+        }
+    }
+}
+
+/// [추가] nk 셀 메타데이터
+#[derive(Debug, Clone)]
+pub struct RegistryKey {
+    pub offset: u32,
+    pub name: String,
+    pub last_write: Option<DateTime<Utc>>,
+    /// nk 플래그 (0x0004 루트 키, 0x0020 ASCII 이름 등)
+    pub flags: u16,
+    pub parent_offset: u32,
+    pub subkey_count: u32,
+    pub value_count: u32,
+    /// 클래스 이름 (예: LSA JD/Skew1/GBG/Data 키는 부트 키 조각을 클래스 이름에 저장한다)
+    pub class_name: Option<String>,
+    pub security: Option<KeySecurity>,
+}
+
+/// [추가] sk 셀의 보안 설명자 (self-relative SECURITY_DESCRIPTOR)
+#[derive(Debug, Clone)]
+pub struct KeySecurity {
+    /// 이 sk 셀을 공유하는 키 수
+    pub reference_count: u32,
+    pub control: u16,
+    pub owner_sid: Option<String>,
+    pub group_sid: Option<String>,
+    pub descriptor: Vec<u8>,
+}
+
+/// [추가] 해제된 셀(크기 필드가 양수)에 남은 nk에서 복원한 삭제 키
+#[derive(Debug, Clone)]
+pub struct DeletedKey {
+    pub key: RegistryKey,
+    /// 부모 nk를 따라 만든 루트 기준 경로. 부모 체인이 끊기면 "(orphan)\" 뒤에 확인한 부분만 남는다.
+    pub path: String,
+    pub values: Vec<RegistryValue>,
+}
+
+/// [추가] 하이브 해제 셀 스캔 결과
+#[derive(Debug, Clone, Default)]
+pub struct DeletedCells {
+    pub keys: Vec<DeletedKey>,
+    /// 복원한 키의 값 목록에 없는 해제된 vk (원래 키를 알 수 없다)
+    pub values: Vec<RegistryValue>,
+    /// 해제된 sk 셀
+    pub security: Vec<KeySecurity>,
+}
+
+/// 바이너리 SID를 "S-1-5-21-..." 문자열로 변환한다.
+pub fn format_sid(data: &[u8]) -> Option<String> {
+    if data.len() < 8 { return None; }
+    let count = data[1] as usize;
+    if data.len() < 8 + count * 4 { return None; }
+    let authority = data[2..8].iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+    let mut sid = format!("S-{}-{}", data[0], authority);
+    for i in 0..count {
+        let off = 8 + i * 4;
+        sid.push_str(&format!("-{}", u32::from_le_bytes(data[off..off+4].try_into().unwrap())));
+    }
+    Some(sid)
+}
+
+impl KeySecurity {
+    fn parse(reference_count: u32, descriptor: &[u8]) -> Option<Self> {
+        if descriptor.len() < 20 || descriptor[0] != 1 { return None; }
+        let control = u16::from_le_bytes([descriptor[2], descriptor[3]]);
+        let sid_at = |pos: usize| {
+            let off = u32::from_le_bytes(descriptor[pos..pos+4].try_into().unwrap()) as usize;
+            if off == 0 { None } else { descriptor.get(off..).and_then(format_sid) }
+        };
+        Some(Self {
+            reference_count,
+            control,
+            owner_sid: sid_at(4),
+            group_sid: sid_at(8),
+            descriptor: descriptor.to_vec(),
+        })
+    }
 }
 
 pub struct HiveParser<'a> {
     data: &'a [u8],
+    /// 셀 오프셋 base_offset이 위치하는 data 내 인덱스 (정상 하이브는 regf 헤더 뒤 4096)
+    bins_start: usize,
+    base_offset: u32,
+}
+
+/// [추가] 하이브 밖(미할당 영역 등)에서 발견한 hbin 블록에 남아 있는 키
+#[derive(Debug, Clone)]
+pub struct CarvedKey {
+    pub name: String,
+    pub last_write: Option<DateTime<Utc>>,
+    pub values: Vec<RegistryValue>,
+    /// 셀 크기가 양수(해제된 셀)인 경우
+    pub is_deleted: bool,
+}
+
+/// hbin 헤더와 셀 체인을 검증하여 hbin 크기를 반환한다. 셀 크기의 합이 hbin 크기와 정확히 맞아야 한다.
+pub fn hbin_size(data: &[u8]) -> Option<usize> {
+    if data.len() < 0x20 || &data[0..4] != b"hbin" { return None; }
+    let offset = u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
+    let size = u32::from_le_bytes(data[8..12].try_into().unwrap()) as usize;
+    if !offset.is_multiple_of(4096) || size == 0 || !size.is_multiple_of(4096) || size > MAX_HBIN_SIZE || size > data.len() {
+        return None;
+    }
+
+    let mut pos = 0x20;
+    while pos < size {
+        if pos + 4 > size { return None; }
+        let cell_len = i32::from_le_bytes(data[pos..pos+4].try_into().unwrap()).unsigned_abs() as usize;
+        if cell_len < 8 || !cell_len.is_multiple_of(8) || pos + cell_len > size { return None; }
+        pos += cell_len;
+    }
+    Some(size)
 }
 
 impl<'a> HiveParser<'a> {
@@ -17,11 +250,38 @@ impl<'a> HiveParser<'a> {
         if data.len() < 4096 || &data[0..4] != b"regf" {
             bail!("Invalid Registry Hive signature");
         }
-        Ok(Self { data })
+        Ok(Self { data, bins_start: 4096, base_offset: 0 })
+    }
+
+    /// [추가] regf 헤더 없이 hbin 하나만으로 파서를 만든다. hbin 헤더의 오프셋을 기준으로 셀 오프셋을 해석하므로
+    /// 같은 hbin 안의 셀(값 목록, vk, 데이터)은 그대로 따라갈 수 있다.
+    pub fn from_hbin(data: &'a [u8]) -> Result<Self> {
+        let Some(size) = hbin_size(data) else { bail!("Invalid hbin block"); };
+        let base_offset = u32::from_le_bytes(data[4..8].try_into().unwrap());
+        Ok(Self { data: &data[..size], bins_start: 0, base_offset })
     }
 
+    /// 범위를 벗어난 셀 오프셋은 data 끝을 가리키게 하여 호출자의 길이 검사에서 걸러지게 한다.
     fn abs_offset(&self, offset: u32) -> usize {
-        (4096 + offset) as usize
+        offset.checked_sub(self.base_offset)
+            .map(|rel| self.bins_start + rel as usize)
+            .filter(|&abs| abs <= self.data.len())
+            .unwrap_or(self.data.len())
+    }
+
+    /// 셀 오프셋의 내용(크기 필드 뒤)을 셀 크기만큼 반환한다.
+    fn cell(&self, offset: u32) -> Option<&'a [u8]> {
+        let start = self.abs_offset(offset);
+        let size = i32::from_le_bytes(self.data.get(start..start+4)?.try_into().unwrap()).unsigned_abs() as usize;
+        if size < 4 { return None; }
+        self.data.get(start+4..start+size)
+    }
+
+    /// regf 헤더의 하이브 마지막 기록 시각 (from_hbin으로 만든 파서는 None)
+    pub fn last_written(&self) -> Option<DateTime<Utc>> {
+        if self.bins_start == 0 { return None; }
+        let filetime = u64::from_le_bytes(self.data[0x0C..0x14].try_into().unwrap());
+        (filetime > 0).then(|| StandardInformation::to_datetime(filetime))
     }
 
     pub fn get_root_offset(&self) -> u32 {
@@ -53,6 +313,147 @@ impl<'a> HiveParser<'a> {
         }
     }
 
+    /// nk 셀의 마지막 수정 시각 (FILETIME)
+    pub fn get_key_last_write(&self, nk_offset: u32) -> Option<DateTime<Utc>> {
+        let data_start = self.abs_offset(nk_offset) + 4;
+        if data_start + 76 > self.data.len() || &self.data[data_start..data_start+2] != b"nk" { return None; }
+        let filetime = u64::from_le_bytes(self.data[data_start+0x04..data_start+0x0C].try_into().unwrap());
+        (filetime > 0).then(|| StandardInformation::to_datetime(filetime))
+    }
+
+    /// [추가] nk 셀의 이름, 수정 시각, 하위 키/값 개수, 클래스 이름과 보안 설명자를 읽는다.
+    pub fn get_key(&self, nk_offset: u32) -> Option<RegistryKey> {
+        let nk = self.cell(nk_offset).filter(|c| c.len() >= 0x4C && &c[0..2] == b"nk")?;
+        let read_u32 = |pos: usize| u32::from_le_bytes(nk[pos..pos+4].try_into().unwrap());
+        Some(RegistryKey {
+            offset: nk_offset,
+            name: self.get_key_name(nk_offset),
+            last_write: self.get_key_last_write(nk_offset),
+            flags: u16::from_le_bytes([nk[0x02], nk[0x03]]),
+            parent_offset: read_u32(0x10),
+            subkey_count: read_u32(0x14),
+            value_count: read_u32(0x24),
+            class_name: self.class_name(read_u32(0x30), u16::from_le_bytes([nk[0x4A], nk[0x4B]]) as usize),
+            security: self.key_security(read_u32(0x2C)),
+        })
+    }
+
+    fn class_name(&self, class_offset: u32, class_len: usize) -> Option<String> {
+        if class_offset == NO_CELL || class_len == 0 { return None; }
+        self.cell(class_offset).and_then(|c| c.get(..class_len)).map(utf16_string)
+    }
+
+    /// sk 셀: 0x0C 참조 수, 0x10 설명자 크기, 0x14 설명자
+    fn key_security(&self, sk_offset: u32) -> Option<KeySecurity> {
+        if sk_offset == NO_CELL { return None; }
+        let sk = self.cell(sk_offset).filter(|c| c.len() >= 0x14 && &c[0..2] == b"sk")?;
+        let reference_count = u32::from_le_bytes(sk[0x0C..0x10].try_into().unwrap());
+        let size = u32::from_le_bytes(sk[0x10..0x14].try_into().unwrap()) as usize;
+        KeySecurity::parse(reference_count, sk.get(0x14..0x14 + size)?)
+    }
+
+    /// 모든 hbin의 셀을 순서대로 순회하며 (data 내 위치, 셀 크기 필드)를 넘긴다. 크기 필드가 양수이면 해제된 셀이다.
+    fn for_each_cell(&self, mut f: impl FnMut(usize, i32)) {
+        let mut hbin = self.bins_start;
+        while hbin + 0x20 <= self.data.len() && &self.data[hbin..hbin+4] == b"hbin" {
+            let size = u32::from_le_bytes(self.data[hbin+8..hbin+12].try_into().unwrap()) as usize;
+            if size < 0x20 || !size.is_multiple_of(4096) { break; }
+            let end = (hbin + size).min(self.data.len());
+            let mut pos = hbin + 0x20;
+            while pos + 4 <= end {
+                let raw_len = i32::from_le_bytes(self.data[pos..pos+4].try_into().unwrap());
+                let cell_len = raw_len.unsigned_abs() as usize;
+                if cell_len < 8 { break; }
+                f(pos, raw_len);
+                pos += cell_len;
+            }
+            hbin += size;
+        }
+    }
+
+    /// data 내 위치를 셀 오프셋으로 변환한다.
+    fn cell_offset(&self, pos: usize) -> Option<u32> {
+        self.base_offset.checked_add(u32::try_from(pos.checked_sub(self.bins_start)?).ok()?)
+    }
+
+    /// [추가] hbin 안의 모든 nk 셀(할당/해제 모두)을 순회하여 키 이름, 수정 시각, 값을 복원한다. (from_hbin 전용)
+    pub fn carved_keys(&self) -> Vec<CarvedKey> {
+        let mut keys = Vec::new();
+        self.for_each_cell(|pos, raw_len| {
+            if pos + 6 <= self.data.len() && &self.data[pos+4..pos+6] == b"nk"
+                && let Some(nk_offset) = self.cell_offset(pos) {
+                let name = self.get_key_name(nk_offset);
+                if !name.is_empty() {
+                    keys.push(CarvedKey {
+                        name,
+                        last_write: self.get_key_last_write(nk_offset),
+                        values: self.get_values(nk_offset),
+                        is_deleted: raw_len > 0,
+                    });
+                }
+            }
+        });
+        keys
+    }
+
+    /// [추가] 모든 hbin의 해제된 셀에서 nk/vk/sk 잔존물을 찾아 삭제된 키와 값을 복원한다.
+    /// 해제된 셀은 인접한 해제 셀과 합쳐지므로 셀 안을 8바이트 단위로 훑어 원래 셀 헤더 위치의 서명을 찾는다.
+    /// 삭제 키의 값 목록/vk가 이후 재사용되었을 수 있으므로 값은 vk 서명이 남아 있는 것만 복원된다.
+    pub fn deleted_cells(&self) -> DeletedCells {
+        let mut free_cells = Vec::new();
+        self.for_each_cell(|pos, raw_len| if raw_len > 0 { free_cells.push((pos, raw_len as usize)); });
+
+        let mut result = DeletedCells::default();
+        let mut free_values = Vec::new();
+        let mut referenced_values = HashSet::new();
+        for (start, len) in free_cells {
+            for pos in (start..start + len).step_by(8) {
+                if pos + 6 > self.data.len() { break; }
+                let Some(offset) = self.cell_offset(pos) else { continue };
+                match &self.data[pos+4..pos+6] {
+                    b"nk" => {
+                        let Some(key) = self.get_key(offset).filter(Self::is_plausible_deleted_key) else { continue };
+                        referenced_values.extend(self.value_offsets(offset));
+                        result.keys.push(DeletedKey { path: self.key_path(&key), values: self.get_values(offset), key });
+                    },
+                    b"vk" => free_values.push(offset),
+                    b"sk" => result.security.extend(self.key_security(offset)),
+                    _ => {},
+                }
+            }
+        }
+        result.values = free_values.into_iter()
+            .filter(|offset| !referenced_values.contains(offset))
+            .filter_map(|offset| self.get_value(offset))
+            .filter(|value| value.data_type <= REG_QWORD || value.data_type & 0xFFFF0000 == 0xFFFF0000)
+            .collect();
+        result
+    }
+
+    /// 해제 셀의 nk는 이름이 있고 수정 시각이 현실적인 범위일 때만 키로 인정한다.
+    fn is_plausible_deleted_key(key: &RegistryKey) -> bool {
+        !key.name.is_empty() && key.last_write.is_some_and(|t| (1990..=2100).contains(&t.year()))
+    }
+
+    /// 부모 nk를 따라 루트 키(하이브 진입점) 바로 아래까지의 경로를 만든다.
+    fn key_path(&self, key: &RegistryKey) -> String {
+        let mut parts = vec![key.name.clone()];
+        let mut parent = key.parent_offset;
+        let mut complete = false;
+        for _ in 0..MAX_KEY_DEPTH {
+            let Some(parent_key) = self.get_key(parent) else { break };
+            if parent_key.flags & KEY_HIVE_ENTRY != 0 {
+                complete = true;
+                break;
+            }
+            parent = parent_key.parent_offset;
+            parts.push(parent_key.name);
+        }
+        parts.reverse();
+        let path = parts.join("\\");
+        if complete { path } else { format!("(orphan)\\{}", path) }
+    }
+
     /// [Industry Standard] 특정 노드(nk) 하위에서 원하는 이름(target_name)을 가진 자식만 초고속으로 찾아낸다.
     pub fn find_child(&self, nk_offset: u32, target_name: &str) -> Option<u32> {
         let data_start = self.abs_offset(nk_offset) + 4;
@@ -174,82 +575,249 @@ impl<'a> HiveParser<'a> {
     }
 
     pub fn get_values(&self, nk_offset: u32) -> Vec<RegistryValue> {
+        self.value_offsets(nk_offset).into_iter().filter_map(|vk_off| self.get_value(vk_off)).collect()
+    }
+
+    /// nk의 값 목록 셀에 있는 vk 오프셋
+    fn value_offsets(&self, nk_offset: u32) -> Vec<u32> {
         let data_start = self.abs_offset(nk_offset) + 4;
-        let mut vals = Vec::new();
-        if data_start + 76 > self.data.len() { return vals; }
+        if data_start + 76 > self.data.len() { return Vec::new(); }
 
         let val_count = u32::from_le_bytes(self.data[data_start+0x24..data_start+0x28].try_into().unwrap());
-        if val_count == 0 { return vals; }
+        if val_count == 0 { return Vec::new(); }
 
         let val_list_offset = u32::from_le_bytes(self.data[data_start+0x28..data_start+0x2C].try_into().unwrap());
-        if val_list_offset == 0xFFFFFFFF { return vals; }
+        if val_list_offset == NO_CELL { return Vec::new(); }
 
         let list_start = self.abs_offset(val_list_offset) + 4;
-        if list_start + (val_count as usize * 4) > self.data.len() { return vals; }
-
-        for i in 0..val_count as usize {
-            let off = list_start + (i * 4);
-            let vk_off = u32::from_le_bytes(self.data[off..off+4].try_into().unwrap());
-            let vk_start = self.abs_offset(vk_off) + 4;
-
-            if vk_start + 20 <= self.data.len() && &self.data[vk_start..vk_start+2] == b"vk" {
-                let name_len = u16::from_le_bytes([self.data[vk_start+0x02], self.data[vk_start+0x03]]) as usize;
-                let mut data_len = u32::from_le_bytes(self.data[vk_start+0x04..vk_start+0x08].try_into().unwrap());
-                let data_off = u32::from_le_bytes(self.data[vk_start+0x08..vk_start+0x0C].try_into().unwrap());
-                let data_type = u32::from_le_bytes(self.data[vk_start+0x0C..vk_start+0x10].try_into().unwrap());
-                let flags = u16::from_le_bytes([self.data[vk_start+0x10], self.data[vk_start+0x11]]);
-
-                let name_start = vk_start + 0x14;
-                let raw_name = if name_len == 0 {
-                    "(Default)".to_string()
-                } else if name_start + name_len <= self.data.len() {
-                    if (flags & 0x0001) != 0 { 
-                        String::from_utf8_lossy(&self.data[name_start..name_start+name_len]).to_string()
-                    } else { 
-                        let u16_name: Vec<u16> = self.data[name_start..name_start+name_len].chunks_exact(2)
-                            .map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
-                        String::from_utf16_lossy(&u16_name)
-                    }
-                } else { "Unknown".to_string() };
-                
-                let name = raw_name.replace('\0', "").trim().to_string();
-
-                let is_inline = (data_len & 0x80000000) != 0;
-                data_len &= 0x7FFFFFFF;
-
-                let mut data_raw = Vec::new();
-                let data_string = if data_len > 0 {
-                    let data_bytes = if is_inline {
-                        let end = std::cmp::min(4, data_len) as usize;
-                        &self.data[vk_start+0x08 .. vk_start+0x08+end]
-                    } else {
-                        let d_start = self.abs_offset(data_off) + 4; 
-                        if d_start + data_len as usize <= self.data.len() {
-                            &self.data[d_start .. d_start + data_len as usize]
-                        } else {
-                            &[]
-                        }
-                    };
-                    
-                    data_raw = data_bytes.to_vec();
-
-                    if data_bytes.is_empty() {
-                        "".to_string()
-                    } else if data_type == 1 || data_type == 2 || data_type == 7 { // REG_SZ 계열
-                        let u16_data: Vec<u16> = data_bytes.chunks_exact(2)
-                            .map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
-                        String::from_utf16_lossy(&u16_data).replace('\0', "").trim().to_string()
-                    } else if data_type == 4 && data_bytes.len() >= 4 { // REG_DWORD
-                        let dword = u32::from_le_bytes(data_bytes[0..4].try_into().unwrap());
-                        format!("0x{:08X}", dword)
-                    } else {
-                        format!("BINARY DATA ({} bytes)", data_bytes.len()) 
-                    }
-                } else { "".to_string() };
+        if list_start + (val_count as usize * 4) > self.data.len() { return Vec::new(); }
+
+        (0..val_count as usize)
+            .map(|i| u32::from_le_bytes(self.data[list_start+i*4..list_start+i*4+4].try_into().unwrap()))
+            .collect()
+    }
 
-                vals.push(RegistryValue { name, data_type, data_string, data_raw });
+    /// vk 셀 하나를 해석한다.
+    ///   0x02 이름 길이, 0x04 데이터 크기(최상위 비트: 오프셋 필드에 직접 저장), 0x08 데이터 오프셋, 0x0C 타입, 0x10 플래그, 0x14 이름
+    fn get_value(&self, vk_off: u32) -> Option<RegistryValue> {
+        let vk_start = self.abs_offset(vk_off) + 4;
+        if vk_start + 20 > self.data.len() || &self.data[vk_start..vk_start+2] != b"vk" { return None; }
+
+        let name_len = u16::from_le_bytes([self.data[vk_start+0x02], self.data[vk_start+0x03]]) as usize;
+        let data_len = u32::from_le_bytes(self.data[vk_start+0x04..vk_start+0x08].try_into().unwrap());
+        let data_off = u32::from_le_bytes(self.data[vk_start+0x08..vk_start+0x0C].try_into().unwrap());
+        let data_type = u32::from_le_bytes(self.data[vk_start+0x0C..vk_start+0x10].try_into().unwrap());
+        let flags = u16::from_le_bytes([self.data[vk_start+0x10], self.data[vk_start+0x11]]);
+
+        let name_start = vk_start + 0x14;
+        let raw_name = if name_len == 0 {
+            "(Default)".to_string()
+        } else if name_start + name_len <= self.data.len() {
+            if (flags & 0x0001) != 0 {
+                String::from_utf8_lossy(&self.data[name_start..name_start+name_len]).to_string()
+            } else {
+                let u16_name: Vec<u16> = self.data[name_start..name_start+name_len].chunks_exact(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+                String::from_utf16_lossy(&u16_name)
             }
+        } else { "Unknown".to_string() };
+        let name = raw_name.replace('\0', "").trim().to_string();
+
+        let is_inline = (data_len & 0x80000000) != 0;
+        let data_len = (data_len & 0x7FFFFFFF) as usize;
+        let data_raw = if data_len == 0 {
+            Vec::new()
+        } else if is_inline {
+            self.data[vk_start+0x08 .. vk_start+0x08+data_len.min(4)].to_vec()
+        } else {
+            self.value_data(data_off, data_len).unwrap_or_default()
+        };
+
+        let data = RegistryData::from_raw(data_type, &data_raw);
+        Some(RegistryValue { name, data_type, data_string: data.to_string(), data_raw, data })
+    }
+
+    /// 값 데이터 셀을 읽는다. 16344바이트를 넘는 데이터는 db 셀(0x02 세그먼트 수, 0x04 세그먼트 목록 오프셋)의
+    /// 세그먼트를 순서대로 이어 붙인다. 세그먼트 하나라도 읽을 수 없으면 None을 반환한다.
+    fn value_data(&self, data_off: u32, data_len: usize) -> Option<Vec<u8>> {
+        let cell = self.cell(data_off)?;
+        if data_len > BIG_DATA_SEGMENT_SIZE && cell.len() >= 8 && &cell[0..2] == b"db" {
+            let segments = u16::from_le_bytes([cell[2], cell[3]]) as usize;
+            let list = self.cell(u32::from_le_bytes(cell[4..8].try_into().unwrap()))?.get(..segments * 4)?;
+            let mut data = Vec::with_capacity(data_len);
+            for entry in list.chunks_exact(4) {
+                let segment = self.cell(u32::from_le_bytes(entry.try_into().unwrap()))?;
+                let take = (data_len - data.len()).min(BIG_DATA_SEGMENT_SIZE).min(segment.len());
+                data.extend_from_slice(&segment[..take]);
+                if data.len() == data_len { break; }
+            }
+            return (data.len() == data_len).then_some(data);
         }
-        vals
+        cell.get(..data_len).map(<[u8]>::to_vec)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_hive::{HiveBuilder, filetime, utf16z};
+
+    const KEY: &str = "Software\\Vendor\\Settings";
+
+    /// self-relative 보안 설명자: 소유자 S-1-5-32-544, 그룹 S-1-5-18
+    fn descriptor() -> Vec<u8> {
+        let mut sd = vec![1, 0, 0x04, 0x80];
+        sd.extend(20u32.to_le_bytes());
+        sd.extend(36u32.to_le_bytes());
+        sd.extend([0u8; 8]);
+        sd.extend([1, 2, 0, 0, 0, 0, 0, 5, 32, 0, 0, 0, 0x20, 0x02, 0, 0]);
+        sd.extend([1, 1, 0, 0, 0, 0, 0, 5, 18, 0, 0, 0]);
+        sd
+    }
+
+    fn multi_sz(items: &[&str]) -> Vec<u8> {
+        let mut data: Vec<u8> = items.iter().flat_map(|item| utf16z(item)).collect();
+        data.extend([0, 0]);
+        data
+    }
+
+    fn value<'a>(values: &'a [RegistryValue], name: &str) -> &'a RegistryValue {
+        values.iter().find(|v| v.name == name).unwrap_or_else(|| panic!("no value {}", name))
+    }
+
+    #[test]
+    fn values_are_decoded_by_type() {
+        let installed = filetime("2024-03-01T09:00:00Z");
+        let mut builder = HiveBuilder::new(filetime("2024-03-02T00:00:00Z"));
+        builder
+            .value(KEY, "", REG_SZ, &utf16z("default"))
+            .value(KEY, "Empty", REG_NONE, &[])
+            .string(KEY, "Path", "  C:\\Tools\\agent.exe  ")
+            .value(KEY, "Expand", REG_EXPAND_SZ, &utf16z("%SystemRoot%\\system32"))
+            .value(KEY, "Blob", REG_BINARY, &(0..80).collect::<Vec<u8>>())
+            .value(KEY, "Count", REG_DWORD, &0x2Au32.to_le_bytes())
+            .value(KEY, "BigEndian", REG_DWORD_BIG_ENDIAN, &0x2Au32.to_be_bytes())
+            .value(KEY, "Link", REG_LINK, &utf16z("\\Registry\\Machine\\Software\\Other"))
+            .value(KEY, "List", REG_MULTI_SZ, &multi_sz(&["one", "two"]))
+            .value(KEY, "Installed", REG_QWORD, &installed.to_le_bytes())
+            .value(KEY, "ShortDword", REG_DWORD, &[1, 2])
+            .value(KEY, "DevpropTime", 0xFFFF0010, &installed.to_le_bytes());
+        let data = builder.build();
+        let hive = HiveParser::new(&data).unwrap();
+        let values = hive.get_values(hive.find_key(KEY).unwrap());
+        assert_eq!(values.len(), 12);
+
+        assert_eq!(value(&values, "(Default)").data, RegistryData::String("default".into()));
+        assert_eq!(value(&values, "Empty").data, RegistryData::None);
+        assert_eq!(value(&values, "Path").data_string, "C:\\Tools\\agent.exe");
+        assert_eq!(value(&values, "Expand").data, RegistryData::ExpandString("%SystemRoot%\\system32".into()));
+        let blob = value(&values, "Blob");
+        assert_eq!(blob.data_raw.len(), 80);
+        assert!(blob.data_string.starts_with("000102") && blob.data_string.ends_with("... (80 bytes)"));
+        assert_eq!(value(&values, "Count").data, RegistryData::Dword(42));
+        assert_eq!(value(&values, "Count").data_string, "0x0000002A");
+        assert_eq!(value(&values, "BigEndian").data, RegistryData::DwordBigEndian(42));
+        assert_eq!(value(&values, "Link").data.as_str(), Some("\\Registry\\Machine\\Software\\Other"));
+        assert_eq!(value(&values, "List").data_string, "one; two");
+        assert_eq!(value(&values, "Installed").data_string, format!("0x{:016X}", installed));
+        assert_eq!(value(&values, "Installed").data.as_filetime(), Some(StandardInformation::to_datetime(installed)));
+        // 크기가 타입과 맞지 않거나 알 수 없는 타입은 바이너리로 남는다.
+        assert_eq!(value(&values, "ShortDword").data, RegistryData::Binary(vec![1, 2]));
+        assert_eq!(value(&values, "DevpropTime").data.as_filetime(), Some(StandardInformation::to_datetime(installed)));
+    }
+
+    #[test]
+    fn big_data_value_is_reassembled_from_segments() {
+        let payload: Vec<u8> = (0..40_000u32).map(|i| (i % 253) as u8).collect();
+        let mut builder = HiveBuilder::new(filetime("2024-03-02T00:00:00Z"));
+        builder.value(KEY, "Blob", REG_BINARY, &payload);
+        let mut data = builder.build();
+        let hive = HiveParser::new(&data).unwrap();
+        let values = hive.get_values(hive.find_key(KEY).unwrap());
+        assert_eq!(values[0].data_raw, payload);
+
+        // 세그먼트 목록을 잃으면 일부만 이어 붙이지 않고 데이터 없음으로 처리한다.
+        let db = data.windows(4).position(|w| w == b"db\x03\x00").unwrap();
+        data[db + 4..db + 8].copy_from_slice(&NO_CELL.to_le_bytes());
+        let hive = HiveParser::new(&data).unwrap();
+        let values = hive.get_values(hive.find_key(KEY).unwrap());
+        assert!(values[0].data_raw.is_empty());
+    }
+
+    #[test]
+    fn key_metadata_includes_last_write_class_and_security() {
+        let mut builder = HiveBuilder::new(filetime("2024-03-02T00:00:00Z"));
+        builder
+            .key(KEY, filetime("2024-03-01T09:30:00Z"))
+            .class_name(KEY, "8a2b3c4d")
+            .security(KEY, &descriptor())
+            .string(KEY, "Path", "C:\\Tools\\agent.exe")
+            .key(&format!("{}\\Child", KEY), filetime("2024-03-01T09:31:00Z"));
+        let data = builder.build();
+        let hive = HiveParser::new(&data).unwrap();
+        assert_eq!(hive.last_written(), Some(StandardInformation::to_datetime(filetime("2024-03-02T00:00:00Z"))));
+
+        // 경로 구성 요소는 대소문자를 구분하지 않는다.
+        let offset = hive.find_key("SOFTWARE\\vendor\\settings").unwrap();
+        let key = hive.get_key(offset).unwrap();
+        assert_eq!(key.name, "Settings");
+        assert_eq!(key.last_write, Some(StandardInformation::to_datetime(filetime("2024-03-01T09:30:00Z"))));
+        assert_eq!((key.subkey_count, key.value_count), (1, 1));
+        assert_eq!(key.class_name.as_deref(), Some("8a2b3c4d"));
+        assert_eq!(key.parent_offset, hive.find_key("Software\\Vendor").unwrap());
+        let security = key.security.unwrap();
+        assert_eq!(security.owner_sid.as_deref(), Some("S-1-5-32-544"));
+        assert_eq!(security.group_sid.as_deref(), Some("S-1-5-18"));
+        assert_eq!((security.reference_count, security.control), (1, 0x8004));
+
+        let root = hive.get_key(hive.get_root_offset()).unwrap();
+        assert_ne!(root.flags & KEY_HIVE_ENTRY, 0);
+        assert!(hive.find_key("Software\\Missing").is_none());
+        assert!(HiveParser::new(&data[..4095]).is_err());
+    }
+
+    #[test]
+    fn free_cells_yield_deleted_run_keys_and_values() {
+        const RUN: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+        const RUN_ONCE: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\RunOnce";
+        let mut builder = HiveBuilder::new(filetime("2024-03-02T00:00:00Z"));
+        builder
+            .key(RUN, filetime("2024-03-01T10:00:00Z"))
+            .string(RUN, "OneDrive", "C:\\Users\\kim\\OneDrive.exe /background")
+            .deleted_value(RUN, "Backdoor", REG_SZ, &utf16z("C:\\ProgramData\\svc.exe"))
+            .deleted_key(RUN_ONCE, filetime("2024-03-01T09:45:00Z"))
+            .security(RUN_ONCE, &descriptor())
+            .string(RUN_ONCE, "Stage2", "powershell -w hidden -enc SQBFAFgA")
+            .deleted_key("Software\\Gone", filetime("2024-02-20T00:00:00Z"))
+            .deleted_key("Software\\Gone\\Leaf", filetime("2024-02-20T00:00:01Z"))
+            // 수정 시각이 비현실적인 잔여 nk는 키로 인정하지 않는다.
+            .deleted_key("Software\\Ancient", filetime("1985-01-01T00:00:00Z"));
+        let mut data = builder.build();
+
+        let hive = HiveParser::new(&data).unwrap();
+        assert!(hive.find_key(RUN_ONCE).is_none());
+        let live: Vec<String> = hive.get_values(hive.find_key(RUN).unwrap()).into_iter().map(|v| v.name).collect();
+        assert_eq!(live, ["OneDrive"]);
+
+        let deleted = hive.deleted_cells();
+        let paths: Vec<&str> = deleted.keys.iter().map(|k| k.path.as_str()).collect();
+        assert_eq!(paths, [RUN_ONCE, "Software\\Gone", "Software\\Gone\\Leaf"]);
+        let run_once = &deleted.keys[0];
+        assert_eq!(run_once.key.last_write, Some(StandardInformation::to_datetime(filetime("2024-03-01T09:45:00Z"))));
+        assert_eq!(run_once.values.len(), 1);
+        assert_eq!((run_once.values[0].name.as_str(), run_once.values[0].data_string.as_str()), ("Stage2", "powershell -w hidden -enc SQBFAFgA"));
+        assert_eq!(run_once.key.security.as_ref().and_then(|s| s.owner_sid.as_deref()), Some("S-1-5-32-544"));
+        assert_eq!(deleted.security.len(), 1);
+
+        // 복원한 키에 속한 값(Stage2)은 주인 없는 값 목록에 다시 나오지 않는다.
+        let orphans: Vec<(&str, &str)> = deleted.values.iter().map(|v| (v.name.as_str(), v.data_string.as_str())).collect();
+        assert_eq!(orphans, [("Backdoor", "C:\\ProgramData\\svc.exe")]);
+
+        // 부모 nk가 재사용되어 체인이 끊기면 확인한 부분만 orphan 경로로 남긴다.
+        let name = data.windows(4).position(|w| w == b"Leaf").unwrap();
+        let parent = name - 0x4C + 0x10;
+        data[parent..parent + 4].copy_from_slice(&NO_CELL.to_le_bytes());
+        let hive = HiveParser::new(&data).unwrap();
+        assert!(hive.deleted_cells().keys.iter().any(|k| k.path == "(orphan)\\Leaf"));
+    }
+}
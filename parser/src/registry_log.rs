@@ -0,0 +1,378 @@
+use anyhow::{Result, bail};
+
+/// regf 베이스 블록 크기 (체크섬은 앞 508바이트의 XOR)
+const BASE_BLOCK_SIZE: usize = 512;
+/// 주 하이브 파일에서 hbin 데이터가 시작하는 위치
+const HIVE_BINS_START: usize = 4096;
+/// 로그 항목과 더티 페이지의 정렬 단위
+const LOG_ENTRY_ALIGNMENT: usize = 512;
+const PAGE_SIZE: usize = 4096;
+/// 새 형식(Windows 8.1 이후) 트랜잭션 로그의 파일 유형
+const FILE_TYPE_LOG_NEW: u32 = 6;
+/// HvLE 항목 해시에 쓰는 Marvin32 시드
+const MARVIN32_SEED: u64 = 0x82EF4D887A4E55C5;
+
+/// [추가] 트랜잭션 로그 재생 결과
+#[derive(Debug, Clone, Default)]
+pub struct ReplaySummary {
+    pub applied_entries: usize,
+    pub dirty_pages: usize,
+    /// 마지막으로 적용한 로그 항목의 시퀀스 번호
+    pub last_sequence: Option<u32>,
+    /// 주 파일 베이스 블록이 손상되어 로그의 베이스 블록으로 대체했는지 여부
+    pub base_block_recovered: bool,
+}
+
+/// 하이브 트랜잭션 로그 경로(.LOG1/.LOG2)이면 대응하는 하이브 경로를 반환한다.
+/// 예: "Windows\System32\config\SYSTEM.LOG1" -> "Windows\System32\config\SYSTEM"
+pub fn log_hive_path(path: &str) -> Option<&str> {
+    let (hive, ext) = path.rsplit_once('.')?;
+    (ext.eq_ignore_ascii_case("LOG1") || ext.eq_ignore_ascii_case("LOG2")).then_some(hive)
+}
+
+fn read_u32(data: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap())
+}
+
+fn base_block_checksum(block: &[u8]) -> u32 {
+    let checksum = (0..508).step_by(4).fold(0u32, |acc, pos| acc ^ read_u32(block, pos));
+    match checksum {
+        0 => 1,
+        0xFFFFFFFF => 0xFFFFFFFE,
+        other => other,
+    }
+}
+
+fn is_valid_base_block(data: &[u8]) -> bool {
+    data.len() >= BASE_BLOCK_SIZE && &data[0..4] == b"regf" && read_u32(data, 0x1FC) == base_block_checksum(data)
+}
+
+/// 주 하이브가 더티 상태인지 검사한다. 베이스 블록의 주/보조 시퀀스 번호가 다르거나 체크섬이 맞지 않으면
+/// 마지막 변경이 주 파일에 반영되지 않은 것이다.
+pub fn is_dirty(hive: &[u8]) -> bool {
+    if hive.len() < BASE_BLOCK_SIZE || &hive[0..4] != b"regf" { return false; }
+    !is_valid_base_block(hive) || read_u32(hive, 0x04) != read_u32(hive, 0x08)
+}
+
+fn marvin32(seed: u64, data: &[u8]) -> u64 {
+    fn mix(lo: &mut u32, hi: &mut u32, value: u32) {
+        *lo = lo.wrapping_add(value);
+        *hi ^= *lo;
+        *lo = lo.rotate_left(20).wrapping_add(*hi);
+        *hi = hi.rotate_left(9) ^ *lo;
+        *lo = lo.rotate_left(27).wrapping_add(*hi);
+        *hi = hi.rotate_left(19);
+    }
+
+    let (mut lo, mut hi) = (seed as u32, (seed >> 32) as u32);
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        mix(&mut lo, &mut hi, u32::from_le_bytes(chunk.try_into().unwrap()));
+    }
+    let last = chunks.remainder().iter().rev().fold(0x80u32, |acc, &b| (acc << 8) | b as u32);
+    mix(&mut lo, &mut hi, last);
+    mix(&mut lo, &mut hi, 0);
+    ((hi as u64) << 32) | lo as u64
+}
+
+/// HvLE 로그 항목 하나
+///   0x00 "HvLE", 0x04 항목 크기, 0x08 플래그, 0x0C 시퀀스 번호, 0x10 hbin 데이터 크기, 0x14 더티 페이지 수,
+///   0x18 Hash-1 (0x28 이후 데이터의 Marvin32), 0x20 Hash-2 (앞 0x20바이트의 Marvin32),
+///   0x28 더티 페이지 참조 (hbin 기준 오프셋 u32, 크기 u32) 목록, 이어서 페이지 데이터
+struct LogEntry<'a> {
+    sequence: u32,
+    hive_bins_size: usize,
+    pages: Vec<(usize, &'a [u8])>,
+}
+
+fn parse_log_entry(data: &[u8]) -> Option<LogEntry<'_>> {
+    if data.len() < 0x28 || &data[0..4] != b"HvLE" { return None; }
+    let size = read_u32(data, 0x04) as usize;
+    if size < 0x28 || !size.is_multiple_of(LOG_ENTRY_ALIGNMENT) || size > data.len() { return None; }
+    let entry = &data[..size];
+
+    let hash_1 = u64::from_le_bytes(entry[0x18..0x20].try_into().unwrap());
+    let hash_2 = u64::from_le_bytes(entry[0x20..0x28].try_into().unwrap());
+    if marvin32(MARVIN32_SEED, &entry[0x28..]) != hash_1 || marvin32(MARVIN32_SEED, &entry[..0x20]) != hash_2 { return None; }
+
+    let hive_bins_size = read_u32(entry, 0x10) as usize;
+    let page_count = read_u32(entry, 0x14) as usize;
+    if !hive_bins_size.is_multiple_of(PAGE_SIZE) { return None; }
+    let mut data_pos = 0x28usize.checked_add(page_count.checked_mul(8)?)?;
+    if data_pos > size { return None; }
+
+    let mut pages = Vec::with_capacity(page_count);
+    for i in 0..page_count {
+        let offset = read_u32(entry, 0x28 + i * 8) as usize;
+        let page_size = read_u32(entry, 0x2C + i * 8) as usize;
+        if !offset.is_multiple_of(PAGE_SIZE) || !page_size.is_multiple_of(PAGE_SIZE) || offset + page_size > hive_bins_size {
+            return None;
+        }
+        pages.push((offset, entry.get(data_pos..data_pos + page_size)?));
+        data_pos += page_size;
+    }
+
+    Some(LogEntry { sequence: read_u32(entry, 0x0C), hive_bins_size, pages })
+}
+
+/// 로그 파일의 유효한 HvLE 항목을 처음부터 차례로 읽는다. 항목이 손상되었거나 끝나면 멈춘다.
+fn log_entries(log: &[u8]) -> Vec<LogEntry<'_>> {
+    let mut entries = Vec::new();
+    let mut pos = BASE_BLOCK_SIZE;
+    while let Some(entry) = log.get(pos..).and_then(parse_log_entry) {
+        pos += read_u32(log, pos + 0x04) as usize;
+        entries.push(entry);
+    }
+    entries
+}
+
+/// [추가] 더티 하이브에 트랜잭션 로그(.LOG1/.LOG2)의 HvLE 항목을 재생한다.
+/// 주 파일의 보조 시퀀스 번호부터 시작하는 항목을 두 로그에서 모아 시퀀스 순서대로, 번호가 끊기기 전까지 적용하고
+/// 베이스 블록의 시퀀스 번호, hbin 데이터 크기와 체크섬을 갱신한다.
+/// 이전 형식(DIRT) 로그는 지원하지 않으며, 재생할 항목이 없으면 hbin 데이터는 바꾸지 않는다.
+pub fn replay_transaction_logs(hive: &mut Vec<u8>, logs: &[&[u8]]) -> Result<ReplaySummary> {
+    if hive.len() < HIVE_BINS_START || &hive[0..4] != b"regf" { bail!("Invalid Registry Hive signature"); }
+
+    let valid_logs: Vec<&[u8]> = logs.iter().copied()
+        .filter(|log| is_valid_base_block(log) && read_u32(log, 0x1C) == FILE_TYPE_LOG_NEW)
+        .collect();
+    if valid_logs.is_empty() { bail!("No valid HvLE transaction log"); }
+
+    let mut summary = ReplaySummary::default();
+    if !is_valid_base_block(hive) {
+        // 주 파일 베이스 블록이 손상되었으면 가장 최근(주 시퀀스 번호가 큰) 로그의 베이스 블록을 쓴다.
+        let newest = valid_logs.iter().max_by_key(|log| read_u32(log, 0x04)).unwrap();
+        hive[..BASE_BLOCK_SIZE].copy_from_slice(&newest[..BASE_BLOCK_SIZE]);
+        summary.base_block_recovered = true;
+    }
+    let start_sequence = read_u32(hive, 0x08);
+
+    let mut entries: Vec<LogEntry> = valid_logs.iter()
+        .flat_map(|log| log_entries(log))
+        .filter(|entry| entry.sequence >= start_sequence)
+        .collect();
+    entries.sort_by_key(|entry| entry.sequence);
+    entries.dedup_by_key(|entry| entry.sequence);
+
+    let mut hive_bins_size = read_u32(hive, 0x28) as usize;
+    // 로그로 늘어나는 hbin 영역은 로그에 담긴 페이지에서 오므로 주 파일과 로그 크기의 합을 넘을 수 없다.
+    // 이보다 큰 hbin 크기는 손상된 항목으로 보고 재생을 멈춘다. (수 GB 할당 방지)
+    let max_hive_bins_size = (hive.len() - HIVE_BINS_START) + valid_logs.iter().map(|log| log.len()).sum::<usize>();
+    // [Fix] 첫 항목도 보조 시퀀스 번호와 정확히 이어져야 한다. 앞쪽 항목이 유실된 로그를 재생하면 중간 상태가 섞인다.
+    let mut expected = start_sequence;
+    for entry in &entries {
+        if entry.sequence != expected || entry.hive_bins_size > max_hive_bins_size { break; }
+        expected = entry.sequence.wrapping_add(1);
+        hive_bins_size = entry.hive_bins_size;
+        hive.resize(HIVE_BINS_START + hive_bins_size, 0);
+        for (offset, page) in &entry.pages {
+            let start = HIVE_BINS_START + offset;
+            hive[start..start + page.len()].copy_from_slice(page);
+        }
+        summary.applied_entries += 1;
+        summary.dirty_pages += entry.pages.len();
+        summary.last_sequence = Some(entry.sequence);
+    }
+
+    if let Some(last) = summary.last_sequence {
+        let next = last.wrapping_add(1);
+        hive[0x04..0x08].copy_from_slice(&next.to_le_bytes());
+        hive[0x08..0x0C].copy_from_slice(&next.to_le_bytes());
+        hive[0x28..0x2C].copy_from_slice(&(hive_bins_size as u32).to_le_bytes());
+        let checksum = base_block_checksum(hive);
+        hive[0x1FC..0x200].copy_from_slice(&checksum.to_le_bytes());
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marvin32_matches_reference_vectors() {
+        // Marvin32 참조 구현의 테스트 벡터 (seed 0x004FB61A001BDBCC)
+        const SEED: u64 = 0x004F_B61A_001B_DBCC;
+        let vectors: [(&[u8], u64); 8] = [
+            (&[], 0x30ED35C100CD3C7D),
+            (&[0xAF], 0x48E73FC77D75DDC1),
+            (&[0xE7, 0x0F], 0xB5F6E1FC485DBFF8),
+            (&[0x37, 0xF4, 0x95], 0xF0B07C789B8CF7E8),
+            (&[0x86, 0x42, 0xDC, 0x59], 0x7008F2E87E9CF556),
+            (&[0x15, 0x3F, 0xB7, 0x98, 0x26], 0xE6C08C6DA2AFA997),
+            (&[0x09, 0x32, 0xE6, 0x24, 0x6C, 0x47], 0x6F04BF1A5EA24060),
+            (&[0xAB, 0x42, 0x7E, 0xA8, 0xD1, 0x0F, 0xC7], 0xE11847E4F0678C41),
+        ];
+        for (data, expected) in vectors {
+            assert_eq!(marvin32(SEED, data), expected, "{:02X?}", data);
+        }
+    }
+
+    const HIVE_SEQUENCE: u32 = 10;
+
+    fn write_u32(data: &mut [u8], pos: usize, value: u32) {
+        data[pos..pos + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn seal_base_block(block: &mut [u8]) {
+        let checksum = base_block_checksum(block);
+        write_u32(block, 0x1FC, checksum);
+    }
+
+    /// 주/보조 시퀀스가 어긋난(더티) 하이브: 베이스 블록 + 0x11로 채운 hbin 페이지 하나
+    fn dirty_hive() -> Vec<u8> {
+        let mut hive = vec![0u8; HIVE_BINS_START + PAGE_SIZE];
+        hive[0..4].copy_from_slice(b"regf");
+        write_u32(&mut hive, 0x04, HIVE_SEQUENCE + 3);
+        write_u32(&mut hive, 0x08, HIVE_SEQUENCE);
+        write_u32(&mut hive, 0x28, PAGE_SIZE as u32);
+        seal_base_block(&mut hive);
+        hive[HIVE_BINS_START..].fill(0x11);
+        hive
+    }
+
+    /// 더티 페이지 (hbin 기준 오프셋, 채움 값) 목록을 담은 HvLE 항목
+    fn log_entry(sequence: u32, hive_bins_size: usize, pages: &[(usize, u8)]) -> Vec<u8> {
+        let header_size = 0x28 + pages.len() * 8;
+        let mut entry = vec![0u8; (header_size + pages.len() * PAGE_SIZE).next_multiple_of(LOG_ENTRY_ALIGNMENT)];
+        entry[0..4].copy_from_slice(b"HvLE");
+        let size = entry.len() as u32;
+        write_u32(&mut entry, 0x04, size);
+        write_u32(&mut entry, 0x0C, sequence);
+        write_u32(&mut entry, 0x10, hive_bins_size as u32);
+        write_u32(&mut entry, 0x14, pages.len() as u32);
+        for (i, &(offset, fill)) in pages.iter().enumerate() {
+            write_u32(&mut entry, 0x28 + i * 8, offset as u32);
+            write_u32(&mut entry, 0x2C + i * 8, PAGE_SIZE as u32);
+            let data = header_size + i * PAGE_SIZE;
+            entry[data..data + PAGE_SIZE].fill(fill);
+        }
+        let hash_1 = marvin32(MARVIN32_SEED, &entry[0x28..]);
+        entry[0x18..0x20].copy_from_slice(&hash_1.to_le_bytes());
+        let hash_2 = marvin32(MARVIN32_SEED, &entry[..0x20]);
+        entry[0x20..0x28].copy_from_slice(&hash_2.to_le_bytes());
+        entry
+    }
+
+    /// 새 형식 로그 파일: 주 시퀀스 번호를 가진 베이스 블록 뒤에 HvLE 항목이 이어진다.
+    fn log_file(sequence: u32, entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut log = vec![0u8; BASE_BLOCK_SIZE];
+        log[0..4].copy_from_slice(b"regf");
+        write_u32(&mut log, 0x04, sequence);
+        write_u32(&mut log, 0x08, sequence);
+        write_u32(&mut log, 0x1C, FILE_TYPE_LOG_NEW);
+        write_u32(&mut log, 0x28, PAGE_SIZE as u32);
+        seal_base_block(&mut log);
+        for entry in entries {
+            log.extend(entry);
+        }
+        log
+    }
+
+    fn page(hive: &[u8], offset: usize) -> &[u8] {
+        &hive[HIVE_BINS_START + offset..HIVE_BINS_START + offset + PAGE_SIZE]
+    }
+
+    #[test]
+    fn replays_both_logs_in_sequence_until_gap() {
+        let log1 = log_file(HIVE_SEQUENCE + 2, &[
+            log_entry(HIVE_SEQUENCE, PAGE_SIZE, &[(0, 0xA0)]),
+            log_entry(HIVE_SEQUENCE + 1, PAGE_SIZE, &[(0, 0xA1)]),
+        ]);
+        // LOG2는 LOG1과 겹치는 항목(11)을 다시 담고, 13이 빠진 채 14로 건너뛴다.
+        let log2 = log_file(HIVE_SEQUENCE + 5, &[
+            log_entry(HIVE_SEQUENCE + 1, PAGE_SIZE, &[(0, 0xA1)]),
+            log_entry(HIVE_SEQUENCE + 2, PAGE_SIZE * 2, &[(PAGE_SIZE, 0xB2)]),
+            log_entry(HIVE_SEQUENCE + 4, PAGE_SIZE * 2, &[(0, 0xEE)]),
+        ]);
+        let mut hive = dirty_hive();
+        assert!(is_dirty(&hive));
+
+        let summary = replay_transaction_logs(&mut hive, &[&log1, &log2]).unwrap();
+        assert_eq!(summary.applied_entries, 3);
+        assert_eq!(summary.dirty_pages, 3);
+        assert_eq!(summary.last_sequence, Some(HIVE_SEQUENCE + 2));
+        assert!(!summary.base_block_recovered);
+
+        assert_eq!(hive.len(), HIVE_BINS_START + PAGE_SIZE * 2);
+        assert!(page(&hive, 0).iter().all(|&b| b == 0xA1));
+        assert!(page(&hive, PAGE_SIZE).iter().all(|&b| b == 0xB2));
+
+        // 시퀀스, hbin 크기와 체크섬을 다시 써서 더 이상 더티가 아니다.
+        assert_eq!(read_u32(&hive, 0x04), HIVE_SEQUENCE + 3);
+        assert_eq!(read_u32(&hive, 0x08), HIVE_SEQUENCE + 3);
+        assert_eq!(read_u32(&hive, 0x28), (PAGE_SIZE * 2) as u32);
+        assert!(is_valid_base_block(&hive));
+        assert!(!is_dirty(&hive));
+    }
+
+    #[test]
+    fn leading_gap_is_not_replayed() {
+        let log = log_file(HIVE_SEQUENCE + 3, &[
+            log_entry(HIVE_SEQUENCE + 1, PAGE_SIZE, &[(0, 0xA1)]),
+            log_entry(HIVE_SEQUENCE + 2, PAGE_SIZE, &[(0, 0xA2)]),
+        ]);
+        let mut hive = dirty_hive();
+        let original = hive.clone();
+
+        let summary = replay_transaction_logs(&mut hive, &[&log]).unwrap();
+        assert_eq!(summary.applied_entries, 0);
+        assert_eq!(summary.last_sequence, None);
+        assert_eq!(hive, original);
+    }
+
+    #[test]
+    fn recovers_base_block_from_newest_log() {
+        let log1 = log_file(HIVE_SEQUENCE, &[]);
+        let log2 = log_file(HIVE_SEQUENCE + 1, &[log_entry(HIVE_SEQUENCE + 1, PAGE_SIZE, &[(0, 0xC1)])]);
+        let mut hive = dirty_hive();
+        hive[0x1FC] ^= 0xFF;
+        assert!(is_dirty(&hive));
+
+        let summary = replay_transaction_logs(&mut hive, &[&log1, &log2]).unwrap();
+        assert!(summary.base_block_recovered);
+        assert_eq!(summary.applied_entries, 1);
+        assert!(page(&hive, 0).iter().all(|&b| b == 0xC1));
+        assert_eq!(read_u32(&hive, 0x08), HIVE_SEQUENCE + 2);
+        assert!(is_valid_base_block(&hive));
+    }
+
+    #[test]
+    fn corrupt_entry_hash_ends_the_log() {
+        let mut corrupt = log_entry(HIVE_SEQUENCE + 1, PAGE_SIZE, &[(0, 0xA1)]);
+        let last = corrupt.len() - 1;
+        corrupt[last] ^= 0xFF;
+        let log = log_file(HIVE_SEQUENCE + 2, &[
+            log_entry(HIVE_SEQUENCE, PAGE_SIZE, &[(0, 0xA0)]),
+            corrupt,
+            log_entry(HIVE_SEQUENCE + 2, PAGE_SIZE, &[(0, 0xA2)]),
+        ]);
+        let mut hive = dirty_hive();
+
+        let summary = replay_transaction_logs(&mut hive, &[&log]).unwrap();
+        assert_eq!(summary.applied_entries, 1);
+        assert!(page(&hive, 0).iter().all(|&b| b == 0xA0));
+
+        // 올바른 새 형식 로그가 하나도 없으면 오류
+        let mut old_format = log_file(HIVE_SEQUENCE, &[]);
+        write_u32(&mut old_format, 0x1C, 1);
+        seal_base_block(&mut old_format);
+        assert!(replay_transaction_logs(&mut dirty_hive(), &[&old_format]).is_err());
+    }
+
+    #[test]
+    fn oversized_hive_bins_size_stops_replay() {
+        let log = log_file(HIVE_SEQUENCE + 2, &[
+            log_entry(HIVE_SEQUENCE, PAGE_SIZE, &[(0, 0xA0)]),
+            log_entry(HIVE_SEQUENCE + 1, 0xFFFF_F000, &[]),
+            log_entry(HIVE_SEQUENCE + 2, PAGE_SIZE, &[(0, 0xA2)]),
+        ]);
+        let mut hive = dirty_hive();
+
+        let summary = replay_transaction_logs(&mut hive, &[&log]).unwrap();
+        assert_eq!(summary.applied_entries, 1);
+        assert_eq!(hive.len(), HIVE_BINS_START + PAGE_SIZE);
+        assert!(page(&hive, 0).iter().all(|&b| b == 0xA0));
+        assert_eq!(read_u32(&hive, 0x28), PAGE_SIZE as u32);
+    }
+}
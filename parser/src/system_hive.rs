@@ -1,6 +1,7 @@
 use anyhow::Result;
 use chrono::Utc;
 use models::event::{ForensicEvent, PersistenceEvent};
+use crate::registry::HiveParser;
 use std::collections::HashSet;
 
 pub fn parse_system_services(data: &[u8], filename: &str) -> Result<Vec<ForensicEvent>> {
@@ -24,14 +25,35 @@ pub fn parse_system_services(data: &[u8], filename: &str) -> Result<Vec<Forensic
         }
     }
 
+    // 카빙한 문자열은 어느 키에 속하는지 알 수 없으므로 하이브 헤더의 마지막 기록 시각을 쓴다.
+    let timestamp = HiveParser::new(data).ok().and_then(|hive| hive.last_written()).unwrap_or_else(Utc::now);
     for path in extracted {
         events.push(ForensicEvent::Persistence(PersistenceEvent {
-            timestamp: Utc::now(),
+            timestamp,
             persistence_type: "Service ImagePath (SYSTEM)".to_string(),
             target_name: "Service".to_string(),
             target_path: path,
             source_artifact: format!("Registry: {}", filename),
+            user: None,
         }));
     }
     Ok(events)
-}
\ No newline at end of file
+}
+
+/// SYSTEM 하이브의 현재 컨트롤 셋 번호 (Select\Current, 없으면 1)
+pub fn current_control_set(hive: &HiveParser) -> u32 {
+    hive.find_key("Select")
+        .and_then(|nk| hive.get_values(nk).into_iter().find(|v| v.name.eq_ignore_ascii_case("Current")))
+        .and_then(|v| v.data.as_u32())
+        .unwrap_or(1)
+}
+
+/// [추가] SYSTEM 하이브의 현재 컨트롤 셋(Select\Current)에서 컴퓨터 이름을 읽는다.
+pub fn computer_name(data: &[u8]) -> Option<String> {
+    let hive = HiveParser::new(data).ok()?;
+    let key = hive.find_key(&format!("ControlSet{:03}\\Control\\ComputerName\\ComputerName", current_control_set(&hive)))?;
+    hive.get_values(key).into_iter()
+        .find(|v| v.name.eq_ignore_ascii_case("ComputerName"))
+        .map(|v| v.data_string)
+        .filter(|name| !name.is_empty())
+}
@@ -29,9 +29,10 @@ pub fn parse_task_xml(data: &[u8], filename: &str) -> Result<Vec<ForensicEvent>>
         events.push(ForensicEvent::Persistence(PersistenceEvent {
             timestamp: Utc::now(),
             persistence_type: "Scheduled Task (XML)".to_string(),
-            target_name: filename.split('\\').last().unwrap_or(filename).to_string(),
+            target_name: filename.split('\\').next_back().unwrap_or(filename).to_string(),
             target_path,
             source_artifact: format!("Task: {}", filename),
+            user: None,
         }));
     }
 
@@ -0,0 +1,243 @@
+//! 테스트용 레지스트리 하이브 작성기. regf 헤더 뒤 hbin 하나에 키와 값을 셀로 배치한다.
+
+/// 셀 오프셋이 없음을 나타내는 값
+const NO_CELL: u32 = 0xFFFFFFFF;
+/// 이보다 큰 값 데이터는 db 셀의 세그먼트로 나눈다.
+const BIG_DATA_SEGMENT_SIZE: usize = 16344;
+
+#[derive(Default)]
+struct Key {
+    name: String,
+    last_write: u64,
+    values: Vec<(String, u32, Vec<u8>)>,
+    /// 값 목록에서 빠진 채 해제된 셀로 남는 값
+    deleted_values: Vec<(String, u32, Vec<u8>)>,
+    subkeys: Vec<Key>,
+    /// 키 전체(nk, 값 목록, vk, 데이터)가 해제된 셀로 남고 부모의 하위 키 목록에서 빠진다.
+    deleted: bool,
+    class_name: Option<String>,
+    /// sk 셀에 담을 self-relative 보안 설명자
+    security: Option<Vec<u8>>,
+}
+
+impl Key {
+    fn child(&mut self, name: &str) -> &mut Key {
+        let index = match self.subkeys.iter().position(|k| k.name.eq_ignore_ascii_case(name)) {
+            Some(index) => index,
+            None => {
+                self.subkeys.push(Key { name: name.to_string(), last_write: self.last_write, ..Default::default() });
+                self.subkeys.len() - 1
+            },
+        };
+        &mut self.subkeys[index]
+    }
+}
+
+/// 경로("Software\Microsoft\...")로 키를 만들고 값을 추가한 뒤 build()로 하이브 바이트를 얻는다.
+pub(crate) struct HiveBuilder {
+    root: Key,
+    /// hbin 시작 기준 셀 영역 (셀 오프셋 = 위치)
+    cells: Vec<u8>,
+    /// 삭제된 키를 쓰는 동안 셀을 해제 상태(크기 필드 양수)로 만든다.
+    freeing: bool,
+}
+
+impl HiveBuilder {
+    /// 루트 키의 수정 시각(FILETIME). 새로 만들어지는 하위 키는 부모의 수정 시각을 물려받는다.
+    pub(crate) fn new(last_write: u64) -> Self {
+        Self { root: Key { name: "ROOT".into(), last_write, ..Default::default() }, cells: vec![0u8; 0x20], freeing: false }
+    }
+
+    fn node(&mut self, path: &str) -> &mut Key {
+        path.split('\\').filter(|p| !p.is_empty()).fold(&mut self.root, |key, part| key.child(part))
+    }
+
+    /// 키(와 중간 키)를 만들고 수정 시각을 지정한다.
+    pub(crate) fn key(&mut self, path: &str, last_write: u64) -> &mut Self {
+        self.node(path).last_write = last_write;
+        self
+    }
+
+    /// 값 이름이 빈 문자열이면 기본값("(Default)")이다.
+    pub(crate) fn value(&mut self, path: &str, name: &str, data_type: u32, data: &[u8]) -> &mut Self {
+        self.node(path).values.push((name.to_string(), data_type, data.to_vec()));
+        self
+    }
+
+    /// 삭제된 키: 지정한 수정 시각을 가진 채 해제된 셀로 남는다.
+    pub(crate) fn deleted_key(&mut self, path: &str, last_write: u64) -> &mut Self {
+        let key = self.node(path);
+        key.last_write = last_write;
+        key.deleted = true;
+        self
+    }
+
+    pub(crate) fn deleted_value(&mut self, path: &str, name: &str, data_type: u32, data: &[u8]) -> &mut Self {
+        self.node(path).deleted_values.push((name.to_string(), data_type, data.to_vec()));
+        self
+    }
+
+    pub(crate) fn class_name(&mut self, path: &str, class_name: &str) -> &mut Self {
+        self.node(path).class_name = Some(class_name.to_string());
+        self
+    }
+
+    pub(crate) fn security(&mut self, path: &str, descriptor: &[u8]) -> &mut Self {
+        self.node(path).security = Some(descriptor.to_vec());
+        self
+    }
+
+    pub(crate) fn string(&mut self, path: &str, name: &str, text: &str) -> &mut Self {
+        self.value(path, name, crate::registry::REG_SZ, &utf16z(text))
+    }
+
+    /// 8바이트 정렬된 할당 셀(크기 필드 음수)을 추가하고 셀 오프셋을 반환한다.
+    fn alloc(&mut self, content: &[u8]) -> u32 {
+        let offset = self.cells.len() as u32;
+        let size = (4 + content.len()).next_multiple_of(8);
+        let size_field = if self.freeing { size as i32 } else { -(size as i32) };
+        self.cells.extend(size_field.to_le_bytes());
+        self.cells.extend(content);
+        self.cells.resize(offset as usize + size, 0);
+        offset
+    }
+
+    fn patch_u32(&mut self, cell: u32, field: usize, value: u32) {
+        let pos = cell as usize + 4 + field;
+        self.cells[pos..pos + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_value(&mut self, name: &str, data_type: u32, data: &[u8]) -> u32 {
+        let mut vk = vec![0u8; 0x14];
+        vk[0..2].copy_from_slice(b"vk");
+        vk[0x02..0x04].copy_from_slice(&(name.len() as u16).to_le_bytes());
+        vk[0x0C..0x10].copy_from_slice(&data_type.to_le_bytes());
+        vk[0x10..0x12].copy_from_slice(&1u16.to_le_bytes());
+        vk.extend(name.as_bytes());
+        if data.len() <= 4 {
+            vk[0x04..0x08].copy_from_slice(&(data.len() as u32 | 0x80000000).to_le_bytes());
+            vk[0x08..0x08 + data.len()].copy_from_slice(data);
+        } else if data.len() > BIG_DATA_SEGMENT_SIZE {
+            // db 셀: 0x02 세그먼트 수, 0x04 세그먼트 목록 오프셋
+            let list: Vec<u8> = data.chunks(BIG_DATA_SEGMENT_SIZE).flat_map(|segment| self.alloc(segment).to_le_bytes()).collect();
+            let mut db = b"db".to_vec();
+            db.extend(((list.len() / 4) as u16).to_le_bytes());
+            db.extend(self.alloc(&list).to_le_bytes());
+            vk[0x04..0x08].copy_from_slice(&(data.len() as u32).to_le_bytes());
+            vk[0x08..0x0C].copy_from_slice(&self.alloc(&db).to_le_bytes());
+        } else {
+            let data_offset = self.alloc(data);
+            vk[0x04..0x08].copy_from_slice(&(data.len() as u32).to_le_bytes());
+            vk[0x08..0x0C].copy_from_slice(&data_offset.to_le_bytes());
+        }
+        self.alloc(&vk)
+    }
+
+    /// nk 셀을 먼저 배치하고, 값/하위 키 셀을 쓴 뒤 목록 오프셋을 채운다.
+    fn write_key(&mut self, key: &Key, parent: u32, flags: u16) -> u32 {
+        let freeing = self.freeing;
+        self.freeing |= key.deleted;
+        let mut nk = vec![0u8; 0x4C];
+        nk[0..2].copy_from_slice(b"nk");
+        nk[0x02..0x04].copy_from_slice(&(flags | 0x0020).to_le_bytes());
+        nk[0x04..0x0C].copy_from_slice(&key.last_write.to_le_bytes());
+        nk[0x10..0x14].copy_from_slice(&parent.to_le_bytes());
+        for field in [0x1C, 0x20, 0x28, 0x2C, 0x30] {
+            nk[field..field + 4].copy_from_slice(&NO_CELL.to_le_bytes());
+        }
+        nk[0x48..0x4A].copy_from_slice(&(key.name.len() as u16).to_le_bytes());
+        nk.extend(key.name.as_bytes());
+        let offset = self.alloc(&nk);
+
+        if let Some(class_name) = &key.class_name {
+            let class: Vec<u8> = class_name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+            let class_offset = self.alloc(&class);
+            self.patch_u32(offset, 0x30, class_offset);
+            let pos = offset as usize + 4 + 0x4A;
+            self.cells[pos..pos + 2].copy_from_slice(&(class.len() as u16).to_le_bytes());
+        }
+        if let Some(descriptor) = &key.security {
+            // sk 셀: 0x0C 참조 수, 0x10 설명자 크기, 0x14 설명자
+            let mut sk = vec![0u8; 0x14];
+            sk[0..2].copy_from_slice(b"sk");
+            sk[0x0C..0x10].copy_from_slice(&1u32.to_le_bytes());
+            sk[0x10..0x14].copy_from_slice(&(descriptor.len() as u32).to_le_bytes());
+            sk.extend(descriptor);
+            let sk_offset = self.alloc(&sk);
+            self.patch_u32(offset, 0x2C, sk_offset);
+        }
+
+        if !key.values.is_empty() {
+            let list: Vec<u8> = key.values.iter()
+                .flat_map(|(name, data_type, data)| self.write_value(name, *data_type, data).to_le_bytes())
+                .collect();
+            let list_offset = self.alloc(&list);
+            self.patch_u32(offset, 0x24, key.values.len() as u32);
+            self.patch_u32(offset, 0x28, list_offset);
+        }
+        let live: Vec<u32> = key.subkeys.iter()
+            .filter_map(|subkey| {
+                let child = self.write_key(subkey, offset, 0);
+                (!subkey.deleted).then_some(child)
+            })
+            .collect();
+        if !live.is_empty() {
+            let mut list = b"lh".to_vec();
+            list.extend((live.len() as u16).to_le_bytes());
+            for child in &live {
+                list.extend(child.to_le_bytes());
+                list.extend(0u32.to_le_bytes());
+            }
+            let list_offset = self.alloc(&list);
+            self.patch_u32(offset, 0x14, live.len() as u32);
+            self.patch_u32(offset, 0x1C, list_offset);
+        }
+
+        self.freeing = true;
+        for (name, data_type, data) in &key.deleted_values {
+            self.write_value(name, *data_type, data);
+        }
+        self.freeing = freeing;
+        offset
+    }
+
+    pub(crate) fn build(&mut self) -> Vec<u8> {
+        let root = std::mem::take(&mut self.root);
+        let root_offset = self.write_key(&root, NO_CELL, 0x0004);
+        let last_write = root.last_write;
+        self.root = root;
+
+        // hbin 나머지는 해제된 셀 하나로 채운다.
+        let size = (self.cells.len() + 8).next_multiple_of(4096);
+        let free = size - self.cells.len();
+        self.cells.extend((free as i32).to_le_bytes());
+        self.cells.resize(size, 0);
+        let mut hbin = std::mem::replace(&mut self.cells, vec![0u8; 0x20]);
+        hbin[0..4].copy_from_slice(b"hbin");
+        hbin[8..12].copy_from_slice(&(size as u32).to_le_bytes());
+
+        let mut hive = vec![0u8; 4096];
+        hive[0..4].copy_from_slice(b"regf");
+        hive[0x04..0x08].copy_from_slice(&1u32.to_le_bytes());
+        hive[0x08..0x0C].copy_from_slice(&1u32.to_le_bytes());
+        hive[0x0C..0x14].copy_from_slice(&last_write.to_le_bytes());
+        hive[0x14..0x18].copy_from_slice(&1u32.to_le_bytes());
+        hive[0x18..0x1C].copy_from_slice(&5u32.to_le_bytes());
+        hive[0x20..0x24].copy_from_slice(&1u32.to_le_bytes());
+        hive[0x24..0x28].copy_from_slice(&root_offset.to_le_bytes());
+        hive[0x28..0x2C].copy_from_slice(&(size as u32).to_le_bytes());
+        hive.extend(hbin);
+        hive
+    }
+}
+
+/// NUL로 끝나는 UTF-16LE 문자열
+pub(crate) fn utf16z(text: &str) -> Vec<u8> {
+    text.encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect()
+}
+
+/// RFC 3339 시각을 FILETIME으로 변환한다.
+pub(crate) fn filetime(rfc3339: &str) -> u64 {
+    let time = chrono::DateTime::parse_from_rfc3339(rfc3339).unwrap();
+    (time.timestamp() + 11_644_473_600) as u64 * 10_000_000 + time.timestamp_subsec_nanos() as u64 / 100
+}
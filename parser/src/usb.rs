@@ -0,0 +1,531 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use evtx::EvtxParser;
+use models::event::{ForensicEvent, UsbEvent};
+use models::usb::{UsbDevice, UsbDeviceUser};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{Read, Seek};
+use crate::registry::HiveParser;
+use crate::ntuser::profile_user;
+use crate::system_hive::current_control_set;
+
+/// Partition/Diagnostic 운영 로그 파일 이름. EID 1006은 디스크 연결(용량 > 0)/해제(용량 0)마다 기록된다.
+pub const PARTITION_DIAGNOSTIC_LOG: &str = "Microsoft-Windows-Partition%4Diagnostic.evtx";
+const PARTITION_DIAGNOSTIC_EVENT: u64 = 1006;
+/// 장치 설치/연결 시각 속성(DEVPKEY_Device_InstallDate 등)의 속성 집합
+const DEVICE_PROPERTY_SET: &str = "{83da6326-97a6-4088-9453-a1923f573b29}";
+/// 속성 ID: 0064 최초 설치, 0065 설치, 0066 마지막 연결, 0067 마지막 해제
+const PROP_FIRST_INSTALL: u32 = 0x64;
+const PROP_INSTALL: u32 = 0x65;
+const PROP_LAST_ARRIVAL: u32 = 0x66;
+const PROP_LAST_REMOVAL: u32 = 0x67;
+const MOUNT_POINTS2: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\MountPoints2";
+
+/// setupapi.dev.log의 장치 설치 섹션 하나
+#[derive(Debug, Clone)]
+pub struct SetupApiSection {
+    /// 장치 인스턴스 ID (예: USBSTOR\Disk&Ven_SanDisk&Prod_Cruzer&Rev_1.00\4C530001230523110403&0)
+    pub device_instance_id: String,
+    /// 섹션 시작 시각. setupapi는 로컬 시간으로 기록한다.
+    pub section_start: NaiveDateTime,
+}
+
+/// ">>>  [Device Install (Hardware initiated) - <인스턴스 ID>]" 헤더와 바로 뒤의 ">>>  Section start yyyy/mm/dd hh:mm:ss.fff"를 짝짓는다.
+pub fn parse_setupapi_log(data: &[u8]) -> Vec<SetupApiSection> {
+    let text = if data.starts_with(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = data[2..].chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(data).into_owned()
+    };
+
+    let mut sections = Vec::new();
+    let mut pending: Option<String> = None;
+    for line in text.lines().map(str::trim) {
+        let Some(body) = line.strip_prefix(">>>") else { continue };
+        let body = body.trim();
+        if let Some(header) = body.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+            pending = header.contains("Device Install")
+                .then(|| header.split_once(" - ").map(|(_, id)| id.trim().to_string()))
+                .flatten();
+        } else if let Some(start) = body.strip_prefix("Section start")
+            && let Some(device_instance_id) = pending.take()
+            && let Ok(section_start) = NaiveDateTime::parse_from_str(start.trim(), "%Y/%m/%d %H:%M:%S%.f") {
+            sections.push(SetupApiSection { device_instance_id, section_start });
+        }
+    }
+    sections
+}
+
+/// 장치 인스턴스 ID를 (열거자, 장치 ID, 인스턴스)로 나눈다. 구분자는 '\'(레지스트리/setupapi) 또는 '#'(MountedDevices)
+fn split_instance_id(id: &str) -> Option<(&str, &str, &str)> {
+    let mut parts = id.split(['\\', '#']);
+    Some((parts.next()?, parts.next()?, parts.next()?))
+}
+
+/// 장치를 묶는 일련번호 키. USBSTOR 인스턴스의 "&N" 접미사는 떼어 USB\VID&PID 키의 일련번호와 맞춘다.
+/// 두 번째 문자가 '&'인 인스턴스는 장치에 일련번호가 없어 Windows가 만든 값이므로 그대로 쓴다.
+fn serial_key(enumerator: &str, instance: &str) -> String {
+    let upper = instance.to_ascii_uppercase();
+    if enumerator.eq_ignore_ascii_case("USBSTOR") && upper.as_bytes().get(1) != Some(&b'&')
+        && let Some((serial, suffix)) = upper.rsplit_once('&')
+        && !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()) {
+        return serial.to_string();
+    }
+    upper
+}
+
+/// "Disk&Ven_SanDisk&Prod_Cruzer&Rev_1.00" -> (제조사, 제품, 리비전)
+fn parse_usbstor_device_id(device_id: &str) -> (String, String, String) {
+    let (mut vendor, mut product, mut revision) = (String::new(), String::new(), String::new());
+    for part in device_id.split('&') {
+        let (key, value) = part.split_once('_').unwrap_or((part, ""));
+        let value = value.replace('_', " ").trim().to_string();
+        match key.to_ascii_lowercase().as_str() {
+            "ven" => vendor = value,
+            "prod" => product = value,
+            "rev" => revision = value,
+            _ => {},
+        }
+    }
+    (vendor, product, revision)
+}
+
+/// "VID_0781&PID_5567" -> (VID, PID)
+fn parse_usb_device_id(device_id: &str) -> Option<(String, String)> {
+    let mut vid = None;
+    let mut pid = None;
+    for part in device_id.split('&') {
+        let upper = part.to_ascii_uppercase();
+        if let Some(v) = upper.strip_prefix("VID_") { vid = Some(v.to_string()); }
+        if let Some(p) = upper.strip_prefix("PID_") { pid = Some(p.to_string()); }
+    }
+    Some((vid?, pid?))
+}
+
+/// MountedDevices 값 데이터("_??_USBSTOR#Disk&Ven_...#<일련번호>&0#{...}")에서 USB 저장 장치의 일련번호 키를 꺼낸다.
+/// 고정 디스크의 데이터(디스크 서명 + 오프셋 12바이트, GPT 파티션 GUID 등)는 None이다.
+fn mounted_device_serial(data: &[u8]) -> Option<String> {
+    if data.len() < 8 || !data.len().is_multiple_of(2) { return None; }
+    let units: Vec<u16> = data.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    let text = String::from_utf16(&units).ok()?;
+    let parts: Vec<&str> = text.trim_end_matches('\0').split('#').collect();
+    let index = parts.iter().position(|p| p.trim_start_matches("_??_").trim_start_matches("\\??\\").eq_ignore_ascii_case("USBSTOR"))?;
+    Some(serial_key("USBSTOR", parts.get(index + 2)?))
+}
+
+/// 볼륨 GUID 표기 통일: "\??\Volume{ABC...}" / "{ABC...}" -> "{abc...}"
+fn normalize_guid(value: &str) -> Option<String> {
+    let start = value.find('{')?;
+    let end = value[start..].find('}')? + start;
+    Some(value[start..=end].to_ascii_lowercase())
+}
+
+fn keep_earliest(slot: &mut Option<DateTime<Utc>>, time: Option<DateTime<Utc>>) {
+    if let Some(time) = time && slot.is_none_or(|current| time < current) { *slot = Some(time); }
+}
+
+fn keep_latest(slot: &mut Option<DateTime<Utc>>, time: Option<DateTime<Utc>>) {
+    if let Some(time) = time && slot.is_none_or(|current| time > current) { *slot = Some(time); }
+}
+
+fn add_source(device: &mut UsbDevice, source: &str) {
+    if !device.sources.iter().any(|s| s == source) { device.sources.push(source.to_string()); }
+}
+
+/// 1006 EventData 필드 (evtx 크레이트는 숫자 필드를 JSON 숫자로 넘긴다)
+fn event_data_field(event_data: &Value, name: &str) -> Option<String> {
+    match event_data.get(name)? {
+        Value::String(s) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// [추가] USB 저장 장치 이력 수집기. 볼륨에서 수집되는 setupapi.dev.log, SYSTEM/NTUSER 하이브, Partition/Diagnostic 로그를
+/// 순서와 무관하게 받아 두었다가, 일련번호/볼륨 GUID/컨테이너 ID로 서로 연결하여 장치 목록을 만든다.
+#[derive(Default)]
+pub struct UsbHistory {
+    /// 일련번호 키 -> 장치 (USBSTOR 키, setupapi USBSTOR 섹션, 1006 이벤트가 장치를 만든다)
+    devices: BTreeMap<String, UsbDevice>,
+    setupapi: Vec<SetupApiSection>,
+    /// SYSTEM TimeZoneInformation의 ActiveTimeBias (분, UTC = 로컬 + bias). setupapi 로컬 시각 변환에 쓴다.
+    time_bias_minutes: Option<i64>,
+    /// Enum\USB 일련번호 키 -> (VID, PID)
+    usb_ids: BTreeMap<String, (String, String)>,
+    /// MountedDevices: (일련번호 키, 값 이름)
+    mounted: Vec<(String, String)>,
+    /// DeviceContainers\{컨테이너 ID} 키 수정 시각
+    container_times: BTreeMap<String, DateTime<Utc>>,
+    /// MountPoints2: (볼륨 GUID, 사용자, 키 수정 시각)
+    mount_points: Vec<(String, String, Option<DateTime<Utc>>)>,
+}
+
+impl UsbHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn device(&mut self, serial: &str) -> &mut UsbDevice {
+        self.devices.entry(serial.to_string()).or_insert_with(|| UsbDevice { serial_number: serial.to_string(), ..Default::default() })
+    }
+
+    pub fn add_setupapi_log(&mut self, data: &[u8]) {
+        self.setupapi.extend(parse_setupapi_log(data));
+    }
+
+    /// SYSTEM 하이브의 현재 컨트롤 셋에서 Enum\USBSTOR, Enum\USB, DeviceContainers, TimeZoneInformation과 MountedDevices를 읽는다.
+    pub fn add_system_hive(&mut self, data: &[u8]) -> Result<()> {
+        let hive = HiveParser::new(data)?;
+        let control_set = format!("ControlSet{:03}", current_control_set(&hive));
+
+        if let Some(tz) = hive.find_key(&format!("{}\\Control\\TimeZoneInformation", control_set)) {
+            let values = hive.get_values(tz);
+            self.time_bias_minutes = ["ActiveTimeBias", "Bias"].iter()
+                .find_map(|name| values.iter().find(|v| v.name.eq_ignore_ascii_case(name)))
+                .and_then(|v| v.data.as_u32())
+                .map(|bias| bias as i32 as i64);
+        }
+
+        let usbstor_path = format!("{}\\Enum\\USBSTOR", control_set);
+        let source = format!("SYSTEM\\{}", usbstor_path);
+        for class_key in hive.find_key(&usbstor_path).map(|k| hive.get_subkeys(k)).unwrap_or_default() {
+            let (vendor, product, revision) = parse_usbstor_device_id(&hive.get_key_name(class_key));
+            for instance_key in hive.get_subkeys(class_key) {
+                let serial = serial_key("USBSTOR", &hive.get_key_name(instance_key));
+                let values = hive.get_values(instance_key);
+                let value = |name: &str| values.iter().find(|v| v.name.eq_ignore_ascii_case(name)).map(|v| v.data_string.clone()).filter(|s| !s.is_empty());
+                let (friendly_name, container_id) = (value("FriendlyName"), value("ContainerID").and_then(|c| normalize_guid(&c)));
+                let property = |id| Self::device_property_time(&hive, instance_key, id);
+                let first = property(PROP_FIRST_INSTALL).or_else(|| property(PROP_INSTALL));
+                let arrival = property(PROP_LAST_ARRIVAL).or_else(|| hive.get_key_last_write(instance_key));
+                let removal = property(PROP_LAST_REMOVAL);
+
+                let device = self.device(&serial);
+                if device.vendor.is_empty() { device.vendor = vendor.clone(); }
+                if device.product.is_empty() { device.product = product.clone(); }
+                if device.revision.is_empty() { device.revision = revision.clone(); }
+                if friendly_name.is_some() { device.friendly_name = friendly_name; }
+                if container_id.is_some() { device.container_id = container_id; }
+                keep_earliest(&mut device.first_connected, first);
+                keep_latest(&mut device.last_connected, arrival);
+                keep_latest(&mut device.last_removed, removal);
+                add_source(device, &source);
+            }
+        }
+
+        for id_key in hive.find_key(&format!("{}\\Enum\\USB", control_set)).map(|k| hive.get_subkeys(k)).unwrap_or_default() {
+            let Some(ids) = parse_usb_device_id(&hive.get_key_name(id_key)) else { continue };
+            for instance_key in hive.get_subkeys(id_key) {
+                self.usb_ids.insert(serial_key("USB", &hive.get_key_name(instance_key)), ids.clone());
+            }
+        }
+
+        for container_key in hive.find_key(&format!("{}\\Control\\DeviceContainers", control_set)).map(|k| hive.get_subkeys(k)).unwrap_or_default() {
+            if let Some(id) = normalize_guid(&hive.get_key_name(container_key))
+                && let Some(last_write) = hive.get_key_last_write(container_key) {
+                self.container_times.insert(id, last_write);
+            }
+        }
+
+        if let Some(mounted_devices) = hive.find_key("MountedDevices") {
+            for value in hive.get_values(mounted_devices) {
+                if let Some(serial) = mounted_device_serial(&value.data_raw) {
+                    self.mounted.push((serial, value.name));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 장치 속성 시각. Win8 이후는 "{집합}\0064" 키의 기본값, Win7은 "{집합}\00000064\00000000" 키의 Data 값에 FILETIME이 있다.
+    fn device_property_time(hive: &HiveParser, instance_key: u32, id: u32) -> Option<DateTime<Utc>> {
+        let set = hive.find_child(instance_key, "Properties").and_then(|p| hive.find_child(p, DEVICE_PROPERTY_SET))?;
+        let key = hive.find_child(set, &format!("{:04X}", id)).or_else(|| hive.find_child(set, &format!("{:08X}", id)))?;
+        let key = hive.find_child(key, "00000000").unwrap_or(key);
+        hive.get_values(key).iter().find_map(|v| v.data.as_filetime())
+    }
+
+    /// 사용자 NTUSER.DAT의 MountPoints2\{볼륨 GUID} 키. 사용자는 하이브 경로(Users\<사용자>\NTUSER.DAT)에서 얻는다.
+    pub fn add_ntuser_hive(&mut self, path: &str, data: &[u8]) -> Result<()> {
+        let hive = HiveParser::new(data)?;
+        let user = profile_user(path).unwrap_or_else(|| path.to_string());
+        for key in hive.find_key(MOUNT_POINTS2).map(|k| hive.get_subkeys(k)).unwrap_or_default() {
+            if let Some(guid) = normalize_guid(&hive.get_key_name(key)) {
+                self.mount_points.push((guid, user.clone(), hive.get_key_last_write(key)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Partition/Diagnostic EID 1006: 연결(용량 > 0)은 첫/마지막 연결, 해제(용량 0)는 마지막 해제 시각으로 쓴다.
+    pub fn add_partition_diagnostic<R: Read + Seek>(&mut self, reader: R) -> Result<()> {
+        let mut parser = EvtxParser::from_read_seek(reader)?;
+        for record in parser.records_json_value().flatten() {
+            self.add_partition_event(&record.data);
+        }
+        Ok(())
+    }
+
+    /// 레코드 하나(evtx JSON 문서). 1006이 아니거나 장치 일련번호를 알 수 없으면 무시한다.
+    fn add_partition_event(&mut self, doc: &Value) {
+        if doc["Event"]["System"]["EventID"].as_u64() != Some(PARTITION_DIAGNOSTIC_EVENT) { return; }
+        let event_data = &doc["Event"]["EventData"];
+        let parent = event_data_field(event_data, "ParentId").unwrap_or_default();
+        let from_parent = split_instance_id(&parent).filter(|(enumerator, _, _)| enumerator.eq_ignore_ascii_case("USB"));
+        let Some(serial) = from_parent.map(|(e, _, instance)| serial_key(e, instance))
+            .or_else(|| event_data_field(event_data, "SerialNumber").map(|s| s.to_ascii_uppercase())) else { return };
+        let timestamp = doc["Event"]["System"]["TimeCreated"]["#attributes"]["SystemTime"].as_str()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc));
+        let capacity = event_data_field(event_data, "Capacity").and_then(|c| c.parse::<u64>().ok()).unwrap_or(0);
+
+        if let Some((_, device_id, _)) = from_parent
+            && let Some(ids) = parse_usb_device_id(device_id) {
+            self.usb_ids.insert(serial.clone(), ids);
+        }
+        let device = self.device(&serial);
+        if device.vendor.is_empty() { device.vendor = event_data_field(event_data, "Manufacturer").unwrap_or_default(); }
+        if device.product.is_empty() { device.product = event_data_field(event_data, "Model").unwrap_or_default(); }
+        if device.revision.is_empty() { device.revision = event_data_field(event_data, "Revision").unwrap_or_default(); }
+        if capacity > 0 {
+            device.capacity = Some(capacity);
+            keep_earliest(&mut device.first_connected, timestamp);
+            keep_latest(&mut device.last_connected, timestamp);
+        } else {
+            keep_latest(&mut device.last_removed, timestamp);
+        }
+        add_source(device, &format!("{} (EID: {})", PARTITION_DIAGNOSTIC_LOG, PARTITION_DIAGNOSTIC_EVENT));
+    }
+
+    fn setupapi_time(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        (local + Duration::minutes(self.time_bias_minutes.unwrap_or(0))).and_utc()
+    }
+
+    /// 받아 둔 모든 아티팩트를 연결한 장치 목록
+    pub fn devices(&self) -> Vec<UsbDevice> {
+        let mut devices = self.devices.clone();
+
+        // setupapi: USBSTOR 섹션은 장치를 만들고, USB\VID&PID 섹션은 같은 일련번호의 저장 장치에만 시각을 보탠다.
+        for section in &self.setupapi {
+            let Some((enumerator, device_id, instance)) = split_instance_id(&section.device_instance_id) else { continue };
+            let serial = serial_key(enumerator, instance);
+            let is_storage = enumerator.eq_ignore_ascii_case("USBSTOR");
+            if !is_storage && !devices.contains_key(&serial) { continue; }
+            let device = devices.entry(serial.clone()).or_insert_with(|| UsbDevice { serial_number: serial, ..Default::default() });
+            if is_storage && device.vendor.is_empty() {
+                (device.vendor, device.product, device.revision) = parse_usbstor_device_id(device_id);
+            }
+            keep_earliest(&mut device.first_connected, Some(self.setupapi_time(section.section_start)));
+            add_source(device, "setupapi.dev.log");
+        }
+
+        for (serial, (vid, pid)) in &self.usb_ids {
+            if let Some(device) = devices.get_mut(serial) {
+                device.vid = Some(vid.clone());
+                device.pid = Some(pid.clone());
+            }
+        }
+
+        for (serial, value_name) in &self.mounted {
+            let Some(device) = devices.get_mut(serial) else { continue };
+            if let Some(letter) = value_name.strip_prefix("\\DosDevices\\") {
+                device.drive_letter = Some(letter.to_ascii_uppercase());
+            } else if value_name.starts_with("\\??\\Volume") {
+                device.volume_guid = normalize_guid(value_name);
+            }
+            add_source(device, "SYSTEM\\MountedDevices");
+        }
+
+        for device in devices.values_mut() {
+            if let Some(time) = device.container_id.as_ref().and_then(|id| self.container_times.get(id)) {
+                keep_latest(&mut device.last_connected, Some(*time));
+            }
+            let Some(guid) = device.volume_guid.clone() else { continue };
+            for (_, user, last_mounted) in self.mount_points.iter().filter(|(g, _, _)| *g == guid) {
+                device.users.push(UsbDeviceUser { user: user.clone(), last_mounted: *last_mounted });
+                add_source(device, &format!("NTUSER.DAT ({})\\{}", user, MOUNT_POINTS2));
+            }
+        }
+        devices.into_values().collect()
+    }
+
+    /// 장치별 첫/마지막 연결, 마지막 해제, 사용자별 마운트 이벤트
+    pub fn events(&self) -> Vec<ForensicEvent> {
+        let mut events = Vec::new();
+        for device in self.devices() {
+            let event = |timestamp, action: &str, user: Option<String>, source: String| ForensicEvent::Usb(UsbEvent {
+                timestamp,
+                action: action.to_string(),
+                device_name: device.display_name(),
+                serial_number: device.serial_number.clone(),
+                drive_letter: device.drive_letter.clone(),
+                volume_guid: device.volume_guid.clone(),
+                user,
+                source_artifact: source,
+            });
+            let sources = device.sources.join(", ");
+            if let Some(time) = device.first_connected { events.push(event(time, "First Connected", None, sources.clone())); }
+            if let Some(time) = device.last_connected { events.push(event(time, "Last Connected", None, sources.clone())); }
+            if let Some(time) = device.last_removed { events.push(event(time, "Last Removed", None, sources.clone())); }
+            for user in &device.users {
+                if let Some(time) = user.last_mounted {
+                    events.push(event(time, "Mounted", Some(user.user.clone()), format!("NTUSER.DAT ({})\\{}", user.user, MOUNT_POINTS2)));
+                }
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{REG_BINARY, REG_DWORD};
+    use crate::test_hive::{HiveBuilder, filetime, utf16z};
+    use serde_json::json;
+
+    const SERIAL: &str = "4C530001230523110403";
+    const USBSTOR_CLASS: &str = "Disk&Ven_SanDisk&Prod_Cruzer_Blade&Rev_1.00";
+    const CONTAINER: &str = "{5A4F1C2E-0B6D-5E3A-9C11-0D2F7B8A9E01}";
+    const VOLUME: &str = "{7a1c9d22-3b4e-11ef-a5b1-806e6f6e6963}";
+    /// DEVPROP_TYPE_FILETIME
+    const DEVPROP_FILETIME: u32 = 0xFFFF0010;
+
+    const SETUPAPI_LOG: &str = "\
+[Device Install Log]
+     OS Version = 10.0.19045
+>>>  [Device Install (Hardware initiated) - SWD\\WPDBUSENUM\\_??_USBSTOR#Disk&Ven_SanDisk&Prod_Cruzer_Blade&Rev_1.00#4C530001230523110403&0#{53f56307-b6bf-11d0-94f2-00a0c91efb8b}]
+>>>  Section start 2024/02/28 17:58:05.100
+<<<  Section end 2024/02/28 17:58:06.000
+>>>  [Device Install (Hardware initiated) - USB\\VID_0781&PID_5567\\4C530001230523110403]
+>>>  Section start 2024/02/28 17:58:00.123
+     ump: Creating Install Process: DrvInst.exe 17:58:00.130
+<<<  Section end 2024/02/28 17:58:02.456
+<<<  [Exit status: SUCCESS]
+>>>  [Device Install (Hardware initiated) - USBSTOR\\Disk&Ven_SanDisk&Prod_Cruzer_Blade&Rev_1.00\\4C530001230523110403&0]
+>>>  Section start 2024/02/28 17:58:03.000
+<<<  Section end 2024/02/28 17:58:04.000
+>>>  [Device Install (Hardware initiated) - USB\\VID_046D&PID_C52B\\5&2A3B4C5D&0&2]
+>>>  Section start 2024/02/28 18:10:00.000
+";
+
+    fn system_hive() -> Vec<u8> {
+        let control = "ControlSet001";
+        let instance = format!("{}\\Enum\\USBSTOR\\{}\\{}&0", control, USBSTOR_CLASS, SERIAL);
+        let property = |id: &str| format!("{}\\Properties\\{}\\{}", instance, DEVICE_PROPERTY_SET, id);
+        let mounted: Vec<u8> = utf16z(&format!("_??_USBSTOR#{}#{}&0#{{53f56307-b6bf-11d0-94f2-00a0c91efb8b}}", USBSTOR_CLASS, SERIAL));
+        let mounted = &mounted[..mounted.len() - 2];
+
+        let mut hive = HiveBuilder::new(filetime("2024-03-06T12:00:00Z"));
+        hive.value("Select", "Current", REG_DWORD, &1u32.to_le_bytes())
+            // KST: UTC = 로컬 - 9시간
+            .value(&format!("{}\\Control\\TimeZoneInformation", control), "ActiveTimeBias", REG_DWORD, &(-540i32).to_le_bytes())
+            .key(&instance, filetime("2024-03-05T01:00:10Z"))
+            .string(&instance, "FriendlyName", "SanDisk Cruzer Blade USB Device")
+            .string(&instance, "ContainerID", CONTAINER)
+            .value(&property("0064"), "", DEVPROP_FILETIME, &filetime("2024-02-28T08:58:05Z").to_le_bytes())
+            .value(&property("0066"), "", DEVPROP_FILETIME, &filetime("2024-03-05T01:00:00Z").to_le_bytes())
+            .value(&property("0067"), "", DEVPROP_FILETIME, &filetime("2024-03-05T02:00:00Z").to_le_bytes())
+            .key(&format!("{}\\Enum\\USB\\VID_0781&PID_5567\\{}", control, SERIAL), filetime("2024-03-05T01:00:00Z"))
+            .key(&format!("{}\\Control\\DeviceContainers\\{}", control, CONTAINER), filetime("2024-03-04T00:00:00Z"))
+            .value("MountedDevices", "\\DosDevices\\E:", REG_BINARY, mounted)
+            .value("MountedDevices", &format!("\\??\\Volume{}", VOLUME.to_uppercase()), REG_BINARY, mounted)
+            // 고정 디스크: 디스크 서명 + 파티션 오프셋
+            .value("MountedDevices", "\\DosDevices\\C:", REG_BINARY, &[0x11, 0x22, 0x33, 0x44, 0, 0, 0x10, 0, 0, 0, 0, 0]);
+        hive.build()
+    }
+
+    fn ntuser_hive(volume: &str, mounted: &str) -> Vec<u8> {
+        let mut hive = HiveBuilder::new(filetime("2024-03-06T12:00:00Z"));
+        hive.key(&format!("{}\\{}", MOUNT_POINTS2, volume), filetime(mounted));
+        hive.build()
+    }
+
+    fn partition_event(time: &str, capacity: u64) -> Value {
+        json!({"Event": {
+            "System": {"EventID": 1006, "TimeCreated": {"#attributes": {"SystemTime": time}}},
+            "EventData": {
+                "Capacity": capacity, "Manufacturer": "SanDisk", "Model": "Cruzer Blade", "Revision": "1.00",
+                "SerialNumber": SERIAL, "ParentId": format!("USB\\VID_0781&PID_5567\\{}", SERIAL),
+            },
+        }})
+    }
+
+    fn at(rfc3339: &str) -> Option<DateTime<Utc>> {
+        Some(DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc))
+    }
+
+    #[test]
+    fn setupapi_sections_pair_header_with_start_time() {
+        let sections = parse_setupapi_log(SETUPAPI_LOG.as_bytes());
+        assert_eq!(sections.len(), 4);
+        assert_eq!(sections[1].device_instance_id, format!("USB\\VID_0781&PID_5567\\{}", SERIAL));
+        assert_eq!(sections[1].section_start.to_string(), "2024-02-28 17:58:00.123");
+
+        // 메모장 등으로 저장한 UTF-16LE(BOM) 로그
+        let utf16: Vec<u8> = [0xFF, 0xFE].into_iter().chain(SETUPAPI_LOG.encode_utf16().flat_map(u16::to_le_bytes)).collect();
+        assert_eq!(parse_setupapi_log(&utf16).len(), 4);
+    }
+
+    #[test]
+    fn merges_setupapi_system_ntuser_and_partition_events() {
+        let mut history = UsbHistory::new();
+        // 아티팩트는 수집 순서대로 들어오며 순서와 무관하게 합쳐져야 한다.
+        history.add_partition_event(&partition_event("2024-03-06T10:00:00.000000Z", 31_914_983_424));
+        history.add_partition_event(&partition_event("2024-03-06T11:00:00.000000Z", 0));
+        history.add_partition_event(&json!({"Event": {"System": {"EventID": 1001}, "EventData": {"SerialNumber": "OTHER"}}}));
+        history.add_setupapi_log(SETUPAPI_LOG.as_bytes());
+        history.add_ntuser_hive("Users\\kim\\NTUSER.DAT", &ntuser_hive(VOLUME, "2024-03-06T10:01:00Z")).unwrap();
+        history.add_ntuser_hive("Users\\lee\\NTUSER.DAT", &ntuser_hive("{00000000-1111-2222-3333-444444444444}", "2024-03-01T00:00:00Z")).unwrap();
+        history.add_system_hive(&system_hive()).unwrap();
+
+        // 저장 장치가 아닌 USB 장치(마우스 수신기)는 목록에 없다.
+        let devices = history.devices();
+        assert_eq!(devices.len(), 1, "{:#?}", devices);
+        let device = &devices[0];
+        assert_eq!(device.serial_number, SERIAL);
+        assert_eq!((device.vendor.as_str(), device.product.as_str(), device.revision.as_str()), ("SanDisk", "Cruzer Blade", "1.00"));
+        assert_eq!((device.vid.as_deref(), device.pid.as_deref()), (Some("0781"), Some("5567")));
+        assert_eq!(device.display_name(), "SanDisk Cruzer Blade USB Device");
+        assert_eq!(device.container_id.as_deref(), Some(CONTAINER.to_lowercase().as_str()));
+        // 최초 연결은 setupapi USB 섹션(로컬 17:58:00.123, KST)이 장치 속성 0064보다 이르다.
+        assert_eq!(device.first_connected, at("2024-02-28T08:58:00.123Z"));
+        // 마지막 연결/해제는 속성 0066/0067보다 늦은 1006 이벤트
+        assert_eq!(device.last_connected, at("2024-03-06T10:00:00Z"));
+        assert_eq!(device.last_removed, at("2024-03-06T11:00:00Z"));
+        assert_eq!(device.capacity, Some(31_914_983_424));
+        assert_eq!(device.drive_letter.as_deref(), Some("E:"));
+        assert_eq!(device.volume_guid.as_deref(), Some(VOLUME));
+        assert_eq!(device.users.len(), 1);
+        assert_eq!(device.users[0].user, "kim");
+        assert_eq!(device.users[0].last_mounted, at("2024-03-06T10:01:00Z"));
+        for source in ["setupapi.dev.log", "SYSTEM\\MountedDevices", "Microsoft-Windows-Partition%4Diagnostic.evtx (EID: 1006)"] {
+            assert!(device.sources.iter().any(|s| s == source), "{:?}", device.sources);
+        }
+
+        let events = history.events();
+        let action = |name: &str| events.iter().find_map(|e| match e {
+            ForensicEvent::Usb(u) if u.action == name => Some(u.clone()),
+            _ => None,
+        }).unwrap();
+        assert_eq!(action("First Connected").timestamp, at("2024-02-28T08:58:00.123Z").unwrap());
+        assert_eq!(action("Last Connected").timestamp, at("2024-03-06T10:00:00Z").unwrap());
+        assert_eq!(action("Last Removed").timestamp, at("2024-03-06T11:00:00Z").unwrap());
+        let mounted = action("Mounted");
+        assert_eq!((mounted.user.as_deref(), mounted.drive_letter.as_deref()), (Some("kim"), Some("E:")));
+        assert_eq!(mounted.serial_number, SERIAL);
+    }
+
+    #[test]
+    fn registry_times_stand_alone_without_event_log() {
+        let mut history = UsbHistory::new();
+        history.add_system_hive(&system_hive()).unwrap();
+        let devices = history.devices();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].first_connected, at("2024-02-28T08:58:05Z"));
+        assert_eq!(devices[0].last_connected, at("2024-03-05T01:00:00Z"));
+        assert_eq!(devices[0].last_removed, at("2024-03-05T02:00:00Z"));
+    }
+}
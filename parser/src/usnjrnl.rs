@@ -1,67 +1,410 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use models::mft::StandardInformation;
+use models::mft::{mft_entry_number, mft_sequence_number, StandardInformation};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+
+/// USN_REASON_RENAME_OLD_NAME
+const REASON_RENAME_OLD_NAME: u32 = 0x0000_1000;
+/// 부모 경로를 찾지 못한 레코드의 경로 접두어 (예: \$Unresolved\Entry1234-5\file.txt)
+pub const UNRESOLVED_PATH_PREFIX: &str = "\\$Unresolved";
+const MAX_PATH_DEPTH: usize = 256;
+const ROOT_ENTRY: u64 = 5;
 
 #[derive(Debug, Clone)]
 pub struct UsnRecord {
+    pub major_version: u16,
+    pub usn: i64,
+    /// 파일 참조 (엔트리 + 시퀀스). V3/V4의 128비트 File ID는 NTFS에서 하위 64비트만 사용한다.
+    pub file_reference: u64,
+    pub parent_reference: u64,
     pub timestamp: DateTime<Utc>,
     pub file_name: String,
     pub reason_flags: u32,
+    pub source_info: u32,
+    pub security_id: u32,
     pub file_attributes: u32,
+    /// V4(범위 추적) 레코드의 변경 구간 (오프셋, 길이)
+    pub extents: Vec<(i64, i64)>,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_name(record: &[u8], name_off: usize, name_len: usize) -> Option<String> {
+    if name_len == 0 || name_off + name_len > record.len() { return None; }
+    let u16_name: Vec<u16> = record[name_off..name_off + name_len].chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    Some(String::from_utf16_lossy(&u16_name))
+}
+
+/// USN_RECORD_V2 / V3: 두 버전은 파일 참조 크기(8 / 16바이트)만 다르다.
+fn parse_v2_v3(record: &[u8], major_version: u16) -> Option<UsnRecord> {
+    let (ref_size, min_len) = if major_version == 2 { (8, 60) } else { (16, 76) };
+    if record.len() < min_len { return None; }
+    let base = 8 + ref_size * 2;
+
+    let name_len = read_u16(record, base + 32) as usize;
+    let name_off = read_u16(record, base + 34) as usize;
+    let file_name = read_name(record, name_off, name_len)?;
+
+    Some(UsnRecord {
+        major_version,
+        file_reference: read_u64(record, 8),
+        parent_reference: read_u64(record, 8 + ref_size),
+        usn: read_u64(record, base) as i64,
+        timestamp: StandardInformation::to_datetime(read_u64(record, base + 8)),
+        reason_flags: read_u32(record, base + 16),
+        source_info: read_u32(record, base + 20),
+        security_id: read_u32(record, base + 24),
+        file_attributes: read_u32(record, base + 28),
+        file_name,
+        extents: Vec::new(),
+    })
+}
+
+/// USN_RECORD_V4에는 시각과 이름이 없으므로, 같은 파일의 직전 V2/V3 레코드에서 이어받는다.
+fn parse_v4(record: &[u8], last_seen: &HashMap<u64, (DateTime<Utc>, String)>) -> Option<UsnRecord> {
+    if record.len() < 64 { return None; }
+    let file_reference = read_u64(record, 8);
+    let (timestamp, file_name) = last_seen.get(&file_reference).cloned()?;
+
+    let extent_count = read_u16(record, 60) as usize;
+    let extent_size = read_u16(record, 62) as usize;
+    let extents = (0..extent_count)
+        .map(|i| 64 + i * extent_size)
+        .take_while(|off| extent_size >= 16 && off + 16 <= record.len())
+        .map(|off| (read_u64(record, off) as i64, read_u64(record, off + 8) as i64))
+        .collect();
+
+    Some(UsnRecord {
+        major_version: 4,
+        file_reference,
+        parent_reference: read_u64(record, 24),
+        usn: read_u64(record, 40) as i64,
+        timestamp,
+        reason_flags: read_u32(record, 48),
+        source_info: read_u32(record, 52),
+        security_id: 0,
+        file_attributes: 0,
+        file_name,
+        extents,
+    })
+}
+
+/// 레코드 길이 필드가 이 값을 넘으면 손상된 데이터로 보고 파싱을 멈춘다.
+const MAX_RECORD_SIZE: usize = 0x10000;
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// $UsnJrnl:$J 스트림을 앞에서부터 한 레코드씩 읽는 이터레이터. 희소 영역과 페이지 끝 패딩(0)은 8바이트 정렬 단위로 건너뛴다.
+pub struct UsnRecordReader<R: Read> {
+    reader: BufReader<R>,
+    /// V4 레코드에 이어줄 파일별 직전 (시각, 이름)
+    last_seen: HashMap<u64, (DateTime<Utc>, String)>,
+}
+
+impl<R: Read> UsnRecordReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader: BufReader::with_capacity(READ_BUFFER_SIZE, reader), last_seen: HashMap::new() }
+    }
+
+    /// 버퍼에 있는 0 패딩을 한 번에 소비한다. 수 GB의 희소 구간도 레코드 단위 읽기 없이 지나간다.
+    fn skip_padding(&mut self) -> std::io::Result<bool> {
+        let buf = self.reader.fill_buf()?;
+        if buf.is_empty() { return Ok(false); }
+        let zeros = buf.chunks_exact(8).take_while(|c| c.iter().all(|&b| b == 0)).count() * 8;
+        self.reader.consume(zeros);
+        Ok(true)
+    }
+}
+
+impl<R: Read> Iterator for UsnRecordReader<R> {
+    type Item = UsnRecord;
+
+    fn next(&mut self) -> Option<UsnRecord> {
+        loop {
+            if !self.skip_padding().ok()? { return None; }
+
+            let mut header = [0u8; 8];
+            self.reader.read_exact(&mut header).ok()?;
+            let record_len = read_u32(&header, 0) as usize;
+
+            // 패딩(0)을 만나면 8바이트 정렬 단위로 전진
+            if record_len == 0 { continue; }
+            if !(8..=MAX_RECORD_SIZE).contains(&record_len) { return None; }
+
+            let mut record = vec![0u8; record_len];
+            record[..8].copy_from_slice(&header);
+            self.reader.read_exact(&mut record[8..]).ok()?;
+            let padding = record_len.next_multiple_of(8) - record_len;
+            let mut pad = [0u8; 8];
+            // 마지막 레코드 뒤에는 정렬 패딩이 없을 수 있다.
+            let _ = self.reader.read_exact(&mut pad[..padding]);
+
+            let major_version = read_u16(&record, 4);
+            let parsed = match major_version {
+                2 | 3 => parse_v2_v3(&record, major_version),
+                4 => parse_v4(&record, &self.last_seen),
+                _ => None,
+            };
+            if let Some(rec) = parsed {
+                if rec.major_version != 4 {
+                    self.last_seen.insert(rec.file_reference, (rec.timestamp, rec.file_name.clone()));
+                }
+                return Some(rec);
+            }
+        }
+    }
 }
 
 pub fn parse_usnjrnl_stream(data: &[u8]) -> Result<Vec<UsnRecord>> {
-    let mut records = Vec::new();
-    let mut offset = 0;
-
-    while offset + 60 <= data.len() {
-        let record_len = u32::from_le_bytes(data[offset..offset+4].try_into().unwrap()) as usize;
-        
-        // 패딩(0)을 만나면 8바이트 정렬 단위로 전진
-        if record_len == 0 {
-            offset += 8;
-            continue;
+    Ok(UsnRecordReader::new(data).collect())
+}
+
+/// 저널 자체의 기록(생성/이름 변경)만으로 부모 참조를 경로로 재구성한다. MFT 경로를 사용할 수 없을 때의 대체 수단이다.
+/// 각 레코드는 그 시점까지의 이름 이력으로 해석하고, 아직 등장하지 않은 부모는 저널 전체에서 처음 기록된 이름을 사용한다.
+#[derive(Default)]
+pub struct UsnPathResolver {
+    /// 파일 참조별 (USN, 이름, 부모 참조) 이력 (USN 오름차순)
+    history: HashMap<u64, Vec<(i64, String, u64)>>,
+}
+
+impl UsnPathResolver {
+    pub fn new() -> Self {
+        Self { history: HashMap::new() }
+    }
+
+    pub fn from_records(records: &[UsnRecord]) -> Self {
+        let mut resolver = Self::new();
+        for rec in records {
+            resolver.add(rec);
         }
+        resolver
+    }
 
-        if offset + record_len > data.len() {
-            break;
+    /// 레코드 하나의 (이름, 부모) 이력을 추가한다. 스트림을 두 번 읽을 때 첫 번째 순회에서 호출한다.
+    pub fn add(&mut self, rec: &UsnRecord) {
+        let versions = self.history.entry(rec.file_reference).or_default();
+        let at = versions.partition_point(|(usn, _, _)| *usn <= rec.usn);
+        if at > 0 && versions[at - 1].1 == rec.file_name && versions[at - 1].2 == rec.parent_reference {
+            return;
         }
+        versions.insert(at, (rec.usn, rec.file_name.clone(), rec.parent_reference));
+    }
 
-        let major_version = u16::from_le_bytes([data[offset+4], data[offset+5]]);
-        
-        // USN V2 레코드 파싱
-        if major_version == 2 {
-            let filetime = u64::from_le_bytes(data[offset+32..offset+40].try_into().unwrap());
-            let timestamp = StandardInformation::to_datetime(filetime);
-            
-            let reason_flags = u32::from_le_bytes(data[offset+40..offset+44].try_into().unwrap());
-            let file_attributes = u32::from_le_bytes(data[offset+52..offset+56].try_into().unwrap());
-            
-            let name_len = u16::from_le_bytes([data[offset+56], data[offset+57]]) as usize;
-            let name_off = u16::from_le_bytes([data[offset+58], data[offset+59]]) as usize;
-
-            let name_start = offset + name_off;
-            let file_name = if name_start + name_len <= offset + record_len {
-                let name_bytes = &data[name_start..name_start+name_len];
-                let u16_name: Vec<u16> = name_bytes.chunks_exact(2)
-                    .map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
-                String::from_utf16_lossy(&u16_name)
-            } else {
-                "Unknown".to_string()
-            };
+    /// USN 시점에 유효했던 (이름, 부모 참조)
+    fn name_at(&self, reference: u64, usn: i64) -> Option<&(i64, String, u64)> {
+        let versions = self.history.get(&reference)?;
+        versions.iter().rev().find(|(u, _, _)| *u <= usn).or_else(|| versions.first())
+    }
+
+    /// 레코드의 전체 경로. 루트까지 이어지지 않으면 \$Unresolved\Entry<번호>-<시퀀스> 아래에 둔다.
+    pub fn resolve(&self, rec: &UsnRecord) -> String {
+        // 이름 변경 전 이름(RENAME_OLD_NAME)은 레코드 자체의 이름이 그 시점의 이름이다.
+        let mut components = vec![rec.file_name.clone()];
+        let mut parent = rec.parent_reference;
+        let usn = if rec.reason_flags & REASON_RENAME_OLD_NAME != 0 { rec.usn - 1 } else { rec.usn };
 
-            if file_name != "Unknown" {
-                records.push(UsnRecord {
-                    timestamp,
-                    file_name,
-                    reason_flags,
-                    file_attributes,
-                });
+        for _ in 0..MAX_PATH_DEPTH {
+            if mft_entry_number(parent) == ROOT_ENTRY {
+                return Self::join(String::new(), &components);
             }
+            match self.name_at(parent, usn) {
+                Some((_, name, grandparent)) if *grandparent != parent => {
+                    components.push(name.clone());
+                    parent = *grandparent;
+                },
+                _ => break,
+            }
+        }
+        let prefix = format!("{}\\Entry{}-{}", UNRESOLVED_PATH_PREFIX, mft_entry_number(parent), mft_sequence_number(parent));
+        Self::join(prefix, &components)
+    }
+
+    fn join(prefix: String, components: &[String]) -> String {
+        components.iter().rev().fold(prefix, |mut path, c| {
+            path.push('\\');
+            path.push_str(c);
+            path
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-03-01T09:00:00Z 기준 FILETIME
+    const FILETIME: u64 = (1_709_283_600 + 11_644_473_600) * 10_000_000;
+    const REASON_FILE_CREATE: u32 = 0x0000_0100;
+    const REASON_DATA_EXTEND: u32 = 0x0000_0002;
+
+    fn reference(entry: u64, sequence: u64) -> u64 {
+        (sequence << 48) | entry
+    }
+
+    fn utf16(name: &str) -> Vec<u8> {
+        name.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    /// V2/V3 공통 레이아웃. 참조는 ref_size 바이트로 기록하고, 레코드 길이는 8바이트 정렬로 맞춘다.
+    fn v2_v3_record(major: u16, file_ref: u128, parent_ref: u128, usn: i64, reason: u32, name: &str) -> Vec<u8> {
+        let ref_size = if major == 2 { 8 } else { 16 };
+        let base = 8 + ref_size * 2;
+        let name = utf16(name);
+        let name_off = base + 36;
+        let mut record = vec![0u8; (name_off + name.len()).next_multiple_of(8)];
+        let len = record.len() as u32;
+        record[0..4].copy_from_slice(&len.to_le_bytes());
+        record[4..6].copy_from_slice(&major.to_le_bytes());
+        record[8..8 + ref_size].copy_from_slice(&file_ref.to_le_bytes()[..ref_size]);
+        record[8 + ref_size..base].copy_from_slice(&parent_ref.to_le_bytes()[..ref_size]);
+        record[base..base + 8].copy_from_slice(&usn.to_le_bytes());
+        record[base + 8..base + 16].copy_from_slice(&FILETIME.to_le_bytes());
+        record[base + 16..base + 20].copy_from_slice(&reason.to_le_bytes());
+        record[base + 28..base + 32].copy_from_slice(&0x20u32.to_le_bytes());
+        record[base + 32..base + 34].copy_from_slice(&(name.len() as u16).to_le_bytes());
+        record[base + 34..base + 36].copy_from_slice(&(name_off as u16).to_le_bytes());
+        record[name_off..name_off + name.len()].copy_from_slice(&name);
+        record
+    }
+
+    fn v4_record(file_ref: u128, parent_ref: u128, usn: i64, reason: u32, extents: &[(i64, i64)]) -> Vec<u8> {
+        let mut record = vec![0u8; 64 + extents.len() * 16];
+        let len = record.len() as u32;
+        record[0..4].copy_from_slice(&len.to_le_bytes());
+        record[4..6].copy_from_slice(&4u16.to_le_bytes());
+        record[8..24].copy_from_slice(&file_ref.to_le_bytes());
+        record[24..40].copy_from_slice(&parent_ref.to_le_bytes());
+        record[40..48].copy_from_slice(&usn.to_le_bytes());
+        record[48..52].copy_from_slice(&reason.to_le_bytes());
+        record[60..62].copy_from_slice(&(extents.len() as u16).to_le_bytes());
+        record[62..64].copy_from_slice(&16u16.to_le_bytes());
+        for (i, (offset, length)) in extents.iter().enumerate() {
+            let at = 64 + i * 16;
+            record[at..at + 8].copy_from_slice(&offset.to_le_bytes());
+            record[at + 8..at + 16].copy_from_slice(&length.to_le_bytes());
         }
-        
-        offset += record_len;
+        record
     }
 
-    Ok(records)
-}
\ No newline at end of file
+    #[test]
+    fn parses_v2_record_after_sparse_padding() {
+        let mut stream = vec![0u8; 4096];
+        stream.extend(v2_v3_record(2, reference(0x40, 3) as u128, reference(5, 5) as u128, 0x1000, REASON_FILE_CREATE, "evil.exe"));
+
+        let records = parse_usnjrnl_stream(&stream).unwrap();
+        assert_eq!(records.len(), 1);
+        let rec = &records[0];
+        assert_eq!(rec.major_version, 2);
+        assert_eq!(rec.file_reference, reference(0x40, 3));
+        assert_eq!(rec.parent_reference, reference(5, 5));
+        assert_eq!(rec.usn, 0x1000);
+        assert_eq!(rec.timestamp.to_rfc3339(), "2024-03-01T09:00:00+00:00");
+        assert_eq!(rec.reason_flags, REASON_FILE_CREATE);
+        assert_eq!(rec.file_attributes, 0x20);
+        assert_eq!(rec.file_name, "evil.exe");
+    }
+
+    #[test]
+    fn v3_record_uses_low_64_bits_of_128_bit_references() {
+        let high = 0xDEAD_BEEF_u128 << 64;
+        let stream = v2_v3_record(3, high | reference(0x41, 2) as u128, high | reference(0x40, 3) as u128, 0x2000, REASON_FILE_CREATE, "payload.dll");
+
+        let records = parse_usnjrnl_stream(&stream).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].major_version, 3);
+        assert_eq!(records[0].file_reference, reference(0x41, 2));
+        assert_eq!(records[0].parent_reference, reference(0x40, 3));
+        assert_eq!(records[0].usn, 0x2000);
+        assert_eq!(records[0].file_name, "payload.dll");
+    }
+
+    #[test]
+    fn v4_record_inherits_time_and_name_from_previous_record() {
+        let file_ref = reference(0x41, 2) as u128;
+        let parent_ref = reference(0x40, 3) as u128;
+        let mut stream = Vec::new();
+        // 같은 파일의 V2/V3 기록이 없는 V4 레코드는 시각/이름을 알 수 없으므로 버린다.
+        stream.extend(v4_record(reference(0x99, 1) as u128, parent_ref, 0x0F00, REASON_DATA_EXTEND, &[(0, 4096)]));
+        stream.extend(v2_v3_record(3, file_ref, parent_ref, 0x1000, REASON_FILE_CREATE, "payload.dll"));
+        stream.extend(v4_record(file_ref, parent_ref, 0x1100, REASON_DATA_EXTEND, &[(0, 4096), (8192, 512)]));
+
+        let records = parse_usnjrnl_stream(&stream).unwrap();
+        assert_eq!(records.len(), 2);
+        let v4 = &records[1];
+        assert_eq!(v4.major_version, 4);
+        assert_eq!(v4.file_reference, reference(0x41, 2));
+        assert_eq!(v4.usn, 0x1100);
+        assert_eq!(v4.file_name, "payload.dll");
+        assert_eq!(v4.timestamp, records[0].timestamp);
+        assert_eq!(v4.reason_flags, REASON_DATA_EXTEND);
+        assert_eq!(v4.extents, vec![(0, 4096), (8192, 512)]);
+    }
+
+    #[test]
+    fn truncated_record_ends_the_stream() {
+        let mut stream = v2_v3_record(2, reference(0x40, 3) as u128, reference(5, 5) as u128, 0x1000, REASON_FILE_CREATE, "a.txt");
+        let second = v2_v3_record(2, reference(0x41, 1) as u128, reference(5, 5) as u128, 0x1050, REASON_FILE_CREATE, "b.txt");
+        stream.extend(&second[..second.len() - 10]);
+
+        let records = parse_usnjrnl_stream(&stream).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].file_name, "a.txt");
+
+        // 길이 필드가 최소 크기보다 작은 레코드도 패닉 없이 건너뛴다.
+        let mut short = v2_v3_record(2, reference(0x40, 3) as u128, reference(5, 5) as u128, 0x1000, REASON_FILE_CREATE, "a.txt");
+        short[0..4].copy_from_slice(&40u32.to_le_bytes());
+        short.truncate(40);
+        assert!(parse_usnjrnl_stream(&short).unwrap().is_empty());
+
+        // 비정상적으로 큰 길이 필드는 손상으로 보고 멈춘다.
+        let mut oversized = stream.clone();
+        oversized[0..4].copy_from_slice(&0x0100_0000u32.to_le_bytes());
+        assert!(parse_usnjrnl_stream(&oversized).unwrap().is_empty());
+    }
+
+    #[test]
+    fn path_resolver_follows_rename_history() {
+        let dir = reference(0x40, 3);
+        let file = reference(0x41, 2);
+        let records = vec![
+            UsnRecord { file_name: "Tools".into(), ..record(dir, reference(5, 5), 0x100, REASON_FILE_CREATE) },
+            UsnRecord { file_name: "a.exe".into(), ..record(file, dir, 0x200, REASON_FILE_CREATE) },
+            UsnRecord { file_name: "Tools".into(), ..record(dir, reference(5, 5), 0x300, REASON_RENAME_OLD_NAME) },
+            UsnRecord { file_name: "Hidden".into(), ..record(dir, reference(5, 5), 0x310, 0x0000_2000) },
+            UsnRecord { file_name: "a.exe".into(), ..record(file, dir, 0x400, REASON_DATA_EXTEND) },
+            UsnRecord { file_name: "orphan.txt".into(), ..record(reference(0x50, 1), reference(0x60, 7), 0x500, REASON_FILE_CREATE) },
+        ];
+        let resolver = UsnPathResolver::from_records(&records);
+
+        assert_eq!(resolver.resolve(&records[1]), "\\Tools\\a.exe");
+        assert_eq!(resolver.resolve(&records[4]), "\\Hidden\\a.exe");
+        assert_eq!(resolver.resolve(&records[5]), "\\$Unresolved\\Entry96-7\\orphan.txt");
+    }
+
+    fn record(file_reference: u64, parent_reference: u64, usn: i64, reason_flags: u32) -> UsnRecord {
+        UsnRecord {
+            major_version: 2,
+            usn,
+            file_reference,
+            parent_reference,
+            timestamp: StandardInformation::to_datetime(FILETIME),
+            file_name: String::new(),
+            reason_flags,
+            source_info: 0,
+            security_id: 0,
+            file_attributes: 0,
+            extents: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use chrono::{DateTime, Utc};
+use models::FactError;
+use models::mft::StandardInformation;
+use crate::partition::format_guid;
+
+/// VSS 볼륨 헤더 위치 (NTFS $Boot 영역 내부)
+pub const VSS_VOLUME_HEADER_OFFSET: u64 = 0x1E00;
+/// 카탈로그/저장소 블록 크기
+pub const VSS_BLOCK_SIZE: u64 = 0x4000;
+/// 모든 VSS 블록 헤더 뒤에 오는 데이터 시작 위치
+const BLOCK_HEADER_SIZE: usize = 128;
+const CATALOG_ENTRY_SIZE: usize = 128;
+const BLOCK_DESCRIPTOR_SIZE: usize = 32;
+
+/// {3808876B-C176-4E48-B7AE-04046E6CC752}
+const VSS_IDENTIFIER: [u8; 16] = [0x6B, 0x87, 0x08, 0x38, 0x76, 0xC1, 0x48, 0x4E, 0xB7, 0xAE, 0x04, 0x04, 0x6E, 0x6C, 0xC7, 0x52];
+
+pub const RECORD_TYPE_VOLUME_HEADER: u32 = 1;
+pub const RECORD_TYPE_CATALOG: u32 = 2;
+pub const RECORD_TYPE_BLOCK_LIST: u32 = 3;
+pub const RECORD_TYPE_STORE_HEADER: u32 = 4;
+
+/// 블록 디스크립터 플래그
+pub const BLOCK_FLAG_FORWARDER: u32 = 0x1;
+pub const BLOCK_FLAG_OVERLAY: u32 = 0x2;
+pub const BLOCK_FLAG_NOT_USED: u32 = 0x4;
+
+fn vss_error(details: impl Into<String>) -> FactError {
+    FactError::ParseError { artifact_name: "VSS".into(), details: details.into() }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+#[derive(Debug, Clone)]
+pub struct VssVolumeHeader {
+    pub version: u32,
+    /// 0이면 섀도 복사본이 한 번도 생성되지 않은 볼륨
+    pub catalog_offset: u64,
+    pub maximum_size: u64,
+    pub volume_id: String,
+}
+
+/// 카탈로그/저장소 블록 공통 헤더 (128바이트)
+#[derive(Debug, Clone)]
+pub struct VssBlockHeader {
+    pub record_type: u32,
+    pub current_offset: u64,
+    /// 같은 목록의 다음 블록 볼륨 오프셋 (0 = 마지막)
+    pub next_offset: u64,
+}
+
+/// 카탈로그 엔트리: 스냅숏마다 타입 2(볼륨 정보)와 타입 3(저장소 위치) 엔트리가 저장소 GUID로 짝지어진다.
+#[derive(Debug, Clone)]
+pub enum CatalogEntry {
+    Snapshot { store_id: String, volume_size: u64, creation_time: DateTime<Utc> },
+    Store { store_id: String, block_list_offset: u64, store_header_offset: u64 },
+}
+
+/// 저장소 블록 목록의 엔트리. 원본 16KB 블록이 덮어쓰이기 전 내용의 위치를 가리킨다.
+#[derive(Debug, Clone)]
+pub struct BlockDescriptor {
+    pub original_offset: u64,
+    /// 포워더일 때 실제 데이터를 가진 원본 블록 오프셋
+    pub relative_offset: u64,
+    pub store_offset: u64,
+    pub flags: u32,
+    /// 오버레이 블록에서 유효한 512바이트 섹터 비트맵
+    pub allocation_bitmap: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct VssStoreInformation {
+    pub shadow_copy_id: String,
+    pub shadow_copy_set_id: String,
+    pub operating_machine: String,
+    pub service_machine: String,
+}
+
+pub fn parse_volume_header(data: &[u8]) -> Result<VssVolumeHeader, FactError> {
+    if data.len() < 0x60 { return Err(vss_error("Volume header too small")); }
+    if data[0..16] != VSS_IDENTIFIER { return Err(vss_error("Missing VSS identifier")); }
+    if read_u32(data, 20) != RECORD_TYPE_VOLUME_HEADER { return Err(vss_error("Not a volume header record")); }
+
+    Ok(VssVolumeHeader {
+        version: read_u32(data, 16),
+        catalog_offset: read_u64(data, 48),
+        maximum_size: read_u64(data, 56),
+        volume_id: format_guid(&data[64..80]),
+    })
+}
+
+pub fn parse_block_header(data: &[u8], expected_type: u32) -> Result<VssBlockHeader, FactError> {
+    if data.len() < BLOCK_HEADER_SIZE { return Err(vss_error("Block header too small")); }
+    if data[0..16] != VSS_IDENTIFIER { return Err(vss_error("Missing VSS identifier in block header")); }
+    let record_type = read_u32(data, 20);
+    if record_type != expected_type {
+        return Err(vss_error(format!("Unexpected record type {} (expected {})", record_type, expected_type)));
+    }
+    Ok(VssBlockHeader { record_type, current_offset: read_u64(data, 32), next_offset: read_u64(data, 40) })
+}
+
+/// 16KB 카탈로그 블록 하나의 엔트리를 파싱한다. 빈 엔트리(타입 0)와 삭제된 엔트리(타입 1)는 건너뛴다.
+pub fn parse_catalog_block(block: &[u8]) -> Result<(VssBlockHeader, Vec<CatalogEntry>), FactError> {
+    let header = parse_block_header(block, RECORD_TYPE_CATALOG)?;
+    let mut entries = Vec::new();
+
+    for raw in block[BLOCK_HEADER_SIZE..].chunks_exact(CATALOG_ENTRY_SIZE) {
+        match read_u64(raw, 0) {
+            2 => entries.push(CatalogEntry::Snapshot {
+                volume_size: read_u64(raw, 8),
+                store_id: format_guid(&raw[16..32]),
+                creation_time: StandardInformation::to_datetime(read_u64(raw, 48)),
+            }),
+            3 => entries.push(CatalogEntry::Store {
+                block_list_offset: read_u64(raw, 8),
+                store_id: format_guid(&raw[16..32]),
+                store_header_offset: read_u64(raw, 32),
+            }),
+            _ => {},
+        }
+    }
+    Ok((header, entries))
+}
+
+/// 16KB 저장소 블록 목록 블록 하나의 디스크립터를 파싱한다.
+pub fn parse_block_list(block: &[u8]) -> Result<(VssBlockHeader, Vec<BlockDescriptor>), FactError> {
+    let header = parse_block_header(block, RECORD_TYPE_BLOCK_LIST)?;
+    let descriptors = block[BLOCK_HEADER_SIZE..].chunks_exact(BLOCK_DESCRIPTOR_SIZE)
+        .map(|raw| BlockDescriptor {
+            original_offset: read_u64(raw, 0),
+            relative_offset: read_u64(raw, 8),
+            store_offset: read_u64(raw, 16),
+            flags: read_u32(raw, 24),
+            allocation_bitmap: read_u32(raw, 28),
+        })
+        .filter(|d| d.original_offset != 0 || d.relative_offset != 0 || d.store_offset != 0 || d.flags != 0)
+        .collect();
+    Ok((header, descriptors))
+}
+
+fn read_sized_utf16(data: &[u8], offset: usize) -> Option<(String, usize)> {
+    let size = u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().unwrap()) as usize;
+    let raw = data.get(offset + 2..offset + 2 + size)?;
+    let units: Vec<u16> = raw.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    Some((String::from_utf16_lossy(&units), offset + 2 + size))
+}
+
+/// 저장소 헤더 블록의 저장소 정보 (섀도 복사본 ID, 세트 ID, 생성 호스트)
+pub fn parse_store_header(block: &[u8]) -> Result<VssStoreInformation, FactError> {
+    parse_block_header(block, RECORD_TYPE_STORE_HEADER)?;
+    let info = &block[BLOCK_HEADER_SIZE..];
+    if info.len() < 0x42 { return Err(vss_error("Store information too small")); }
+
+    let (operating_machine, next) = read_sized_utf16(info, 0x40).unwrap_or_default();
+    let (service_machine, _) = if next > 0 { read_sized_utf16(info, next).unwrap_or_default() } else { Default::default() };
+    Ok(VssStoreInformation {
+        shadow_copy_id: format_guid(&info[16..32]),
+        shadow_copy_set_id: format_guid(&info[32..48]),
+        operating_machine,
+        service_machine,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STORE_GUID: [u8; 16] = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF, 0x00];
+    /// 2024-03-01T09:00:00Z 기준 FILETIME
+    const FILETIME: u64 = (1_709_283_600 + 11_644_473_600) * 10_000_000;
+
+    fn block(record_type: u32, current: u64, next: u64) -> Vec<u8> {
+        let mut block = vec![0u8; VSS_BLOCK_SIZE as usize];
+        block[0..16].copy_from_slice(&VSS_IDENTIFIER);
+        block[16..20].copy_from_slice(&1u32.to_le_bytes());
+        block[20..24].copy_from_slice(&record_type.to_le_bytes());
+        block[32..40].copy_from_slice(&current.to_le_bytes());
+        block[40..48].copy_from_slice(&next.to_le_bytes());
+        block
+    }
+
+    fn put_u64(data: &mut [u8], offset: usize, value: u64) {
+        data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    #[test]
+    fn volume_header_requires_identifier_and_type() {
+        let mut header = vec![0u8; 512];
+        header[0..16].copy_from_slice(&VSS_IDENTIFIER);
+        header[16..20].copy_from_slice(&1u32.to_le_bytes());
+        header[20..24].copy_from_slice(&RECORD_TYPE_VOLUME_HEADER.to_le_bytes());
+        put_u64(&mut header, 48, 0x1_4000);
+        put_u64(&mut header, 56, 0x100_0000);
+        header[64..80].copy_from_slice(&STORE_GUID);
+
+        let parsed = parse_volume_header(&header).unwrap();
+        assert_eq!(parsed.catalog_offset, 0x1_4000);
+        assert_eq!(parsed.maximum_size, 0x100_0000);
+        assert_eq!(parsed.volume_id, "44332211-6655-8877-99AA-BBCCDDEEFF00");
+
+        header[20] = RECORD_TYPE_CATALOG as u8;
+        assert!(parse_volume_header(&header).is_err());
+        header[0] ^= 0xFF;
+        assert!(parse_volume_header(&header).is_err());
+        assert!(parse_volume_header(&header[..0x40]).is_err());
+    }
+
+    #[test]
+    fn catalog_block_pairs_snapshot_and_store_entries() {
+        let mut catalog = block(RECORD_TYPE_CATALOG, 0x1_4000, 0x2_0000);
+        // 엔트리 0: 스냅숏 정보, 엔트리 1: 삭제됨(타입 1), 엔트리 2: 저장소 위치
+        let entry = BLOCK_HEADER_SIZE;
+        put_u64(&mut catalog, entry, 2);
+        put_u64(&mut catalog, entry + 8, 0x4000_0000);
+        catalog[entry + 16..entry + 32].copy_from_slice(&STORE_GUID);
+        put_u64(&mut catalog, entry + 48, FILETIME);
+        put_u64(&mut catalog, entry + CATALOG_ENTRY_SIZE, 1);
+        let entry = BLOCK_HEADER_SIZE + 2 * CATALOG_ENTRY_SIZE;
+        put_u64(&mut catalog, entry, 3);
+        put_u64(&mut catalog, entry + 8, 0x8_0000);
+        catalog[entry + 16..entry + 32].copy_from_slice(&STORE_GUID);
+        put_u64(&mut catalog, entry + 32, 0x8_4000);
+
+        let (header, entries) = parse_catalog_block(&catalog).unwrap();
+        assert_eq!(header.next_offset, 0x2_0000);
+        assert_eq!(entries.len(), 2);
+        match &entries[0] {
+            CatalogEntry::Snapshot { store_id, volume_size, creation_time } => {
+                assert_eq!(store_id, "44332211-6655-8877-99AA-BBCCDDEEFF00");
+                assert_eq!(*volume_size, 0x4000_0000);
+                assert_eq!(creation_time.to_rfc3339(), "2024-03-01T09:00:00+00:00");
+            },
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(&entries[1], CatalogEntry::Store { block_list_offset: 0x8_0000, store_header_offset: 0x8_4000, .. }));
+
+        // 블록 목록 블록을 카탈로그로 읽으면 거부한다.
+        assert!(parse_catalog_block(&block(RECORD_TYPE_BLOCK_LIST, 0, 0)).is_err());
+    }
+
+    #[test]
+    fn block_list_skips_empty_descriptors() {
+        let mut list = block(RECORD_TYPE_BLOCK_LIST, 0x8_0000, 0);
+        let first = BLOCK_HEADER_SIZE;
+        put_u64(&mut list, first, 0x2_0000);
+        put_u64(&mut list, first + 16, 0x9_0000);
+        let second = first + 2 * BLOCK_DESCRIPTOR_SIZE;
+        put_u64(&mut list, second, 0x3_0000);
+        put_u64(&mut list, second + 8, 0x4_0000);
+        list[second + 24..second + 28].copy_from_slice(&(BLOCK_FLAG_FORWARDER | BLOCK_FLAG_OVERLAY).to_le_bytes());
+        list[second + 28..second + 32].copy_from_slice(&0b101u32.to_le_bytes());
+
+        let (_, descriptors) = parse_block_list(&list).unwrap();
+        assert_eq!(descriptors.len(), 2);
+        assert_eq!(descriptors[0].store_offset, 0x9_0000);
+        assert_eq!(descriptors[1].relative_offset, 0x4_0000);
+        assert_eq!(descriptors[1].flags, BLOCK_FLAG_FORWARDER | BLOCK_FLAG_OVERLAY);
+        assert_eq!(descriptors[1].allocation_bitmap, 0b101);
+    }
+
+    #[test]
+    fn store_header_reads_ids_and_machine_names() {
+        let mut store = block(RECORD_TYPE_STORE_HEADER, 0x8_4000, 0);
+        let info = BLOCK_HEADER_SIZE;
+        store[info + 16..info + 32].copy_from_slice(&STORE_GUID);
+        store[info + 32..info + 48].copy_from_slice(&[0xAB; 16]);
+        let mut at = info + 0x40;
+        for name in ["WS01.corp.local", "WS01"] {
+            let encoded: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+            store[at..at + 2].copy_from_slice(&(encoded.len() as u16).to_le_bytes());
+            store[at + 2..at + 2 + encoded.len()].copy_from_slice(&encoded);
+            at += 2 + encoded.len();
+        }
+
+        let information = parse_store_header(&store).unwrap();
+        assert_eq!(information.shadow_copy_id, "44332211-6655-8877-99AA-BBCCDDEEFF00");
+        assert_eq!(information.shadow_copy_set_id, "ABABABAB-ABAB-ABAB-ABAB-ABABABABABAB");
+        assert_eq!(information.operating_machine, "WS01.corp.local");
+        assert_eq!(information.service_machine, "WS01");
+    }
+}
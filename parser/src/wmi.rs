@@ -31,6 +31,7 @@ pub fn parse_wmi_carve(data: &[u8], filename: &str) -> Result<Vec<ForensicEvent>
             target_name: "WMI Object".to_string(),
             target_path: path,
             source_artifact: format!("WMI: {}", filename),
+            user: None,
         }));
     }
     Ok(events)